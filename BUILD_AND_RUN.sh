@@ -1,8 +1,8 @@
 # Build the project
 cargo build --release
 
-# Run the main example
-cargo run --release
+# Fetch one phone through the CLI (see `cargo run --release -- --help`)
+cargo run --release -- specs apple_iphone_15_pro_max-12548
 
 # Run specific examples:
 
@@ -0,0 +1,51 @@
+//! Parsing and serialization hot paths, over the pages in `tests/fixtures`
+//!
+//! `cargo bench --bench parsing`; reprocessing the whole corpus is bound by these.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gsmarena_scraper::brand_scraper::{parse_brands_page, parse_phone_list_page};
+use gsmarena_scraper::mongodb::parse_specifications;
+use gsmarena_scraper::scraper::parse_specification_html;
+use gsmarena_scraper::{PhoneDocument, PhoneListItem};
+use std::hint::black_box;
+
+fn fixture(page: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}.html", env!("CARGO_MANIFEST_DIR"), page);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn bench_html(c: &mut Criterion) {
+    let makers = fixture("makers.php3");
+    let brand_page = fixture("apple-phones-48.php");
+    let phone_page = fixture("samsung_galaxy_s24-12773.php");
+
+    c.bench_function("parse_brands_page", |b| b.iter(|| parse_brands_page(black_box(&makers))));
+    c.bench_function("parse_phone_list_page", |b| b.iter(|| parse_phone_list_page(black_box(&brand_page))));
+    c.bench_function("parse_specification_html", |b| {
+        b.iter(|| parse_specification_html("samsung_galaxy_s24-12773", black_box(&phone_page)).unwrap())
+    });
+}
+
+fn bench_documents(c: &mut Criterion) {
+    let spec = parse_specification_html("samsung_galaxy_s24-12773", &fixture("samsung_galaxy_s24-12773.php")).unwrap();
+    let spec_json = serde_json::to_value(spec).unwrap();
+    let item = PhoneListItem {
+        phone_id: "samsung_galaxy_s24-12773".to_string(),
+        name: "Galaxy S24".to_string(),
+        url: "https://www.gsmarena.com/samsung_galaxy_s24-12773.php".to_string(),
+        image_url: None,
+        device_type: None,
+    };
+    let document = PhoneDocument::new(&item, "Samsung", spec_json.clone());
+    let bson = mongodb::bson::to_document(&document).unwrap();
+
+    c.bench_function("parse_specifications", |b| b.iter(|| parse_specifications(black_box(&spec_json))));
+    c.bench_function("PhoneDocument::new", |b| b.iter(|| PhoneDocument::new(&item, "Samsung", black_box(spec_json.clone()))));
+    c.bench_function("to_bson", |b| b.iter(|| mongodb::bson::to_document(black_box(&document)).unwrap()));
+    c.bench_function("from_bson", |b| {
+        b.iter(|| mongodb::bson::from_document::<PhoneDocument>(black_box(bson.clone())).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_html, bench_documents);
+criterion_main!(benches);
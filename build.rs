@@ -0,0 +1,13 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto/phones.proto");
+
+    // gRPC stubs are only generated with `--features grpc`; protoc is vendored
+    #[cfg(feature = "grpc")]
+    {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+        tonic_prost_build::compile_protos("proto/phones.proto")?;
+    }
+
+    Ok(())
+}
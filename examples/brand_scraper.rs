@@ -1,9 +1,7 @@
-use gsmarena;
-use serde_json;
+use gsmarena_scraper::{search_with_filters, GsmArenaScraper, SearchFilters};
 use std::error::Error;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = std::env::args().collect();
     let brand = if args.len() > 1 {
         args[1].clone()
@@ -13,17 +11,22 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Scraping phones from brand: {}\n", brand);
     
-    // Search for the brand
-    let results = gsmarena::search(&brand).await?;
+    // Search for the brand, keeping only that brand's devices
+    let filters = SearchFilters {
+        brand: Some(brand.clone()),
+        ..Default::default()
+    };
+    let results = search_with_filters(&brand, &filters)?;
     
     println!("Found {} phones from {}\n", results.len(), brand);
     
+    let scraper = GsmArenaScraper::new();
     let mut all_phones = Vec::new();
     
     for (index, result) in results.iter().enumerate().take(10) {
         println!("[{}/{}] Fetching: {}", index + 1, results.len().min(10), result.name);
         
-        match gsmarena::phone(&result.url).await {
+        match scraper.get_phone_details(&result.phone_id) {
             Ok(phone) => {
                 all_phones.push(phone);
             }
@@ -33,7 +36,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
         
         // Add a small delay to be respectful to the server
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        std::thread::sleep(std::time::Duration::from_millis(500));
     }
 
     println!("\n✓ Successfully scraped {} phones", all_phones.len());
@@ -46,8 +49,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     // Print summary
     println!("\nSummary:");
-    for phone in &all_phones {
-        println!("  - {}", phone.name);
+    for result in results.iter().take(10) {
+        println!("  - {}", result.name);
     }
 
     Ok(())
@@ -1,5 +1,4 @@
 use gsmarena_scraper::{fetch_all_brands, fetch_phones_by_brand};
-use serde_json;
 use std::error::Error;
 use std::fs;
 
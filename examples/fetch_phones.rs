@@ -1,6 +1,4 @@
-use gsmarena;
 use gsmarena_scraper::models::get_device_name;
-use serde_json;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
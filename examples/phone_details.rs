@@ -1,9 +1,8 @@
-use gsmarena;
-use serde_json;
+use gsmarena_scraper::search;
+use gsmarena_scraper::utils::format_phone_info;
 use std::error::Error;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = std::env::args().collect();
     let phone_query = if args.len() > 1 {
         args[1..].join(" ")
@@ -14,7 +13,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     println!("Fetching details for: {}\n", phone_query);
     
     // First search for the phone
-    let search_results = gsmarena::search(&phone_query).await?;
+    let search_results = search(&phone_query)?;
     
     if search_results.is_empty() {
         println!("No phones found matching '{}'", phone_query);
@@ -22,26 +21,23 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
 
     let first_result = &search_results[0];
-    println!("Found: {}", first_result.name);
+    println!("Found: {} ({})", first_result.name, first_result.phone_id);
     println!("Fetching detailed specifications...\n");
 
     // Get detailed information
-    let phone = gsmarena::phone(&first_result.url).await?;
+    let phone = gsmarena::get_specification(&first_result.phone_id);
 
-    println!("=== {} ===\n", phone.name);
-    if let Some(img) = &phone.img {
+    println!("=== {} ===\n", first_result.name);
+    if let Some(img) = &first_result.image_url {
         println!("Image: {}\n", img);
     }
 
     // Display specifications
-    println!("Specifications:");
-    println!("-".repeat(50));
-    for (key, value) in &phone.specs {
-        println!("{:25} : {}", key, value);
-    }
+    println!("{}", "-".repeat(50));
+    println!("{}", format_phone_info(&phone));
 
     // Save to JSON
-    let filename = format!("{}.json", phone.name.replace(" ", "_"));
+    let filename = format!("{}.json", first_result.phone_id);
     let json = serde_json::to_string_pretty(&phone)?;
     std::fs::write(&filename, json)?;
     println!("\n✓ Saved to '{}'", filename);
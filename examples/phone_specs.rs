@@ -1,16 +1,32 @@
-use gsmarena;
 use gsmarena_scraper::models::get_device_name;
-use serde_json;
+use gsmarena_scraper::resolve_phone_id;
+use gsmarena_scraper::search::device_id_from_phone_id;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = std::env::args().collect();
-    let phone_id = if args.len() > 1 {
-        args[1].clone()
+    let mut phone_id = if args.len() > 1 {
+        args[1..].join(" ")
     } else {
         "apple_iphone_15_pro_max-12548".to_string()
     };
 
+    // Accept human-readable names ("iphone 15 pro max") as well as phone IDs
+    if device_id_from_phone_id(&phone_id).is_none() {
+        println!("Resolving \"{}\"...", phone_id);
+        let candidates = resolve_phone_id(&phone_id)?;
+
+        for (candidate, score) in &candidates {
+            println!("  {:.3}  {}", score, candidate);
+        }
+
+        phone_id = match candidates.into_iter().next() {
+            Some((best, _)) => best,
+            None => return Err(format!("No phone found matching \"{}\"", phone_id).into()),
+        };
+        println!();
+    }
+
     println!("Fetching detailed specifications for: {}\n", phone_id);
     
     let device_spec = gsmarena::get_specification(&phone_id);
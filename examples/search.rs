@@ -1,26 +1,42 @@
-use gsmarena;
+use gsmarena_scraper::{search_with_filters, SearchFilters};
 use std::error::Error;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    // Get search query from command line or use default
-    let args: Vec<String> = std::env::args().collect();
-    let query = if args.len() > 1 {
-        args[1..].join(" ")
-    } else {
+fn main() -> Result<(), Box<dyn Error>> {
+    // Usage: search [--brand <brand>] [--year <year>] <query...>
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let mut filters = SearchFilters::default();
+    let mut query_parts = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--brand" => filters.brand = iter.next().cloned(),
+            "--year" => filters.year = iter.next().and_then(|y| y.parse().ok()),
+            _ => query_parts.push(arg.clone()),
+        }
+    }
+
+    let query = if query_parts.is_empty() {
         "Samsung Galaxy S24".to_string()
+    } else {
+        query_parts.join(" ")
     };
 
     println!("Searching for: {}\n", query);
     
-    let results = gsmarena::search(&query).await?;
+    let results = search_with_filters(&query, &filters)?;
 
     println!("Found {} results:\n", results.len());
     
     for (index, phone) in results.iter().enumerate() {
         println!("{}. {}", index + 1, phone.name);
+        println!("   ID: {}", phone.phone_id);
         println!("   URL: {}", phone.url);
-        if let Some(img) = &phone.img {
+        if let Some(year) = phone.announced_year {
+            println!("   Announced: {}", year);
+        }
+        if let Some(img) = &phone.image_url {
             println!("   Image: {}", img);
         }
         println!();
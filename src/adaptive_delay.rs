@@ -0,0 +1,177 @@
+//! A delay between phone requests that follows how hard the site pushes back
+//!
+//! A fixed `rate_limit.delay_between_phones_ms` is either too slow while GSMArena is
+//! lenient or too fast once it starts answering 429/403. With `rate_limit.adaptive`,
+//! every response of a rate-limited request (direct, proxy, browser) goes into a sliding
+//! window: when the share of 429/403 in it reaches `adaptive_threshold`, the delay
+//! doubles (up to `adaptive_max_delay_ms`); after a full window of clean responses it
+//! comes down by a quarter, never below the configured delay.
+
+use crate::config::RateLimitConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Responses needed in the window before its share of throttled responses means anything
+const MIN_SAMPLE: usize = 10;
+/// Smallest delay after a raise, so a configured delay of 0 can still back off
+const MIN_RAISED_MS: u64 = 500;
+
+/// How the delay moved during a run, as stored in the run report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveDelayStats {
+    pub base_ms: u64,
+    pub final_ms: u64,
+    pub peak_ms: u64,
+    pub raises: u64,
+    pub lowers: u64,
+    pub throttled: u64, // 429 and 403 responses seen
+}
+
+#[derive(Debug)]
+struct State {
+    delay_ms: u64,
+    window: VecDeque<(Instant, bool)>, // Response time, whether it was throttled
+    last_change: Instant,
+    stats: AdaptiveDelayStats,
+}
+
+/// Delay between rate-limited phone requests, raised on 429/403 and lowered on clean responses
+/// Cloning shares the state, so every fetcher of a run feeds the same window.
+#[derive(Debug, Clone)]
+pub struct AdaptiveDelay {
+    base_ms: u64,
+    max_ms: u64,
+    window: Duration,
+    threshold: f64,
+    state: Arc<Mutex<State>>,
+}
+
+impl AdaptiveDelay {
+    /// `None` unless `rate_limit.adaptive` is set
+    pub fn from_config(rate_limit: &RateLimitConfig) -> Option<Self> {
+        rate_limit.adaptive.then(|| {
+            Self::new(
+                rate_limit.delay_between_phones_ms,
+                rate_limit.adaptive_max_delay_ms,
+                Duration::from_secs(rate_limit.adaptive_window_secs.max(1)),
+                rate_limit.adaptive_threshold,
+            )
+        })
+    }
+
+    pub fn new(base_ms: u64, max_ms: u64, window: Duration, threshold: f64) -> Self {
+        let stats = AdaptiveDelayStats { base_ms, final_ms: base_ms, peak_ms: base_ms, ..Default::default() };
+        Self {
+            base_ms,
+            max_ms: max_ms.max(base_ms),
+            window,
+            threshold,
+            state: Arc::new(Mutex::new(State { delay_ms: base_ms, window: VecDeque::new(), last_change: Instant::now(), stats })),
+        }
+    }
+
+    /// Delay to wait before the next rate-limited phone request
+    pub fn current(&self) -> Duration {
+        Duration::from_millis(self.current_ms())
+    }
+
+    pub fn current_ms(&self) -> u64 {
+        self.state.lock().unwrap().delay_ms
+    }
+
+    /// Count the status code of one response
+    pub fn record(&self, status: u16) {
+        self.record_at(status, Instant::now());
+    }
+
+    fn record_at(&self, status: u16, now: Instant) {
+        let throttled = matches!(status, 403 | 429);
+        let mut state = self.state.lock().unwrap();
+        state.window.push_back((now, throttled));
+        while state.window.front().is_some_and(|&(at, _)| now.duration_since(at) > self.window) {
+            state.window.pop_front();
+        }
+        if throttled {
+            state.stats.throttled += 1;
+        }
+
+        let throttled_count = state.window.iter().filter(|(_, throttled)| *throttled).count();
+        let share = throttled_count as f64 / state.window.len().max(MIN_SAMPLE) as f64;
+        if share >= self.threshold {
+            let raised = (state.delay_ms * 2).max(MIN_RAISED_MS).min(self.max_ms);
+            if raised > state.delay_ms {
+                warn!(from_ms = state.delay_ms, to_ms = raised, throttled = throttled_count, responses = state.window.len(), "Throttled by the site, raising the delay");
+                state.delay_ms = raised;
+                state.stats.raises += 1;
+                state.stats.peak_ms = state.stats.peak_ms.max(raised);
+            }
+            // The next raise needs fresh evidence at the new delay
+            state.window.clear();
+            state.last_change = now;
+        } else if throttled_count == 0
+            && state.delay_ms > self.base_ms
+            && state.window.len() >= MIN_SAMPLE
+            && now.duration_since(state.last_change) >= self.window
+        {
+            let lowered = (state.delay_ms * 3 / 4).max(self.base_ms);
+            info!(from_ms = state.delay_ms, to_ms = lowered, "Responses clean, lowering the delay");
+            state.delay_ms = lowered;
+            state.stats.lowers += 1;
+            state.last_change = now;
+        }
+        state.stats.final_ms = state.delay_ms;
+    }
+
+    /// How the delay moved so far
+    pub fn stats(&self) -> AdaptiveDelayStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_delay() {
+        let delay = AdaptiveDelay::new(500, 4000, Duration::from_secs(60), 0.2);
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // A couple of 429s among clean responses stay under the threshold
+        for second in 0..9 {
+            delay.record_at(200, at(second));
+        }
+        delay.record_at(429, at(9));
+        delay.record_at(429, at(10));
+        assert_eq!(delay.current_ms(), 500);
+
+        // More of them double the delay, up to the maximum
+        delay.record_at(429, at(11));
+        assert_eq!(delay.current_ms(), 1000);
+        for second in 12..30 {
+            delay.record_at(if second % 2 == 0 { 429 } else { 403 }, at(second));
+        }
+        assert_eq!(delay.current_ms(), 4000);
+        let cloned = delay.clone();
+
+        // Clean responses bring it down a quarter per window, not below the base
+        for second in 30..89 {
+            cloned.record_at(200, at(second));
+        }
+        assert_eq!(delay.current_ms(), 4000);
+        cloned.record_at(200, at(89));
+        assert_eq!(delay.current_ms(), 3000);
+        for second in 90..1000 {
+            cloned.record_at(200, at(second));
+        }
+        assert_eq!(delay.current(), Duration::from_millis(500));
+
+        let stats = delay.stats();
+        assert_eq!((stats.raises, stats.peak_ms, stats.final_ms, stats.throttled), (3, 4000, 500, 21));
+        assert!(stats.lowers >= 6);
+    }
+}
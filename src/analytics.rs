@@ -0,0 +1,275 @@
+//! Distributions over the corpus: battery and display trends, 5G adoption, OS versions
+//!
+//! Every function takes any iterator of phones (a `Dataset`, a slice, a query result)
+//! and reads the stored `normalized` values, so run `migrate` on older collections first.
+
+use crate::mongodb::PhoneDocument;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Summary of one numeric spec for the phones announced in a year
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YearStat {
+    pub year: i32,
+    pub phones: usize, // Phones with a value
+    pub average: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// How many of a brand's phones have a feature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrandShare {
+    pub brand: String,
+    pub phones: usize, // Phones where the feature is known
+    pub matching: usize,
+    pub share: f64, // 0-1
+}
+
+/// One row of a categorical breakdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Breakdown {
+    pub label: String,
+    pub phones: usize,
+    pub share: f64, // 0-1 of the phones with a value
+}
+
+/// All reports at once, as written by `analytics --format json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusStats {
+    pub phones: usize,
+    pub battery_by_year: Vec<YearStat>,
+    pub display_size_by_year: Vec<YearStat>,
+    pub five_g_by_brand: Vec<BrandShare>,
+    pub os_versions: Vec<Breakdown>,
+}
+
+impl CorpusStats {
+    pub fn compute<'a>(phones: impl IntoIterator<Item = &'a PhoneDocument>) -> Self {
+        let phones: Vec<&PhoneDocument> = phones.into_iter().collect();
+        Self {
+            phones: phones.len(),
+            battery_by_year: battery_by_year(phones.iter().copied()),
+            display_size_by_year: display_size_by_year(phones.iter().copied()),
+            five_g_by_brand: five_g_share_by_brand(phones.iter().copied()),
+            os_versions: os_breakdown(phones.iter().copied()),
+        }
+    }
+}
+
+/// Battery capacity (mAh) per announcement year
+pub fn battery_by_year<'a>(phones: impl IntoIterator<Item = &'a PhoneDocument>) -> Vec<YearStat> {
+    by_year(phones, |phone| phone.normalized.battery_mah.map(f64::from))
+}
+
+/// Display size (inches) per announcement year
+pub fn display_size_by_year<'a>(phones: impl IntoIterator<Item = &'a PhoneDocument>) -> Vec<YearStat> {
+    by_year(phones, |phone| phone.normalized.display_size_in)
+}
+
+fn by_year<'a>(phones: impl IntoIterator<Item = &'a PhoneDocument>, value: impl Fn(&PhoneDocument) -> Option<f64>) -> Vec<YearStat> {
+    let mut years: BTreeMap<i32, Vec<f64>> = BTreeMap::new();
+    for phone in phones {
+        if let (Some(year), Some(value)) = (phone.normalized.announced_year, value(phone)) {
+            years.entry(year).or_default().push(value);
+        }
+    }
+
+    years
+        .into_iter()
+        .map(|(year, mut values)| {
+            values.sort_by(f64::total_cmp);
+            let middle = values.len() / 2;
+            let median = if values.len() % 2 == 0 { (values[middle - 1] + values[middle]) / 2.0 } else { values[middle] };
+            YearStat {
+                year,
+                phones: values.len(),
+                average: round2(values.iter().sum::<f64>() / values.len() as f64),
+                median: round2(median),
+                min: values[0],
+                max: values[values.len() - 1],
+            }
+        })
+        .collect()
+}
+
+/// Share of each brand's phones with 5G; phones without network data are left out
+pub fn five_g_share_by_brand<'a>(phones: impl IntoIterator<Item = &'a PhoneDocument>) -> Vec<BrandShare> {
+    let mut brands: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for phone in phones {
+        if let Some(has_5g) = phone.normalized.has_5g {
+            let (known, matching) = brands.entry(phone.brand.clone()).or_default();
+            *known += 1;
+            *matching += usize::from(has_5g);
+        }
+    }
+
+    brands
+        .into_iter()
+        .map(|(brand, (phones, matching))| BrandShare { brand, phones, matching, share: round2(matching as f64 / phones as f64) })
+        .collect()
+}
+
+/// Phones per OS version ("Android 14", "iOS 17"), most common first
+pub fn os_breakdown<'a>(phones: impl IntoIterator<Item = &'a PhoneDocument>) -> Vec<Breakdown> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for os in phones.into_iter().filter_map(|phone| os_version(phone.platform.as_ref()?.os.as_deref()?)) {
+        *counts.entry(os).or_default() += 1;
+    }
+
+    let total: usize = counts.values().sum();
+    let mut rows: Vec<Breakdown> = counts
+        .into_iter()
+        .map(|(label, phones)| Breakdown { label, phones, share: round2(phones as f64 / total as f64) })
+        .collect();
+    rows.sort_by(|a, b| b.phones.cmp(&a.phones).then_with(|| a.label.cmp(&b.label)));
+    rows
+}
+
+static LAUNCH_VERSION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(.*?)\s*(\d+)(?:\.(\d+))?").unwrap());
+
+/// OS and launch version: "Android 14, up to 4 major upgrades, One UI 6.1" -> "Android 14",
+/// "Android 4.4.2 (KitKat)" -> "Android 4.4", "iOS 17.0" -> "iOS 17"
+pub fn os_version(os: &str) -> Option<String> {
+    let launch = os.split(',').next()?.trim();
+    if launch.is_empty() {
+        return None;
+    }
+
+    let captures = LAUNCH_VERSION.captures(launch);
+    match captures {
+        Some(c) if !c[1].is_empty() => match c.get(3).map(|m| m.as_str()) {
+            Some(minor) if minor != "0" => Some(format!("{} {}.{}", &c[1], &c[2], minor)),
+            _ => Some(format!("{} {}", &c[1], &c[2])),
+        },
+        _ => Some(launch.to_string()),
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// One report of `CorpusStats`, for CSV output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsReport {
+    BatteryByYear,
+    DisplaySizeByYear,
+    FiveGByBrand,
+    OsVersions,
+}
+
+impl StatsReport {
+    pub const ALL: [StatsReport; 4] =
+        [StatsReport::BatteryByYear, StatsReport::DisplaySizeByYear, StatsReport::FiveGByBrand, StatsReport::OsVersions];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            StatsReport::BatteryByYear => "battery-by-year",
+            StatsReport::DisplaySizeByYear => "display-by-year",
+            StatsReport::FiveGByBrand => "5g-by-brand",
+            StatsReport::OsVersions => "os-versions",
+        }
+    }
+
+    /// The report as CSV with a header row
+    pub fn to_csv(&self, stats: &CorpusStats) -> String {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        match self {
+            StatsReport::BatteryByYear | StatsReport::DisplaySizeByYear => {
+                rows.push(["year", "phones", "average", "median", "min", "max"].map(String::from).to_vec());
+                let years = if *self == StatsReport::BatteryByYear { &stats.battery_by_year } else { &stats.display_size_by_year };
+                for y in years {
+                    rows.push(vec![y.year.to_string(), y.phones.to_string(), y.average.to_string(), y.median.to_string(), y.min.to_string(), y.max.to_string()]);
+                }
+            }
+            StatsReport::FiveGByBrand => {
+                rows.push(["brand", "phones", "with_5g", "share"].map(String::from).to_vec());
+                for b in &stats.five_g_by_brand {
+                    rows.push(vec![b.brand.clone(), b.phones.to_string(), b.matching.to_string(), b.share.to_string()]);
+                }
+            }
+            StatsReport::OsVersions => {
+                rows.push(["os", "phones", "share"].map(String::from).to_vec());
+                for o in &stats.os_versions {
+                    rows.push(vec![o.label.clone(), o.phones.to_string(), o.share.to_string()]);
+                }
+            }
+        }
+
+        rows.iter()
+            .map(|row| row.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>().join(",") + "\n")
+            .collect()
+    }
+}
+
+impl FromStr for StatsReport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StatsReport::ALL.into_iter().find(|report| report.key() == s.trim().to_lowercase()).ok_or_else(|| {
+            let keys: Vec<&str> = StatsReport::ALL.iter().map(StatsReport::key).collect();
+            format!("unknown report '{}' (expected one of: {})", s, keys.join(", "))
+        })
+    }
+}
+
+impl fmt::Display for StatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(id: &str, brand: &str, announced: &str, battery: &str, technology: &str, os: &str) -> PhoneDocument {
+        PhoneDocument::fixture(id)
+            .brand(brand)
+            .category("Network", &[("Technology", technology)])
+            .category("Launch", &[("Announced", announced)])
+            .category("Platform", &[("OS", os)])
+            .category("Battery", &[("Type", battery)])
+            .build()
+    }
+
+    #[test]
+    fn test_corpus_stats() {
+        let phones = [
+            phone("a-1", "Samsung", "2023, January 17", "Li-Ion 5000 mAh", "GSM / HSPA / LTE / 5G", "Android 13, up to 4 major Android upgrades, One UI 5.1"),
+            phone("b-2", "Samsung", "2023, March 02", "Li-Ion 4000 mAh", "GSM / HSPA / LTE", "Android 13, One UI 5.1"),
+            phone("c-3", "Samsung", "2023, May 11", "Li-Ion 4500 mAh", "GSM / HSPA / LTE / 5G", "Android 4.4.2 (KitKat)"),
+            phone("d-4", "Apple", "2024, September 09", "Li-Ion 3561 mAh", "GSM / CDMA / HSPA / EVDO / LTE / 5G", "iOS 18.0, upgradable to iOS 18.1"),
+        ];
+        let stats = CorpusStats::compute(&phones);
+
+        assert_eq!(stats.battery_by_year[0], YearStat { year: 2023, phones: 3, average: 4500.0, median: 4500.0, min: 4000.0, max: 5000.0 });
+        assert_eq!(stats.battery_by_year[1].year, 2024);
+        assert_eq!(stats.five_g_by_brand[1], BrandShare { brand: "Samsung".to_string(), phones: 3, matching: 2, share: 0.67 });
+        assert_eq!(stats.os_versions[0].label, "Android 13");
+        assert_eq!(stats.os_versions[0].phones, 2);
+        assert_eq!(os_version("Android 4.4.2 (KitKat)").as_deref(), Some("Android 4.4"));
+        assert_eq!(os_version("iOS 18.0, upgradable to iOS 18.1").as_deref(), Some("iOS 18"));
+        assert_eq!(os_version("Feature phone").as_deref(), Some("Feature phone"));
+
+        let csv = StatsReport::FiveGByBrand.to_csv(&stats);
+        assert_eq!(csv, "brand,phones,with_5g,share\nApple,1,1,1\nSamsung,3,2,0.67\n");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!("os-versions".parse::<StatsReport>(), Ok(StatsReport::OsVersions));
+    }
+}
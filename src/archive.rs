@@ -0,0 +1,151 @@
+//! Zip and tar.gz archives of exported data (see `export --format zip`)
+
+use crate::analytics::csv_field;
+use crate::mongodb::PhoneDocument;
+use crate::normalize::NormalizedSpecs;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// Streams files into an archive on disk; call `finish` to complete it
+pub enum ArchiveWriter {
+    Zip(ZipWriter<File>),
+    TarGz(tar::Builder<GzEncoder<File>>),
+}
+
+impl ArchiveWriter {
+    pub fn create(path: &Path, format: ArchiveFormat) -> Result<Self, Box<dyn Error>> {
+        let file = File::create(path)?;
+        Ok(match format {
+            ArchiveFormat::Zip => ArchiveWriter::Zip(ZipWriter::new(file)),
+            ArchiveFormat::TarGz => ArchiveWriter::TarGz(tar::Builder::new(GzEncoder::new(file, Compression::default()))),
+        })
+    }
+
+    /// Add a file at `path` ("samsung/phones/samsung_galaxy_s24-12773.json")
+    pub fn add(&mut self, path: &str, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        match self {
+            ArchiveWriter::Zip(zip) => {
+                zip.start_file(path, SimpleFileOptions::default())?;
+                zip.write_all(bytes)?;
+            }
+            ArchiveWriter::TarGz(tar) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(bytes.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+                header.set_cksum();
+                tar.append_data(&mut header, path, bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        match self {
+            ArchiveWriter::Zip(zip) => {
+                zip.finish()?;
+            }
+            ArchiveWriter::TarGz(tar) => {
+                tar.into_inner()?.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One row per phone: phone_id, name, then every `normalized` field (alphabetically)
+pub fn normalized_csv(phones: &[PhoneDocument]) -> Result<String, Box<dyn Error>> {
+    let mut columns: Vec<String> = match serde_json::to_value(NormalizedSpecs::default())? {
+        serde_json::Value::Object(fields) => fields.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+    columns.sort();
+
+    let mut csv = format!("phone_id,name,{}\n", columns.join(","));
+    for phone in phones {
+        let fields = serde_json::to_value(&phone.normalized)?;
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| match fields.get(column) {
+                None | Some(serde_json::Value::Null) => String::new(),
+                Some(serde_json::Value::String(text)) => csv_field(text),
+                Some(serde_json::Value::Array(values)) => {
+                    let values: Vec<String> = values.iter().map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string)).collect();
+                    csv_field(&values.join("; "))
+                }
+                Some(value) => value.to_string(),
+            })
+            .collect();
+        csv.push_str(&format!("{},{},{}\n", csv_field(&phone.phone_id), csv_field(&phone.name), cells.join(",")));
+    }
+    Ok(csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_write_archives() {
+        let nokia = PhoneDocument::fixture("nokia_3310-1")
+            .name("Nokia 3310, \"2017\"")
+            .brand("Nokia")
+            .category("Battery", &[("Type", "Li-Ion 1200 mAh")])
+            .build();
+        let csv = normalized_csv(&[nokia]).unwrap();
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header[..3], ["phone_id", "name", "announced_year"]);
+        let battery = header.iter().position(|column| *column == "battery_mah").unwrap();
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("nokia_3310-1,\"Nokia 3310, \"\"2017\"\"\","));
+        // The quoted name holds one comma
+        assert_eq!(row.split(',').nth(battery + 1), Some("1200"));
+
+        let dir = std::env::temp_dir();
+        for format in [ArchiveFormat::Zip, ArchiveFormat::TarGz] {
+            let path = dir.join(format!("gsmarena-archive-{}.{}", std::process::id(), format.extension()));
+            let mut writer = ArchiveWriter::create(&path, format).unwrap();
+            writer.add("nokia/normalized.csv", csv.as_bytes()).unwrap();
+            writer.finish().unwrap();
+
+            let mut contents = String::new();
+            match format {
+                ArchiveFormat::Zip => {
+                    let mut zip = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+                    zip.by_name("nokia/normalized.csv").unwrap().read_to_string(&mut contents).unwrap();
+                }
+                ArchiveFormat::TarGz => {
+                    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(File::open(&path).unwrap()));
+                    let mut entry = tar.entries().unwrap().next().unwrap().unwrap();
+                    assert_eq!(entry.path().unwrap().to_str(), Some("nokia/normalized.csv"));
+                    entry.read_to_string(&mut contents).unwrap();
+                }
+            }
+            std::fs::remove_file(&path).ok();
+            assert_eq!(contents, csv);
+        }
+    }
+}
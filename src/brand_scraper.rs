@@ -1,7 +1,21 @@
+use crate::device_type::DeviceType;
+use crate::rate_limiter::RateLimiter;
+use crate::site::{page_url, DEFAULT_BASE_URL};
 use reqwest::blocking;
-use scraper::{Html, Selector};
+use regex::Regex;
+use crate::selectors::SelectorChain;
+use scraper::Html;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tracing::{error, info};
+
+static BRAND_SLUG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"([\w.\-]+-phones-\d+)\.php").unwrap());
+static TRAILING_DEVICE_COUNT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^(.*?)\s*(\d[\d,]*)\s+devices?$").unwrap());
+static DEVICE_COUNT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(\d[\d,]*)\s*devices?").unwrap());
+static YEAR_RANGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"nYearMin=(\d{4}).*nYearMax=(\d{4})|nYearMax=(\d{4}).*nYearMin=(\d{4})").unwrap());
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Brand {
@@ -10,64 +24,182 @@ pub struct Brand {
     pub device_count: u32,
 }
 
+impl Brand {
+    /// Phones missing from a listing of `found` against `device_count` (capped at `max_phones`),
+    /// when more than `tolerance` of the expected phones are missing (silent block pages)
+    pub fn listing_shortfall(&self, found: usize, max_phones: usize, tolerance: f64) -> Option<usize> {
+        let expected = (self.device_count as usize).min(max_phones);
+        let missing = expected.saturating_sub(found);
+        (missing > 0 && missing as f64 > expected as f64 * tolerance).then_some(missing)
+    }
+
+    /// GSMArena's numeric maker ID, the end of the slug ("apple-phones-48" -> 48)
+    pub fn maker_id(&self) -> Option<u32> {
+        self.slug.rsplit_once('-')?.1.parse().ok()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrandDetails {
+    pub slug: String,
+    pub name: Option<String>,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+}
+
+/// Devices a brand announced in one year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct YearCount {
+    pub year: i32,
+    pub devices: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhoneListItem {
     pub name: String,
     pub url: String,
     pub phone_id: String,
     pub image_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<DeviceType>, // Guessed from the name; None when it doesn't say
 }
 
 /// Fetch all brands from GSMArena
 pub fn fetch_all_brands() -> Result<Vec<Brand>, Box<dyn Error>> {
-    let url = "https://www.gsmarena.com/makers.php3";
-    let response = blocking::get(url)?;
+    fetch_all_brands_from(DEFAULT_BASE_URL)
+}
+
+/// Fetch all brands from a mirror or test server
+pub fn fetch_all_brands_from(base_url: &str) -> Result<Vec<Brand>, Box<dyn Error>> {
+    let response = blocking::get(page_url(base_url, "makers.php3"))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Makers page returned status: {}", response.status()).into());
+    }
+
+    Ok(parse_brands_page(&response.text()?))
+}
+
+/// Parse the makers page into brands
+///
+/// Each brand is `<a href="apple-phones-48.php">Apple<br><span>123 devices</span></a>`:
+/// the name is the link's own text and the count comes from the `<span>`, so names with
+/// numbers ("10.or", "Plum 4") and counts like "1,386 devices" survive. Links without a
+/// span ("Apple 123 devices") are split on the trailing count; the `brand_links` fallback
+/// is the brand menu other pages carry (names only).
+pub fn parse_brands_page(html: &str) -> Vec<Brand> {
+    let document = Html::parse_document(html);
+    let links = SelectorChain::get("brand_links");
+    let counts = SelectorChain::get("brand_count");
+
+    let mut brands: Vec<Brand> = Vec::new();
+    for element in links.select_in(&document) {
+        // Only brand pages, whatever the prefix: "apple-phones-48.php", "/apple-phones-48.php?sSort=1"
+        let Some(slug) = element.value().attr("href").and_then(|href| Some(BRAND_SLUG.captures(href)?[1].to_string())) else {
+            continue;
+        };
+        if brands.iter().any(|brand| brand.slug == slug) {
+            continue;
+        }
+
+        let own_text: Vec<&str> = element.children().filter_map(|node| node.value().as_text()).map(|text| text.trim()).collect();
+        let mut name = own_text.into_iter().filter(|text| !text.is_empty()).collect::<Vec<_>>().join(" ");
+        let mut device_count = counts.first(element).and_then(|span| device_count(&span.text().collect::<String>()));
+
+        if device_count.is_none() {
+            let full_text = element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+            if let Some(caps) = TRAILING_DEVICE_COUNT.captures(&full_text) {
+                name = caps[1].to_string();
+                device_count = device_count_number(&caps[2]);
+            } else if name.is_empty() {
+                name = full_text;
+            }
+        }
+        if name.is_empty() {
+            continue;
+        }
+
+        brands.push(Brand { name, slug, device_count: device_count.unwrap_or(0) });
+    }
+
+    brands
+}
+
+/// "1,386 devices" or "1 device" -> the number
+fn device_count(text: &str) -> Option<u32> {
+    let caps = DEVICE_COUNT.captures(text)?;
+    device_count_number(&caps[1])
+}
+
+fn device_count_number(number: &str) -> Option<u32> {
+    number.replace(',', "").parse().ok()
+}
+
+/// Fetch brand metadata (logo, description, official website) from the brand page header
+pub fn fetch_brand_details(brand_slug: &str) -> Result<BrandDetails, Box<dyn Error>> {
+    fetch_brand_details_from(DEFAULT_BASE_URL, brand_slug)
+}
+
+/// Fetch brand metadata from a mirror or test server
+pub fn fetch_brand_details_from(base_url: &str, brand_slug: &str) -> Result<BrandDetails, Box<dyn Error>> {
+    let response = blocking::get(brand_page_url_from(base_url, brand_slug, 1))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Brand page returned status: {}", response.status()).into());
+    }
+
     let body = response.text()?;
-    let document = Html::parse_document(&body);
+    let mut details = parse_brand_details(&body);
+    details.slug = brand_slug.to_string();
 
-    let mut brands = Vec::new();
-    
-    // Select brand links
-    let brand_selector = Selector::parse("div.st-text table td a").unwrap();
-    
-    for element in document.select(&brand_selector) {
-        if let Some(href) = element.value().attr("href") {
-            // Get the full text (e.g., "Apple 123 devices")
-            let full_text = element.text().collect::<Vec<_>>().join(" ").trim().to_string();
-            
-            // Try to extract device count and name
-            let parts: Vec<&str> = full_text.split_whitespace().collect();
-            
-            let (brand_name, device_count) = if parts.len() >= 2 {
-                // Check if second-to-last word is a number
-                if let Some(count_str) = parts.iter().rev().nth(1) {
-                    if let Ok(count) = count_str.parse::<u32>() {
-                        // Everything before the count is the brand name
-                        let name = parts[..parts.len() - 2].join(" ");
-                        (name, count)
-                    } else {
-                        // No device count found, use full text as name
-                        (full_text.clone(), 0)
-                    }
-                } else {
-                    (full_text.clone(), 0)
-                }
+    Ok(details)
+}
+
+/// Parse the header of a brand page into `BrandDetails`
+pub fn parse_brand_details(html: &str) -> BrandDetails {
+    let document = Html::parse_document(html);
+
+    let name = SelectorChain::get("brand_name")
+        .first_in(&document)
+        .map(|e| e.text().collect::<String>().trim().trim_end_matches(" phones").to_string())
+        .filter(|n| !n.is_empty());
+
+    let logo_url = SelectorChain::get("brand_logo")
+        .first_in(&document)
+        .and_then(|img| img.value().attr("src"))
+        .map(|src| {
+            if src.starts_with("http") {
+                src.to_string()
             } else {
-                (full_text.clone(), 0)
-            };
-            
-            // Extract slug from href (e.g., "apple-phones-48.php" -> "apple-phones-48")
-            let slug = href.trim_end_matches(".php").to_string();
-            
-            brands.push(Brand {
-                name: brand_name,
-                slug,
-                device_count,
-            });
-        }
+                page_url(DEFAULT_BASE_URL, src)
+            }
+        });
+
+    let description = SelectorChain::get("brand_description")
+        .select_in(&document)
+        .into_iter()
+        .map(|p| p.text().collect::<String>().trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    // The official site is the first external (non-GSMArena) link in the header
+    let website = SelectorChain::get("brand_website")
+        .select_in(&document)
+        .into_iter()
+        .filter_map(|a| a.value().attr("href"))
+        .find(|href| !href.contains("gsmarena.com"))
+        .map(|href| href.to_string());
+
+    BrandDetails {
+        slug: String::new(),
+        name,
+        logo_url,
+        description: if description.is_empty() { None } else { Some(description) },
+        website,
     }
-    
-    Ok(brands)
 }
 
 /// Fetch all phones for a specific brand
@@ -76,111 +208,349 @@ pub fn fetch_phones_by_brand(brand_slug: &str) -> Result<Vec<PhoneListItem>, Box
 }
 
 /// Fetch phones for a specific brand with pagination support and max limit
+/// Use `phone_pages` to work on each page as it arrives instead
 pub fn fetch_phones_by_brand_paginated(brand_slug: &str, max_phones: usize) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
-    let mut all_phones = Vec::new();
-    let mut page = 1; // Start with page 1
-    
-    loop {
-        if all_phones.len() >= max_phones {
-            break;
+    fetch_phones_by_brand_from(DEFAULT_BASE_URL, brand_slug, max_phones)
+}
+
+/// Fetch up to `max_phones` of a brand's phones from a mirror or test server
+pub fn fetch_phones_by_brand_from(base_url: &str, brand_slug: &str, max_phones: usize) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
+    fetch_phones_by_brand_with(base_url, brand_slug, max_phones, fetch_listing_page)
+}
+
+/// A brand's phones from `start_page` on, to resume a listing that failed part-way
+pub fn fetch_phones_by_brand_from_page(brand_slug: &str, start_page: usize) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
+    let pages = phone_pages(brand_slug, usize::MAX).starting_at(start_page);
+    Ok(pages.collect::<Result<Vec<_>, _>>()?.concat())
+}
+
+/// Devices per announce year, from the year-filtered listings linked on a brand page
+/// Empty when the page links no years
+pub fn fetch_brand_year_breakdown(brand_slug: &str) -> Result<Vec<YearCount>, Box<dyn Error>> {
+    fetch_brand_year_breakdown_from(DEFAULT_BASE_URL, brand_slug)
+}
+
+/// Devices per announce year, fetched from a mirror or test server
+pub fn fetch_brand_year_breakdown_from(base_url: &str, brand_slug: &str) -> Result<Vec<YearCount>, Box<dyn Error>> {
+    fetch_brand_year_breakdown_with(base_url, brand_slug, fetch_listing_page)
+}
+
+/// Devices per announce year, using a custom page fetcher (proxy, ScrapingBee, ...)
+/// Any failed year listing fails the whole breakdown, so a partial one is never stored
+pub fn fetch_brand_year_breakdown_with<F>(base_url: &str, brand_slug: &str, mut fetch: F) -> Result<Vec<YearCount>, Box<dyn Error>>
+where
+    F: FnMut(&str) -> Result<String, Box<dyn Error>>,
+{
+    let brand_page = fetch(&brand_page_url_from(base_url, brand_slug, 1))?;
+    let mut counts = Vec::new();
+
+    for (year, href) in parse_brand_year_links(&brand_page) {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let url = if href.starts_with("http") { href } else { page_url(base_url, &href) };
+        let listing = fetch(&url).map_err(|e| format!("{} {} listing: {}", brand_slug, year, e))?;
+        counts.push(YearCount {
+            year,
+            devices: parse_phone_list_page(&listing).len() as u32,
+        });
+    }
+    Ok(counts)
+}
+
+/// Links to listings filtered to a single announce year (`nYearMin=2023&nYearMax=2023`), oldest first
+pub fn parse_brand_year_links(html: &str) -> Vec<(i32, String)> {
+    let document = Html::parse_document(html);
+
+    let mut links: Vec<(i32, String)> = SelectorChain::get("brand_year_links")
+        .select_in(&document)
+        .into_iter()
+        .filter_map(|a| {
+            let href = a.value().attr("href")?.replace("&amp;", "&");
+            let caps = YEAR_RANGE.captures(&href)?;
+            let (min, max) = match (caps.get(1), caps.get(2)) {
+                (Some(min), Some(max)) => (min.as_str(), max.as_str()),
+                _ => (caps.get(4)?.as_str(), caps.get(3)?.as_str()),
+            };
+            (min == max).then(|| Some((min.parse().ok()?, href.clone()))).flatten()
+        })
+        .collect();
+    links.sort();
+    links.dedup_by_key(|(year, _)| *year);
+    links
+}
+
+/// A brand's listing, one page per iteration, fetched directly
+pub fn phone_pages(brand_slug: &str, max_phones: usize) -> PhonePages<ListingFetch> {
+    phone_pages_from(DEFAULT_BASE_URL, brand_slug, max_phones)
+}
+
+/// A brand's listing on a mirror or test server, one page per iteration
+pub fn phone_pages_from(base_url: &str, brand_slug: &str, max_phones: usize) -> PhonePages<ListingFetch> {
+    PhonePages::new(base_url, brand_slug, max_phones, fetch_listing_page)
+}
+
+/// Page fetcher used by the direct listing functions
+pub type ListingFetch = fn(&str) -> Result<String, Box<dyn Error>>;
+
+fn fetch_listing_page(url: &str) -> Result<String, Box<dyn Error>> {
+    let response = blocking::get(url)?;
+    if response.status() != 200 {
+        return Err(format!("Brand page returned status: {}", response.status()).into());
+    }
+    Ok(response.text()?)
+}
+
+/// A brand listing, fetched one page at a time as it is iterated
+///
+/// Each item is one page of phones, capped at `max_phones` in total, so stopping
+/// early saves the remaining requests. The page count comes from the pagination
+/// widget on the first page fetched, so the listing ends without probing past the last
+/// page, and a failed or empty page before it comes out as an error instead of a short
+/// listing. After an error `next_page` is the page that failed, to resume from with `starting_at`.
+pub struct PhonePages<F> {
+    base_url: String,
+    brand_slug: String,
+    max_phones: usize,
+    page: usize,
+    total_pages: Option<usize>, // Known once the first page is fetched
+    phones_yielded: usize,
+    finished: bool,
+    fetch: F,
+}
+
+impl<F> PhonePages<F>
+where
+    F: FnMut(&str) -> Result<String, Box<dyn Error>>,
+{
+    pub fn new(base_url: &str, brand_slug: &str, max_phones: usize, fetch: F) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            brand_slug: brand_slug.to_string(),
+            max_phones,
+            page: 1,
+            total_pages: None,
+            phones_yielded: 0,
+            finished: false,
+            fetch,
         }
-        
-        // GSMArena pagination format:
-        // Page 1: brand-phones-48.php
-        // Page 2: brand-phones-48-p2.php  
-        // Page 3: brand-phones-48-p3.php
-        let url = if page == 1 {
-            format!("https://www.gsmarena.com/{}.php", brand_slug)
-        } else {
-            format!("https://www.gsmarena.com/{}-p{}.php", brand_slug, page)
-        };
-        
+    }
+
+    /// Start at a later page instead of page 1
+    pub fn starting_at(mut self, page: usize) -> Self {
+        self.page = page.max(1);
+        self
+    }
+
+    /// Number of the page the next iteration fetches
+    pub fn next_page(&self) -> usize {
+        self.page
+    }
+
+    /// Pages in the listing, from the pagination widget of the first page fetched
+    pub fn total_pages(&self) -> Option<usize> {
+        self.total_pages
+    }
+}
+
+impl<F> PhonePages<F> {
+    fn page_error(&self, reason: &str) -> Box<dyn Error> {
+        let total = self.total_pages.map_or_else(|| "?".to_string(), |total| total.to_string());
+        format!("{} page {} of {}: {}", self.brand_slug, self.page, total, reason).into()
+    }
+}
+
+impl<F> Iterator for PhonePages<F>
+where
+    F: FnMut(&str) -> Result<String, Box<dyn Error>>,
+{
+    type Item = Result<Vec<PhoneListItem>, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished || self.phones_yielded >= self.max_phones || self.total_pages.is_some_and(|total| self.page > total) {
+            return None;
+        }
+
         // Add delay before request to avoid rate limiting
-        if page > 1 {
+        if self.total_pages.is_some() {
             std::thread::sleep(std::time::Duration::from_millis(200));
         }
-        
-        let response = match blocking::get(&url) {
-            Ok(r) => r,
-            Err(_) => break,
+
+        let body = match (self.fetch)(&brand_page_url_from(&self.base_url, &self.brand_slug, self.page)) {
+            Ok(body) => body,
+            Err(e) if self.page == 1 => {
+                self.finished = true;
+                return Some(Err(e));
+            }
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(self.page_error(&e.to_string())));
+            }
         };
-        
-        if response.status() != 200 {
-            break;
+
+        if self.total_pages.is_none() {
+            self.total_pages = Some(parse_page_count(&body));
         }
-        
-        let body = match response.text() {
-            Ok(b) => b,
-            Err(_) => break,
-        };
-        
-        let document = Html::parse_document(&body);
-        
-        let phone_selector = Selector::parse("div.makers ul li a").unwrap();
-        let img_selector = Selector::parse("img").unwrap();
-        
-        let page_start_count = all_phones.len();
-        
-        for element in document.select(&phone_selector) {
-            if all_phones.len() >= max_phones {
-                break;
-            }
-            
-            if let Some(href) = element.value().attr("href") {
-                let name = element.text().collect::<String>().trim().to_string();
-                let url = format!("https://www.gsmarena.com/{}", href);
-                
-                // Extract phone ID from URL (e.g., "apple_iphone_15-12559.php" -> "apple_iphone_15-12559")
-                let phone_id = href.trim_end_matches(".php").to_string();
-                
-                // Try to get image URL
-                let image_url = element
-                    .select(&img_selector)
-                    .next()
-                    .and_then(|img| img.value().attr("src"))
-                    .map(|src| {
-                        if src.starts_with("http") {
-                            src.to_string()
-                        } else {
-                            format!("https://www.gsmarena.com/{}", src)
-                        }
-                    });
-                
-                all_phones.push(PhoneListItem {
-                    name,
-                    url,
-                    phone_id,
-                    image_url,
+
+        let mut phones = parse_phone_list_page(&body);
+        phones.truncate(self.max_phones - self.phones_yielded);
+        if phones.is_empty() {
+            self.finished = true;
+            // An empty first page is a brand without phones; an empty later page is most likely a block page
+            return (self.page > 1).then(|| Err(self.page_error("no phones on the page (blocked?)")));
+        }
+
+        self.phones_yielded += phones.len();
+        self.page += 1;
+        Some(Ok(phones))
+    }
+}
+
+/// Fetch the phone lists of many brands at once, with at most `parallelism` in flight
+///
+/// Every page request waits for a slot from `limiter`, so adding workers overlaps
+/// the waiting on responses without raising the request rate.
+/// Results are in the order of `brand_slugs`.
+pub fn fetch_brand_listings_concurrently<F>(
+    base_url: &str,
+    brand_slugs: &[String],
+    max_phones: usize,
+    parallelism: usize,
+    limiter: &RateLimiter,
+    fetch: F,
+) -> Vec<Result<Vec<PhoneListItem>, String>>
+where
+    F: Fn(&str) -> Result<String, Box<dyn Error>> + Sync,
+{
+    let next_brand = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; brand_slugs.len()]);
+
+    std::thread::scope(|scope| {
+        for _ in 0..parallelism.clamp(1, brand_slugs.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next_brand.fetch_add(1, Ordering::Relaxed);
+                let Some(brand_slug) = brand_slugs.get(index) else {
+                    break;
+                };
+
+                let phones = fetch_phones_by_brand_with(base_url, brand_slug, max_phones, |url| {
+                    limiter.wait();
+                    fetch(url)
                 });
-            }
+                results.lock().unwrap()[index] = Some(phones.map_err(|e| e.to_string()));
+            });
         }
-        
-        // If no new phones found on this page, we've reached the end
-        if all_phones.len() == page_start_count {
-            break;
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Err("not fetched".to_string())))
+        .collect()
+}
+
+/// URL of a brand listing page
+/// GSMArena pagination format:
+/// Page 1: brand-phones-48.php
+/// Page 2: brand-phones-48-p2.php
+/// Page 3: brand-phones-48-p3.php
+pub fn brand_page_url(brand_slug: &str, page: usize) -> String {
+    brand_page_url_from(DEFAULT_BASE_URL, brand_slug, page)
+}
+
+/// URL of a brand listing page on a mirror or test server
+pub fn brand_page_url_from(base_url: &str, brand_slug: &str, page: usize) -> String {
+    if page == 1 {
+        page_url(base_url, &format!("{}.php", brand_slug))
+    } else {
+        page_url(base_url, &format!("{}-p{}.php", brand_slug, page))
+    }
+}
+
+/// Paginate a brand listing using a custom page fetcher (proxy, ScrapingBee, ...)
+/// Fails if any page up to the count in the pagination widget fails
+pub fn fetch_phones_by_brand_with<F>(
+    base_url: &str,
+    brand_slug: &str,
+    max_phones: usize,
+    fetch: F,
+) -> Result<Vec<PhoneListItem>, Box<dyn Error>>
+where
+    F: FnMut(&str) -> Result<String, Box<dyn Error>>,
+{
+    let pages = PhonePages::new(base_url, brand_slug, max_phones, fetch).collect::<Result<Vec<_>, _>>()?;
+    Ok(pages.concat())
+}
+
+/// A brand together with its phone list
+pub type BrandPhones = (Brand, Vec<PhoneListItem>);
+
+/// Number of pages in a brand listing, from the "1 2 3 ..." widget (1 without one)
+pub fn parse_page_count(html: &str) -> usize {
+    let document = Html::parse_document(html);
+    SelectorChain::get("listing_pages")
+        .select_in(&document)
+        .into_iter()
+        .filter_map(|element| element.text().collect::<String>().trim().parse::<usize>().ok())
+        .max()
+        .unwrap_or(1)
+}
+
+/// Parse a phone listing page (brand page or Phone Finder results) into list items
+pub fn parse_phone_list_page(html: &str) -> Vec<PhoneListItem> {
+    let document = Html::parse_document(html);
+    
+    let images = SelectorChain::get("phone_image");
+    
+    let mut phones = Vec::new();
+    
+    for element in SelectorChain::get("phone_links").select_in(&document) {
+        if let Some(href) = element.value().attr("href") {
+            let name = element.text().collect::<String>().trim().to_string();
+            let url = page_url(DEFAULT_BASE_URL, href);
+            
+            // Extract phone ID from URL (e.g., "apple_iphone_15-12559.php" -> "apple_iphone_15-12559")
+            let phone_id = href.trim_end_matches(".php").to_string();
+            
+            // Try to get image URL
+            let image_url = images
+                .first(element)
+                .and_then(|img| img.value().attr("src"))
+                .map(|src| {
+                    if src.starts_with("http") {
+                        src.to_string()
+                    } else {
+                        page_url(DEFAULT_BASE_URL, src)
+                    }
+                });
+            
+            phones.push(PhoneListItem {
+                device_type: DeviceType::from_name(&name),
+                name,
+                url,
+                phone_id,
+                image_url,
+            });
         }
-        
-        page += 1;
     }
     
-    Ok(all_phones)
+    phones
 }
 
 /// Fetch all phones from all brands
-pub fn fetch_all_phones() -> Result<Vec<(Brand, Vec<PhoneListItem>)>, Box<dyn Error>> {
+pub fn fetch_all_phones() -> Result<Vec<BrandPhones>, Box<dyn Error>> {
     let brands = fetch_all_brands()?;
     let mut all_data = Vec::new();
     
     for brand in brands {
-        println!("Fetching phones for: {} ({} devices)", brand.name, brand.device_count);
+        info!(brand = %brand.name, devices = brand.device_count, "Fetching phones");
         
         match fetch_phones_by_brand(&brand.slug) {
             Ok(phones) => {
-                println!("  ✓ Found {} phones", phones.len());
+                info!(brand = %brand.name, count = phones.len(), "✓ Found phones");
                 all_data.push((brand, phones));
             }
             Err(e) => {
-                eprintln!("  ✗ Error: {}", e);
+                error!(brand = %brand.name, error = %e, "✗ Failed to fetch phones");
             }
         }
         
@@ -190,3 +560,108 @@ pub fn fetch_all_phones() -> Result<Vec<(Brand, Vec<PhoneListItem>)>, Box<dyn Er
     
     Ok(all_data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_brand_details() {
+        let html = r#"
+            <div class="article-info">
+                <h1 class="article-info-name">Samsung phones</h1>
+                <img src="https://fdn2.gsmarena.com/vv/logos/samsung.png">
+                <div class="st-text"><p>South Korean electronics maker.</p></div>
+                <a href="https://www.gsmarena.com/news.php3">News</a>
+                <a href="https://www.samsung.com/">Official site</a>
+            </div>
+        "#;
+
+        let details = parse_brand_details(html);
+        assert_eq!(details.name.as_deref(), Some("Samsung"));
+        assert_eq!(details.logo_url.as_deref(), Some("https://fdn2.gsmarena.com/vv/logos/samsung.png"));
+        assert_eq!(details.description.as_deref(), Some("South Korean electronics maker."));
+        assert_eq!(details.website.as_deref(), Some("https://www.samsung.com/"));
+    }
+
+    #[test]
+    fn test_parse_brands_page() {
+        let html = r#"
+            <div class="st-text"><table><tr>
+                <td><a href="samsung-phones-9.php">Samsung<br><span>1,386 devices</span></a></td>
+                <td><a href="10_or-phones-111.php">10.or<br><span>1 device</span></a></td>
+                <td><a href="/plum-phones-72.php?sSort=1">Plum 4<br><span>45 devices</span></a></td>
+                <td><a href="apple-phones-48.php">Apple 123 devices</a></td>
+                <td><a href="samsung-phones-9.php">Samsung<br><span>1,386 devices</span></a></td>
+                <td><a href="news.php3">News</a></td>
+            </tr></table></div>
+        "#;
+        let brands: Vec<(String, String, u32)> = parse_brands_page(html).into_iter().map(|b| (b.name, b.slug, b.device_count)).collect();
+        assert_eq!(
+            brands,
+            vec![
+                ("Samsung".to_string(), "samsung-phones-9".to_string(), 1386),
+                ("10.or".to_string(), "10_or-phones-111".to_string(), 1),
+                ("Plum 4".to_string(), "plum-phones-72".to_string(), 45),
+                ("Apple".to_string(), "apple-phones-48".to_string(), 123),
+            ]
+        );
+
+        let menu = r#"<div class="brandmenu-v2"><ul><li><a href="nokia-phones-1.php">Nokia</a></li></ul></div>"#;
+        let brands = parse_brands_page(menu);
+        assert_eq!((brands[0].name.as_str(), brands[0].slug.as_str(), brands[0].device_count), ("Nokia", "nokia-phones-1", 0));
+        assert!(parse_brands_page("<html></html>").is_empty());
+    }
+
+    #[test]
+    fn test_parse_page_count() {
+        let html = r#"
+            <div class="nav-pages">
+                <a class="prevnextbutton disabled" title="Previous page">&#9668;</a>
+                <strong>1</strong><a href="nokia-phones-f-1-0-p2.php">2</a><a href="nokia-phones-f-1-0-p3.php">3</a>
+                <a class="prevnextbutton" href="nokia-phones-f-1-0-p2.php" title="Next page">&#9658;</a>
+            </div>
+        "#;
+        assert_eq!(parse_page_count(html), 3);
+        assert_eq!(parse_page_count("<div class=\"makers\"><ul></ul></div>"), 1);
+    }
+
+    #[test]
+    fn test_listing_shortfall() {
+        let brand = Brand { name: "Apple".to_string(), slug: "apple-phones-48".to_string(), device_count: 100 };
+        assert_eq!(brand.listing_shortfall(40, usize::MAX, 0.05), Some(60));
+        assert_eq!(brand.listing_shortfall(97, usize::MAX, 0.05), None); // Within tolerance
+        assert_eq!(brand.listing_shortfall(10, 10, 0.05), None); // Capped by phones_per_brand
+        assert_eq!(brand.listing_shortfall(0, 10, 0.05), Some(10));
+    }
+
+    #[test]
+    fn test_brand_year_breakdown() {
+        let brand_page = r#"<div class="brandmenu-v2">
+            <a href="results.php3?nYearMin=2023&amp;nYearMax=2023&amp;sMakers=48">2023</a>
+            <a href="results.php3?sMakers=48&nYearMax=2022&nYearMin=2022">2022</a>
+            <a href="results.php3?nYearMin=2010&nYearMax=2019&sMakers=48">2010s</a>
+            <a href="results.php3?nYearMin=2023&nYearMax=2023&sMakers=48">2023</a>
+        </div>"#;
+        let links = parse_brand_year_links(brand_page);
+        assert_eq!(
+            links.iter().map(|(year, _)| *year).collect::<Vec<_>>(),
+            vec![2022, 2023] // Ranges and duplicates left out
+        );
+
+        let listing = |phones: usize| {
+            let items = "<li><a href=\"apple_iphone_15-12559.php\"><img src=\"x.jpg\"><strong><span>iPhone 15</span></strong></a></li>".repeat(phones);
+            format!(r#"<div class="makers"><ul>{}</ul></div>"#, items)
+        };
+        let counts = fetch_brand_year_breakdown_with("https://www.gsmarena.com/", "apple-phones-48", |url| {
+            Ok(match url {
+                "https://www.gsmarena.com/apple-phones-48.php" => brand_page.to_string(),
+                url if url.contains("nYearMin=2022") => listing(3),
+                url if url.contains("nYearMin=2023") => listing(5),
+                other => return Err(format!("unexpected {}", other).into()),
+            })
+        })
+        .unwrap();
+        assert_eq!(counts, vec![YearCount { year: 2022, devices: 3 }, YearCount { year: 2023, devices: 5 }]);
+    }
+}
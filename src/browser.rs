@@ -0,0 +1,133 @@
+//! Headless Chrome as a last-resort page source (`browser` feature)
+//!
+//! Some regions get JavaScript or Cloudflare challenge pages that a plain HTTP client
+//! can never pass. Chrome runs each page in `--headless` mode and prints the DOM once
+//! scripts have settled (`--dump-dom`), so the challenge is solved like in a browser.
+//! It is slow (a browser start per page), so it belongs at the end of a `[chain]`.
+
+use crate::config::BrowserConfig;
+use std::error::Error;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Executables looked up on the PATH when `browser.binary` is unset
+const CANDIDATES: [&str; 4] = ["chromium", "chromium-browser", "google-chrome", "google-chrome-stable"];
+
+/// Markers of a challenge page that was not solved in time
+const CHALLENGE_MARKERS: [&str; 3] = ["<title>Just a moment...</title>", "cf-challenge", "challenge-platform"];
+
+/// One headless Chrome run per page
+#[derive(Debug, Clone)]
+pub struct HeadlessBrowser {
+    binary: PathBuf,
+    timeout: Duration,
+    settle_ms: u64, // Virtual time scripts get before the DOM is printed
+}
+
+impl HeadlessBrowser {
+    pub fn new(binary: impl Into<PathBuf>, timeout: Duration, settle_ms: u64) -> Self {
+        Self { binary: binary.into(), timeout, settle_ms }
+    }
+
+    /// Browser from `[browser]`, finding Chrome on the PATH when no binary is configured
+    pub fn from_config(config: &BrowserConfig) -> Result<Self, Box<dyn Error>> {
+        let binary = match config.binary {
+            Some(ref binary) => PathBuf::from(binary),
+            None => find_on_path().ok_or_else(|| format!("No Chrome found on the PATH ({}); set browser.binary", CANDIDATES.join(", ")))?,
+        };
+        Ok(Self::new(binary, Duration::from_secs(config.timeout_secs), config.settle_ms))
+    }
+
+    /// Load `url` and return the rendered DOM; Err on a timeout, a failed run or an unsolved challenge
+    pub fn fetch(&self, url: &str, user_agent: &str) -> Result<String, Box<dyn Error>> {
+        let mut child = Command::new(&self.binary)
+            .args(["--headless=new", "--disable-gpu", "--no-first-run", "--dump-dom", "--hide-scrollbars"])
+            .arg(format!("--virtual-time-budget={}", self.settle_ms))
+            .arg(format!("--user-agent={}", user_agent))
+            .arg(url)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Cannot start {}: {}", self.binary.display(), e))?;
+
+        // Read on a thread so a large page cannot fill the pipe while we wait
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = std::thread::spawn(move || {
+            let mut body = String::new();
+            stdout.read_to_string(&mut body).map(|_| body)
+        });
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() > self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Headless browser timed out after {}s on {}", self.timeout.as_secs(), url).into());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        };
+
+        let body = reader.join().map_err(|_| "Headless browser output reader panicked")??;
+        if !status.success() {
+            return Err(format!("Headless browser exited with {} on {}", status, url).into());
+        }
+        if is_challenge(&body) {
+            return Err(format!("Headless browser still got a challenge page for {}", url).into());
+        }
+        Ok(body)
+    }
+}
+
+/// Whether a page is an (unsolved) anti-bot challenge rather than the content
+pub fn is_challenge(body: &str) -> bool {
+    body.trim().is_empty() || CHALLENGE_MARKERS.iter().any(|marker| body.contains(marker))
+}
+
+fn find_on_path() -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    CANDIDATES
+        .iter()
+        .flat_map(|name| std::env::split_paths(&path).map(move |dir| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A stand-in for Chrome: a shell script printing a page for the last argument (the URL)
+    fn fake_browser(name: &str, script: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("gsmarena-fake-chrome-{}-{}", name, std::process::id()));
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_headless_browser() {
+        let page = fake_browser("page", r#"for last; do :; done; echo "<html><body>$last</body></html>""#);
+        let browser = HeadlessBrowser::new(&page, Duration::from_secs(10), 1000);
+        let body = browser.fetch("https://www.gsmarena.com/apple_iphone_15-12559.php", "Mozilla/5.0").unwrap();
+        assert_eq!(body.trim(), "<html><body>https://www.gsmarena.com/apple_iphone_15-12559.php</body></html>");
+
+        let challenge = fake_browser("challenge", r#"echo "<html><head><title>Just a moment...</title></head></html>""#);
+        let error = HeadlessBrowser::new(&challenge, Duration::from_secs(10), 1000).fetch("https://x/", "UA").unwrap_err();
+        assert!(error.to_string().contains("challenge page"), "{}", error);
+
+        let hanging = fake_browser("hanging", "sleep 5");
+        let error = HeadlessBrowser::new(&hanging, Duration::from_millis(200), 1000).fetch("https://x/", "UA").unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{}", error);
+
+        let failing = fake_browser("failing", "exit 3");
+        assert!(HeadlessBrowser::new(&failing, Duration::from_secs(10), 1000).fetch("https://x/", "UA").is_err());
+        for path in [page, challenge, hanging, failing] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Hard caps on what one run may spend (`[budget]`)
+
+use crate::config::BudgetConfig;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// What a request costs against the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spend {
+    DirectRequest,
+    ProxyRequest,
+    ScrapingBeeCredits,
+}
+
+/// Requests and credits consumed so far, as stored in the run report
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub direct_requests: u64,
+    pub proxy_requests: u64,
+    pub scrapingbee_credits: u64,
+    pub exhausted: Option<String>, // The cap that stopped the run, if any
+}
+
+/// Live consumption against the `[budget]` caps, shared by every fetcher of a run
+///
+/// Direct and proxy requests are refused once their cap is reached. ScrapingBee is
+/// billed after the fact, so a request is allowed while credits remain and its actual
+/// cost is recorded afterwards (the last request may overshoot by its own cost).
+#[derive(Debug, Clone, Default)]
+pub struct RequestBudget {
+    limits: BudgetConfig,
+    spent: Arc<[AtomicU64; 3]>,
+}
+
+impl Spend {
+    fn index(self) -> usize {
+        match self {
+            Spend::DirectRequest => 0,
+            Spend::ProxyRequest => 1,
+            Spend::ScrapingBeeCredits => 2,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Spend::DirectRequest => "budget.max_direct_requests",
+            Spend::ProxyRequest => "budget.max_proxy_requests",
+            Spend::ScrapingBeeCredits => "budget.max_scrapingbee_credits",
+        }
+    }
+}
+
+impl RequestBudget {
+    pub fn new(limits: &BudgetConfig) -> Self {
+        Self {
+            limits: limits.clone(),
+            spent: Arc::default(),
+        }
+    }
+
+    fn limit(&self, kind: Spend) -> Option<u64> {
+        match kind {
+            Spend::DirectRequest => self.limits.max_direct_requests,
+            Spend::ProxyRequest => self.limits.max_proxy_requests,
+            Spend::ScrapingBeeCredits => self.limits.max_scrapingbee_credits,
+        }
+    }
+
+    pub fn spent(&self, kind: Spend) -> u64 {
+        self.spent[kind.index()].load(Ordering::Relaxed)
+    }
+
+    /// Reserve one direct or proxy request, or check that ScrapingBee credits remain
+    pub fn reserve(&self, kind: Spend) -> Result<(), Box<dyn Error>> {
+        let counter = &self.spent[kind.index()];
+        let limit = self.limit(kind).unwrap_or(u64::MAX);
+        let allowed = match kind {
+            Spend::ScrapingBeeCredits => counter.load(Ordering::Relaxed) < limit,
+            _ => counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |spent| (spent < limit).then_some(spent + 1)).is_ok(),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(format!("{} ({}) reached", kind.name(), limit).into())
+        }
+    }
+
+    /// Record ScrapingBee credits a request actually cost
+    pub fn record_credits(&self, credits: u64) {
+        self.spent[Spend::ScrapingBeeCredits.index()].fetch_add(credits, Ordering::Relaxed);
+    }
+
+    /// The first cap that has been reached, if any
+    pub fn exhausted(&self) -> Option<&'static str> {
+        [Spend::DirectRequest, Spend::ProxyRequest, Spend::ScrapingBeeCredits]
+            .into_iter()
+            .find(|&kind| self.limit(kind).is_some_and(|limit| self.spent(kind) >= limit))
+            .map(Spend::name)
+    }
+
+    pub fn usage(&self) -> BudgetUsage {
+        BudgetUsage {
+            direct_requests: self.spent(Spend::DirectRequest),
+            proxy_requests: self.spent(Spend::ProxyRequest),
+            scrapingbee_credits: self.spent(Spend::ScrapingBeeCredits),
+            exhausted: self.exhausted().map(str::to_string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_budget() {
+        let budget = RequestBudget::new(&BudgetConfig {
+            max_direct_requests: Some(2),
+            max_proxy_requests: None,
+            max_scrapingbee_credits: Some(10),
+        });
+        let shared = budget.clone();
+
+        assert!(budget.reserve(Spend::DirectRequest).is_ok());
+        assert!(shared.reserve(Spend::DirectRequest).is_ok());
+        assert_eq!(budget.exhausted(), Some("budget.max_direct_requests"));
+        assert!(budget.reserve(Spend::DirectRequest).is_err());
+        assert_eq!(budget.spent(Spend::DirectRequest), 2); // Refused requests are not counted
+
+        assert!(budget.reserve(Spend::ProxyRequest).is_ok()); // Unlimited
+        assert!(budget.reserve(Spend::ScrapingBeeCredits).is_ok());
+        budget.record_credits(25);
+        assert!(budget.reserve(Spend::ScrapingBeeCredits).is_err());
+
+        let usage = shared.usage();
+        assert_eq!((usage.direct_requests, usage.proxy_requests, usage.scrapingbee_credits), (2, 1, 25));
+        assert_eq!(usage.exhausted.as_deref(), Some("budget.max_direct_requests"));
+    }
+}
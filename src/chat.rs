@@ -0,0 +1,388 @@
+//! End-of-run summaries and new-device announcements for Telegram and Discord
+//!
+//! Senders are compiled in with the `telegram` / `discord` features. Credentials
+//! are only read from `TELEGRAM_BOT_TOKEN` and `DISCORD_WEBHOOK_URL`.
+
+use crate::config::ChatConfig;
+use crate::mongodb::PhoneDocument;
+use crate::run_report::RunReport;
+use crate::secrets::env_secret;
+use async_trait::async_trait;
+use std::error::Error;
+use tracing::{info, warn};
+
+/// New devices listed by name in the run summary
+const SUMMARY_DEVICE_LIMIT: usize = 20;
+/// Failed phones listed by name in the run summary
+const SUMMARY_FAILURE_LIMIT: usize = 5;
+
+/// A phone inserted for the first time during the run
+#[derive(Debug, Clone)]
+pub struct NewDevice {
+    pub phone_id: String,
+    pub name: String,
+    pub brand: String,
+    pub url: String,
+    pub image_url: Option<String>,
+    pub key_specs: Vec<(&'static str, String)>,
+}
+
+impl NewDevice {
+    pub fn from_phone(phone: &PhoneDocument) -> Self {
+        let specs = [
+            ("Announced", phone.launch.as_ref().and_then(|l| l.announced.clone())),
+            ("Display", phone.display.as_ref().and_then(|d| d.size.clone())),
+            ("Chipset", phone.platform.as_ref().and_then(|p| p.chipset.clone())),
+            ("Memory", phone.memory.as_ref().and_then(|m| m.internal.clone())),
+            ("Main camera", phone.main_camera.as_ref().and_then(|c| c.modules.clone())),
+            ("Battery", phone.battery.as_ref().and_then(|b| b.battery_type.clone())),
+            ("Price", phone.misc.as_ref().and_then(|m| m.price.clone())),
+        ];
+
+        Self {
+            phone_id: phone.phone_id.clone(),
+            name: phone.name.clone(),
+            brand: phone.brand.clone(),
+            url: phone.url.clone(),
+            image_url: phone.image_url.clone(),
+            key_specs: specs.into_iter().filter_map(|(label, value)| Some((label, value?))).collect(),
+        }
+    }
+
+    /// "Samsung Galaxy A55", without doubling brands that are already in the name
+    pub fn title(&self) -> String {
+        if self.name.to_lowercase().starts_with(&self.brand.to_lowercase()) {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.brand, self.name)
+        }
+    }
+
+    /// Key specs, one per line (multi-line values are flattened)
+    pub fn spec_lines(&self) -> String {
+        self.key_specs
+            .iter()
+            .map(|(label, value)| format!("{}: {}", label, value.split_whitespace().collect::<Vec<_>>().join(" ")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Plain-text summary of a finished run
+pub fn summary_text(report: &RunReport, new_devices: &[NewDevice]) -> String {
+    let counts = &report.counts;
+    let mut lines = vec![format!(
+        "{} GSMArena scrape {} (run {}{})",
+        if report.interrupted { "⚠" } else { "✓" },
+        if report.interrupted { "interrupted" } else { "finished" },
+        report.run_id,
+        report.duration_secs.map(|secs| format!(", {}", format_duration(secs))).unwrap_or_default(),
+    )];
+
+    lines.push(format!("New devices: {}", new_devices.len()));
+    for device in new_devices.iter().take(SUMMARY_DEVICE_LIMIT) {
+        lines.push(format!("  • {}", device.title()));
+    }
+    if new_devices.len() > SUMMARY_DEVICE_LIMIT {
+        lines.push(format!("  … and {} more", new_devices.len() - SUMMARY_DEVICE_LIMIT));
+    }
+
+    lines.push(format!(
+        "Saved: {} · Unchanged: {} · Skipped: {} · Failed: {} phones, {} brands",
+        counts.phones_inserted, counts.phones_unchanged, counts.phones_skipped, counts.phones_failed, counts.brands_failed
+    ));
+    for failed in report.failed_phones.iter().take(SUMMARY_FAILURE_LIMIT) {
+        lines.push(format!("  ✗ {} {}: {}", failed.brand, failed.name, failed.error));
+    }
+    if report.failed_phones.len() > SUMMARY_FAILURE_LIMIT {
+        lines.push(format!("  … and {} more (see the run report)", report.failed_phones.len() - SUMMARY_FAILURE_LIMIT));
+    }
+    if counts.failures.failed() > 0 {
+        lines.push(format!("Failure causes: {}", counts.failures));
+    }
+
+    if report.scrapingbee_credits > 0 {
+        lines.push(format!("ScrapingBee credits: {}", report.scrapingbee_credits));
+    }
+    lines.push(format!("Phones in database: {} ({:+})", counts.final_count, counts.final_count as i64 - counts.initial_count as i64));
+
+    lines.join("\n")
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Cut text to at most `max` characters, marking the cut
+#[cfg(any(feature = "telegram", feature = "discord"))]
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// One chat destination
+#[async_trait]
+pub trait ChatSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn send_text(&self, text: &str) -> Result<(), Box<dyn Error>>;
+    async fn announce(&self, device: &NewDevice) -> Result<(), Box<dyn Error>>;
+}
+
+/// Sends run summaries (and optionally new devices) to every configured chat
+pub struct ChatNotifier {
+    sinks: Vec<Box<dyn ChatSink>>,
+    announce_new_devices: bool,
+    max_announcements: usize,
+}
+
+impl ChatNotifier {
+    /// `None` when no chat is configured (or the matching feature is not compiled in)
+    pub fn from_config(config: &ChatConfig) -> Option<Self> {
+        let env = |key: &str| match env_secret(key) {
+            Ok(value) => value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()),
+            Err(e) => {
+                warn!(error = %e, "Ignoring chat setting");
+                None
+            }
+        };
+        #[allow(unused_mut)] // Nothing is pushed without the telegram/discord features
+        let mut sinks: Vec<Box<dyn ChatSink>> = Vec::new();
+
+        if let (Some(token), Some(chat_id)) = (env("TELEGRAM_BOT_TOKEN"), config.telegram_chat_id.clone()) {
+            #[cfg(feature = "telegram")]
+            sinks.push(Box::new(telegram::TelegramSink::new(token, chat_id)));
+            #[cfg(not(feature = "telegram"))]
+            {
+                let _ = (token, chat_id);
+                warn!("TELEGRAM_BOT_TOKEN is set but this build lacks the `telegram` feature");
+            }
+        }
+
+        if let Some(webhook_url) = env("DISCORD_WEBHOOK_URL") {
+            #[cfg(feature = "discord")]
+            sinks.push(Box::new(discord::DiscordSink::new(webhook_url)));
+            #[cfg(not(feature = "discord"))]
+            {
+                let _ = webhook_url;
+                warn!("DISCORD_WEBHOOK_URL is set but this build lacks the `discord` feature");
+            }
+        }
+
+        (!sinks.is_empty()).then_some(Self {
+            sinks,
+            announce_new_devices: config.announce_new_devices,
+            max_announcements: config.max_announcements,
+        })
+    }
+
+    pub fn sink_names(&self) -> Vec<&'static str> {
+        self.sinks.iter().map(|sink| sink.name()).collect()
+    }
+
+    /// Whether the `index`-th new device of a run (0-based) gets its own message
+    pub fn should_announce(&self, index: usize) -> bool {
+        self.announce_new_devices && index < self.max_announcements
+    }
+
+    pub async fn announce(&self, device: &NewDevice) {
+        for sink in &self.sinks {
+            if let Err(e) = sink.announce(device).await {
+                warn!(chat = sink.name(), phone = %device.phone_id, error = %e, "✗ Chat announcement failed");
+            }
+        }
+    }
+
+    /// Post any text (e.g. a Markdown comparison) to every chat; failures are logged
+    pub async fn send_message(&self, text: &str) {
+        for sink in &self.sinks {
+            if let Err(e) = sink.send_text(text).await {
+                warn!(chat = sink.name(), error = %e, "✗ Chat message failed");
+            }
+        }
+    }
+
+    /// Post the end-of-run summary; failures are logged, never fatal
+    pub async fn send_summary(&self, report: &RunReport, new_devices: &[NewDevice]) {
+        let text = summary_text(report, new_devices);
+        for sink in &self.sinks {
+            match sink.send_text(&text).await {
+                Ok(()) => info!(chat = sink.name(), "✓ Sent run summary"),
+                Err(e) => warn!(chat = sink.name(), error = %e, "✗ Failed to send run summary"),
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "telegram", feature = "discord"))]
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(concat!("gsmarena-scraper/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+/// Error for a non-2xx reply, including the API's explanation
+#[cfg(any(feature = "telegram", feature = "discord"))]
+async fn check_status(response: reqwest::Response) -> Result<(), Box<dyn Error>> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("status {}: {}", status, truncate(&body, 200)).into())
+}
+
+#[cfg(feature = "telegram")]
+mod telegram {
+    use super::*;
+
+    const MESSAGE_LIMIT: usize = 4096;
+    const CAPTION_LIMIT: usize = 1024;
+
+    /// Bot API `sendMessage` / `sendPhoto` to one chat
+    pub struct TelegramSink {
+        client: reqwest::Client,
+        token: String,
+        chat_id: String,
+    }
+
+    impl TelegramSink {
+        pub fn new(token: String, chat_id: String) -> Self {
+            Self { client: http_client(), token, chat_id }
+        }
+
+        async fn call(&self, method: &str, body: serde_json::Value) -> Result<(), Box<dyn Error>> {
+            let url = format!("https://api.telegram.org/bot{}/{}", self.token, method);
+            // reqwest errors include the URL, which contains the token
+            let response = self.client.post(&url).json(&body).send().await.map_err(|e| e.without_url())?;
+            check_status(response).await
+        }
+    }
+
+    #[async_trait]
+    impl ChatSink for TelegramSink {
+        fn name(&self) -> &'static str {
+            "telegram"
+        }
+
+        async fn send_text(&self, text: &str) -> Result<(), Box<dyn Error>> {
+            let body = serde_json::json!({
+                "chat_id": self.chat_id,
+                "text": truncate(text, MESSAGE_LIMIT),
+                "disable_web_page_preview": true,
+            });
+            self.call("sendMessage", body).await
+        }
+
+        async fn announce(&self, device: &NewDevice) -> Result<(), Box<dyn Error>> {
+            let text = format!("🆕 {}\n{}\n{}", device.title(), device.spec_lines(), device.url);
+
+            match device.image_url {
+                Some(ref image_url) => {
+                    let body = serde_json::json!({
+                        "chat_id": self.chat_id,
+                        "photo": image_url,
+                        "caption": truncate(&text, CAPTION_LIMIT),
+                    });
+                    self.call("sendPhoto", body).await
+                }
+                None => self.send_text(&text).await,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "discord")]
+mod discord {
+    use super::*;
+
+    const CONTENT_LIMIT: usize = 2000;
+    const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+
+    /// Discord incoming webhook
+    pub struct DiscordSink {
+        client: reqwest::Client,
+        webhook_url: String,
+    }
+
+    impl DiscordSink {
+        pub fn new(webhook_url: String) -> Self {
+            Self { client: http_client(), webhook_url }
+        }
+
+        async fn post(&self, body: serde_json::Value) -> Result<(), Box<dyn Error>> {
+            // The webhook URL is the credential; keep it out of error messages
+            let response = self.client.post(&self.webhook_url).json(&body).send().await.map_err(|e| e.without_url())?;
+            check_status(response).await
+        }
+    }
+
+    #[async_trait]
+    impl ChatSink for DiscordSink {
+        fn name(&self) -> &'static str {
+            "discord"
+        }
+
+        async fn send_text(&self, text: &str) -> Result<(), Box<dyn Error>> {
+            self.post(serde_json::json!({ "content": truncate(text, CONTENT_LIMIT) })).await
+        }
+
+        async fn announce(&self, device: &NewDevice) -> Result<(), Box<dyn Error>> {
+            let mut embed = serde_json::json!({
+                "title": format!("🆕 {}", device.title()),
+                "url": device.url,
+                "description": truncate(&device.spec_lines(), EMBED_DESCRIPTION_LIMIT),
+            });
+            if let Some(ref image_url) = device.image_url {
+                embed["image"] = serde_json::json!({ "url": image_url });
+            }
+            self.post(serde_json::json!({ "embeds": [embed] })).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::stats::FailureCategory;
+
+    #[test]
+    fn test_summary_text() {
+        let mut report = RunReport::new(&Config::default());
+        report.start_brand("Samsung", "samsung-phones-9");
+        report.record_brand_phones(2);
+        report.record_inserted();
+        report.record_failed("samsung_galaxy_a35-12705", "Galaxy A35", "Samsung", FailureCategory::Blocked, "status 429");
+        report.scrapingbee_credits = 25;
+        report.counts.final_count = 101;
+        report.counts.initial_count = 100;
+        report.finish(false);
+
+        let device = NewDevice {
+            phone_id: "samsung_galaxy_a55-12824".to_string(),
+            name: "Galaxy A55".to_string(),
+            brand: "Samsung".to_string(),
+            url: "https://www.gsmarena.com/samsung_galaxy_a55-12824.php".to_string(),
+            image_url: None,
+            key_specs: vec![("Chipset", "Exynos 1480 (4 nm)".to_string())],
+        };
+
+        let text = summary_text(&report, &[device]);
+        assert!(text.starts_with("✓ GSMArena scrape finished"));
+        assert!(text.contains("New devices: 1\n  • Samsung Galaxy A55"));
+        assert!(text.contains("Failed: 1 phones, 0 brands\n  ✗ Samsung Galaxy A35: status 429\nFailure causes: blocked=1"));
+        assert!(text.contains("ScrapingBee credits: 25"));
+        assert!(text.contains("Phones in database: 101 (+1)"));
+    }
+}
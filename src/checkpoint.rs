@@ -0,0 +1,152 @@
+//! Where a chain of short runs got to, in one small JSON file
+//!
+//! Scheduled CI jobs are capped in time, so a full crawl is split across runs. Each run
+//! reads the checkpoint the previous one left (`--checkpoint-in`) and writes its own
+//! (`--checkpoint-out`), which a workflow keeps as an artifact in between. The file
+//! holds only what the next run needs: the brand (and listing page) to continue from and
+//! the phones that failed and were not saved since.
+
+use crate::brand_scraper::Brand;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::path::Path;
+
+/// Format version written to new checkpoints
+/// 2 added `page`; version 1 files still read, continuing their brand from page 1
+pub const CHECKPOINT_VERSION: u32 = 2;
+
+/// State handed from one run of a chain to the next
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    pub chain_id: String, // run_id of the run that started the chain
+    pub run_id: String,   // Run that wrote this checkpoint
+    pub runs: usize,      // Runs in the chain so far, this one included
+    pub passes_completed: usize, // Times the chain got through every selected brand
+    pub written_at: DateTime<Utc>,
+    pub cursor: Option<String>, // Slug of the brand the next run starts at; None = start a new pass
+    #[serde(default)]
+    pub page: Option<usize>, // Listing page of the cursor brand to start at; None = its first page
+    pub failed_ids: Vec<String>, // Failed phones not saved since, retried first by the next run
+}
+
+impl Checkpoint {
+    /// The first checkpoint of a chain, or the one after `previous`
+    pub fn next(previous: Option<&Checkpoint>, run_id: &str) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            chain_id: previous.map_or_else(|| run_id.to_string(), |previous| previous.chain_id.clone()),
+            run_id: run_id.to_string(),
+            runs: previous.map_or(0, |previous| previous.runs) + 1,
+            passes_completed: previous.map_or(0, |previous| previous.passes_completed),
+            written_at: Utc::now(),
+            cursor: None,
+            page: None,
+            failed_ids: Vec::new(),
+        }
+    }
+
+    /// Continue at `brand` (from listing `page`) next time, or start a new pass when None
+    pub fn set_cursor(&mut self, brand: Option<&Brand>, page: Option<usize>) {
+        self.cursor = brand.map(|brand| brand.slug.clone());
+        self.page = page.filter(|&page| page > 1 && self.cursor.is_some());
+        if self.cursor.is_none() {
+            self.passes_completed += 1;
+        }
+    }
+
+    /// Keep the phones that failed (here or in earlier runs) and are still not saved
+    pub fn set_failed<'a>(&mut self, failed: impl IntoIterator<Item = &'a str>, is_saved: impl Fn(&str) -> bool) {
+        let failed: BTreeSet<&str> = failed.into_iter().filter(|phone_id| !is_saved(phone_id)).collect();
+        self.failed_ids = failed.into_iter().map(str::to_string).collect();
+    }
+
+    /// Index in `brands` to start at; 0 when the cursor is unset or names a brand no longer selected
+    pub fn start_index(&self, brands: &[Brand]) -> usize {
+        self.cursor
+            .as_deref()
+            .and_then(|slug| brands.iter().position(|brand| brand.slug == slug))
+            .unwrap_or(0)
+    }
+
+    /// Listing page to start the cursor brand at; None when it starts at page 1 or is no longer selected
+    pub fn start_page(&self, brands: &[Brand]) -> Option<usize> {
+        let cursor = self.cursor.as_deref()?;
+        self.page.filter(|_| brands.iter().any(|brand| brand.slug == cursor))
+    }
+
+    /// Read a checkpoint; None when the file does not exist (the first run of a chain)
+    pub fn read(path: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Cannot read checkpoint {}: {}", path.display(), e).into()),
+        };
+        let checkpoint: Self = serde_json::from_str(&contents).map_err(|e| format!("Invalid checkpoint {}: {}", path.display(), e))?;
+        if checkpoint.version > CHECKPOINT_VERSION {
+            return Err(format!("Checkpoint {} has version {}, this build reads up to {}", path.display(), checkpoint.version, CHECKPOINT_VERSION).into());
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Write through a temporary file, so a killed job never leaves half a checkpoint
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brand(slug: &str) -> Brand {
+        Brand { name: slug.to_string(), slug: slug.to_string(), device_count: 10 }
+    }
+
+    #[test]
+    fn test_checkpoint_chain() {
+        let brands = vec![brand("acer-phones-59"), brand("apple-phones-48"), brand("asus-phones-46")];
+
+        let mut first = Checkpoint::next(None, "20261016T020000Z");
+        first.set_cursor(Some(&brands[1]), Some(3));
+        first.set_failed(["apple_iphone_15-12559", "acer_liquid_z6-8254", "apple_iphone_15-12559"], |_| false);
+        assert_eq!((first.start_index(&brands), first.start_page(&brands)), (1, Some(3)));
+        assert_eq!(first.start_page(&brands[2..]), None);
+        assert_eq!(first.failed_ids, vec!["acer_liquid_z6-8254", "apple_iphone_15-12559"]);
+
+        let path = std::env::temp_dir().join(format!("gsmarena-checkpoint-{}", std::process::id())).join("checkpoint.json");
+        assert!(Checkpoint::read(&path).unwrap().is_none());
+        first.write(&path).unwrap();
+        let read = Checkpoint::read(&path).unwrap().unwrap();
+        assert_eq!(read, first);
+
+        // The second run finishes the pass and saves one of the failed phones
+        let mut second = Checkpoint::next(Some(&read), "20261023T020000Z");
+        second.set_cursor(None, Some(2));
+        second.set_failed(read.failed_ids.iter().map(String::as_str), |phone_id| phone_id.starts_with("apple"));
+        assert_eq!((second.chain_id.as_str(), second.runs, second.passes_completed), ("20261016T020000Z", 2, 1));
+        assert_eq!(second.failed_ids, vec!["acer_liquid_z6-8254"]);
+        assert_eq!((second.start_index(&brands), second.start_page(&brands)), (0, None));
+
+        // Version 1 files have no page
+        let v1 = serde_json::json!({
+            "version": 1, "chain_id": "c", "run_id": "r", "runs": 1, "passes_completed": 0,
+            "written_at": "2026-10-16T02:00:00Z", "cursor": "apple-phones-48", "failed_ids": []
+        });
+        std::fs::write(&path, v1.to_string()).unwrap();
+        assert_eq!(Checkpoint::read(&path).unwrap().unwrap().start_page(&brands), None);
+
+        second.version = CHECKPOINT_VERSION + 1;
+        second.write(&path).unwrap();
+        assert!(Checkpoint::read(&path).is_err());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
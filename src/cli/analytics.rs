@@ -0,0 +1,81 @@
+use super::CollectionArgs;
+use clap::{Args, ValueEnum};
+use gsmarena_scraper::analytics::{CorpusStats, StatsReport};
+use gsmarena_scraper::{Config, Dataset, MongoDBClient};
+use mongodb::bson::doc;
+use std::error::Error;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// Every report in one JSON object
+    Json,
+    /// One report as CSV (needs --report)
+    Csv,
+}
+
+#[derive(Debug, Args)]
+pub struct AnalyticsArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Read phones from a JSON Lines export instead of MongoDB
+    #[arg(long, value_name = "PATH")]
+    pub from_jsonl: Option<PathBuf>,
+
+    /// Only include phones of this brand
+    #[arg(long)]
+    pub brand: Option<String>,
+
+    /// Report to output: battery-by-year, display-by-year, 5g-by-brand or os-versions (default: all, JSON only)
+    #[arg(long)]
+    pub report: Option<StatsReport>,
+
+    #[arg(long, value_enum, default_value = "json")]
+    pub format: StatsFormat,
+
+    /// Write the output to this file instead of stdout
+    #[arg(long, short, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+pub async fn run(args: AnalyticsArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    if args.format == StatsFormat::Csv && args.report.is_none() {
+        return Err("--format csv needs --report".into());
+    }
+
+    let dataset = match args.from_jsonl {
+        Some(ref path) => Dataset::load_from_jsonl(path)?,
+        None => {
+            info!("Connecting to MongoDB...");
+            let mongo_client = MongoDBClient::from_env().await?;
+            let filter = args.brand.as_ref().map_or_else(|| doc! {}, |brand| doc! { "brand": brand });
+            Dataset::load_from_mongo(&mongo_client, &config.collections.phones, filter).await?
+        }
+    };
+
+    let stats = match (&args.from_jsonl, &args.brand) {
+        (Some(_), Some(brand)) => CorpusStats::compute(dataset.by_brand(brand)),
+        _ => CorpusStats::compute(&dataset),
+    };
+
+    let rendered = match (args.format, args.report) {
+        (StatsFormat::Csv, Some(report)) => report.to_csv(&stats),
+        (_, Some(StatsReport::BatteryByYear)) => serde_json::to_string_pretty(&stats.battery_by_year)? + "\n",
+        (_, Some(StatsReport::DisplaySizeByYear)) => serde_json::to_string_pretty(&stats.display_size_by_year)? + "\n",
+        (_, Some(StatsReport::FiveGByBrand)) => serde_json::to_string_pretty(&stats.five_g_by_brand)? + "\n",
+        (_, Some(StatsReport::OsVersions)) => serde_json::to_string_pretty(&stats.os_versions)? + "\n",
+        (_, None) => serde_json::to_string_pretty(&stats)? + "\n",
+    };
+
+    match args.output {
+        Some(ref path) => {
+            std::fs::write(path, rendered)?;
+            info!(phones = stats.phones, path = %path.display(), "✓ Wrote analytics");
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
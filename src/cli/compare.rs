@@ -0,0 +1,89 @@
+use super::{phone_id_for, CollectionArgs};
+use clap::Args;
+use gsmarena_scraper::comparison::{compare, ComparisonWeights};
+use gsmarena_scraper::comparison_report::{comparison_html, comparison_markdown, ReportFormat};
+use gsmarena_scraper::scraper::{fetch_specification_from, phone_page_url};
+use gsmarena_scraper::site::SiteConfig;
+use gsmarena_scraper::sitemap::{brand_from_name, phone_from_url};
+use gsmarena_scraper::{Config, MongoDBClient, PhoneDocument};
+use std::error::Error;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    /// Phones to compare (IDs or names)
+    #[arg(num_args = 2.., required = true, value_name = "PHONE")]
+    pub phones: Vec<String>,
+
+    /// Read the phones from the specs collection instead of fetching them live
+    #[arg(long)]
+    pub from_db: bool,
+
+    /// Override a metric's weight in the overall score, e.g. battery_mah=3 (repeatable)
+    #[arg(long = "weight", value_name = "METRIC=WEIGHT")]
+    pub weights: Vec<String>,
+
+    /// Output format: table, json, markdown or html
+    #[arg(long, default_value_t = ReportFormat::Table)]
+    pub format: ReportFormat,
+
+    /// Write the comparison to this file instead of stdout
+    #[arg(long, short, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+}
+
+pub async fn run(args: CompareArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let mut weights = ComparisonWeights::default();
+    for assignment in &args.weights {
+        weights.apply_override(assignment)?;
+    }
+
+    let phone_ids = args.phones.iter().map(|phone| phone_id_for(&config.site, phone)).collect::<Result<Vec<_>, _>>()?;
+
+    let phones = if args.from_db {
+        info!("Connecting to MongoDB...");
+        let mongo_client = MongoDBClient::from_env().await?;
+        let mut phones = Vec::new();
+        for phone_id in &phone_ids {
+            let phone = mongo_client.get_phone(&config.collections.phones, phone_id).await?;
+            phones.push(phone.ok_or_else(|| format!("{} is not in {}", phone_id, config.collections.phones))?);
+        }
+        phones
+    } else {
+        tokio::task::block_in_place(|| phone_ids.iter().map(|phone_id| fetch_phone_document(&config.site.base_url, phone_id)).collect::<Result<Vec<_>, _>>())?
+    };
+
+    let result = compare(&phones, &weights);
+    let rendered = match args.format {
+        ReportFormat::Table => result.to_table(),
+        ReportFormat::Json => serde_json::to_string_pretty(&result)? + "\n",
+        ReportFormat::Markdown => comparison_markdown(&result, &phones),
+        ReportFormat::Html => comparison_html(&result, &phones),
+    };
+
+    match args.output {
+        Some(ref path) => {
+            std::fs::write(path, rendered)?;
+            info!(path = %path.display(), format = %args.format, "✓ Wrote comparison");
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Fetch a phone live and parse it like the scraper would
+fn fetch_phone_document(base_url: &str, phone_id: &str) -> Result<PhoneDocument, Box<dyn Error>> {
+    let mut phone = phone_from_url(&SiteConfig::default(), &phone_page_url(phone_id)).ok_or("invalid phone ID")?;
+    let spec = serde_json::to_value(fetch_specification_from(base_url, phone_id)?)?;
+    if let Some(name) = spec.get("name").and_then(|v| v.as_str()) {
+        phone.name = name.to_string(); // "Apple iPhone 15" reads better than the ID-derived "Apple Iphone 15"
+    }
+
+    let brand = brand_from_name(&phone);
+    Ok(PhoneDocument::new(&phone, &brand, spec))
+}
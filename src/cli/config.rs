@@ -0,0 +1,52 @@
+use clap::{Subcommand, ValueEnum};
+use gsmarena_scraper::Config;
+use std::error::Error;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the merged configuration (file + environment) and validate it
+    PrintEffective {
+        /// Output format
+        #[arg(long, value_enum, default_value = "toml")]
+        format: ConfigFormat,
+    },
+    /// Validate the configuration without printing it
+    Validate,
+}
+
+pub fn run(command: ConfigCommand, config: Config) -> Result<(), Box<dyn Error>> {
+    let source = match config.source {
+        Some(ref path) => path.display().to_string(),
+        None => "(none, defaults + environment)".to_string(),
+    };
+
+    match command {
+        ConfigCommand::PrintEffective { format } => {
+            println!("# Config file: {}", source);
+            match format {
+                ConfigFormat::Toml => print!("{}", config.to_toml()?),
+                ConfigFormat::Yaml => print!("{}", serde_yaml::to_string(&config)?),
+            }
+        }
+        ConfigCommand::Validate => println!("Config file: {}", source),
+    }
+
+    match config.validate() {
+        Ok(()) => {
+            eprintln!("✓ Configuration is valid");
+            Ok(())
+        }
+        Err(problems) => {
+            for problem in &problems {
+                eprintln!("✗ {}", problem);
+            }
+            Err(format!("{} configuration problem(s)", problems.len()).into())
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::Utc;
+use gsmarena_scraper::{CronSchedule, Shutdown};
+use std::error::Error;
+use std::future::Future;
+use tracing::{error, info, info_span, warn, Instrument};
+
+/// Run `cycle` on the cron schedule until the process is stopped
+///
+/// Cycles never overlap: scheduled times that pass while a cycle is still running
+/// are skipped, and the next run is the first fire time after it finishes.
+/// A failed cycle is reported and the daemon keeps going; a shutdown signal
+/// ends the daemon once the current cycle has stopped.
+pub async fn run_scheduled<F, Fut>(schedule: &CronSchedule, shutdown: &Shutdown, mut cycle: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), Box<dyn Error>>>,
+{
+    info!(schedule = schedule.expression(), "Daemon mode");
+    let mut run = 0;
+
+    loop {
+        let now = Utc::now();
+        let next = schedule.next_after(now).ok_or("Cron schedule has no future runs")?;
+        let delay = (next - now).to_std().unwrap_or_default() + schedule.jitter_delay();
+
+        info!(next_run = %next.to_rfc3339(), in_secs = delay.as_secs(), "⏳ Waiting for next run");
+        if !shutdown.sleep(delay).await {
+            break;
+        }
+
+        run += 1;
+        let span = info_span!("run", run);
+        info!(parent: &span, "Scheduled run started");
+
+        if let Err(e) = cycle().instrument(span).await {
+            error!(run, error = %e, "✗ Scheduled run failed");
+        }
+
+        if shutdown.is_requested() {
+            break;
+        }
+
+        let missed = schedule.missed_between(next, Utc::now());
+        if missed > 0 {
+            warn!(run, missed, "Run overran scheduled times; they were skipped");
+        }
+    }
+
+    info!(runs = run, "✓ Daemon stopped");
+    Ok(())
+}
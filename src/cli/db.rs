@@ -0,0 +1,509 @@
+use super::{validated, CollectionArgs};
+use chrono::{Duration, Utc};
+use clap::Subcommand;
+use futures::stream::StreamExt;
+use gsmarena_scraper::collection_diff::{diff_collections, VOLATILE_FIELDS};
+use gsmarena_scraper::compression::FileCompression;
+use gsmarena_scraper::dump::{download_from_s3, dump_collection, read_dump, restore_collection, upload_to_s3};
+use gsmarena_scraper::{group_variants, validate, Availability, Config, MongoDBClient, PhoneQuery, Validation, VariantCandidate};
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::FindOptions;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Show document counts and the largest brands
+    Stats {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// Number of brands to list
+        #[arg(long, default_value_t = 10)]
+        top: i64,
+    },
+    /// Check every stored phone for missing or wrong data and score its completeness
+    Validate {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// Number of least complete phones to list
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+
+        /// Store scores and re-scrape flags on the documents
+        #[arg(long)]
+        write: bool,
+    },
+    /// Find regional variants and duplicates of the same device across phone_ids
+    Variants {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// Only look at phones of this brand (exact name as stored)
+        #[arg(long)]
+        brand: Option<String>,
+
+        /// Set variant_group_id on grouped phones (and clear it on the rest)
+        #[arg(long)]
+        write: bool,
+    },
+    /// List phones whose Launch.status turned Discontinued recently (default: the last quarter)
+    Discontinued {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// How far back to look
+        #[arg(long, default_value_t = 91)]
+        days: i64,
+    },
+    /// Snapshot a collection to a (gzipped) JSONL file, optionally uploading it to S3
+    Dump {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// Collection to dump (default: the specs collection)
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
+
+        /// Output file (default: <collection>-<UTC timestamp>.jsonl.gz)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// none or gzip; `db restore` and `db diff` read either
+        #[arg(long, value_name = "CODEC", default_value = "gzip")]
+        compress: FileCompression,
+
+        /// Also upload the dump here, e.g. s3://backups/gsmarena (`s3` feature)
+        #[arg(long, value_name = "S3_URL")]
+        upload: Option<String>,
+    },
+    /// List phone_list entries marked stale (no longer on GSMArena) and optionally delete them
+    PruneStale {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// Only entries stale for at least this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than_days: Option<i64>,
+
+        /// Delete them instead of only listing them
+        #[arg(long)]
+        delete: bool,
+    },
+    /// Compare two collections or `db dump` files phone by phone, field by field
+    Diff {
+        /// The "before" side: a collection name or a dump file
+        before: String,
+
+        /// The "after" side: a collection name or a dump file
+        after: String,
+
+        /// Also ignore this field (dotted path, repeatable); _id, scraped_at and updated_at always are
+        #[arg(long = "ignore", value_name = "FIELD")]
+        ignore: Vec<String>,
+
+        /// Write the full diff as JSON to this file
+        #[arg(long, value_name = "PATH")]
+        json: Option<PathBuf>,
+
+        /// Number of changed phones to print with their field changes
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+    },
+    /// Load a snapshot written by `db dump` back into a collection
+    Restore {
+        #[command(flatten)]
+        collections: CollectionArgs,
+
+        /// Dump file, or an s3://bucket/key object (`s3` feature)
+        input: String,
+
+        /// Collection to restore into (default: the specs collection)
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
+
+        /// Drop the collection first instead of replacing documents by _id
+        #[arg(long)]
+        drop: bool,
+    },
+}
+
+pub async fn run(command: DbCommand, mut config: Config) -> Result<(), Box<dyn Error>> {
+    match command {
+        DbCommand::Stats { collections, top } => {
+            collections.apply(&mut config);
+            stats(&validated(config)?, top).await
+        }
+        DbCommand::Validate { collections, top, write } => {
+            collections.apply(&mut config);
+            validate_phones(&validated(config)?, top, write).await
+        }
+        DbCommand::Variants { collections, brand, write } => {
+            collections.apply(&mut config);
+            variants(&validated(config)?, brand, write).await
+        }
+        DbCommand::Discontinued { collections, days } => {
+            collections.apply(&mut config);
+            discontinued(&validated(config)?, days).await
+        }
+        DbCommand::Dump { collections, collection, output, upload, compress } => {
+            collections.apply(&mut config);
+            dump(&validated(config)?, collection, output, upload, compress).await
+        }
+        DbCommand::PruneStale { collections, older_than_days, delete } => {
+            collections.apply(&mut config);
+            prune_stale(&validated(config)?, older_than_days, delete).await
+        }
+        DbCommand::Diff { before, after, ignore, json, top } => diff(&before, &after, ignore, json, top).await,
+        DbCommand::Restore { collections, input, collection, drop } => {
+            collections.apply(&mut config);
+            restore(&validated(config)?, &input, collection, drop).await
+        }
+    }
+}
+
+async fn dump(
+    config: &Config,
+    collection: Option<String>,
+    output: Option<PathBuf>,
+    upload: Option<String>,
+    compression: FileCompression,
+) -> Result<(), Box<dyn Error>> {
+    let collection_name = collection.unwrap_or_else(|| config.collections.phones.clone());
+    let output = output.unwrap_or_else(|| {
+        let name = PathBuf::from(format!("{}-{}.jsonl", collection_name, Utc::now().format("%Y%m%dT%H%M%SZ")));
+        compression.apply_to(&name)
+    });
+
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let collection = mongo_client.database().collection::<Document>(&collection_name);
+
+    let documents = dump_collection(&collection, &output, compression).await?;
+    info!(collection = %collection_name, documents, output = %output.display(), "✓ Dumped collection");
+
+    if let Some(destination) = upload {
+        let url = upload_to_s3(&output, &destination).await?;
+        info!(url = %url, "✓ Uploaded dump");
+    }
+    Ok(())
+}
+
+async fn prune_stale(config: &Config, older_than_days: Option<i64>, delete: bool) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let phone_list = mongo_client.database().collection::<Document>(&config.collections.phone_list);
+
+    let mut filter = doc! { "stale": true };
+    if let Some(days) = older_than_days {
+        filter.insert("stale_since", doc! { "$lte": (Utc::now() - Duration::days(days)).to_rfc3339() });
+    }
+
+    let options = FindOptions::builder().sort(doc! { "brand": 1, "name": 1 }).build();
+    let mut cursor = phone_list.find(filter.clone(), options).await?;
+    let mut count = 0;
+    println!("\nStale phone_list entries in {}:", config.collections.phone_list);
+    while let Some(entry) = cursor.next().await {
+        let entry = entry?;
+        count += 1;
+        println!(
+            "  {}  {:15} {}",
+            entry.get_str("stale_since").unwrap_or_default().get(..10).unwrap_or_default(),
+            entry.get_str("brand").unwrap_or_default(),
+            entry.get_str("phone_id").unwrap_or_default()
+        );
+    }
+    println!("Total: {}", count);
+
+    if delete {
+        let deleted = phone_list.delete_many(filter, None).await?.deleted_count;
+        info!(deleted, collection = %config.collections.phone_list, "✓ Deleted stale entries");
+    }
+    Ok(())
+}
+
+async fn diff(before: &str, after: &str, ignore: Vec<String>, json: Option<PathBuf>, top: usize) -> Result<(), Box<dyn Error>> {
+    let needs_mongo = !Path::new(before).is_file() || !Path::new(after).is_file();
+    let mongo_client = if needs_mongo {
+        info!("Connecting to MongoDB...");
+        Some(MongoDBClient::from_env().await?)
+    } else {
+        None
+    };
+
+    let ignore: Vec<String> = VOLATILE_FIELDS.iter().map(|field| field.to_string()).chain(ignore).collect();
+    let diff = diff_collections(
+        &load_phones(mongo_client.as_ref(), before).await?,
+        &load_phones(mongo_client.as_ref(), after).await?,
+        &ignore,
+    );
+
+    println!("\n{} -> {}", before, after);
+    println!("  Added: {}", diff.added.len());
+    println!("  Removed: {}", diff.removed.len());
+    println!("  Changed: {}", diff.changed.len());
+    println!("  Unchanged: {}", diff.unchanged);
+
+    if !diff.changed.is_empty() {
+        println!("\nFields that changed most:");
+        for (field, count) in diff.field_counts().iter().take(10) {
+            println!("  {:6}  {}", count, field);
+        }
+        println!("\nChanged phones:");
+        for phone in diff.changed.iter().take(top) {
+            println!("  {}", phone.phone_id);
+            for field in &phone.fields {
+                println!("    {}: {} -> {}", field.field, field.before, field.after);
+            }
+        }
+    }
+    for (label, phone_ids) in [("Added", &diff.added), ("Removed", &diff.removed)] {
+        if !phone_ids.is_empty() {
+            println!("\n{}: {}", label, phone_ids.iter().take(top).cloned().collect::<Vec<_>>().join(", "));
+        }
+    }
+
+    if let Some(path) = json {
+        std::fs::write(&path, serde_json::to_string_pretty(&diff)?)?;
+        info!(path = %path.display(), "✓ Wrote diff");
+    }
+    Ok(())
+}
+
+/// Documents of a collection or dump file as relaxed extended JSON, keyed by phone_id
+async fn load_phones(mongo_client: Option<&MongoDBClient>, source: &str) -> Result<BTreeMap<String, serde_json::Value>, Box<dyn Error>> {
+    let mut documents = Vec::new();
+    if Path::new(source).is_file() {
+        for document in read_dump(Path::new(source))? {
+            documents.push(document?);
+        }
+    } else {
+        let mongo_client = mongo_client.ok_or("no MongoDB connection")?;
+        let mut cursor = mongo_client.database().collection::<Document>(source).find(doc! {}, None).await?;
+        while let Some(document) = cursor.next().await {
+            documents.push(document?);
+        }
+        if documents.is_empty() {
+            warn!(collection = source, "Collection is empty (or does not exist)");
+        }
+    }
+
+    let mut phones = BTreeMap::new();
+    let mut without_id = 0;
+    for document in documents {
+        match document.get_str("phone_id") {
+            Ok(phone_id) => {
+                phones.insert(phone_id.to_string(), Bson::Document(document).into_relaxed_extjson());
+            }
+            Err(_) => without_id += 1,
+        }
+    }
+    if without_id > 0 {
+        warn!(source, documents = without_id, "Skipped documents without a phone_id");
+    }
+    Ok(phones)
+}
+
+async fn restore(config: &Config, input: &str, collection: Option<String>, drop: bool) -> Result<(), Box<dyn Error>> {
+    let collection_name = collection.unwrap_or_else(|| config.collections.phones.clone());
+
+    // S3 objects are downloaded to the temp directory first
+    let (path, downloaded) = if input.starts_with("s3://") {
+        let path = std::env::temp_dir().join(format!("gsmarena-restore-{}.jsonl.gz", std::process::id()));
+        download_from_s3(input, &path).await?;
+        (path, true)
+    } else {
+        (PathBuf::from(input), false)
+    };
+
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let collection = mongo_client.database().collection::<Document>(&collection_name);
+
+    if drop {
+        warn!(collection = %collection_name, "Dropping the collection before restoring");
+    }
+    let restored = restore_collection(&collection, &path, drop).await;
+    if downloaded {
+        std::fs::remove_file(&path).ok();
+    }
+    info!(collection = %collection_name, documents = restored?, input, "✓ Restored collection");
+    Ok(())
+}
+
+async fn discontinued(config: &Config, days: i64) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+
+    let since = Utc::now() - Duration::days(days);
+    let query = PhoneQuery::new().discontinued_since(since);
+    let options = FindOptions::builder().sort(doc! { "brand": 1, "name": 1 }).build();
+    let mut cursor = mongo_client.get_collection(&config.collections.phones).find(query.to_document(), options).await?;
+
+    let mut phones = Vec::new();
+    while let Some(result) = cursor.next().await {
+        phones.push(result?);
+    }
+
+    println!("\nDiscontinued since {}: {} phones", since.date_naive(), phones.len());
+    for phone in &phones {
+        let at = phone.became(Availability::Discontinued).map(|at| at.date_naive().to_string()).unwrap_or_default();
+        println!("  {}  {:15} {:40} {}", at, phone.brand, phone.phone_id, phone.name);
+    }
+
+    Ok(())
+}
+
+async fn variants(config: &Config, brand: Option<String>, write: bool) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let phones = mongo_client.database().collection::<Document>(&config.collections.phones);
+
+    let filter = match brand {
+        Some(ref brand) => doc! { "brand": brand },
+        None => doc! {},
+    };
+    let options = FindOptions::builder()
+        .projection(doc! { "phone_id": 1, "brand": 1, "name": 1, "misc.models": 1, "_id": 0 })
+        .build();
+
+    let mut cursor = phones.find(filter.clone(), options).await?;
+    let mut candidates = Vec::new();
+    while let Some(result) = cursor.next().await {
+        let document = result?;
+        candidates.push(VariantCandidate {
+            phone_id: document.get_str("phone_id").unwrap_or_default().to_string(),
+            brand: document.get_str("brand").unwrap_or_default().to_string(),
+            name: document.get_str("name").unwrap_or_default().to_string(),
+            models: document.get_document("misc").ok().and_then(|misc| misc.get_str("models").ok()).map(str::to_string),
+        });
+    }
+
+    let groups = group_variants(&candidates);
+    let grouped: usize = groups.iter().map(|g| g.phone_ids.len()).sum();
+
+    println!("\nPhones checked: {}", candidates.len());
+    println!("Variant groups: {} ({} phones)", groups.len(), grouped);
+    for group in &groups {
+        println!("  {} ({}):", group.variant_group_id, group.phone_ids.len());
+        for phone_id in &group.phone_ids {
+            println!("    {}", phone_id);
+        }
+    }
+
+    if write {
+        // Clear stale links first, then set the current groups
+        phones.update_many(filter, doc! { "$unset": { "variant_group_id": "" } }, None).await?;
+        for group in &groups {
+            phones
+                .update_many(
+                    doc! { "phone_id": { "$in": &group.phone_ids } },
+                    doc! { "$set": { "variant_group_id": &group.variant_group_id } },
+                    None,
+                )
+                .await?;
+        }
+        info!(groups = groups.len(), phones = grouped, "✓ Stored variant_group_id");
+    }
+
+    Ok(())
+}
+
+async fn validate_phones(config: &Config, top: usize, write: bool) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let collection_name = &config.collections.phones;
+    let min_completeness = config.validation.min_completeness;
+
+    let mut cursor = mongo_client.get_collection(collection_name).find(doc! {}, None).await?;
+    let mut scored: Vec<(String, Validation)> = Vec::new();
+    let mut unreadable = 0;
+    let mut written = 0;
+
+    while let Some(result) = cursor.next().await {
+        let mut phone = match result {
+            Ok(phone) => phone,
+            Err(e) => {
+                warn!(error = %e, "Unreadable document");
+                unreadable += 1;
+                continue;
+            }
+        };
+
+        let validation = validate(&phone, min_completeness);
+        if write && phone.apply_validation(&validation) {
+            mongo_client.upsert_phone(collection_name, phone.clone()).await?;
+            written += 1;
+        }
+        scored.push((phone.phone_id, validation));
+    }
+
+    let flagged = scored.iter().filter(|(_, v)| v.needs_rescrape).count();
+    let empty = scored.iter().filter(|(_, v)| !v.has_specifications).count();
+    let average = scored.iter().map(|(_, v)| v.completeness_score).sum::<f64>() / scored.len().max(1) as f64;
+
+    println!("\nCollection: {}", collection_name);
+    println!("  Phones checked: {}", scored.len());
+    println!("  Unreadable documents: {}", unreadable);
+    println!("  Without specifications: {}", empty);
+    println!("  Below {:.0}% complete (re-scrape): {}", min_completeness * 100.0, flagged);
+    println!("  Average completeness: {:.1}%", average * 100.0);
+    if write {
+        println!("  Documents updated: {}", written);
+    }
+
+    scored.sort_by(|(_, a), (_, b)| a.completeness_score.total_cmp(&b.completeness_score));
+    println!("\nLeast complete phones:");
+    for (phone_id, validation) in scored.iter().take(top) {
+        let issues = validation.issues.iter().take(3).cloned().collect::<Vec<_>>().join("; ");
+        println!("  {:5.1}%  {:40} {}", validation.completeness_score * 100.0, phone_id, issues);
+    }
+
+    Ok(())
+}
+
+async fn stats(config: &Config, top: i64) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let db = mongo_client.database();
+    let collections = &config.collections;
+
+    let phones = db.collection::<Document>(&collections.phones);
+    let phone_list = db.collection::<Document>(&collections.phone_list);
+    let brands = db.collection::<Document>(&collections.brands);
+
+    let phone_count = phones.count_documents(doc! {}, None).await?;
+    let list_count = phone_list.count_documents(doc! {}, None).await?;
+    let complete_count = phone_list.count_documents(doc! { "is_complete": true }, None).await?;
+    let stale_count = phone_list.count_documents(doc! { "stale": true }, None).await?;
+    let incomplete_count = phone_list.count_documents(doc! { "is_complete": { "$ne": true }, "stale": { "$ne": true } }, None).await?;
+    let brand_count = brands.count_documents(doc! {}, None).await?;
+
+    println!("\nDatabase: {}", db.name());
+    println!("  Specs collection ({}): {} phones", collections.phones, phone_count);
+    println!("  Phone list collection ({}): {} phones", collections.phone_list, list_count);
+    println!("    Complete: {}", complete_count);
+    println!("    Incomplete: {}", incomplete_count);
+    println!("    Stale (no longer listed): {}", stale_count);
+    println!("  Brands collection ({}): {} brands", collections.brands, brand_count);
+
+    let pipeline = vec![
+        doc! { "$group": { "_id": "$brand", "count": { "$sum": 1 } } },
+        doc! { "$sort": { "count": -1 } },
+        doc! { "$limit": top },
+    ];
+
+    println!("\nTop {} brands by phones with specs:", top);
+    let mut cursor = phones.aggregate(pipeline, None).await?;
+    while let Some(result) = cursor.next().await {
+        let group = result?;
+        let brand = group.get_str("_id").unwrap_or("(unknown)");
+        let count = group.get_i32("count").map(i64::from).or_else(|_| group.get_i64("count")).unwrap_or(0);
+        println!("  {:25} {}", brand, count);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use super::fetch::PageFetcher;
+use super::limit_label;
+use super::scrape::{log_brand_filter, prefetch_brand_listings, select_brands, SourceArgs};
+use clap::Args;
+use gsmarena_scraper::brand_scraper::{fetch_phones_by_brand_with, parse_brands_page};
+use gsmarena_scraper::phone_finder::{search_phone_finder_with, PhoneFinderQuery};
+use gsmarena_scraper::sitemap::{discover_phones_from_sitemap_with, group_phones_by_brand};
+use gsmarena_scraper::{Brand, Config, DiscoveryMode, NetworkFilter, PhoneListItem, RequestBudget};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Args)]
+pub struct DiscoverArgs {
+    #[command(flatten)]
+    pub source: SourceArgs, // Hybrid behaves like scrapingbee for listings
+
+    /// Write the brands and phone lists to this JSON file
+    #[arg(long, short, default_value = "discovered_phones.json")]
+    pub output: PathBuf,
+
+    /// Only phones announced in this year, or years (2020-2023), through Phone Finder
+    #[arg(long, value_parser = parse_years, value_name = "YEAR")]
+    pub year: Option<(i32, i32)>,
+
+    /// Only devices Phone Finder lists with this network technology: 5g-only, lte-only or gsm-only [config: scraping.network_filter]
+    #[arg(long, value_name = "FILTER")]
+    pub filter: Option<NetworkFilter>,
+}
+
+impl DiscoverArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.source.apply(config);
+        if self.filter.is_some() {
+            config.scraping.network_filter = self.filter;
+        }
+    }
+}
+
+/// A brand with its discovered phones, as written to the output file
+#[derive(Debug, Serialize)]
+struct DiscoveredBrand {
+    #[serde(flatten)]
+    brand: Brand,
+    phones: Vec<PhoneListItem>,
+}
+
+pub async fn run(args: DiscoverArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    info!("GSMArena Scraper - Discovery");
+
+    let fetcher = PageFetcher::for_provider(config)?.with_budget(&RequestBudget::new(&config.budget));
+    let scraping = &config.scraping;
+    log_brand_filter(&config.brands);
+
+    info!("Fetching brands from GSMArena...");
+    let brands = select_brands(parse_brands_page(&fetcher.fetch(&config.site.makers_url())?), &config.brands);
+    info!(count = brands.len(), listing = %limit_label(scraping.max_brands), "✓ Found brands");
+
+    // One Phone Finder query per brand: a query over every brand only returns the first results
+    let finder_query = match (args.year, scraping.network_filter) {
+        (None, None) => None,
+        (years, network) => {
+            let mut query = PhoneFinderQuery::new();
+            if let Some((min, max)) = years {
+                info!(years = %year_label((min, max)), "Discovering phones by announcement year");
+                query = query.years(min, max);
+            }
+            if let Some(network) = network {
+                info!(filter = %network, "Discovering phones by network technology");
+                query = query.network(network);
+            }
+            Some(query)
+        }
+    };
+
+    let mut sitemap_phones = match scraping.discovery {
+        _ if finder_query.is_some() => None,
+        DiscoveryMode::Sitemap => {
+            let sitemap_url = config.sitemap_url();
+            info!(url = %sitemap_url, "Discovering phones from sitemap");
+            let phones = discover_phones_from_sitemap_with(&config.site, &sitemap_url, |url| fetcher.fetch(url))?;
+            info!(count = phones.len(), "✓ Found phones in sitemap");
+            Some(group_phones_by_brand(&brands, phones))
+        }
+        DiscoveryMode::Brands => None,
+    };
+
+    let brand_total = scraping.max_brands.unwrap_or(usize::MAX).min(brands.len());
+    let mut prefetched = match finder_query {
+        Some(_) => HashMap::new(),
+        None => prefetch_brand_listings(config, &fetcher, &brands[..brand_total], usize::MAX, None),
+    };
+
+    let mut discovered = Vec::new();
+    let mut total_phones = 0;
+    let mut failed = 0;
+
+    for brand in brands.into_iter().take(brand_total) {
+        if scraping.error_policy.stop_reason(failed).is_some() {
+            break;
+        }
+        let listed = match (&finder_query, sitemap_phones.as_mut(), prefetched.remove(&brand.slug)) {
+            (Some(query), _, _) => match brand.maker_id() {
+                Some(maker_id) => search_phone_finder_with(&config.site.base_url, &query.clone().maker(maker_id), |url| fetcher.fetch(url)),
+                None => Err(format!("no maker ID in brand slug '{}'", brand.slug).into()),
+            },
+            (None, Some(grouped), _) => Ok(grouped.remove(&brand.slug).unwrap_or_default()),
+            (None, None, Some(phones)) => Ok(phones),
+            (None, None, None) => fetch_phones_by_brand_with(&config.site.base_url, &brand.slug, usize::MAX, |url| fetcher.fetch(url)),
+        };
+        let phones = match listed {
+            Ok(p) => p,
+            Err(e) => {
+                error!(brand = %brand.name, error = %e, "✗ Failed to fetch phone list");
+                failed += 1;
+                continue;
+            }
+        };
+
+        if finder_query.is_some() {
+            // device_count covers every device, so there is no shortfall to check
+            if phones.is_empty() {
+                continue;
+            }
+        } else if let Some(missing) = brand.listing_shortfall(phones.len(), usize::MAX, config.validation.max_listing_shortfall) {
+            warn!(brand = %brand.name, devices = brand.device_count, missing, "Phone list is short of the device count (blocked?)");
+        }
+        info!(brand = %brand.name, count = phones.len(), "✓ Phones");
+        total_phones += phones.len();
+        discovered.push(DiscoveredBrand { brand, phones });
+    }
+
+    std::fs::write(&args.output, serde_json::to_string_pretty(&discovered)?)?;
+
+    info!(brands = discovered.len(), phones = total_phones, output = %args.output.display(), "✓ Saved discovered phones");
+
+    // The brands listed so far are saved either way
+    match scraping.error_policy.stop_reason(failed) {
+        Some(reason) => Err(format!("Discovery aborted: {}", reason).into()),
+        None => Ok(()),
+    }
+}
+
+/// "2024" or "2020-2023"
+fn parse_years(value: &str) -> Result<(i32, i32), String> {
+    let year = |text: &str| match text.trim().parse::<i32>() {
+        Ok(year) if (1990..=2100).contains(&year) => Ok(year),
+        _ => Err(format!("'{}' is not a year", text.trim())),
+    };
+    let (min, max) = match value.split_once('-') {
+        Some((min, max)) => (year(min)?, year(max)?),
+        None => (year(value)?, year(value)?),
+    };
+    if min > max {
+        return Err(format!("{} comes after {}", min, max));
+    }
+    Ok((min, max))
+}
+
+fn year_label((min, max): (i32, i32)) -> String {
+    if min == max { min.to_string() } else { format!("{}-{}", min, max) }
+}
@@ -0,0 +1,171 @@
+use super::CollectionArgs;
+use clap::Args;
+use gsmarena_scraper::brand_scraper::parse_brands_page;
+use gsmarena_scraper::migrations::Migrator;
+use gsmarena_scraper::secrets::env_secret;
+use gsmarena_scraper::{Config, MongoDBClient, ProxyManager, ScrapingBeeClient, UserAgentPool};
+use reqwest::blocking;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Args)]
+pub struct DoctorArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+}
+
+impl DoctorArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+enum Status {
+    Pass,
+    Fail,
+    Skip, // Not configured, or depends on a failed check
+}
+
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    elapsed: Duration,
+}
+
+/// Time a check; an error fails it with the error as detail
+fn check(name: &'static str, run: impl FnOnce() -> Result<(Status, String), Box<dyn Error>>) -> Check {
+    let started = Instant::now();
+    let (status, detail) = run().unwrap_or_else(|e| (Status::Fail, e.to_string()));
+    Check { name, status, detail, elapsed: started.elapsed() }
+}
+
+pub async fn run(_args: DoctorArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let mut checks = Vec::new();
+
+    // MongoDB: connect and ping, then check the indexes and migrations `scrape` relies on
+    let started = Instant::now();
+    let mongo_client = MongoDBClient::from_env().await;
+    let connect_elapsed = started.elapsed();
+    match mongo_client {
+        Ok(mongo_client) => {
+            let topology = match mongo_client.supports_transactions().await {
+                Ok(true) => "replica set",
+                Ok(false) => "standalone",
+                Err(_) => "unknown topology",
+            };
+            let detail = format!("database {} ({})", mongo_client.database().name(), topology);
+            checks.push(Check { name: "MongoDB", status: Status::Pass, detail, elapsed: connect_elapsed });
+
+            let started = Instant::now();
+            let (status, detail) = match mongo_state(&mongo_client, config).await {
+                Ok(state) => state,
+                Err(e) => (Status::Fail, e.to_string()),
+            };
+            checks.push(Check { name: "MongoDB indexes", status, detail, elapsed: started.elapsed() });
+        }
+        Err(e) => {
+            checks.push(Check { name: "MongoDB", status: Status::Fail, detail: e.to_string(), elapsed: connect_elapsed });
+            checks.push(Check { name: "MongoDB indexes", status: Status::Skip, detail: "MongoDB unreachable".to_string(), elapsed: Duration::ZERO });
+        }
+    }
+
+    // The rest are blocking HTTP calls
+    tokio::task::block_in_place(|| {
+        checks.push(check("Appwrite proxies", || appwrite(config)));
+        checks.push(check("ScrapingBee", scrapingbee));
+        checks.push(check("GSMArena", || gsmarena(config)));
+    });
+
+    println!("\n{:18} {:8} {:>7}  Details", "Check", "Result", "Time");
+    for check in &checks {
+        let status = match check.status {
+            Status::Pass => "✓ pass",
+            Status::Fail => "✗ fail",
+            Status::Skip => "- skip",
+        };
+        println!("{:18} {:8} {:>5}ms  {}", check.name, status, check.elapsed.as_millis(), check.detail);
+    }
+
+    let failed = checks.iter().filter(|check| matches!(check.status, Status::Fail)).count();
+    match failed {
+        0 => {
+            println!("\n✓ All configured dependencies are reachable");
+            Ok(())
+        }
+        _ => Err(format!("{} of {} checks failed", failed, checks.len()).into()),
+    }
+}
+
+/// Indexes of the phones collection and pending schema migrations
+async fn mongo_state(mongo_client: &MongoDBClient, config: &Config) -> Result<(Status, String), Box<dyn Error>> {
+    let missing = mongo_client.missing_indexes(&config.collections.phones).await?;
+    let pending = Migrator::new(mongo_client, config).pending(None).await?;
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing on {}: {} (created by `scrape`)", config.collections.phones, missing.join(", ")));
+    }
+    if !pending.is_empty() {
+        problems.push(format!("{} pending migration(s) (run `migrate`)", pending.len()));
+    }
+    Ok(match problems.is_empty() {
+        true => (Status::Pass, format!("{} indexed, migrations up to date", config.collections.phones)),
+        false => (Status::Fail, problems.join("; ")),
+    })
+}
+
+/// Read access to the proxy collection
+fn appwrite(config: &Config) -> Result<(Status, String), Box<dyn Error>> {
+    let settings = &config.proxy;
+    let configured = settings.appwrite_project_id.is_some()
+        || settings.appwrite_database_id.is_some()
+        || settings.appwrite_collection_id.is_some()
+        || env_secret("APPWRITE_API_KEY")?.is_some();
+    if !configured {
+        return Ok((Status::Skip, "not configured".to_string()));
+    }
+
+    let manager = ProxyManager::from_settings(settings)?;
+    let count = manager.fetch_proxies()?;
+    Ok((Status::Pass, format!("{} active proxies", count)))
+}
+
+/// Every API key against the usage endpoint
+fn scrapingbee() -> Result<(Status, String), Box<dyn Error>> {
+    if env_secret("SCRAPINGBEE_API_KEYS")?.is_none_or(|keys| keys.trim().is_empty()) {
+        return Ok((Status::Skip, "not configured".to_string()));
+    }
+
+    let client = ScrapingBeeClient::from_env()?;
+    let mut credits_left = 0;
+    let mut invalid = Vec::new();
+    for (index, usage) in client.key_usage().into_iter().enumerate() {
+        match usage {
+            Ok(usage) => credits_left += usage.credits_left(),
+            Err(e) => invalid.push(format!("key {}: {}", index + 1, e)),
+        }
+    }
+
+    Ok(match invalid.is_empty() {
+        true => (Status::Pass, format!("{} keys, {} credits left", client.api_key_count(), credits_left)),
+        false => (Status::Fail, invalid.join("; ")),
+    })
+}
+
+/// The makers page, fetched directly and parsed
+fn gsmarena(config: &Config) -> Result<(Status, String), Box<dyn Error>> {
+    let user_agents = UserAgentPool::from_config(&config.user_agents)?;
+    let client = blocking::Client::builder().user_agent(user_agents.user_agent()).timeout(Duration::from_secs(30)).build()?;
+    let url = config.site.makers_url();
+    let response = client.get(&url).send()?;
+    if !response.status().is_success() {
+        return Ok((Status::Fail, format!("{} returned status: {}", url, response.status())));
+    }
+
+    let brands = parse_brands_page(&response.text()?).len();
+    Ok(match brands {
+        0 => (Status::Fail, format!("no brands on {} (blocked or layout changed)", url)),
+        _ => (Status::Pass, format!("{} brands on {}", brands, url)),
+    })
+}
@@ -0,0 +1,240 @@
+use super::CollectionArgs;
+use clap::{Args, ValueEnum};
+use futures::stream::StreamExt;
+use gsmarena_scraper::archive::{normalized_csv, ArchiveFormat, ArchiveWriter};
+use gsmarena_scraper::compression::{self, FileCompression};
+use gsmarena_scraper::images::ImageStorage;
+use gsmarena_scraper::{Brand, Config, FileSystemStore, MongoDBClient, PhoneDocument, PhoneListItem, PhoneStore};
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::FindOptions;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON array
+    Json,
+    /// One document per line (loadable with `Dataset::load_from_jsonl`)
+    Jsonl,
+    /// Everything for one brand: phone list, per-phone JSON, normalized CSV and stored images
+    Zip,
+    /// Same as zip, as a gzipped tarball
+    #[value(name = "tar.gz")]
+    TarGz,
+    /// A directory per brand: phone_list.json, per-phone JSON, all_specs.json, plus stats (`FileSystemStore`)
+    Dirs,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Only export phones of this brand (case-insensitive; required for archives)
+    #[arg(long)]
+    pub brand: Option<String>,
+
+    /// Maximum number of phones to export
+    #[arg(long)]
+    pub limit: Option<i64>,
+
+    #[arg(long, value_enum, default_value = "json")]
+    pub format: ExportFormat,
+
+    /// Output file (default: phones_export.json, or <brand>.zip / <brand>.tar.gz for archives,
+    /// or the directory scraped_data for dirs)
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Compress json/jsonl output or the files of dirs: none or gzip (default: gzip when the
+    /// output ends in .gz); `.gz` is appended to file names
+    #[arg(long, value_name = "CODEC")]
+    pub compress: Option<FileCompression>,
+}
+
+pub async fn run(args: ExportArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let archive_format = match args.format {
+        ExportFormat::Zip => Some(ArchiveFormat::Zip),
+        ExportFormat::TarGz => Some(ArchiveFormat::TarGz),
+        ExportFormat::Json | ExportFormat::Jsonl | ExportFormat::Dirs => None,
+    };
+    if archive_format.is_some() && args.brand.is_none() {
+        return Err("archive exports need --brand".into());
+    }
+    if archive_format.is_some() && args.compress.is_some() {
+        return Err("--compress applies to json, jsonl and dirs; archives are compressed already".into());
+    }
+
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+
+    let filter = match args.brand {
+        Some(ref brand) => doc! { "brand": { "$regex": format!("^{}$", regex::escape(brand)), "$options": "i" } },
+        None => doc! {},
+    };
+    let phones = find_documents(&mongo_client, &config.collections.phones, filter.clone(), args.limit).await?;
+
+    if let (Some(format), Some(brand)) = (archive_format, args.brand.as_deref()) {
+        if phones.is_empty() {
+            return Err(format!("no phones of brand {} in {}", brand, config.collections.phones).into());
+        }
+        let output = args.output.clone().unwrap_or_else(|| PathBuf::from(format!("{}.{}", slug(brand), format.extension())));
+        let phone_list = find_documents(&mongo_client, &config.collections.phone_list, filter, None).await?;
+        return write_brand_archive(&mongo_client, config, brand, phones, phone_list, format, &output).await;
+    }
+
+    if args.format == ExportFormat::Dirs {
+        let output = args.output.clone().unwrap_or_else(|| PathBuf::from("scraped_data"));
+        let phone_list = find_documents(&mongo_client, &config.collections.phone_list, filter, None).await?;
+        let brands = find_documents(&mongo_client, &config.collections.brands, doc! {}, None).await?;
+        return write_brand_dirs(phones, phone_list, brands, &output, args.compress.unwrap_or_default()).await;
+    }
+
+    let output = args.output.unwrap_or_else(|| PathBuf::from("phones_export.json"));
+    let compression = args.compress.unwrap_or_else(|| FileCompression::from_path(&output));
+    let output = compression.apply_to(&output);
+    if args.format == ExportFormat::Jsonl {
+        let lines: Vec<String> = phones.iter().map(serde_json::to_string).collect::<Result<_, _>>()?;
+        compression::write(&output, (lines.join("\n") + "\n").as_bytes(), compression)?;
+    } else {
+        compression::write(&output, serde_json::to_string_pretty(&phones)?.as_bytes(), compression)?;
+    }
+
+    info!(count = phones.len(), collection = %config.collections.phones, output = %output.display(), "✓ Exported phones");
+
+    Ok(())
+}
+
+/// Documents as relaxed extended JSON, without `_id`, ordered by brand and name
+async fn find_documents(
+    mongo_client: &MongoDBClient,
+    collection_name: &str,
+    filter: Document,
+    limit: Option<i64>,
+) -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+    let collection = mongo_client.database().collection::<Document>(collection_name);
+    let options = FindOptions::builder()
+        .projection(doc! { "_id": 0 })
+        .sort(doc! { "brand": 1, "name": 1 })
+        .limit(limit)
+        .build();
+
+    let mut cursor = collection.find(filter, options).await?;
+    let mut documents = Vec::new();
+    while let Some(result) = cursor.next().await {
+        // Relaxed extended JSON keeps dates readable ("$date": "2024-...")
+        documents.push(Bson::Document(result?).into_relaxed_extjson());
+    }
+    Ok(documents)
+}
+
+/// `<brand>/phone_list.json`, `<brand>/phones/<phone_id>.json`, `<brand>/normalized.csv`
+/// and `<brand>/images/<phone_id>/<file>` for every stored image that can be read back
+async fn write_brand_archive(
+    mongo_client: &MongoDBClient,
+    config: &Config,
+    brand: &str,
+    documents: Vec<serde_json::Value>,
+    phone_list: Vec<serde_json::Value>,
+    format: ArchiveFormat,
+    output: &std::path::Path,
+) -> Result<(), Box<dyn Error>> {
+    let root = slug(brand);
+    let mut archive = ArchiveWriter::create(output, format)?;
+    archive.add(&format!("{}/phone_list.json", root), serde_json::to_string_pretty(&phone_list)?.as_bytes())?;
+
+    let mut phones: Vec<PhoneDocument> = Vec::with_capacity(documents.len());
+    for document in documents {
+        let phone_id = document.get("phone_id").and_then(|id| id.as_str()).unwrap_or_default().to_string();
+        archive.add(&format!("{}/phones/{}.json", root, phone_id), serde_json::to_string_pretty(&document)?.as_bytes())?;
+        match serde_json::from_value::<PhoneDocument>(document) {
+            Ok(phone) => phones.push(phone),
+            Err(e) => warn!(phone_id = %phone_id, error = %e, "Leaving unreadable document out of normalized.csv"),
+        }
+    }
+    archive.add(&format!("{}/normalized.csv", root), normalized_csv(&phones)?.as_bytes())?;
+
+    // `load` follows each reference; S3 ones additionally need S3 storage configured
+    let storage = match ImageStorage::from_config(&config.images, &mongo_client.database()).await? {
+        Some(storage) => storage,
+        None => ImageStorage::parse("gridfs", &mongo_client.database()).await?,
+    };
+    let mut images = 0;
+    for phone in &phones {
+        for image in &phone.stored_images {
+            let file_name = image.storage_ref.rsplit('/').next().unwrap_or(&image.content_hash);
+            match storage.load(&image.storage_ref).await {
+                Ok(bytes) => {
+                    archive.add(&format!("{}/images/{}/{}", root, phone.phone_id, file_name), &bytes)?;
+                    images += 1;
+                }
+                Err(e) => warn!(phone_id = %phone.phone_id, storage_ref = %image.storage_ref, error = %e, "Skipping image"),
+            }
+        }
+    }
+    archive.finish()?;
+
+    info!(brand, phones = phones.len(), listed = phone_list.len(), images, output = %output.display(), "✓ Wrote brand archive");
+    Ok(())
+}
+
+/// The stored phones laid out as `FileSystemStore` brand directories; existing files are updated
+async fn write_brand_dirs(
+    documents: Vec<serde_json::Value>,
+    phone_list: Vec<serde_json::Value>,
+    brands: Vec<serde_json::Value>,
+    output: &Path,
+    compression: FileCompression,
+) -> Result<(), Box<dyn Error>> {
+    let store = FileSystemStore::open(output)?.with_compression(compression);
+    let brands: BTreeMap<String, Brand> = brands
+        .into_iter()
+        .filter_map(|brand| serde_json::from_value::<Brand>(brand).ok())
+        .map(|brand| (brand.name.clone(), brand))
+        .collect();
+
+    let mut listings: BTreeMap<String, Vec<PhoneListItem>> = BTreeMap::new();
+    for entry in phone_list {
+        let brand = entry.get("brand").and_then(|brand| brand.as_str()).unwrap_or_default().to_string();
+        match serde_json::from_value::<PhoneListItem>(entry) {
+            Ok(item) => listings.entry(brand).or_default().push(item),
+            Err(e) => warn!(brand = %brand, error = %e, "Leaving unreadable phone_list entry out"),
+        }
+    }
+    for (name, items) in &listings {
+        let brand = brands.get(name).cloned().unwrap_or_else(|| Brand {
+            name: name.clone(),
+            slug: String::new(),
+            device_count: items.len() as u32,
+        });
+        store.save_brand_listing(&brand, items).await?;
+    }
+
+    // Ordered by brand, so each brand's all_specs.json is written once
+    let mut written = 0;
+    for document in documents {
+        let phone_id = document.get("phone_id").and_then(|id| id.as_str()).unwrap_or_default().to_string();
+        match serde_json::from_value::<PhoneDocument>(document) {
+            Ok(phone) => {
+                store.upsert_phone(phone).await?;
+                written += 1;
+            }
+            Err(e) => warn!(phone_id = %phone_id, error = %e, "Skipping unreadable document"),
+        }
+    }
+    store.flush().await?;
+
+    info!(phones = written, brands = listings.len(), output = %output.display(), "✓ Wrote brand directories");
+    Ok(())
+}
+
+/// "Sony Ericsson" -> "sony_ericsson"
+fn slug(brand: &str) -> String {
+    brand
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
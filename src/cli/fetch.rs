@@ -0,0 +1,579 @@
+use gsmarena_scraper::budget::{RequestBudget, Spend};
+#[cfg(feature = "browser")]
+use gsmarena_scraper::browser::HeadlessBrowser;
+use gsmarena_scraper::{AdaptiveDelay, Config, GlobalRateLimiter, HttpStats, PageCache, PageSource, ProxyManager, SavedPages, ScrapeProvider, ScrapingBeeClient, UserAgentPool, Validators};
+use reqwest::{blocking, StatusCode};
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Where a page body comes from when it is not cached
+pub enum FetchSource {
+    Direct,
+    Proxy {
+        manager: ProxyManager,
+        attempts: usize, // Proxies tried for a single page before giving up
+    },
+    ScrapingBee(ScrapingBeeClient),
+    /// Pages saved on disk (`--from-dir`); never touches the network
+    Saved(SavedPages),
+    /// Providers tried in order for each page (`--provider chain`)
+    Chain(Vec<ChainLink>),
+    /// Headless Chrome (`--provider browser`)
+    #[cfg(feature = "browser")]
+    Browser(HeadlessBrowser),
+}
+
+/// One provider of a fallback chain, with the requests it may still make
+pub struct ChainLink {
+    fetcher: PageFetcher,
+    provider: ScrapeProvider,
+    budget: Option<u64>,
+    used: AtomicU64,
+    exhausted: AtomicBool, // Every ScrapingBee API key is used up
+}
+
+/// A page source used for listings and specification pages, with the optional page cache in front
+pub struct PageFetcher {
+    source: FetchSource,
+    cache: Option<PageCache>,
+    budget: RequestBudget, // Unlimited unless the run sets one
+    global_limiter: Option<GlobalRateLimiter>, // Cap shared with other workers
+    adaptive_delay: Option<AdaptiveDelay>, // Fed the status of every rate-limited response
+    stats: HttpStats,
+    user_agents: UserAgentPool, // Direct and proxy requests
+    not_modified: AtomicU64, // Expired cache entries confirmed unchanged by a 304
+}
+
+/// Live fetches of `PhoneService` go through the run's provider, cache and budget
+impl PageSource for PageFetcher {
+    fn fetch_page(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        self.fetch(url)
+    }
+}
+
+/// Outcome of one request for a page
+enum Fetched {
+    Page(String, Validators),
+    NotModified,
+}
+
+impl PageFetcher {
+    /// Fetcher used for listing pages (makers, brand pages, sitemaps) by the configured provider,
+    /// or the saved pages when `scraping.from_dir` is set
+    pub fn for_provider(config: &Config) -> Result<Self, Box<dyn Error>> {
+        match config.scraping.from_dir {
+            Some(ref dir) => Self::from_dir(dir),
+            None => Self::new(config.scraping.provider, config),
+        }
+    }
+
+    /// Read pages saved in a directory instead of fetching them
+    pub fn from_dir(dir: &str) -> Result<Self, Box<dyn Error>> {
+        let pages = SavedPages::new(dir)?;
+        info!(dir = %pages.dir().display(), "Parsing saved pages (no network)");
+
+        Ok(Self {
+            source: FetchSource::Saved(pages),
+            cache: None,
+            budget: RequestBudget::default(),
+            global_limiter: None,
+            adaptive_delay: None,
+            stats: HttpStats::default(),
+            user_agents: UserAgentPool::default(),
+            not_modified: AtomicU64::new(0),
+        })
+    }
+
+    /// Whether pages come from disk rather than GSMArena
+    pub fn is_offline(&self) -> bool {
+        matches!(self.source, FetchSource::Saved(_))
+    }
+
+    pub fn new(provider: ScrapeProvider, config: &Config) -> Result<Self, Box<dyn Error>> {
+        let user_agents = UserAgentPool::from_config(&config.user_agents)?;
+        let source = match provider {
+            ScrapeProvider::Direct => FetchSource::Direct,
+            ScrapeProvider::Proxy => {
+                info!("Loading proxies from Appwrite...");
+                let mut manager = ProxyManager::from_settings(&config.proxy)
+                    .map_err(|e| format!("Proxy provider needs Appwrite settings: {}", e))?;
+                manager.set_user_agents(&user_agents);
+                tokio::task::block_in_place(|| manager.fetch_proxies())?;
+
+                if manager.proxy_count() == 0 {
+                    return Err("No active proxies available".into());
+                }
+                FetchSource::Proxy { manager, attempts: config.proxy.attempts }
+            }
+            ScrapeProvider::ScrapingBee | ScrapeProvider::Hybrid => {
+                info!("Initializing ScrapingBee...");
+                FetchSource::ScrapingBee(tokio::task::block_in_place(ScrapingBeeClient::from_env)?)
+            }
+            ScrapeProvider::Chain => FetchSource::Chain(chain_links(config)?),
+            ScrapeProvider::Browser => browser_source(config)?,
+        };
+
+        let mut fetcher = Self {
+            source,
+            cache: PageCache::from_config(&config.cache)?,
+            budget: RequestBudget::default(),
+            global_limiter: None,
+            adaptive_delay: None,
+            stats: HttpStats::default(),
+            user_agents: UserAgentPool::default(),
+            not_modified: AtomicU64::new(0),
+        };
+        fetcher.set_user_agents(&user_agents); // One pool for every link of a chain
+        Ok(fetcher)
+    }
+
+    /// Direct fetcher sharing the configured page cache
+    pub fn direct(config: &Config) -> Result<Self, Box<dyn Error>> {
+        Self::new(ScrapeProvider::Direct, config)
+    }
+
+    /// Always go to the network (e.g. when retrying pages that parsed badly)
+    pub fn without_cache(mut self) -> Self {
+        self.cache = None;
+        self
+    }
+
+    /// Count requests against the run's budget (shared by the links of a chain)
+    pub fn with_budget(mut self, budget: &RequestBudget) -> Self {
+        self.set_budget(budget);
+        self
+    }
+
+    fn set_budget(&mut self, budget: &RequestBudget) {
+        self.budget = budget.clone();
+        if let FetchSource::Chain(ref mut links) = self.source {
+            for link in links {
+                link.fetcher.set_budget(budget);
+            }
+        }
+    }
+
+    /// Take a slot of the cross-process request cap before every request (shared by the links of a chain)
+    pub fn with_global_limiter(mut self, limiter: Option<&GlobalRateLimiter>) -> Self {
+        self.set_global_limiter(limiter);
+        self
+    }
+
+    fn set_global_limiter(&mut self, limiter: Option<&GlobalRateLimiter>) {
+        self.global_limiter = limiter.cloned();
+        if let FetchSource::Chain(ref mut links) = self.source {
+            for link in links {
+                link.fetcher.set_global_limiter(limiter);
+            }
+        }
+    }
+
+    /// Report the status of every direct, proxy and browser response to the run's adaptive delay
+    pub fn with_adaptive_delay(mut self, adaptive_delay: Option<&AdaptiveDelay>) -> Self {
+        self.set_adaptive_delay(adaptive_delay);
+        self
+    }
+
+    fn set_adaptive_delay(&mut self, adaptive_delay: Option<&AdaptiveDelay>) {
+        self.adaptive_delay = adaptive_delay.cloned();
+        if let FetchSource::Chain(ref mut links) = self.source {
+            for link in links {
+                link.fetcher.set_adaptive_delay(adaptive_delay);
+            }
+        }
+    }
+
+    /// Record status codes, bytes and latency of every request into the run's stats
+    pub fn with_stats(mut self, stats: &HttpStats) -> Self {
+        self.set_stats(stats);
+        self
+    }
+
+    fn set_stats(&mut self, stats: &HttpStats) {
+        self.stats = stats.clone();
+        match self.source {
+            FetchSource::ScrapingBee(ref mut client) => client.set_stats(stats),
+            FetchSource::Chain(ref mut links) => {
+                for link in links {
+                    link.fetcher.set_stats(stats);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Share one User-Agent pool between fetchers, so `per_session` and `per_brand` hold across them
+    pub fn with_user_agents(mut self, user_agents: &UserAgentPool) -> Self {
+        self.set_user_agents(user_agents);
+        self
+    }
+
+    fn set_user_agents(&mut self, user_agents: &UserAgentPool) {
+        self.user_agents = user_agents.clone();
+        match self.source {
+            FetchSource::Proxy { ref mut manager, .. } => manager.set_user_agents(user_agents),
+            FetchSource::Chain(ref mut links) => {
+                for link in links {
+                    link.fetcher.set_user_agents(user_agents);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Short label shown next to each fetched phone
+    pub fn label(&self) -> &'static str {
+        match self.source {
+            FetchSource::Direct => "[RL]",
+            FetchSource::Proxy { .. } => "[PX]",
+            FetchSource::ScrapingBee(_) => "[SB]",
+            FetchSource::Saved(_) => "[FS]",
+            FetchSource::Chain(_) => "[CH]",
+            #[cfg(feature = "browser")]
+            FetchSource::Browser(_) => "[HB]",
+        }
+    }
+
+    /// ScrapingBee requests are paid for, not rate limited by us; saved pages need no delay
+    pub fn is_rate_limited(&self) -> bool {
+        !matches!(self.source, FetchSource::ScrapingBee(_) | FetchSource::Saved(_))
+    }
+
+    /// Whether the page would be served from the cache without a request
+    pub fn is_cached(&self, url: &str) -> bool {
+        self.cache.as_ref().is_some_and(|cache| cache.contains(url))
+    }
+
+    /// ScrapingBee credits consumed through this fetcher
+    pub fn credits_used(&self) -> u64 {
+        match self.source {
+            FetchSource::ScrapingBee(ref client) => client.credits_used(),
+            FetchSource::Chain(ref links) => links.iter().map(|link| link.fetcher.credits_used()).sum(),
+            _ => 0,
+        }
+    }
+
+    /// Requests made by each provider of a chain, in chain order (empty for other sources)
+    pub fn chain_usage(&self) -> Vec<(ScrapeProvider, u64)> {
+        match self.source {
+            FetchSource::Chain(ref links) => links.iter().map(|link| (link.provider, link.used.load(Ordering::Relaxed))).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Expired cache entries the server reported unchanged (304) instead of resending
+    pub fn pages_not_modified(&self) -> u64 {
+        self.not_modified.load(Ordering::Relaxed)
+    }
+
+    /// Fetch a page body (blocking; safe to call from the async CLI)
+    ///
+    /// Expired cache entries with an ETag or Last-Modified are revalidated with a
+    /// conditional request (direct and proxy only; ScrapingBee always refetches).
+    pub fn fetch(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        self.fetch_accepting(url, &|_| true)
+    }
+
+    /// Fetch a page and parse it; a chain moves on to its next provider when `parse` fails
+    /// (a block page), instead of only on request errors
+    pub fn fetch_parsed<T, P>(&self, url: &str, parse: P) -> Result<(T, String), Box<dyn Error>>
+    where
+        P: Fn(&str) -> Result<T, Box<dyn Error>>,
+    {
+        let body = self.fetch_accepting(url, &|body| parse(body).is_ok())?;
+        Ok((parse(&body)?, body))
+    }
+
+    fn fetch_accepting(&self, url: &str, accept: &dyn Fn(&str) -> bool) -> Result<String, Box<dyn Error>> {
+        let Some(ref cache) = self.cache else {
+            return match tokio::task::block_in_place(|| self.request(url, None, accept))? {
+                Fetched::Page(body, _) => Ok(body),
+                Fetched::NotModified => Err(format!("Unexpected 304 Not Modified for {}", url).into()),
+            };
+        };
+
+        // A cached block page is a miss, and its validators would only get it confirmed
+        let mut revalidate = true;
+        if let Some(body) = cache.get(url) {
+            if accept(&body) {
+                debug!(url, "Page cache hit");
+                return Ok(body);
+            }
+            debug!(url, "Cached page rejected, refetching");
+            revalidate = false;
+        }
+
+        let validators = cache.validators(url).filter(|_| revalidate);
+        match tokio::task::block_in_place(|| self.request(url, validators.as_ref(), accept))? {
+            Fetched::Page(body, validators) => {
+                if let Err(e) = cache.put_with_validators(url, &body, &validators) {
+                    warn!(url, error = %e, "Failed to write page cache");
+                }
+                Ok(body)
+            }
+            Fetched::NotModified => {
+                debug!(url, "Not modified, using cached page");
+                self.not_modified.fetch_add(1, Ordering::Relaxed);
+                if let Err(e) = cache.touch(url) {
+                    warn!(url, error = %e, "Failed to refresh page cache entry");
+                }
+                let body = cache.stored_body(url).ok_or_else(|| format!("Cached page for {} disappeared", url))?;
+                if accept(&body) {
+                    return Ok(body);
+                }
+                debug!(url, "Revalidated page rejected, refetching");
+                self.refetch(cache, url, accept)
+            }
+        }
+    }
+
+    /// Unconditional request replacing a cache entry
+    fn refetch(&self, cache: &PageCache, url: &str, accept: &dyn Fn(&str) -> bool) -> Result<String, Box<dyn Error>> {
+        match tokio::task::block_in_place(|| self.request(url, None, accept))? {
+            Fetched::Page(body, validators) => {
+                if let Err(e) = cache.put_with_validators(url, &body, &validators) {
+                    warn!(url, error = %e, "Failed to write page cache");
+                }
+                Ok(body)
+            }
+            Fetched::NotModified => Err(format!("Unexpected 304 Not Modified for {}", url).into()),
+        }
+    }
+
+    fn request(&self, url: &str, validators: Option<&Validators>, accept: &dyn Fn(&str) -> bool) -> Result<Fetched, Box<dyn Error>> {
+        // Chains take their slots in the link that makes the request
+        if let Some(ref limiter) = self.global_limiter {
+            if !matches!(self.source, FetchSource::Chain(_) | FetchSource::Saved(_)) {
+                limiter.acquire_blocking()?;
+            }
+        }
+        match self.source {
+            FetchSource::Chain(ref links) => fetch_through_chain(links, url, validators, accept),
+            FetchSource::Direct => {
+                self.budget.reserve(Spend::DirectRequest)?;
+                fetch_direct(url, validators, &self.stats, self.adaptive_delay.as_ref(), &self.user_agents)
+            }
+            FetchSource::Proxy { ref manager, attempts } => {
+                fetch_with_proxies(manager, attempts, &self.budget, &self.stats, self.adaptive_delay.as_ref(), url, validators)
+            }
+            FetchSource::ScrapingBee(ref client) => {
+                self.budget.reserve(Spend::ScrapingBeeCredits)?;
+                let (body, cost) = client.fetch_billed(url)?;
+                self.budget.record_credits(cost);
+                Ok(Fetched::Page(body, Validators::default()))
+            }
+            FetchSource::Saved(ref pages) => Ok(Fetched::Page(pages.get(url)?, Validators::default())),
+            #[cfg(feature = "browser")]
+            FetchSource::Browser(ref browser) => {
+                // The browser hits the site from our own address, like a direct request
+                self.budget.reserve(Spend::DirectRequest)?;
+                let started = Instant::now();
+                match browser.fetch(url, &self.user_agents.user_agent()) {
+                    Ok(body) => {
+                        self.stats.record_response(ScrapeProvider::Browser, 200, body.len(), started.elapsed());
+                        if let Some(ref adaptive_delay) = self.adaptive_delay {
+                            adaptive_delay.record(200);
+                        }
+                        Ok(Fetched::Page(body, Validators::default()))
+                    }
+                    Err(e) => {
+                        self.stats.record_error(ScrapeProvider::Browser, started.elapsed());
+                        Err(e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "browser")]
+fn browser_source(config: &Config) -> Result<FetchSource, Box<dyn Error>> {
+    let browser = HeadlessBrowser::from_config(&config.browser)?;
+    info!(browser = ?browser, "Fetching through headless Chrome");
+    Ok(FetchSource::Browser(browser))
+}
+
+#[cfg(not(feature = "browser"))]
+fn browser_source(_config: &Config) -> Result<FetchSource, Box<dyn Error>> {
+    Err("provider 'browser' needs a build with the `browser` feature".into())
+}
+
+/// Fetchers for `[chain]` providers, skipping the ones that cannot be set up (no proxies, no API keys)
+fn chain_links(config: &Config) -> Result<Vec<ChainLink>, Box<dyn Error>> {
+    let mut links = Vec::new();
+    for &provider in &config.chain.providers {
+        if matches!(provider, ScrapeProvider::Hybrid | ScrapeProvider::Chain) {
+            return Err(format!("provider '{}' cannot be part of a chain", provider).into());
+        }
+        // The chain's own cache sits in front of every link
+        match PageFetcher::new(provider, config) {
+            Ok(fetcher) => links.push(ChainLink {
+                fetcher: fetcher.without_cache(),
+                provider,
+                budget: config.chain.budget(provider),
+                used: AtomicU64::new(0),
+                exhausted: AtomicBool::new(false),
+            }),
+            Err(e) => warn!(provider = %provider, error = %e, "Chain provider unavailable, skipping it"),
+        }
+    }
+
+    if links.is_empty() {
+        return Err("No provider of the chain is available".into());
+    }
+    let order: Vec<String> = links.iter().map(|link| link.provider.to_string()).collect();
+    info!(providers = %order.join(" -> "), "Fetching through a provider chain");
+    Ok(links)
+}
+
+/// Try each provider with budget left until one returns an acceptable page
+///
+/// Errors and rejected pages (rate limits, blocks, exhausted keys) escalate to the
+/// next provider; a 404 is the page's real answer and ends the chain.
+fn fetch_through_chain(
+    links: &[ChainLink],
+    url: &str,
+    validators: Option<&Validators>,
+    accept: &dyn Fn(&str) -> bool,
+) -> Result<Fetched, Box<dyn Error>> {
+    let mut last_error: Box<dyn Error> = "every provider's budget is spent".into();
+
+    for link in links {
+        if link.exhausted.load(Ordering::Relaxed) || link.budget.is_some_and(|budget| link.used.load(Ordering::Relaxed) >= budget) {
+            debug!(provider = %link.provider, "Budget spent, skipping provider");
+            continue;
+        }
+        link.used.fetch_add(1, Ordering::Relaxed);
+
+        match link.fetcher.request(url, validators, accept) {
+            Ok(Fetched::Page(body, _)) if !accept(&body) => {
+                warn!(provider = %link.provider, url, "Page looks blocked, escalating");
+                last_error = format!("{} returned a page without the expected content", link.provider).into();
+            }
+            Ok(fetched) => {
+                debug!(provider = %link.provider, url, "Fetched through chain");
+                return Ok(fetched);
+            }
+            Err(e) if is_not_found(e.as_ref()) => return Err(e),
+            Err(e) => {
+                if is_exhausted(e.as_ref()) {
+                    link.exhausted.store(true, Ordering::Relaxed);
+                }
+                warn!(provider = %link.provider, error = %e, "Provider failed, escalating");
+                last_error = e;
+            }
+        }
+    }
+
+    Err(format!("No provider of the chain could fetch {}: {}", url, last_error).into())
+}
+
+/// Build a GET, conditional when validators are known
+fn conditional_get(client: &blocking::Client, url: &str, validators: Option<&Validators>) -> blocking::RequestBuilder {
+    match validators {
+        Some(validators) => validators.apply(client.get(url)),
+        None => client.get(url),
+    }
+}
+
+fn fetch_direct(
+    url: &str,
+    validators: Option<&Validators>,
+    stats: &HttpStats,
+    adaptive_delay: Option<&AdaptiveDelay>,
+    user_agents: &UserAgentPool,
+) -> Result<Fetched, Box<dyn Error>> {
+    let client = blocking::Client::builder().user_agent(user_agents.user_agent()).build()?;
+    let started = Instant::now();
+    let response = match conditional_get(&client, url, validators).send() {
+        Ok(response) => response,
+        Err(e) => {
+            stats.record_error(ScrapeProvider::Direct, started.elapsed());
+            return Err(e.into());
+        }
+    };
+
+    let status = response.status();
+    if let Some(adaptive_delay) = adaptive_delay {
+        adaptive_delay.record(status.as_u16());
+    }
+    if !status.is_success() {
+        stats.record_response(ScrapeProvider::Direct, status.as_u16(), 0, started.elapsed());
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        return Err(format!("GSMArena returned status: {}", status).into());
+    }
+
+    let validators = Validators::from_headers(response.headers());
+    let body = response.text()?;
+    stats.record_response(ScrapeProvider::Direct, status.as_u16(), body.len(), started.elapsed());
+    Ok(Fetched::Page(body, validators))
+}
+
+/// Try successive proxies until one returns the page
+fn fetch_with_proxies(
+    manager: &ProxyManager,
+    attempts: usize,
+    budget: &RequestBudget,
+    stats: &HttpStats,
+    adaptive_delay: Option<&AdaptiveDelay>,
+    url: &str,
+    validators: Option<&Validators>,
+) -> Result<Fetched, Box<dyn Error>> {
+    for attempt in 1..=attempts {
+        budget.reserve(Spend::ProxyRequest)?;
+        let client = match manager.create_client_with_next_proxy() {
+            Ok(c) => c,
+            Err(e) => {
+                warn!(error = %e, "Failed to create proxy client");
+                continue;
+            }
+        };
+
+        let started = Instant::now();
+        let sent = conditional_get(&client, url, validators).send();
+        if let (Ok(response), Some(adaptive_delay)) = (&sent, adaptive_delay) {
+            adaptive_delay.record(response.status().as_u16());
+        }
+        match sent {
+            Ok(response) if response.status() == StatusCode::NOT_MODIFIED => {
+                stats.record_response(ScrapeProvider::Proxy, 304, 0, started.elapsed());
+                return Ok(Fetched::NotModified);
+            }
+            Ok(response) if response.status().is_success() => {
+                let status = response.status().as_u16();
+                let validators = Validators::from_headers(response.headers());
+                let body = response.text()?;
+                stats.record_response(ScrapeProvider::Proxy, status, body.len(), started.elapsed());
+                return Ok(Fetched::Page(body, validators));
+            }
+            Ok(response) => {
+                stats.record_response(ScrapeProvider::Proxy, response.status().as_u16(), 0, started.elapsed());
+                warn!(status = %response.status(), attempt, attempts, "Proxy returned an error status, trying next proxy");
+            }
+            Err(e) => {
+                stats.record_error(ScrapeProvider::Proxy, started.elapsed());
+                // Connection errors are common with free proxies; keep them out of the default output
+                debug!(attempt, attempts, error = %e, "Proxy error");
+            }
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+
+    Err(format!("Failed to fetch {} after trying {} proxies", url, attempts).into())
+}
+
+/// Whether an error is a 404 from GSMArena or ScrapingBee (the page does not exist)
+fn is_not_found(error: &dyn Error) -> bool {
+    error.to_string().contains("status: 404")
+}
+
+/// Whether an error means every ScrapingBee API key is used up
+pub fn is_exhausted(error: &dyn Error) -> bool {
+    let message = error.to_string();
+    message.contains("exhausted") || message.contains("All API keys")
+}
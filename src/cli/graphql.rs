@@ -0,0 +1,33 @@
+use super::CollectionArgs;
+use clap::Args;
+use gsmarena_scraper::graphql::{build_schema, serve};
+use gsmarena_scraper::{Config, MongoDBClient, MongoPhoneStore, PhoneStore};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Debug, Args)]
+pub struct GraphqlArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Address the GraphQL endpoint listens on
+    #[arg(long, default_value = "127.0.0.1:8000")]
+    pub bind: SocketAddr,
+}
+
+impl GraphqlArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+pub async fn run(args: GraphqlArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let store: Arc<dyn PhoneStore> = Arc::new(MongoPhoneStore::new(mongo_client, config.collections.clone()).with_upsert_policy(config.mongodb.upsert_policy));
+
+    println!("GraphQL endpoint: http://{}/graphql", args.bind);
+    serve(build_schema(store), args.bind).await
+}
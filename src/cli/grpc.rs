@@ -0,0 +1,33 @@
+use super::CollectionArgs;
+use clap::Args;
+use gsmarena_scraper::grpc::serve;
+use gsmarena_scraper::{Config, MongoDBClient, MongoPhoneStore, PhoneStore};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Debug, Args)]
+pub struct GrpcArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Address the gRPC server listens on
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    pub bind: SocketAddr,
+}
+
+impl GrpcArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+pub async fn run(args: GrpcArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let store: Arc<dyn PhoneStore> = Arc::new(MongoPhoneStore::new(mongo_client, config.collections.clone()).with_upsert_policy(config.mongodb.upsert_policy));
+
+    println!("gRPC server: {} (service gsmarena.phones.v1.Phones)", args.bind);
+    serve(store, args.bind).await
+}
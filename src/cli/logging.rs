@@ -0,0 +1,31 @@
+use super::progress::LogWriter;
+use clap::ValueEnum;
+use std::io::IsTerminal;
+use tracing_subscriber::EnvFilter;
+
+/// Log output format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event, with the current brand/phone span (Loki, CloudWatch)
+    Json,
+}
+
+/// Install the global subscriber; logs go to stderr so command output on stdout stays clean
+///
+/// `RUST_LOG` takes precedence over `level` when set (e.g. `RUST_LOG=gsmarena_scraper=debug`).
+pub fn init(format: LogFormat, level: &str) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(|| LogWriter)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false);
+
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(true).init(),
+    }
+}
@@ -0,0 +1,70 @@
+use super::CollectionArgs;
+use clap::Args;
+use gsmarena_scraper::migrations::{Migrator, META_COLLECTION, MIGRATIONS};
+use gsmarena_scraper::{Config, MongoDBClient};
+use std::error::Error;
+use tracing::info;
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// List every migration and whether it has been applied
+    #[arg(long, conflicts_with_all = ["dry_run", "to"])]
+    pub status: bool,
+
+    /// Show the pending migrations without applying them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Stop after this migration version
+    #[arg(long, value_name = "VERSION")]
+    pub to: Option<u32>,
+}
+
+impl MigrateArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+pub async fn run(args: MigrateArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let migrator = Migrator::new(&mongo_client, config);
+
+    if args.status {
+        let applied = migrator.applied().await?;
+
+        println!("\nMigrations ({} collection):", META_COLLECTION);
+        for migration in MIGRATIONS {
+            let state = match applied.iter().find(|a| a.version == migration.version) {
+                Some(record) => format!("applied {}", record.applied_at.format("%Y-%m-%d %H:%M")),
+                None => "pending".to_string(),
+            };
+            println!("  {:>3}  {:28} {:18} {}", migration.version, migration.name, state, migration.description);
+        }
+        return Ok(());
+    }
+
+    let pending = migrator.pending(args.to).await?;
+    if pending.is_empty() {
+        info!("✓ Database is up to date");
+        return Ok(());
+    }
+
+    if args.dry_run {
+        for migration in pending {
+            info!(version = migration.version, name = migration.name, description = migration.description, "Pending");
+        }
+        return Ok(());
+    }
+
+    for migration in pending {
+        migrator.apply(migration).await?;
+    }
+
+    info!("✓ Migrations complete");
+    Ok(())
+}
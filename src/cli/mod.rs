@@ -0,0 +1,271 @@
+pub mod analytics;
+pub mod compare;
+pub mod config;
+pub mod daemon;
+pub mod db;
+pub mod discover;
+pub mod doctor;
+pub mod export;
+pub mod fetch;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod logging;
+pub mod migrate;
+pub mod progress;
+pub mod proxy;
+pub mod reprocess;
+pub mod retry;
+pub mod scrape;
+pub mod specs;
+pub mod upcoming;
+pub mod watch;
+
+use clap::{Args, Parser, Subcommand};
+use gsmarena_scraper::search::device_id_from_phone_id;
+use gsmarena_scraper::resolve::resolve_phone_id_from;
+use gsmarena_scraper::site::SiteConfig;
+use gsmarena_scraper::{Config, CronSchedule, ErrorPolicy, Shutdown};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// GSMArena scraper: discovery, specifications and MongoDB storage
+///
+/// Settings come from `scraper.toml` / `scraper.yaml`, then environment variables
+/// (see `.env.example`), then flags. `config print-effective` shows the result.
+#[derive(Debug, Parser)]
+#[command(name = "gsmarena-scraper", version)]
+pub struct Cli {
+    /// Config file (default: $GSMARENA_CONFIG, then ./scraper.toml or ./scraper.yaml)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Log output format
+    #[arg(long, global = true, value_enum, env = "LOG_FORMAT", default_value = "text")]
+    pub log_format: logging::LogFormat,
+
+    /// Minimum log level (error, warn, info, debug, trace); RUST_LOG overrides it
+    #[arg(long, global = true, env = "LOG_LEVEL", default_value = "info")]
+    pub log_level: String,
+
+    /// Disable progress bars (they are only shown on a terminal outside CI anyway)
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+
+    /// Fetch pages from this mirror or test server [config: site.base_url]
+    #[arg(long, global = true, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// On failed phones and listings: continue, fail-fast or fail-after-N (scrape, retry-failed, specs, reprocess, discover) [config: scraping.error_policy]
+    #[arg(long, global = true, value_name = "POLICY")]
+    pub error_policy: Option<ErrorPolicy>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Scrape brands, phone lists and specifications into MongoDB
+    Scrape(Box<scrape::ScrapeArgs>),
+    /// List brands and their phones without fetching specifications
+    Discover(discover::DiscoverArgs),
+    /// Apply pending schema migrations (tracked in the _meta collection)
+    Migrate(migrate::MigrateArgs),
+    /// Re-derive structured specs from the stored specifications_raw, without HTTP traffic
+    Reprocess(reprocess::ReprocessArgs),
+    /// Re-attempt the failed phones of the last run, escalating direct -> proxy -> ScrapingBee
+    RetryFailed(retry::RetryArgs),
+    /// Fetch specifications for phone IDs or names
+    Specs(specs::SpecsArgs),
+    /// Export phones from MongoDB to a JSON file
+    Export(export::ExportArgs),
+    /// Compare phones side by side with per-category winners and a weighted score
+    Compare(compare::CompareArgs),
+    /// Corpus statistics: battery and display trends, 5G share per brand, OS versions
+    Analytics(analytics::AnalyticsArgs),
+    /// Proxy pool utilities
+    Proxy {
+        #[command(subcommand)]
+        command: proxy::ProxyCommand,
+    },
+    /// Database utilities
+    Db {
+        #[command(subcommand)]
+        command: db::DbCommand,
+    },
+    /// Track rumored and coming-soon devices until they launch
+    Upcoming(upcoming::UpcomingArgs),
+    /// Stream inserts and updates of the phones collection to stdout, webhooks or chat
+    Watch(watch::WatchArgs),
+    /// Serve the stored phones over GraphQL (requires the `graphql` feature)
+    #[cfg(feature = "graphql")]
+    ServeGraphql(graphql::GraphqlArgs),
+    /// Serve phone lookups over gRPC (requires the `grpc` feature)
+    #[cfg(feature = "grpc")]
+    ServeGrpc(grpc::GrpcArgs),
+    /// Check that MongoDB, the Appwrite proxies, ScrapingBee and GSMArena are reachable
+    #[command(alias = "healthcheck")]
+    Doctor(doctor::DoctorArgs),
+    /// Configuration utilities
+    Config {
+        #[command(subcommand)]
+        command: config::ConfigCommand,
+    },
+}
+
+impl Command {
+    /// Whether the subcommand stops on `--error-policy`; the others reject the flag
+    fn honors_error_policy(&self) -> bool {
+        matches!(self, Self::Scrape(_) | Self::RetryFailed(_) | Self::Specs(_) | Self::Reprocess(_) | Self::Discover(_))
+    }
+}
+
+/// MongoDB collection overrides shared by the subcommands that touch the database
+#[derive(Debug, Clone, Args)]
+pub struct CollectionArgs {
+    /// Collection holding full phone specifications [config: collections.phones]
+    #[arg(long)]
+    pub collection: Option<String>,
+
+    /// Collection holding brand metadata [config: collections.brands]
+    #[arg(long)]
+    pub brands_collection: Option<String>,
+
+    /// Collection holding every discovered phone and whether its specs are saved [config: collections.phone_list]
+    #[arg(long)]
+    pub phone_list_collection: Option<String>,
+}
+
+impl CollectionArgs {
+    pub fn apply(&self, config: &mut Config) {
+        override_with(&mut config.collections.phones, &self.collection);
+        override_with(&mut config.collections.brands, &self.brands_collection);
+        override_with(&mut config.collections.phone_list, &self.phone_list_collection);
+    }
+}
+
+pub async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    if cli.error_policy.is_some() && !cli.command.honors_error_policy() {
+        return Err("--error-policy only applies to scrape, retry-failed, specs, reprocess and discover".into());
+    }
+    let mut config = Config::load(cli.config.as_deref())?;
+    override_with(&mut config.site.base_url, &cli.base_url);
+    override_with(&mut config.scraping.error_policy, &cli.error_policy);
+
+    match cli.command {
+        Command::Scrape(args) => {
+            args.apply(&mut config);
+            let config = validated(config)?;
+            let shutdown = Shutdown::new().install();
+
+            if args.daemon {
+                let schedule = config.daemon.schedule.as_deref().ok_or("--daemon needs --schedule or daemon.schedule")?;
+                let schedule = CronSchedule::parse(schedule, Duration::from_secs(config.daemon.jitter_secs))?;
+                daemon::run_scheduled(&schedule, &shutdown, || scrape::run(&config, &shutdown)).await
+            } else {
+                scrape::run(&config, &shutdown).await
+            }
+        }
+        Command::Discover(args) => {
+            args.apply(&mut config);
+            discover::run(args, &validated(config)?).await
+        }
+        Command::Migrate(args) => {
+            args.apply(&mut config);
+            migrate::run(args, &validated(config)?).await
+        }
+        Command::Reprocess(args) => {
+            args.apply(&mut config);
+            reprocess::run(args, &validated(config)?).await
+        }
+        Command::RetryFailed(args) => {
+            args.apply(&mut config);
+            retry::run(args, &validated(config)?).await
+        }
+        Command::Specs(args) => {
+            args.apply(&mut config);
+            specs::run(args, &validated(config)?).await
+        }
+        Command::Export(args) => {
+            args.collections.apply(&mut config);
+            export::run(args, &validated(config)?).await
+        }
+        Command::Compare(args) => {
+            args.collections.apply(&mut config);
+            compare::run(args, &validated(config)?).await
+        }
+        Command::Analytics(args) => {
+            args.collections.apply(&mut config);
+            analytics::run(args, &validated(config)?).await
+        }
+        Command::Proxy { command } => proxy::run(command, &validated(config)?).await,
+        Command::Db { command } => db::run(command, config).await,
+        Command::Upcoming(args) => {
+            args.apply(&mut config);
+            upcoming::run(args, &validated(config)?).await
+        }
+        Command::Watch(args) => {
+            args.apply(&mut config);
+            watch::run(args, &validated(config)?).await
+        }
+        #[cfg(feature = "graphql")]
+        Command::ServeGraphql(args) => {
+            args.apply(&mut config);
+            graphql::run(args, &validated(config)?).await
+        }
+        #[cfg(feature = "grpc")]
+        Command::ServeGrpc(args) => {
+            args.apply(&mut config);
+            grpc::run(args, &validated(config)?).await
+        }
+        Command::Doctor(args) => {
+            args.apply(&mut config);
+            doctor::run(args, &validated(config)?).await
+        }
+        Command::Config { command } => config::run(command, config),
+    }
+}
+
+/// Reject invalid configurations before any work starts, then install the selector overrides
+pub fn validated(config: Config) -> Result<Config, Box<dyn Error>> {
+    if let Err(problems) = config.validate() {
+        return Err(format!("Invalid configuration:\n  {}", problems.join("\n  ")).into());
+    }
+    gsmarena_scraper::selectors::configure(&config.selectors).map_err(|problems| problems.join("; "))?;
+    Ok(config)
+}
+
+/// Replace a config value with a flag value when the flag was given
+pub fn override_with<T: Clone>(target: &mut T, value: &Option<T>) {
+    if let Some(value) = value {
+        *target = value.clone();
+    }
+}
+
+/// Format an optional limit for the configuration summary
+pub fn limit_label(limit: Option<usize>) -> String {
+    limit.map(|n| n.to_string()).unwrap_or_else(|| "ALL".to_string())
+}
+
+/// Accept either a phone ID ("apple_iphone_15-12559") or a name ("iphone 15")
+/// Names are resolved to the best-scoring phone ID
+pub fn phone_id_for(site: &SiteConfig, query: &str) -> Result<String, Box<dyn Error>> {
+    if device_id_from_phone_id(query).is_some() {
+        return Ok(query.to_string());
+    }
+
+    let query_owned = query.to_string();
+    let candidates = tokio::task::block_in_place(|| resolve_phone_id_from(&site.base_url, &query_owned))?;
+
+    match candidates.into_iter().next() {
+        Some((phone_id, score)) => {
+            info!(query, phone_id = %phone_id, score = format_args!("{:.3}", score), "Resolved phone name");
+            Ok(phone_id)
+        }
+        None => Err(format!("No phone found matching \"{}\"", query).into()),
+    }
+}
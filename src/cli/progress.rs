@@ -0,0 +1,96 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+
+static MULTI: OnceLock<MultiProgress> = OnceLock::new();
+
+/// Enable progress bars when stderr is an interactive terminal outside CI
+pub fn init(enabled: bool) {
+    if enabled && io::stderr().is_terminal() && std::env::var_os("CI").is_none() {
+        let _ = MULTI.set(MultiProgress::new());
+    }
+}
+
+fn multi() -> Option<&'static MultiProgress> {
+    MULTI.get()
+}
+
+/// Log writer that hides the bars while a line is printed, so logs scroll above them
+pub struct LogWriter;
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match multi() {
+            Some(multi) => multi.suspend(|| io::stderr().write(buf)),
+            None => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// Brands, phones within the current brand, and all phones of the run
+pub struct ScrapeProgress {
+    brands: ProgressBar,
+    phones: ProgressBar,
+    overall: ProgressBar,
+}
+
+impl ScrapeProgress {
+    /// `None` when progress bars are disabled; `phone_estimate` is refined as phone lists arrive
+    pub fn new(brand_total: usize, phone_estimate: usize) -> Option<Self> {
+        let multi = multi()?;
+
+        let style = |template: &str| {
+            ProgressStyle::with_template(template)
+                .unwrap()
+                .progress_chars("=> ")
+        };
+
+        let overall = multi.add(ProgressBar::new(phone_estimate as u64));
+        overall.set_style(style("{prefix:>8} [{bar:40.green}] {pos}/{len} phones  {per_sec}  ETA {eta}"));
+        overall.set_prefix("Overall");
+
+        let brands = multi.add(ProgressBar::new(brand_total as u64));
+        brands.set_style(style("{prefix:>8} [{bar:40.cyan}] {pos}/{len} brands  {msg}"));
+        brands.set_prefix("Brands");
+
+        let phones = multi.add(ProgressBar::new(0));
+        phones.set_style(style("{prefix:>8} [{bar:40.blue}] {pos}/{len} {msg}"));
+        phones.set_prefix("Phones");
+
+        Some(Self { brands, phones, overall })
+    }
+
+    pub fn start_brand(&self, name: &str) {
+        self.brands.set_message(name.to_string());
+        self.phones.reset();
+        self.phones.set_length(0);
+        self.phones.set_message(String::new());
+    }
+
+    /// Replace the brand's estimated phone count with the real one once its list is known
+    pub fn set_brand_phones(&self, estimated: usize, actual: usize) {
+        self.phones.set_length(actual as u64);
+        let length = self.overall.length().unwrap_or(0).saturating_sub(estimated as u64) + actual as u64;
+        self.overall.set_length(length.max(self.overall.position()));
+    }
+
+    pub fn phone_done(&self, name: &str) {
+        self.phones.set_message(name.to_string());
+        self.phones.inc(1);
+        self.overall.inc(1);
+    }
+
+    pub fn brand_done(&self) {
+        self.brands.inc(1);
+    }
+
+    pub fn finish(&self) {
+        self.phones.finish_and_clear();
+        self.brands.finish_and_clear();
+        self.overall.finish();
+    }
+}
@@ -0,0 +1,63 @@
+use clap::Subcommand;
+use gsmarena_scraper::config::ProxySettings;
+use gsmarena_scraper::{Config, ProxyManager};
+use std::error::Error;
+use std::time::Instant;
+use tracing::info;
+
+#[derive(Debug, Subcommand)]
+pub enum ProxyCommand {
+    /// Load the proxy pool from Appwrite and check which proxies reach GSMArena
+    Test {
+        /// Page requested through each proxy (default: the makers page of site.base_url)
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Maximum number of proxies to test
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
+pub async fn run(command: ProxyCommand, config: &Config) -> Result<(), Box<dyn Error>> {
+    match command {
+        ProxyCommand::Test { url, limit } => {
+            let url = url.unwrap_or_else(|| config.site.makers_url());
+            tokio::task::block_in_place(|| test_proxies(&config.proxy, &url, limit))
+        }
+    }
+}
+
+fn test_proxies(settings: &ProxySettings, url: &str, limit: Option<usize>) -> Result<(), Box<dyn Error>> {
+    let manager = ProxyManager::from_settings(settings)
+        .map_err(|e| format!("Appwrite settings are required: {}", e))?;
+    manager.fetch_proxies()?;
+
+    let proxies = manager.get_all_proxies();
+    let to_test = limit.unwrap_or(proxies.len()).min(proxies.len());
+    info!(count = to_test, url, "Testing proxies");
+
+    let mut working = 0;
+
+    for (index, proxy) in proxies.iter().take(to_test).enumerate() {
+        print!("  [{}/{}] {} ({}) ", index + 1, to_test, proxy.to_url(), proxy.proxy_type);
+
+        let started = Instant::now();
+        let result = manager
+            .create_client_with_proxy(proxy)
+            .and_then(|client| Ok(client.get(url).send()?));
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                println!("✓ {}ms", started.elapsed().as_millis());
+                working += 1;
+            }
+            Ok(response) => println!("✗ status {}", response.status()),
+            Err(e) => println!("✗ {}", e.to_string().chars().take(80).collect::<String>()),
+        }
+    }
+
+    println!("\n✓ {}/{} proxies working", working, to_test);
+
+    Ok(())
+}
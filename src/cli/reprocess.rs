@@ -0,0 +1,142 @@
+use super::CollectionArgs;
+use clap::Args;
+use futures::stream::StreamExt;
+use gsmarena_scraper::scraper::parse_specification_html;
+use gsmarena_scraper::{validate, Config, MongoDBClient, PhoneDocument, SnapshotStore};
+use mongodb::bson::doc;
+use std::error::Error;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Args)]
+pub struct ReprocessArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Only reprocess phones of this brand (exact name as stored)
+    #[arg(long)]
+    pub brand: Option<String>,
+
+    /// Only reprocess these phones (repeatable)
+    #[arg(long = "phone-id", value_name = "ID")]
+    pub phone_ids: Vec<String>,
+
+    /// Re-parse specifications_raw from the stored HTML snapshot first (needs snapshots.storage)
+    #[arg(long)]
+    pub from_snapshots: bool,
+
+    /// Report what would change without writing
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl ReprocessArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+#[derive(Debug, Default)]
+struct ReprocessCounts {
+    scanned: usize,
+    updated: usize,
+    unchanged: usize,
+    failed: usize,
+}
+
+pub async fn run(args: ReprocessArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let collection_name = &config.collections.phones;
+
+    let snapshot_store = if args.from_snapshots {
+        let store = SnapshotStore::from_config(&config.snapshots, &mongo_client.database())
+            .await?
+            .ok_or("--from-snapshots needs snapshots.storage (HTML_SNAPSHOT_STORAGE)")?;
+        Some(store)
+    } else {
+        None
+    };
+
+    let mut filter = doc! {};
+    if let Some(ref brand) = args.brand {
+        filter.insert("brand", brand);
+    }
+    if !args.phone_ids.is_empty() {
+        filter.insert("phone_id", doc! { "$in": &args.phone_ids });
+    }
+
+    info!(collection = %collection_name, dry_run = args.dry_run, from_snapshots = args.from_snapshots, "Reprocessing stored specifications");
+
+    let mut cursor = mongo_client.get_collection(collection_name).find(filter, None).await?;
+    let mut counts = ReprocessCounts::default();
+    let error_policy = config.scraping.error_policy;
+
+    while let Some(result) = cursor.next().await {
+        if error_policy.stop_reason(counts.failed as u64).is_some() {
+            break;
+        }
+        counts.scanned += 1;
+        if counts.scanned % 500 == 0 {
+            info!(scanned = counts.scanned, updated = counts.updated, "Progress");
+        }
+
+        let mut phone = match result {
+            Ok(phone) => phone,
+            Err(e) => {
+                error!(error = %e, "✗ Unreadable document");
+                counts.failed += 1;
+                continue;
+            }
+        };
+
+        if let Some(ref store) = snapshot_store {
+            if let Err(e) = refresh_raw_from_snapshot(store, &mut phone).await {
+                warn!(phone = %phone.phone_id, error = %e, "Snapshot not usable, keeping specifications_raw");
+            }
+        }
+
+        let reparsed = phone.reparse();
+        let revalidated = phone.apply_validation(&validate(&phone, config.validation.min_completeness));
+        if !reparsed && !revalidated {
+            counts.unchanged += 1;
+            continue;
+        }
+
+        if args.dry_run {
+            info!(phone = %phone.phone_id, "Would update");
+            counts.updated += 1;
+            continue;
+        }
+
+        match mongo_client.upsert_phone(collection_name, phone).await {
+            Ok(()) => counts.updated += 1,
+            Err(e) => {
+                error!(error = %e, "✗ MongoDB error");
+                counts.failed += 1;
+            }
+        }
+    }
+
+    info!(
+        scanned = counts.scanned,
+        updated = counts.updated,
+        unchanged = counts.unchanged,
+        failed = counts.failed,
+        dry_run = args.dry_run,
+        "✓ Reprocessing complete"
+    );
+
+    match error_policy.stop_reason(counts.failed as u64) {
+        Some(reason) => Err(format!("Reprocessing aborted: {}", reason).into()),
+        None => Ok(()),
+    }
+}
+
+/// Replace `specifications_raw` with a fresh parse of the phone's HTML snapshot
+async fn refresh_raw_from_snapshot(store: &SnapshotStore, phone: &mut PhoneDocument) -> Result<(), Box<dyn Error>> {
+    let snapshot = phone.html_snapshot.as_ref().ok_or("no HTML snapshot")?;
+    let html = store.load(snapshot).await?;
+    let spec = parse_specification_html(&phone.phone_id, &html)?;
+    phone.specifications_raw = serde_json::to_value(&spec)?;
+    Ok(())
+}
@@ -0,0 +1,300 @@
+use super::fetch::PageFetcher;
+use super::scrape::{failures, fetch_phone, log_failures, transactions_available};
+use super::CollectionArgs;
+use chrono::Utc;
+use clap::Args;
+use gsmarena_scraper::lifecycle;
+use gsmarena_scraper::pricing::parse_market_prices;
+use gsmarena_scraper::run_report::BrandOutcome;
+use gsmarena_scraper::scraper::phone_page_url;
+use gsmarena_scraper::{
+    validate, AdaptiveDelay, Config, DeviceType, FailedPhone, FailureCategory, GlobalRateLimiter, MongoDBClient, PhoneDocument, PhoneListItem, PhonePages, PriceSnapshot, RequestBudget, RunReport, ScrapeProvider, Shutdown,
+};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, info_span, warn, Instrument};
+
+#[derive(Debug, Args)]
+pub struct RetryArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Run report to read failed phones from [default: config report.path]
+    #[arg(long, value_name = "PATH", conflicts_with = "from_db")]
+    pub report: Option<PathBuf>,
+
+    /// Read the latest report from the runs collection (report.collection) instead of a file
+    #[arg(long)]
+    pub from_db: bool,
+
+    /// Strategies tried in order for each phone until one succeeds
+    #[arg(long, value_delimiter = ',', default_value = "direct,proxy,scrapingbee")]
+    pub strategies: Vec<ScrapeProvider>,
+}
+
+impl RetryArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+pub async fn run(args: RetryArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let transactional = config.mongodb.transactions && transactions_available(&mongo_client).await;
+
+    let previous = if args.from_db {
+        let collection = config.report.collection.as_deref().ok_or("--from-db needs report.collection (SCRAPE_RUNS_COLLECTION)")?;
+        mongo_client
+            .latest_run_report(collection)
+            .await?
+            .ok_or_else(|| format!("No run reports in {}", collection))?
+    } else {
+        let path = args.report.clone().unwrap_or_else(|| PathBuf::from(&config.report.path));
+        RunReport::read_json(&path)?
+    };
+
+    let unfinished: Vec<BrandOutcome> = previous.unfinished_listings().cloned().collect();
+    let failed = previous.failed_phones;
+    if failed.is_empty() && unfinished.is_empty() {
+        info!(run_id = %previous.run_id, "✓ No failed phones or brand listings in the last run");
+        return Ok(());
+    }
+    info!(run_id = %previous.run_id, count = failed.len(), listings = unfinished.len(), "Retrying failed phones");
+
+    // Strategies that cannot be set up (no proxies, no API keys) are skipped
+    let budget = RequestBudget::new(&config.budget);
+    let global_limiter = GlobalRateLimiter::from_config(config, &mongo_client.database()).await?;
+    let adaptive_delay = AdaptiveDelay::from_config(&config.rate_limit);
+    let mut fetchers = Vec::new();
+    for &strategy in &args.strategies {
+        if strategy == ScrapeProvider::Hybrid {
+            warn!("The hybrid provider is not a retry strategy, skipping it");
+            continue;
+        }
+        // A cached page may be the very one that failed to parse, so retries always refetch
+        match PageFetcher::new(strategy, config) {
+            Ok(fetcher) => fetchers.push(fetcher.without_cache().with_budget(&budget).with_global_limiter(global_limiter.as_ref()).with_adaptive_delay(adaptive_delay.as_ref())),
+            Err(e) => warn!(strategy = %strategy, error = %e, "Strategy unavailable, skipping it"),
+        }
+    }
+    if fetchers.is_empty() {
+        return Err("No retry strategy is available".into());
+    }
+
+    let shutdown = Shutdown::new().install();
+    let mut report = RunReport::new(config);
+    report.counts.phones_found = failed.len();
+
+    for (index, phone) in failed.iter().enumerate() {
+        if let Some(cap) = budget.exhausted() {
+            warn!(budget = cap, remaining = failed.len() - index, "Run budget reached, stopping");
+            shutdown.request();
+        }
+        if shutdown.is_requested() {
+            warn!(remaining = failed.len() - index, "Shutdown requested, stopping");
+            break;
+        }
+        // Phones not tried stay in the report, so the next retry still has them
+        if let Some(reason) = config.scraping.error_policy.stop_reason(failures(&report.counts)) {
+            warn!(reason = %reason, remaining = failed.len() - index, "Stopping the retry");
+            report.stop_reason = Some(reason);
+            report.failed_phones.extend(failed[index..].iter().cloned());
+            break;
+        }
+
+        let span = info_span!("phone", phone = %phone.phone_id, index = index + 1, total = failed.len());
+        let item = failed_phone_item(&mongo_client, config, phone).await;
+        match retry_phone(&mongo_client, config, &fetchers, adaptive_delay.as_ref(), &item, &phone.brand, transactional).instrument(span.clone()).await {
+            Ok(method) => {
+                info!(parent: &span, method, "✓ Saved");
+                report.record_inserted();
+            }
+            Err(e) => {
+                let category = FailureCategory::classify(&e.to_string());
+                error!(parent: &span, category = %category, error = %e, "✗ All strategies failed");
+                report.record_failed(&phone.phone_id, &phone.name, &phone.brand, category, &e.to_string());
+            }
+        }
+    }
+
+    // Listings that stopped part-way resume at the page that failed
+    let phones_per_brand = config.scraping.phones_per_brand.unwrap_or(usize::MAX);
+    for (index, brand) in unfinished.iter().enumerate() {
+        if budget.exhausted().is_some() || shutdown.is_requested() {
+            break;
+        }
+        if let Some(reason) = config.scraping.error_policy.stop_reason(failures(&report.counts)) {
+            warn!(reason = %reason, remaining = unfinished.len() - index, "Stopping the retry before the remaining listings");
+            report.stop_reason.get_or_insert(reason);
+            report.brands.extend(unfinished[index..].iter().cloned());
+            break;
+        }
+        let start_page = brand.failed_page.unwrap_or(1);
+        report.start_brand(&brand.name, &brand.slug);
+
+        let max_phones = phones_per_brand.saturating_sub(brand.phones_found);
+        let (phones, still_failing) = tokio::task::block_in_place(|| resume_listing(config, &fetchers, &brand.slug, start_page, max_phones));
+        info!(brand = %brand.name, start_page, count = phones.len(), "Phones from the rest of the listing");
+
+        for phone in &phones {
+            if shutdown.is_requested() {
+                break;
+            }
+            let span = info_span!("phone", phone = %phone.phone_id, brand = %brand.name);
+            match retry_phone(&mongo_client, config, &fetchers, adaptive_delay.as_ref(), phone, &brand.name, transactional).instrument(span.clone()).await {
+                Ok(method) => {
+                    info!(parent: &span, method, "✓ Saved");
+                    report.record_inserted();
+                }
+                Err(e) => {
+                    let category = FailureCategory::classify(&e.to_string());
+                    error!(parent: &span, category = %category, error = %e, "✗ All strategies failed");
+                    report.record_failed(&phone.phone_id, &phone.name, &brand.name, category, &e.to_string());
+                }
+            }
+        }
+
+        report.record_brand_phones(phones.len());
+        if let Some((page, e)) = still_failing {
+            error!(brand = %brand.name, failed_page = page, error = %e, "✗ Listing still fails");
+            report.record_brand_error(page, &e);
+        }
+    }
+
+    report.scrapingbee_credits = fetchers.iter().map(PageFetcher::credits_used).sum();
+    report.budget = budget.usage();
+    report.adaptive_delay = adaptive_delay.as_ref().map(AdaptiveDelay::stats);
+    report.finish(shutdown.is_requested());
+
+    info!(
+        recovered = report.counts.phones_inserted,
+        still_failing = report.counts.phones_failed,
+        listings_still_failing = report.counts.brands_failed,
+        scrapingbee_credits = report.scrapingbee_credits,
+        "✓ Retry complete"
+    );
+    log_failures(&report.counts.failures);
+
+    // The new report lists only the phones and listings that still fail, so retries can be chained
+    if !config.report.path.is_empty() {
+        report.write_json(Path::new(&config.report.path))?;
+        info!(path = %config.report.path, "✓ Wrote run report");
+    }
+    if let Some(ref collection) = config.report.collection {
+        mongo_client.save_run_report(collection, &report).await?;
+    }
+
+    match report.stop_reason {
+        Some(reason) => Err(format!("Retry aborted: {}", reason).into()),
+        None => Ok(()),
+    }
+}
+
+/// Walk a listing from `start_page`, moving on to the next strategy at the page that fails;
+/// returns the phones listed and, when every strategy failed, the page it stopped at
+fn resume_listing(
+    config: &Config,
+    fetchers: &[PageFetcher],
+    brand_slug: &str,
+    start_page: usize,
+    max_phones: usize,
+) -> (Vec<PhoneListItem>, Option<(usize, String)>) {
+    let mut phones = Vec::new();
+    let mut page = start_page;
+    let mut last_error = None;
+
+    for fetcher in fetchers {
+        let mut pages = PhonePages::new(&config.site.base_url, brand_slug, max_phones.saturating_sub(phones.len()), |url| fetcher.fetch(url))
+            .starting_at(page);
+        last_error = None;
+        for result in pages.by_ref() {
+            match result {
+                Ok(listed) => phones.extend(listed),
+                Err(e) => {
+                    warn!(method = fetcher.label(), error = %e, "Listing page failed, escalating");
+                    last_error = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+        page = pages.next_page();
+        if last_error.is_none() {
+            break;
+        }
+    }
+
+    (phones, last_error.map(|e| (page, e)))
+}
+
+/// The listing entry of a failed phone, with the thumbnail recorded at discovery if any
+async fn failed_phone_item(mongo_client: &MongoDBClient, config: &Config, failed: &FailedPhone) -> PhoneListItem {
+    let image_url = mongo_client
+        .get_phone_list_entry(&config.collections.phone_list, &failed.phone_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|(phone, _)| phone.image_url);
+
+    PhoneListItem {
+        name: failed.name.clone(),
+        url: phone_page_url(&failed.phone_id),
+        phone_id: failed.phone_id.clone(),
+        image_url,
+        device_type: DeviceType::from_name(&failed.name),
+    }
+}
+
+/// Try each strategy in turn; returns the label of the one that worked
+async fn retry_phone(
+    mongo_client: &MongoDBClient,
+    config: &Config,
+    fetchers: &[PageFetcher],
+    adaptive_delay: Option<&AdaptiveDelay>,
+    phone: &PhoneListItem,
+    brand: &str,
+    transactional: bool,
+) -> Result<&'static str, Box<dyn Error>> {
+    let collections = &config.collections;
+    let mut last_error: Box<dyn Error> = "no strategy tried".into();
+
+    for fetcher in fetchers {
+        let delay_ms = adaptive_delay.map_or(config.rate_limit.delay_between_phones_ms, AdaptiveDelay::current_ms);
+        match fetch_phone(fetcher, &config.site, phone, delay_ms).await {
+            Ok((spec_json, html)) => {
+                let mut phone_doc = PhoneDocument::new(phone, brand, spec_json);
+                phone_doc.market_prices = parse_market_prices(&html);
+
+                // A block page is a failed fetch: escalate to the next strategy
+                let validation = validate(&phone_doc, config.validation.min_completeness);
+                if !validation.has_specifications {
+                    warn!(method = fetcher.label(), "Page has no specifications, escalating");
+                    last_error = "no specifications on page (blocked or empty)".into();
+                    continue;
+                }
+                phone_doc.apply_validation(&validation);
+                if let (Some(collection), Some(snapshot)) = (&collections.price_history, PriceSnapshot::of(&phone_doc)) {
+                    if let Err(e) = mongo_client.record_market_prices(collection, &snapshot).await {
+                        warn!(error = %e, "Failed to record market prices");
+                    }
+                }
+
+                let previous = mongo_client.get_phone(&collections.phones, &phone.phone_id).await?;
+                let Some(mut phone_doc) = config.mongodb.upsert_policy.resolve(previous.as_ref(), phone_doc)? else {
+                    return Ok(fetcher.label()); // skip_if_exists, and it is stored
+                };
+                lifecycle::track(previous.as_ref(), &mut phone_doc, Utc::now());
+                mongo_client
+                    .save_phone((&collections.phones, &collections.phone_list), phone_doc, phone, brand, transactional)
+                    .await?;
+                return Ok(fetcher.label());
+            }
+            Err(e) => {
+                warn!(method = fetcher.label(), error = %e, "Strategy failed, escalating");
+                last_error = e;
+            }
+        }
+    }
+
+    Err(last_error)
+}
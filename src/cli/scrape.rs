@@ -0,0 +1,1307 @@
+use super::fetch::{is_exhausted, PageFetcher};
+use super::progress::ScrapeProgress;
+use super::{limit_label, override_with, CollectionArgs};
+use chrono::Utc;
+use clap::Args;
+use gsmarena_scraper::brand_scraper::{
+    fetch_brand_listings_concurrently, fetch_brand_year_breakdown_with, fetch_phones_by_brand_with, parse_brand_details, parse_brands_page, PhonePages,
+};
+use gsmarena_scraper::budget::RequestBudget;
+use gsmarena_scraper::config::{parse_duration, BrandFilter, Shard};
+use gsmarena_scraper::gallery::parse_pictures_page_from;
+use gsmarena_scraper::hybrid::HybridSchedule;
+use gsmarena_scraper::lifecycle;
+use gsmarena_scraper::phone_finder::{search_phone_finder_with, PhoneFinderQuery};
+use gsmarena_scraper::run_report::RunCounts;
+use gsmarena_scraper::runner::{build_phone_document, listing_excludes, PhonePage, PhonePageOptions};
+use gsmarena_scraper::scraper::parse_specification_html;
+use gsmarena_scraper::self_check::self_check;
+use gsmarena_scraper::site::SiteConfig;
+use gsmarena_scraper::sitemap::{discover_phones_from_sitemap_with, group_phones_by_brand};
+use gsmarena_scraper::{AdaptiveDelay, Brand, BrandDetails, BrandDocument, ChatNotifier, Checkpoint, Config, DeviceType, DiscoveryMode, EventPublisher, FailureCategory, FailureStats, GlobalRateLimiter, HttpStats, ImageDownloader, ImageStorage, MongoDBClient, NetworkFilter, NewDevice, PhoneDocument, PhoneEvent, PhoneListItem, PriceSnapshot, RateLimiter, RunReport, ScrapeProvider, Shutdown, SkipReason, SnapshotStore, UserAgentPool, WebhookNotifier};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, info_span, warn, Instrument};
+
+/// Where phones come from and how pages are fetched (shared by `scrape` and `discover`)
+#[derive(Debug, Args)]
+pub struct SourceArgs {
+    /// How pages are fetched: direct, proxy, scrapingbee, hybrid, chain or browser [config: scraping.provider]
+    #[arg(long)]
+    pub provider: Option<ScrapeProvider>,
+
+    /// How phones are discovered: brands or sitemap [config: scraping.discovery]
+    #[arg(long)]
+    pub discovery: Option<DiscoveryMode>,
+
+    /// Sitemap index used with `--discovery sitemap`, default sitemap.xml under the base URL [config: scraping.sitemap_url]
+    #[arg(long)]
+    pub sitemap_url: Option<String>,
+
+    /// Fetch this many brand listings at once before scraping [config: scraping.discovery_parallelism]
+    #[arg(long, value_name = "N")]
+    pub discovery_parallelism: Option<usize>,
+
+    /// Only these brands (by name, case-insensitive; repeatable) [config: brands.include]
+    #[arg(long = "brand", value_name = "NAME")]
+    pub brands: Vec<String>,
+
+    /// Skip these brands (repeatable) [config: brands.exclude]
+    #[arg(long = "exclude-brand", value_name = "NAME")]
+    pub exclude_brands: Vec<String>,
+
+    /// Only the brands of shard N out of M workers, e.g. 0/4 (hash of the brand slug) [config: brands.shard]
+    #[arg(long, value_name = "N/M")]
+    pub shard: Option<Shard>,
+
+    /// Maximum number of brands to process [config: scraping.max_brands]
+    #[arg(long)]
+    pub max_brands: Option<usize>,
+
+    /// Serve fetched pages from (and save them to) this directory [config: cache.dir]
+    #[arg(long, value_name = "DIR", conflicts_with = "no_cache")]
+    pub cache_dir: Option<String>,
+
+    /// Cached pages older than this are fetched again [config: cache.ttl_secs]
+    #[arg(long, value_name = "SECS")]
+    pub cache_ttl_secs: Option<u64>,
+
+    /// Ignore the page cache configured in the file or environment
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Parse pages saved in this directory (or a page cache) instead of fetching them [config: scraping.from_dir]
+    #[arg(long, value_name = "DIR")]
+    pub from_dir: Option<String>,
+}
+
+impl SourceArgs {
+    pub fn apply(&self, config: &mut Config) {
+        override_with(&mut config.scraping.provider, &self.provider);
+        override_with(&mut config.scraping.discovery, &self.discovery);
+        override_with(&mut config.scraping.discovery_parallelism, &self.discovery_parallelism);
+
+        if self.sitemap_url.is_some() {
+            config.scraping.sitemap_url = self.sitemap_url.clone();
+        }
+        if self.max_brands.is_some() {
+            config.scraping.max_brands = self.max_brands;
+        }
+        if !self.brands.is_empty() {
+            config.brands.include = self.brands.clone();
+        }
+        if self.shard.is_some() {
+            config.brands.shard = self.shard;
+        }
+        if !self.exclude_brands.is_empty() {
+            config.brands.exclude = self.exclude_brands.clone();
+        }
+
+        if self.cache_dir.is_some() {
+            config.cache.dir = self.cache_dir.clone();
+        }
+        if self.cache_ttl_secs.is_some() {
+            config.cache.ttl_secs = self.cache_ttl_secs;
+        }
+        if self.no_cache {
+            config.cache.dir = None;
+        }
+        if self.from_dir.is_some() {
+            config.scraping.from_dir = self.from_dir.clone();
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ScrapeArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    #[command(flatten)]
+    pub source: SourceArgs,
+
+    /// Maximum number of phones per brand [config: scraping.phones_per_brand]
+    #[arg(long)]
+    pub phones_per_brand: Option<usize>,
+
+    /// Stop gracefully once the run has taken this long, e.g. 5h or 5h30m [config: scraping.max_runtime]
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub max_runtime: Option<Duration>,
+
+    /// Stop gracefully after fetching this many phones [config: scraping.max_phones]
+    #[arg(long)]
+    pub max_phones: Option<usize>,
+
+    /// Only scrape this kind of device: phone, tablet or watch [config: scraping.device_type]
+    #[arg(long, value_name = "TYPE")]
+    pub device_type: Option<DeviceType>,
+
+    /// Only scrape devices with this network technology: 5g-only, lte-only or gsm-only;
+    /// brands are listed through Phone Finder [config: scraping.network_filter]
+    #[arg(long, value_name = "FILTER")]
+    pub filter: Option<NetworkFilter>,
+
+    /// Continue from the checkpoint a previous run wrote; a missing file starts a new chain [config: scraping.checkpoint_in]
+    #[arg(long, value_name = "PATH")]
+    pub checkpoint_in: Option<String>,
+
+    /// Write where this run got to, for the next run's --checkpoint-in [config: scraping.checkpoint_out]
+    #[arg(long, value_name = "PATH")]
+    pub checkpoint_out: Option<String>,
+
+    /// Skip phones already in the specs collection [config: scraping.skip_existing]
+    #[arg(long, value_name = "BOOL")]
+    pub skip_existing: Option<bool>,
+
+    /// Delay before each rate-limited phone request [config: rate_limit.delay_between_phones_ms]
+    #[arg(long)]
+    pub delay_between_phones_ms: Option<u64>,
+
+    /// Raise the phone delay while the site answers 429/403 and lower it back once responses are clean [config: rate_limit.adaptive]
+    #[arg(long, value_name = "BOOL")]
+    pub adaptive_delay: Option<bool>,
+
+    /// Delay between brands [config: rate_limit.delay_between_brands_ms]
+    #[arg(long)]
+    pub delay_between_brands_ms: Option<u64>,
+
+    /// Phones per batch before the hybrid provider switches method [config: scraping.batch_size]
+    #[arg(long)]
+    pub batch_size: Option<usize>,
+
+    /// Shift hybrid batches towards whichever method is succeeding; false alternates strictly [config: scraping.hybrid_auto_tune]
+    #[arg(long, value_name = "BOOL")]
+    pub hybrid_auto_tune: Option<bool>,
+
+    /// Scrape brand logo, description and website from each brand page
+    #[arg(long)]
+    pub fetch_brand_details: bool,
+
+    /// Count each brand's devices per announce year from its year-filtered listings
+    #[arg(long)]
+    pub fetch_brand_years: bool,
+
+    /// Scrape each phone's picture gallery into gallery_urls
+    #[arg(long)]
+    pub fetch_gallery: bool,
+
+    /// Also download gallery pictures when image storage is configured
+    #[arg(long)]
+    pub download_gallery_images: bool,
+
+    /// Store which raw row each parsed field came from, with the parser version
+    #[arg(long)]
+    pub record_provenance: bool,
+
+    /// Before scraping, check that a pinned brand and phone page still parse; abort if not
+    #[arg(long)]
+    pub self_check: bool,
+
+    /// Write the run report to this JSON file ("" disables it) [config: report.path]
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<String>,
+
+    /// Also upsert the run report into this collection, e.g. scrape_runs [config: report.collection]
+    #[arg(long, value_name = "NAME")]
+    pub report_collection: Option<String>,
+
+    /// Keep running and scrape on the cron schedule instead of once
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// Cron expression for `--daemon`, e.g. "0 3 * * *" [config: daemon.schedule]
+    #[arg(long, value_name = "CRON")]
+    pub schedule: Option<String>,
+
+    /// Maximum random delay added before each scheduled run [config: daemon.jitter_secs]
+    #[arg(long)]
+    pub jitter_secs: Option<u64>,
+}
+
+impl ScrapeArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+        self.source.apply(config);
+
+        let scraping = &mut config.scraping;
+        if self.phones_per_brand.is_some() {
+            scraping.phones_per_brand = self.phones_per_brand;
+        }
+        if let Some(max_runtime) = self.max_runtime {
+            scraping.max_runtime = Some(format!("{}s", max_runtime.as_secs()));
+        }
+        if self.max_phones.is_some() {
+            scraping.max_phones = self.max_phones;
+        }
+        if self.device_type.is_some() {
+            scraping.device_type = self.device_type;
+        }
+        if self.filter.is_some() {
+            scraping.network_filter = self.filter;
+        }
+        if self.checkpoint_in.is_some() {
+            scraping.checkpoint_in = self.checkpoint_in.clone();
+        }
+        if self.checkpoint_out.is_some() {
+            scraping.checkpoint_out = self.checkpoint_out.clone();
+        }
+        override_with(&mut scraping.skip_existing, &self.skip_existing);
+        override_with(&mut scraping.batch_size, &self.batch_size);
+        override_with(&mut scraping.hybrid_auto_tune, &self.hybrid_auto_tune);
+        scraping.fetch_brand_details |= self.fetch_brand_details;
+        scraping.fetch_brand_years |= self.fetch_brand_years;
+        scraping.fetch_gallery |= self.fetch_gallery;
+        scraping.download_gallery_images |= self.download_gallery_images;
+        scraping.record_provenance |= self.record_provenance;
+        scraping.self_check |= self.self_check;
+
+        override_with(&mut config.rate_limit.delay_between_phones_ms, &self.delay_between_phones_ms);
+        override_with(&mut config.rate_limit.adaptive, &self.adaptive_delay);
+        override_with(&mut config.rate_limit.delay_between_brands_ms, &self.delay_between_brands_ms);
+
+        override_with(&mut config.report.path, &self.report);
+        if self.report_collection.is_some() {
+            config.report.collection = self.report_collection.clone();
+        }
+
+        if self.schedule.is_some() {
+            config.daemon.schedule = self.schedule.clone();
+        }
+        override_with(&mut config.daemon.jitter_secs, &self.jitter_secs);
+    }
+}
+
+/// Whether the run continues after a brand
+enum BrandOutcome {
+    Continue,
+    Stop,
+}
+
+/// State shared by every brand and phone of one scrape run
+struct ScrapeRun<'a> {
+    config: &'a Config,
+    shutdown: &'a Shutdown,
+    mongo_client: MongoDBClient,
+    listing_fetcher: &'a PageFetcher,
+    direct_fetcher: PageFetcher,
+    budget: RequestBudget, // Shared with both fetchers
+    http_stats: HttpStats, // Shared with both fetchers
+    user_agents: UserAgentPool, // Shared with both fetchers
+    adaptive_delay: Option<AdaptiveDelay>, // Shared with both fetchers; replaces delay_between_phones_ms when set
+    max_runtime: Option<Duration>,
+    started: Instant,
+    hybrid: Option<HybridSchedule>,
+    image_downloader: Option<ImageDownloader>,
+    snapshot_store: Option<SnapshotStore>,
+    webhooks: Option<WebhookNotifier>,
+    chat: Option<ChatNotifier>,
+    events: Option<EventPublisher>,
+    new_devices: Vec<NewDevice>,
+    existing_phone_ids: HashSet<String>,
+    transactional: bool, // Specs and phone_list entry written in one transaction
+    resume_page: Option<usize>, // Listing page to continue the first brand at (from the checkpoint), or where a stop left the brand
+    report: RunReport,
+    progress: Option<ScrapeProgress>,
+}
+
+pub async fn run(config: &Config, shutdown: &Shutdown) -> Result<(), Box<dyn Error>> {
+    // `max_runtime` covers the whole run, discovery included
+    let started = Instant::now();
+    let scraping = &config.scraping;
+    let rate_limit = &config.rate_limit;
+    let collections = &config.collections;
+    let max_brands = scraping.max_brands.unwrap_or(usize::MAX);
+
+    info!(
+        config_file = config.source.as_ref().map(|p| p.display().to_string()),
+        provider = %scraping.provider,
+        discovery = %scraping.discovery,
+        from_dir = scraping.from_dir.as_deref(),
+        page_cache = config.cache.dir.as_deref(),
+        specs_collection = %collections.phones,
+        phone_list_collection = %collections.phone_list,
+        brands_collection = %collections.brands,
+        max_brands = %limit_label(scraping.max_brands),
+        phones_per_brand = %limit_label(scraping.phones_per_brand),
+        skip_existing = scraping.skip_existing,
+        upsert_policy = %config.mongodb.upsert_policy,
+        error_policy = %scraping.error_policy,
+        delay_between_phones_ms = rate_limit.delay_between_phones_ms,
+        adaptive_delay_max_ms = rate_limit.adaptive.then_some(rate_limit.adaptive_max_delay_ms),
+        delay_between_brands_ms = rate_limit.delay_between_brands_ms,
+        hybrid_batch_size = (scraping.provider == ScrapeProvider::Hybrid).then_some(scraping.batch_size),
+        hybrid_auto_tune = (scraping.provider == ScrapeProvider::Hybrid).then_some(scraping.hybrid_auto_tune),
+        fetch_brand_details = scraping.fetch_brand_details,
+        fetch_brand_years = scraping.fetch_brand_years,
+        fetch_gallery = scraping.fetch_gallery,
+        download_gallery_images = scraping.download_gallery_images,
+        record_provenance = scraping.record_provenance,
+        self_check = scraping.self_check,
+        user_agent_rotation = %config.user_agents.rotation,
+        user_agents_file = config.user_agents.file.as_deref(),
+        "GSMArena Scraper - MongoDB Integration"
+    );
+    log_brand_filter(&config.brands);
+
+    // Listing pages go through the provider; the hybrid provider also alternates spec pages with direct
+    let budget = RequestBudget::new(&config.budget);
+    let http_stats = HttpStats::new();
+    let user_agents = UserAgentPool::from_config(&config.user_agents)?;
+    let adaptive_delay = AdaptiveDelay::from_config(rate_limit);
+    let listing_fetcher = PageFetcher::for_provider(config)?
+        .with_budget(&budget)
+        .with_stats(&http_stats)
+        .with_user_agents(&user_agents)
+        .with_adaptive_delay(adaptive_delay.as_ref());
+
+    // Fail fast, before anything is written, when the site layout changed
+    if scraping.self_check {
+        if listing_fetcher.is_offline() {
+            info!("Self-check skipped: parsing saved pages");
+        } else {
+            self_check(&config.site, |url| listing_fetcher.fetch(url))?;
+        }
+    }
+
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+
+    // Workers sharing the database share one request cap (never needed for saved pages)
+    let global_limiter = match listing_fetcher.is_offline() {
+        true => None,
+        false => GlobalRateLimiter::from_config(config, &mongo_client.database()).await?,
+    };
+    if let Some(ref limiter) = global_limiter {
+        info!(
+            max_requests = limiter.max_requests(),
+            window_secs = limiter.window().as_secs(),
+            collection = %config.rate_limit.global_collection,
+            "✓ Global request cap shared across workers"
+        );
+    }
+    let listing_fetcher = listing_fetcher.with_global_limiter(global_limiter.as_ref());
+
+    // Set up image downloading if image storage is configured (never when parsing saved pages)
+    let image_downloader = if listing_fetcher.is_offline() {
+        None
+    } else {
+        ImageStorage::from_config(&config.images, &mongo_client.database()).await?.map(ImageDownloader::new).transpose()?
+    };
+    if let Some(ref downloader) = image_downloader {
+        info!(storage = %downloader.storage().describe(), "✓ Image storage configured");
+    }
+
+    let snapshot_store = SnapshotStore::from_config(&config.snapshots, &mongo_client.database()).await?;
+    if let Some(ref store) = snapshot_store {
+        info!(storage = %store.storage().describe(), "✓ HTML snapshot storage configured");
+    }
+
+    let webhooks = WebhookNotifier::from_config(&config.webhooks);
+    if let Some(ref webhooks) = webhooks {
+        info!(
+            urls = config.webhooks.urls.len(),
+            tracked_fields = %webhooks.tracked_fields().join(", "),
+            signed = webhooks.is_signed(),
+            "✓ Webhook notifications configured"
+        );
+    }
+
+    let chat = ChatNotifier::from_config(&config.chat);
+    if let Some(ref chat) = chat {
+        info!(chats = %chat.sink_names().join(", "), announce_new_devices = config.chat.announce_new_devices, "✓ Chat notifications configured");
+    }
+
+    let events = EventPublisher::from_config(&config.events).await?;
+    if let Some(ref events) = events {
+        info!(backend = events.backend_name(), topic = events.topic(), "✓ Phone events configured");
+    }
+
+    mongo_client.create_indexes(&collections.phones).await.ok(); // Ignore if already exists
+
+    // An interrupted write can leave a phone_list entry out of step with the specs collection
+    match mongo_client.reconcile_phone_list(&collections.phones, &collections.phone_list).await {
+        Ok((0, 0)) => {}
+        Ok((completed, reopened)) => warn!(completed, reopened, "Repaired phone_list is_complete flags"),
+        Err(e) => warn!(error = %e, "Failed to reconcile phone_list with the specs collection"),
+    }
+    let transactional = config.mongodb.transactions && transactions_available(&mongo_client).await;
+
+    let initial_count = mongo_client.get_phone_count(&collections.phones).await?;
+    info!(count = initial_count, "Current phones in database");
+
+    let existing_phone_ids = if scraping.skip_existing {
+        let ids = mongo_client.get_phone_ids(&collections.phones).await?;
+        info!(count = ids.len(), "✓ Loaded existing phones to skip");
+        ids
+    } else {
+        Default::default()
+    };
+
+    info!("Fetching brands from GSMArena...");
+    let all_brands = select_brands(parse_brands_page(&listing_fetcher.fetch(&config.site.makers_url())?), &config.brands);
+    if all_brands.is_empty() {
+        return Err("No brands found".into());
+    }
+    info!(count = all_brands.len(), "✓ Found brands");
+
+    // A chain of runs continues at the brand the previous run stopped at
+    let checkpoint = match scraping.checkpoint_in.as_deref() {
+        Some(path) => Checkpoint::read(Path::new(path))?,
+        None => None,
+    };
+    if let Some(ref checkpoint) = checkpoint {
+        info!(
+            chain = %checkpoint.chain_id,
+            runs = checkpoint.runs,
+            cursor = checkpoint.cursor.as_deref().unwrap_or("(new pass)"),
+            page = checkpoint.page,
+            failed = checkpoint.failed_ids.len(),
+            "✓ Continuing from checkpoint"
+        );
+    }
+    let brands = &all_brands[checkpoint.as_ref().map_or(0, |checkpoint| checkpoint.start_index(&all_brands))..];
+    let resume_page = checkpoint.as_ref().and_then(|checkpoint| checkpoint.start_page(&all_brands));
+
+    // Sitemap discovery enumerates every device in a handful of requests
+    let mut sitemap_phones: Option<HashMap<String, Vec<PhoneListItem>>> = match scraping.discovery {
+        DiscoveryMode::Sitemap => {
+            let sitemap_url = config.sitemap_url();
+            info!(url = %sitemap_url, "Discovering phones from sitemap");
+            let phones = discover_phones_from_sitemap_with(&config.site, &sitemap_url, |url| listing_fetcher.fetch(url))?;
+            info!(count = phones.len(), "✓ Found phones in sitemap");
+            Some(group_phones_by_brand(brands, phones))
+        }
+        DiscoveryMode::Brands => None,
+    };
+
+    let phones_per_brand = scraping.phones_per_brand.unwrap_or(usize::MAX);
+    let brand_total = max_brands.min(brands.len());
+    let max_runtime = scraping.max_runtime.as_deref().map(parse_duration).transpose()?;
+    let deadline = max_runtime.map(|limit| started + limit);
+    // A brand continued part-way through its listing fetches only the remaining pages itself
+    let prefetch_from = usize::from(resume_page.is_some()).min(brand_total);
+    let mut prefetched = prefetch_brand_listings(config, &listing_fetcher, &brands[prefetch_from..brand_total], phones_per_brand, deadline);
+
+    // Expected phones per brand for the overall progress bar, refined as phone lists arrive
+    let estimates: Vec<usize> = brands
+        .iter()
+        .take(max_brands)
+        .map(|brand| match sitemap_phones {
+            Some(ref grouped) => grouped.get(&brand.slug).map_or(0, Vec::len).min(phones_per_brand),
+            None => prefetched.get(&brand.slug).map_or(brand.device_count as usize, Vec::len).min(phones_per_brand),
+        })
+        .collect();
+
+    let mut run = ScrapeRun {
+        config,
+        shutdown,
+        mongo_client,
+        listing_fetcher: &listing_fetcher,
+        direct_fetcher: PageFetcher::direct(config)?
+            .with_budget(&budget)
+            .with_stats(&http_stats)
+            .with_user_agents(&user_agents)
+            .with_global_limiter(global_limiter.as_ref())
+            .with_adaptive_delay(adaptive_delay.as_ref()),
+        budget,
+        http_stats,
+        user_agents,
+        adaptive_delay,
+        max_runtime,
+        started,
+        hybrid: (scraping.provider == ScrapeProvider::Hybrid && scraping.from_dir.is_none())
+            .then(|| HybridSchedule::new(scraping.batch_size, scraping.hybrid_auto_tune)),
+        image_downloader,
+        snapshot_store,
+        webhooks,
+        chat,
+        events,
+        new_devices: Vec::new(),
+        existing_phone_ids,
+        transactional,
+        resume_page,
+        report: RunReport::new(config),
+        progress: ScrapeProgress::new(brand_total, estimates.iter().sum()),
+    };
+
+    if let Some(ref checkpoint) = checkpoint {
+        run.retry_failed(&checkpoint.failed_ids, &all_brands).await;
+    }
+
+    let mut brands_done = 0;
+    for (brand_index, brand) in brands.iter().take(max_brands).enumerate() {
+        if run.stopping() {
+            break;
+        }
+
+        let estimate = estimates[brand_index];
+        let listed = match sitemap_phones.as_mut() {
+            Some(grouped) => Some(grouped.remove(&brand.slug).unwrap_or_default()),
+            None => prefetched.remove(&brand.slug),
+        };
+        let span = info_span!("brand", brand = %brand.name, index = brand_index + 1, total = brand_total);
+
+        run.report.start_brand(&brand.name, &brand.slug);
+        run.user_agents.start_brand();
+        if let Some(ref progress) = run.progress {
+            progress.start_brand(&brand.name);
+        }
+        let outcome = run.scrape_brand(brand, listed, estimate).instrument(span).await;
+        if let Some(ref progress) = run.progress {
+            progress.brand_done();
+        }
+
+        if let BrandOutcome::Stop = outcome {
+            break;
+        }
+        if run.stopping() {
+            break; // Possibly part-way through the brand, so a checkpoint starts the next run here
+        }
+        brands_done += 1;
+
+        if brand_index + 1 < brand_total && rate_limit.delay_between_brands_ms > 0 && !run.listing_fetcher.is_offline() {
+            debug!(delay_ms = rate_limit.delay_between_brands_ms, "Waiting before next brand");
+            shutdown.sleep(std::time::Duration::from_millis(rate_limit.delay_between_brands_ms)).await;
+        }
+    }
+
+    if let Some(ref progress) = run.progress {
+        progress.finish();
+    }
+
+    // The report and checkpoint still get written when the database is what failed
+    let final_count = match run.mongo_client.get_phone_count(&collections.phones).await {
+        Ok(count) => count,
+        Err(e) => {
+            error!(error = %e, "✗ Cannot count the phones in the database");
+            initial_count
+        }
+    };
+    let report = &mut run.report;
+    report.counts.initial_count = initial_count;
+    report.counts.final_count = final_count;
+    report.scrapingbee_credits = run.listing_fetcher.credits_used();
+    report.budget = run.budget.usage();
+    report.http = run.http_stats.snapshot();
+    report.hybrid = run.hybrid.as_ref().map(HybridSchedule::stats);
+    report.adaptive_delay = run.adaptive_delay.as_ref().map(AdaptiveDelay::stats);
+    for (provider, requests) in run.listing_fetcher.chain_usage() {
+        info!(provider = %provider, requests, budget = ?config.chain.budget(provider), "Chain provider usage");
+    }
+    for (provider, stats) in &report.http {
+        let statuses: Vec<String> = stats.statuses.iter().map(|(status, count)| format!("{}={}", status, count)).collect();
+        info!(
+            provider = %provider,
+            requests = stats.requests,
+            statuses = %statuses.join(" "),
+            errors = stats.errors,
+            bytes = stats.bytes,
+            avg_latency_ms = format!("{:.0}", stats.average_latency_ms()),
+            rate_limited = format!("{:.1}%", stats.rate_limited_share() * 100.0),
+            "HTTP outcomes"
+        );
+    }
+    if let Some(ref hybrid) = report.hybrid {
+        for (method, stats) in [("rate-limited", &hybrid.direct), ("scrapingbee", &hybrid.scrapingbee)] {
+            info!(
+                method,
+                attempts = stats.attempts,
+                successes = stats.successes,
+                failures = stats.failures,
+                success_rate = %stats.success_rate().map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0)),
+                avg_latency_ms = format!("{:.0}", stats.average_latency_ms()),
+                "Hybrid method outcomes"
+            );
+        }
+        info!(scrapingbee_share = format!("{:.0}%", hybrid.scrapingbee_share * 100.0), auto_tune = scraping.hybrid_auto_tune, "Hybrid batch split");
+    }
+    if let Some(ref adaptive) = report.adaptive_delay {
+        info!(
+            base_ms = adaptive.base_ms,
+            final_ms = adaptive.final_ms,
+            peak_ms = adaptive.peak_ms,
+            raises = adaptive.raises,
+            lowers = adaptive.lowers,
+            throttled = adaptive.throttled,
+            "Adaptive phone delay"
+        );
+    }
+    let interrupted = shutdown.is_requested() || report.stop_reason.is_some();
+    report.finish(interrupted);
+
+    if interrupted {
+        warn!(
+            phone_list_collection = %collections.phone_list,
+            "Scraping interrupted; unscraped phones stay is_complete: false and are picked up next run"
+        );
+    }
+
+    let counts = &report.counts;
+    info!(
+        brands_processed = counts.brands_processed,
+        brands_total = brand_total,
+        brands_failed = counts.brands_failed,
+        brands_short = counts.brands_short,
+        phones_stale = counts.phones_stale,
+        phones_found = counts.phones_found,
+        phones_inserted = counts.phones_inserted,
+        phones_skipped = counts.phones_skipped,
+        phones_unchanged = counts.phones_unchanged,
+        phones_failed = counts.phones_failed,
+        collection = %collections.phones,
+        previous_count = initial_count,
+        current_count = final_count,
+        net_change = final_count as i64 - initial_count as i64,
+        scrapingbee_credits = report.scrapingbee_credits,
+        direct_requests = report.budget.direct_requests,
+        proxy_requests = report.budget.proxy_requests,
+        budget_reached = report.budget.exhausted.as_deref(),
+        pages_not_modified = run.listing_fetcher.pages_not_modified() + run.direct_fetcher.pages_not_modified(),
+        "{}",
+        if interrupted { "⚠ Scraping interrupted (partial statistics)" } else { "✓ Scraping complete" }
+    );
+    log_failures(&counts.failures);
+
+    save_report(&run.mongo_client, config, &run.report).await;
+
+    if let Some(ref path) = scraping.checkpoint_out {
+        let mut next = Checkpoint::next(checkpoint.as_ref(), &run.report.run_id);
+        next.set_cursor(brands.get(brands_done), run.resume_page);
+        let earlier_failures = checkpoint.iter().flat_map(|checkpoint| checkpoint.failed_ids.iter().map(String::as_str));
+        let failures = run.report.failed_phones.iter().map(|failed| failed.phone_id.as_str());
+        next.set_failed(earlier_failures.chain(failures), |phone_id| run.existing_phone_ids.contains(phone_id));
+        match next.write(Path::new(path)) {
+            Ok(()) => info!(path = %path, cursor = next.cursor.as_deref().unwrap_or("(new pass)"), page = next.page, failed = next.failed_ids.len(), "✓ Wrote checkpoint"),
+            Err(e) => error!(path = %path, error = %e, "✗ Failed to write checkpoint"),
+        }
+    }
+
+    if let Some(ref chat) = run.chat {
+        chat.send_summary(&run.report, &run.new_devices).await;
+    }
+
+    // An early stop for a budget or limit is a normal end; one for errors is not
+    match scraping.error_policy.stop_reason(failures(&run.report.counts)) {
+        Some(reason) => Err(format!("Run aborted: {}", reason).into()),
+        None => Ok(()),
+    }
+}
+
+/// Failed phones and brand listings, as counted against `error_policy`
+pub(crate) fn failures(counts: &RunCounts) -> u64 {
+    (counts.phones_failed + counts.brands_failed) as u64
+}
+
+/// Whether `mongodb.transactions` can be honoured; standalone servers reject transactions
+pub(crate) async fn transactions_available(mongo_client: &MongoDBClient) -> bool {
+    match mongo_client.supports_transactions().await {
+        Ok(true) => {
+            info!("✓ Writing specs and phone_list entries in transactions");
+            true
+        }
+        Ok(false) => {
+            warn!("mongodb.transactions is set but the server is not a replica set, writing without transactions");
+            false
+        }
+        Err(e) => {
+            warn!(error = %e, "Cannot tell whether the server supports transactions, writing without them");
+            false
+        }
+    }
+}
+
+/// Write the run report file and, if configured, store it in the runs collection
+async fn save_report(mongo_client: &MongoDBClient, config: &Config, report: &RunReport) {
+    if !config.report.path.is_empty() {
+        match report.write_json(Path::new(&config.report.path)) {
+            Ok(()) => info!(path = %config.report.path, run_id = %report.run_id, "✓ Wrote run report"),
+            Err(e) => error!(path = %config.report.path, error = %e, "✗ Failed to write run report"),
+        }
+    }
+
+    if let Some(ref collection) = config.report.collection {
+        match mongo_client.save_run_report(collection, report).await {
+            Ok(()) => info!(collection = %collection, run_id = %report.run_id, "✓ Saved run report"),
+            Err(e) => error!(collection = %collection, error = %e, "✗ Failed to save run report"),
+        }
+    }
+}
+
+impl ScrapeRun<'_> {
+    /// Store the brand and scrape its phones, page by page as the listing is fetched
+    /// (unless discovery already listed them), so stopping early skips the remaining pages
+    async fn scrape_brand(&mut self, brand: &Brand, listed: Option<Vec<PhoneListItem>>, estimate: usize) -> BrandOutcome {
+        let scraping = &self.config.scraping;
+        let phones_per_brand = scraping.phones_per_brand.unwrap_or(usize::MAX);
+        info!(devices = brand.device_count, "Processing brand");
+
+        let fetcher = self.listing_fetcher;
+        // Only a paged listing can continue where an earlier run stopped; discovery and filtered lists come whole
+        let first_page = self.resume_page.take().filter(|_| listed.is_none() && scraping.network_filter.is_none()).unwrap_or(1);
+        if first_page > 1 {
+            info!(page = first_page, "Continuing the phone list from the checkpoint");
+        }
+        let mut pages: Box<dyn Iterator<Item = Result<Vec<PhoneListItem>, Box<dyn Error>>>> = match listed {
+            Some(mut phones) => {
+                info!(count = phones.len(), "Phone list from discovery");
+                phones.truncate(phones_per_brand);
+                Box::new(std::iter::once(Ok(phones)))
+            }
+            None => match scraping.network_filter {
+                Some(filter) => {
+                    let base_url = &self.config.site.base_url;
+                    Box::new(std::iter::once_with(move || {
+                        let maker_id = brand.maker_id().ok_or_else(|| format!("no maker ID in brand slug '{}'", brand.slug))?;
+                        let query = PhoneFinderQuery::new().maker(maker_id).network(filter);
+                        let mut phones = search_phone_finder_with(base_url, &query, |url| fetcher.fetch(url))?;
+                        phones.truncate(phones_per_brand);
+                        Ok(phones)
+                    }))
+                }
+                None => Box::new(PhonePages::new(&self.config.site.base_url, &brand.slug, phones_per_brand, move |url| fetcher.fetch(url)).starting_at(first_page)),
+            },
+        };
+
+        let mut page = match tokio::task::block_in_place(|| pages.next()) {
+            Some(Ok(phones)) => phones,
+            None => Vec::new(),
+            Some(Err(e)) => {
+                error!(error = %e, "✗ Failed to fetch phone list");
+                self.report.record_brand_error(1, &e.to_string());
+                if let Some(ref progress) = self.progress {
+                    progress.set_brand_phones(estimate, 0);
+                }
+
+                if is_exhausted(e.as_ref()) {
+                    error!(brands_processed = self.report.counts.brands_processed, "All ScrapingBee API keys exhausted, stopping");
+                    self.resume_page = (first_page > 1).then_some(first_page);
+                    return BrandOutcome::Stop;
+                }
+                return BrandOutcome::Continue;
+            }
+        };
+
+        // Store brand metadata in its own collection
+        let details = if scraping.fetch_brand_details {
+            match fetch_brand_details(self.listing_fetcher, &self.config.site, &brand.slug) {
+                Ok(d) => Some(d),
+                Err(e) => {
+                    warn!(error = %e, "Brand details error");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut brand_document = BrandDocument::new(brand, details);
+        if scraping.fetch_brand_years {
+            let site = &self.config.site;
+            match fetch_brand_year_breakdown_with(&site.base_url, &brand.slug, |url| self.listing_fetcher.fetch(url)) {
+                Ok(counts) if counts.is_empty() => debug!("Brand page links no year listings"),
+                Ok(counts) => brand_document.devices_by_year = counts,
+                Err(e) => warn!(error = %e, "Brand year breakdown error"),
+            }
+        }
+
+        if let Err(e) = self.mongo_client.upsert_brand(&self.config.collections.brands, brand_document).await {
+            warn!(error = %e, "Failed to save brand");
+        }
+
+        let mut phones_found = 0;
+        let mut phones_with_specs = 0;
+        let mut counted = estimate; // What the overall progress bar currently expects from this brand
+        let mut seen = HashSet::new();
+        // Only a complete listing can tell which phone_list entries went away; a filtered one is partial by
+        // design, and a continued one skipped its first pages
+        let partial = scraping.network_filter.is_some() || first_page > 1;
+        let mut listing_complete = phones_per_brand == usize::MAX && !partial;
+
+        for page_number in first_page.. {
+            phones_found += page.len();
+            debug!(page = page_number, count = page.len(), method = fetcher.label(), "✓ Fetched listing page");
+            if let Some(ref progress) = self.progress {
+                // Keep the estimate until the listing turns out longer
+                progress.set_brand_phones(counted, phones_found.max(estimate));
+                counted = phones_found.max(estimate);
+            }
+
+            seen.extend(page.iter().map(|phone| phone.phone_id.clone()));
+            phones_with_specs += self.scrape_phones(brand, &page, page_number).await;
+
+            if self.stopping() {
+                // The page may be half done; the next run lists it again and skips what was saved
+                self.resume_page = (page_number > 1).then_some(page_number);
+                listing_complete = false;
+                break;
+            }
+            page = match tokio::task::block_in_place(|| pages.next()) {
+                Some(Ok(phones)) => phones,
+                Some(Err(e)) => {
+                    error!(failed_page = page_number + 1, error = %e, "✗ Phone list ended early");
+                    self.report.record_brand_error(page_number + 1, &e.to_string());
+                    listing_complete = false;
+                    break;
+                }
+                None => break,
+            };
+        }
+
+        // A listing well short of makers.php3's device count usually hit a silent block page; list it once more
+        let tolerance = self.config.validation.max_listing_shortfall;
+        if !partial && brand.listing_shortfall(phones_found, phones_per_brand, tolerance).is_some() && !self.stopping() {
+            warn!(found = phones_found, devices = brand.device_count, "Phone list is short of the device count, fetching it again");
+            let base_url = &self.config.site.base_url;
+            let relisted = tokio::task::block_in_place(|| {
+                fetch_phones_by_brand_with(base_url, &brand.slug, phones_per_brand, |url| fetcher.fetch(url))
+            });
+            match relisted {
+                Ok(phones) => {
+                    let missed: Vec<PhoneListItem> = phones.into_iter().filter(|phone| !seen.contains(&phone.phone_id)).collect();
+                    info!(count = missed.len(), "Phones only in the second listing");
+                    seen.extend(missed.iter().map(|phone| phone.phone_id.clone()));
+                    phones_found += missed.len();
+                    if let Some(ref progress) = self.progress {
+                        progress.set_brand_phones(counted, phones_found.max(estimate));
+                        counted = phones_found.max(estimate);
+                    }
+                    phones_with_specs += self.scrape_phones(brand, &missed, 0).await;
+                }
+                Err(e) => warn!(error = %e, "Second phone list failed"),
+            }
+        }
+        if let Some(missing) = brand.listing_shortfall(phones_found, phones_per_brand, tolerance).filter(|_| !partial) {
+            warn!(found = phones_found, missing, "✗ Phone list still short of the device count");
+            self.report.record_brand_shortfall(phones_found + missing);
+            listing_complete = false;
+        }
+
+        if listing_complete {
+            match self.mongo_client.mark_stale_phone_list_entries(&self.config.collections.phone_list, &brand.name, &seen).await {
+                Ok(0) => {}
+                Ok(stale) => {
+                    warn!(stale, "phone_list entries no longer listed, marked stale");
+                    self.report.record_stale(stale as usize);
+                }
+                Err(e) => warn!(error = %e, "Failed to mark stale phone_list entries"),
+            }
+        }
+
+        if let Some(ref progress) = self.progress {
+            progress.set_brand_phones(counted, phones_found);
+        }
+        self.report.record_brand_phones(phones_found);
+
+        info!(found = phones_found, count = phones_with_specs, "✓ Saved phones with full specifications");
+        BrandOutcome::Continue
+    }
+
+    /// Scrape a page of listed phones (page 0: phones found on a second listing); returns how many were saved
+    async fn scrape_phones(&mut self, brand: &Brand, phones: &[PhoneListItem], page: usize) -> usize {
+        let mut saved = 0;
+        for phone in phones {
+            // Stop between phones so the phone in flight is fully written
+            if self.stopping() {
+                warn!(next_phone = %phone.phone_id, "Stopping");
+                break;
+            }
+
+            let span = info_span!("phone", phone = %phone.phone_id, page);
+            if self.scrape_phone(brand, phone).instrument(span).await {
+                saved += 1;
+            }
+            if let Some(ref progress) = self.progress {
+                progress.phone_done(&phone.name);
+            }
+        }
+        saved
+    }
+
+    /// Whether to stop between phones: on a shutdown signal, once a `[budget]` cap,
+    /// `max_runtime` or `max_phones` is reached (which ends this run but not a daemon),
+    /// or when `error_policy` allows no more failures
+    fn stopping(&mut self) -> bool {
+        if self.report.stop_reason.is_some() || self.shutdown.is_requested() {
+            return true;
+        }
+
+        let counts = &self.report.counts;
+        let phones_fetched = counts.phones_inserted + counts.phones_unchanged + counts.phones_failed;
+        let reason = if let Some(reason) = self.config.scraping.error_policy.stop_reason(failures(counts)) {
+            reason
+        } else if let Some(cap) = self.budget.exhausted() {
+            format!("{} reached", cap)
+        } else if let Some(limit) = self.max_runtime.filter(|&limit| self.started.elapsed() >= limit) {
+            format!("max_runtime ({}s) reached", limit.as_secs())
+        } else if let Some(limit) = self.config.scraping.max_phones.filter(|&limit| phones_fetched >= limit) {
+            format!("max_phones ({}) reached", limit)
+        } else {
+            return false;
+        };
+
+        warn!(reason = %reason, "Stopping the run early");
+        self.report.stop_reason = Some(reason);
+        true
+    }
+
+    /// Scrape one phone into the specs collection; returns whether its specifications are
+    /// stored (saved now, or unchanged since the last scrape)
+    async fn scrape_phone(&mut self, brand: &Brand, phone: &PhoneListItem) -> bool {
+        let scraping = &self.config.scraping;
+        let collections = &self.config.collections;
+        let delay_ms = match self.adaptive_delay {
+            Some(ref adaptive) => adaptive.current_ms(),
+            None => self.config.rate_limit.delay_between_phones_ms,
+        };
+
+        if self.existing_phone_ids.contains(&phone.phone_id) {
+            debug!("Already exists, skipping");
+            self.report.record_skipped(SkipReason::Complete);
+            return false;
+        }
+        // Listings only hint at the type from the name; the specs settle it below
+        if listing_excludes(phone, scraping.device_type) {
+            debug!(device_type = ?phone.device_type, "Other device type, skipping");
+            self.report.record_skipped(SkipReason::Filtered);
+            return false;
+        }
+
+        // Record the phone as discovered before fetching its specs
+        let _ = self
+            .mongo_client
+            .upsert_phone_list_entry(&collections.phone_list, phone, &brand.name, false)
+            .await;
+
+        let method = self.hybrid.as_mut().map(|schedule| schedule.next_uses_scrapingbee());
+        let mut via_scrapingbee = method == Some(true);
+        let mut fetcher = match method {
+            Some(false) => &self.direct_fetcher,
+            _ => self.listing_fetcher,
+        };
+
+        let mut started = Instant::now();
+        let mut fetched = fetch_phone(fetcher, &self.config.site, phone, delay_ms).await;
+
+        if let (Err(e), Some(schedule)) = (&fetched, self.hybrid.as_mut()) {
+            if is_exhausted(e.as_ref()) {
+                warn!("ScrapingBee exhausted, switching to rate-limited only");
+                schedule.disable_scrapingbee();
+                fetcher = &self.direct_fetcher;
+                via_scrapingbee = false;
+                started = Instant::now();
+                fetched = fetch_phone(fetcher, &self.config.site, phone, delay_ms).await;
+            }
+        }
+        // Rate-limited fetches wait delay_ms first; leave that out of the method's latency
+        let latency = match via_scrapingbee {
+            true => started.elapsed(),
+            false => started.elapsed().saturating_sub(Duration::from_millis(delay_ms)),
+        };
+
+        let (spec_json, html) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                if let Some(schedule) = self.hybrid.as_mut() {
+                    schedule.record(via_scrapingbee, false, latency);
+                }
+                let category = FailureCategory::classify(&e.to_string());
+                error!(method = fetcher.label(), category = %category, error = %e, "✗ Failed to fetch specifications");
+                self.report.record_failed(&phone.phone_id, &phone.name, &brand.name, category, &e.to_string());
+                return false;
+            }
+        };
+
+        // A block page parses "successfully" into nothing; never let it overwrite real data
+        let built = build_phone_document(phone, &brand.name, spec_json, &html, &PhonePageOptions::from_config(self.config));
+        if let Some(schedule) = self.hybrid.as_mut() {
+            schedule.record(via_scrapingbee, built.is_ok(), latency);
+        }
+        let phone_doc = match built {
+            Ok(PhonePage::Document(phone_doc)) => *phone_doc,
+            Ok(PhonePage::OtherType(device_type)) => {
+                info!(device_type = %device_type, "Other device type, not saved");
+                self.report.record_skipped(SkipReason::Filtered);
+                return false;
+            }
+            Err((category, e)) => {
+                error!(method = fetcher.label(), error = %e, "✗ Page has no specifications (blocked?)");
+                self.report.record_failed(&phone.phone_id, &phone.name, &brand.name, category, &e.to_string());
+                return false;
+            }
+        };
+        // Phone Finder also lists devices with a newer technology than the filter allows
+        if let Some(filter) = scraping.network_filter {
+            let technology = phone_doc.network.as_ref().and_then(|network| network.technology.as_deref()).unwrap_or_default();
+            if !filter.matches(technology) {
+                info!(technology, filter = %filter, "Other network technology, not saved");
+                self.report.record_skipped(SkipReason::Filtered);
+                return false;
+            }
+        }
+
+        // Store prices move while the specifications stay the same: keep every scrape's
+        self.record_market_prices(&phone_doc).await;
+
+        // Compare with the stored document before it is overwritten (unchanged pages,
+        // lifecycle, webhooks and chat announcements)
+        let previous = match self.mongo_client.get_phone(&collections.phones, &phone.phone_id).await {
+            Ok(previous) => Some(previous),
+            Err(e) => {
+                warn!(error = %e, "Cannot load stored phone, no lifecycle tracking or notifications");
+                None
+            }
+        };
+        if previous.as_ref().and_then(Option::as_ref).is_some_and(|stored| phone_doc.is_unchanged_from(stored)) {
+            // Only the prices to write; updated_at keeps meaning "the specifications changed"
+            if let Err(e) = self.mongo_client.update_market_prices(&collections.phones, &phone.phone_id, &phone_doc.market_prices).await {
+                warn!(error = %e, "Failed to update market prices");
+            }
+            info!(name = %phone.name, method = fetcher.label(), "✓ Unchanged, not rewritten");
+            self.keep_stored(brand, phone).await;
+            self.report.record_unchanged();
+            return true;
+        }
+        let stored = previous.as_ref().and_then(Option::as_ref);
+        let mut phone_doc = match self.config.mongodb.upsert_policy.resolve(stored, phone_doc) {
+            Ok(Some(phone_doc)) => phone_doc,
+            Ok(None) => {
+                debug!("Already stored, upsert_policy is skip_if_exists");
+                self.keep_stored(brand, phone).await;
+                self.report.record_skipped(SkipReason::Existing);
+                return true;
+            }
+            Err(e) => {
+                error!(error = %e, "✗ Cannot merge with the stored document");
+                self.report.record_failed(&phone.phone_id, &phone.name, &brand.name, FailureCategory::Parse, &format!("merge: {}", e));
+                return false;
+            }
+        };
+
+        // Fetch picture gallery if enabled (through the same fetcher as the spec page)
+        if scraping.fetch_gallery {
+            let pictures = self
+                .config
+                .site
+                .pictures_page_url(&phone.phone_id)
+                .ok_or_else(|| "invalid phone ID".into())
+                .and_then(|url| fetcher.fetch(&url))
+                .map(|html| parse_pictures_page_from(&self.config.site.base_url, &html));
+
+            match pictures {
+                Ok(pictures) => phone_doc.gallery_urls = pictures.all_urls(),
+                Err(e) => warn!(error = %e, "Gallery error"),
+            }
+        }
+
+        // Keep the raw page so specifications can be re-derived later
+        if let Some(ref store) = self.snapshot_store {
+            match store.save(&phone.url, &html).await {
+                Ok(snapshot) => phone_doc.html_snapshot = Some(snapshot),
+                Err(e) => warn!(error = %e, "HTML snapshot error"),
+            }
+        }
+
+        // Download images to our own storage if configured
+        if let Some(ref downloader) = self.image_downloader {
+            phone_doc.stored_images = downloader
+                .download_phone_images(phone.image_url.as_deref(), &phone_doc.gallery_urls, scraping.download_gallery_images)
+                .await;
+        }
+
+        if let Some(ref previous) = previous {
+            if let Some(transition) = lifecycle::track(previous.as_ref(), &mut phone_doc, Utc::now()) {
+                info!(from = ?transition.from, to = ?transition.to, "Launch status changed");
+            }
+        }
+        let event = match (&self.webhooks, &previous) {
+            (Some(webhooks), Some(previous)) => PhoneEvent::detect(previous.as_ref(), &phone_doc, webhooks.tracked_fields()),
+            _ => None,
+        };
+        let new_device = (self.chat.is_some() && matches!(previous, Some(None))).then(|| NewDevice::from_phone(&phone_doc));
+        let message = self.events.as_ref().and_then(|_| {
+            EventPublisher::encode(&self.report.run_id, &phone_doc).map_err(|e| warn!(error = %e, "Cannot encode phone event")).ok()
+        });
+
+        let saved = self
+            .mongo_client
+            .save_phone((&collections.phones, &collections.phone_list), phone_doc, phone, &brand.name, self.transactional)
+            .await;
+        match saved {
+            Ok(_) => {
+                if let (Some(webhooks), Some(event)) = (&self.webhooks, &event) {
+                    webhooks.notify(&self.report.run_id, event).await;
+                }
+                if let (Some(events), Some(message)) = (&self.events, &message) {
+                    events.publish(&phone.phone_id, message).await;
+                }
+                if let (Some(chat), Some(device)) = (&self.chat, new_device) {
+                    if chat.should_announce(self.new_devices.len()) {
+                        chat.announce(&device).await;
+                    }
+                    self.new_devices.push(device);
+                }
+
+                self.existing_phone_ids.insert(phone.phone_id.clone());
+
+                info!(name = %phone.name, method = fetcher.label(), "✓ Saved");
+                self.report.record_inserted();
+                true
+            }
+            Err(e) => {
+                error!(error = %e, "✗ MongoDB error");
+                self.report.record_failed(&phone.phone_id, &phone.name, &brand.name, FailureCategory::Storage, &format!("MongoDB: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Phones a checkpoint lists as failed, tried again before the brands
+    /// Ones without a phone_list entry (or of an unselected brand) stay in the failed list.
+    async fn retry_failed(&mut self, phone_ids: &[String], brands: &[Brand]) {
+        if !phone_ids.is_empty() {
+            info!(count = phone_ids.len(), "Retrying phones that failed earlier in the chain");
+        }
+        for phone_id in phone_ids {
+            if self.stopping() {
+                break;
+            }
+            let entry = match self.mongo_client.get_phone_list_entry(&self.config.collections.phone_list, phone_id).await {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!(phone = %phone_id, error = %e, "Cannot look up failed phone");
+                    continue;
+                }
+            };
+            let Some((phone, brand)) = entry.and_then(|(phone, name)| Some((phone, brands.iter().find(|brand| brand.name == name)?))) else {
+                debug!(phone = %phone_id, "Failed phone not listed under a selected brand, leaving it");
+                continue;
+            };
+            let span = info_span!("phone", phone = %phone.phone_id, brand = %brand.name);
+            self.scrape_phone(brand, &phone).instrument(span).await;
+        }
+    }
+
+    /// Append the phone's market prices to collections.price_history, when set
+    async fn record_market_prices(&self, phone_doc: &PhoneDocument) {
+        let (Some(collection), Some(snapshot)) = (&self.config.collections.price_history, PriceSnapshot::of(phone_doc)) else {
+            return;
+        };
+        if let Err(e) = self.mongo_client.record_market_prices(collection, &snapshot).await {
+            warn!(error = %e, "Failed to record market prices");
+        }
+    }
+
+    /// The stored document stands: mark its phone_list entry complete again
+    async fn keep_stored(&mut self, brand: &Brand, phone: &PhoneListItem) {
+        let list = &self.config.collections.phone_list;
+        if let Err(e) = self.mongo_client.upsert_phone_list_entry(list, phone, &brand.name, true).await {
+            warn!(error = %e, "Failed to update phone_list entry");
+        }
+        self.existing_phone_ids.insert(phone.phone_id.clone());
+    }
+}
+
+/// With `scraping.discovery_parallelism` above 1, fetch every brand listing up front
+/// Brands whose listing failed are left out and get fetched again on their turn; past
+/// `deadline` (the end of `max_runtime`) no more pages are requested
+pub fn prefetch_brand_listings(
+    config: &Config,
+    fetcher: &PageFetcher,
+    brands: &[Brand],
+    max_phones: usize,
+    deadline: Option<Instant>,
+) -> HashMap<String, Vec<PhoneListItem>> {
+    let parallelism = config.scraping.discovery_parallelism;
+    let out_of_time = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+    if parallelism <= 1 || config.scraping.discovery != DiscoveryMode::Brands || config.scraping.network_filter.is_some() || fetcher.is_offline() || out_of_time() {
+        return HashMap::new();
+    }
+
+    info!(brands = brands.len(), parallelism, "Fetching brand listings in parallel");
+    let slugs: Vec<String> = brands.iter().map(|brand| brand.slug.clone()).collect();
+    let limiter = RateLimiter::new(std::time::Duration::from_millis(config.rate_limit.min_request_interval_ms));
+    let results = tokio::task::block_in_place(|| {
+        fetch_brand_listings_concurrently(&config.site.base_url, &slugs, max_phones, parallelism, &limiter, |url| match out_of_time() {
+            true => Err("max_runtime reached".into()),
+            false => fetcher.fetch(url),
+        })
+    });
+
+    let mut listings = HashMap::new();
+    let mut cut_short = 0;
+    for (brand, result) in brands.iter().zip(results) {
+        match result {
+            Ok(phones) => {
+                listings.insert(brand.slug.clone(), phones);
+            }
+            Err(_) if out_of_time() => cut_short += 1,
+            Err(e) => warn!(brand = %brand.name, error = %e, "Parallel listing failed, retrying in turn"),
+        }
+    }
+    if cut_short > 0 {
+        warn!(brands = cut_short, "max_runtime reached while fetching brand listings");
+    }
+    info!(brands = listings.len(), phones = listings.values().map(Vec::len).sum::<usize>(), "✓ Fetched brand listings");
+    listings
+}
+
+/// Keep only the brands that pass the include/exclude filter and fall in the shard, if any
+pub fn select_brands(brands: Vec<Brand>, filter: &BrandFilter) -> Vec<Brand> {
+    brands.into_iter().filter(|brand| filter.selects(brand)).collect()
+}
+
+/// Log the brand include/exclude lists and shard when set
+pub fn log_brand_filter(filter: &BrandFilter) {
+    if !filter.include.is_empty() {
+        info!(brands = %filter.include.join(", "), "Brand filter");
+    }
+    if !filter.exclude.is_empty() {
+        info!(brands = %filter.exclude.join(", "), "Excluded brands");
+    }
+    if let Some(shard) = filter.shard {
+        info!(shard = %shard, "Taking this shard of the brands");
+    }
+}
+
+/// Fetch brand metadata through the given fetcher
+fn fetch_brand_details(fetcher: &PageFetcher, site: &SiteConfig, brand_slug: &str) -> Result<BrandDetails, Box<dyn Error>> {
+    let html = fetcher.fetch(&site.brand_page_url(brand_slug, 1))?;
+    let mut details = parse_brand_details(&html);
+    details.slug = brand_slug.to_string();
+    Ok(details)
+}
+
+/// Failed and skipped phones by category, after the run summary
+pub fn log_failures(failures: &FailureStats) {
+    if failures.failed() + failures.skipped() == 0 {
+        return;
+    }
+    info!(
+        network = failures.network,
+        blocked = failures.blocked,
+        parse = failures.parse,
+        storage = failures.storage,
+        skipped_complete = failures.skipped_complete,
+        skipped_existing = failures.skipped_existing,
+        skipped_filtered = failures.skipped_filtered,
+        "Failures and skips by category"
+    );
+}
+
+/// Fetch and parse one phone page, returning the raw specification JSON and the page HTML
+pub async fn fetch_phone(
+    fetcher: &PageFetcher,
+    site: &SiteConfig,
+    phone: &PhoneListItem,
+    delay_ms: u64,
+) -> Result<(serde_json::Value, String), Box<dyn Error>> {
+    let url = site.phone_page_url(&phone.phone_id);
+    if fetcher.is_rate_limited() && !fetcher.is_cached(&url) {
+        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+    }
+
+    let (spec, html) = fetcher.fetch_parsed(&url, |html| parse_specification_html(&phone.phone_id, html))?;
+    Ok((serde_json::to_value(&spec)?, html))
+}
@@ -0,0 +1,228 @@
+use super::fetch::PageFetcher;
+use super::scrape::transactions_available;
+use super::{override_with, phone_id_for, CollectionArgs};
+use chrono::Utc;
+use clap::Args;
+use gsmarena_scraper::lifecycle;
+use gsmarena_scraper::scraper::{fetch_specification_from, phone_page_url};
+use gsmarena_scraper::site::SiteConfig;
+use gsmarena_scraper::sitemap::{brand_from_name, phone_from_url};
+use gsmarena_scraper::utils::{format_phone_info, save_phones_to_json};
+use gsmarena_scraper::{Config, DeviceSpecification, FailureCategory, FailureStats, MongoDBClient, MongoPhoneStore, PhoneDocument, PhoneService, Shutdown};
+use std::error::Error;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{error, info, info_span, warn, Instrument};
+
+#[derive(Debug, Args)]
+pub struct SpecsArgs {
+    /// Phone IDs ("apple_iphone_15-12559") or names ("iphone 15")
+    #[arg(required_unless_present = "ids_file", value_name = "PHONE")]
+    pub phones: Vec<String>,
+
+    /// Read phone IDs from this file (one per line, `-` for stdin) and upsert their specs into MongoDB
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["phones", "output"])]
+    pub ids_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Save the specifications to this JSON file instead of printing them
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Serve phones from the specs collection; only missing or stale ones are fetched (and stored)
+    #[arg(long, conflicts_with = "ids_file")]
+    pub cached: bool,
+
+    /// With --cached, also refetch phones scraped more than this many days ago
+    #[arg(long, requires = "cached", value_name = "DAYS")]
+    pub max_age_days: Option<i64>,
+
+    /// Delay between phones [config: rate_limit.delay_between_phones_ms]
+    #[arg(long)]
+    pub delay_between_phones_ms: Option<u64>,
+}
+
+impl SpecsArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+        override_with(&mut config.rate_limit.delay_between_phones_ms, &self.delay_between_phones_ms);
+    }
+}
+
+pub async fn run(args: SpecsArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    if let Some(ref path) = args.ids_file {
+        return upsert_ids(path, config).await;
+    }
+
+    let service = match args.cached {
+        true => Some(phone_service(config, args.max_age_days).await?),
+        false => None,
+    };
+    let mut specs = Vec::new();
+    let mut failures = FailureStats::default();
+
+    for (index, query) in args.phones.iter().enumerate() {
+        if let Some(reason) = config.scraping.error_policy.stop_reason(failures.failed()) {
+            warn!(reason = %reason, remaining = args.phones.len() - index, "Stopping");
+            break;
+        }
+        if index > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(config.rate_limit.delay_between_phones_ms)).await;
+        }
+
+        let result = match (phone_id_for(&config.site, query), &service) {
+            (Ok(phone_id), Some(service)) => cached_specification(service, &phone_id).await,
+            (Ok(phone_id), None) => tokio::task::block_in_place(|| fetch_specification_from(&config.site.base_url, &phone_id)),
+            (Err(e), _) => Err(e),
+        };
+
+        match result {
+            Ok(spec) => {
+                if args.output.is_none() {
+                    println!("{}", format_phone_info(&spec));
+                    println!("{}", "=".repeat(70));
+                }
+                specs.push(spec);
+            }
+            Err(e) => {
+                let category = FailureCategory::classify(&e.to_string());
+                error!(phone = %query, category = %category, error = %e, "✗ Failed to fetch specifications");
+                failures.record_failure(category);
+            }
+        }
+    }
+
+    if let Some(path) = args.output {
+        save_phones_to_json(&specs, &path)?;
+        info!(count = specs.len(), output = %path.display(), "✓ Saved phones");
+    }
+
+    if failures.failed() > 0 {
+        return Err(format!("{} of {} phones failed ({})", failures.failed(), args.phones.len(), failures).into());
+    }
+
+    Ok(())
+}
+
+/// Read-through lookups over the specs collection, fetching through the configured provider
+async fn phone_service(config: &Config, max_age_days: Option<i64>) -> Result<PhoneService, Box<dyn Error>> {
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let store = Arc::new(MongoPhoneStore::new(mongo_client, config.collections.clone()).with_upsert_policy(config.mongodb.upsert_policy));
+    let service = PhoneService::new(store, Arc::new(PageFetcher::for_provider(config)?), config.site.clone())
+        .with_min_completeness(config.validation.min_completeness);
+
+    Ok(match max_age_days {
+        Some(days) => service.with_max_age(chrono::Duration::days(days)),
+        None => service,
+    })
+}
+
+/// The stored (or freshly fetched) specifications of one phone
+async fn cached_specification(service: &PhoneService, phone_id: &str) -> Result<DeviceSpecification, Box<dyn Error>> {
+    let (phone, source) = service.get_with_source(phone_id).await?;
+    info!(phone = %phone_id, source = ?source, "Resolved specifications");
+    Ok(serde_json::from_value(phone.specifications_raw)?)
+}
+
+/// Fetch and upsert an explicit list of phones, skipping discovery
+async fn upsert_ids(path: &Path, config: &Config) -> Result<(), Box<dyn Error>> {
+    let phone_ids = read_phone_ids(path)?;
+    if phone_ids.is_empty() {
+        return Err(format!("No phone IDs in {}", path.display()).into());
+    }
+
+    let collections = &config.collections;
+    info!(count = phone_ids.len(), collection = %collections.phones, "Upserting phones");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let transactional = config.mongodb.transactions && transactions_available(&mongo_client).await;
+    let shutdown = Shutdown::new().install();
+    let mut failures = FailureStats::default();
+    let mut processed = 0;
+
+    for (index, phone_id) in phone_ids.iter().enumerate() {
+        if let Some(reason) = config.scraping.error_policy.stop_reason(failures.failed()) {
+            warn!(reason = %reason, remaining = phone_ids.len() - index, "Stopping");
+            break;
+        }
+        if index > 0 && !shutdown.sleep(std::time::Duration::from_millis(config.rate_limit.delay_between_phones_ms)).await {
+            warn!(remaining = phone_ids.len() - index, "Shutdown requested, stopping");
+            break;
+        }
+        processed += 1;
+        let span = info_span!("phone", phone = %phone_id, index = index + 1, total = phone_ids.len());
+
+        match upsert_phone(&mongo_client, config, phone_id, transactional).instrument(span.clone()).await {
+            Ok(()) => info!(parent: &span, "✓ Saved"),
+            Err(e) => {
+                let category = FailureCategory::classify(&e.to_string());
+                error!(parent: &span, category = %category, error = %e, "✗ Failed");
+                failures.record_failure(category);
+            }
+        }
+    }
+
+    info!(upserted = processed - failures.failed() as usize, total = phone_ids.len(), "✓ Upsert complete");
+
+    if failures.failed() > 0 {
+        return Err(format!("{} of {} phones failed ({})", failures.failed(), phone_ids.len(), failures).into());
+    }
+
+    Ok(())
+}
+
+async fn upsert_phone(mongo_client: &MongoDBClient, config: &Config, phone_id: &str, transactional: bool) -> Result<(), Box<dyn Error>> {
+    let collections = &config.collections;
+
+    // Prefer the name and brand recorded at discovery; otherwise derive them from the ID
+    let (phone, brand) = match mongo_client.get_phone_list_entry(&collections.phone_list, phone_id).await? {
+        Some(entry) => entry,
+        None => {
+            let phone = phone_from_url(&SiteConfig::default(), &phone_page_url(phone_id)).ok_or("invalid phone ID")?;
+            let brand = brand_from_name(&phone);
+            (phone, brand)
+        }
+    };
+
+    let spec = tokio::task::block_in_place(|| fetch_specification_from(&config.site.base_url, phone_id))?;
+    let mut phone_doc = PhoneDocument::new(&phone, &brand, serde_json::to_value(&spec)?);
+
+    let previous = mongo_client.get_phone(&collections.phones, phone_id).await?;
+    lifecycle::track(previous.as_ref(), &mut phone_doc, Utc::now());
+    mongo_client
+        .save_phone((&collections.phones, &collections.phone_list), phone_doc, &phone, &brand, transactional)
+        .await?;
+
+    Ok(())
+}
+
+/// Read phone IDs, one per line; blank lines and `#` comments are skipped, duplicates dropped
+fn read_phone_ids(path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut contents = String::new();
+    if path == Path::new("-") {
+        std::io::stdin().read_to_string(&mut contents)?;
+    } else {
+        contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    }
+
+    let mut phone_ids: Vec<String> = Vec::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if phone_from_url(&SiteConfig::default(), &phone_page_url(line)).is_none() {
+            warn!(line = line_number + 1, value = line, "Not a phone ID, skipping");
+        } else if !phone_ids.iter().any(|id| id == line) {
+            phone_ids.push(line.to_string());
+        }
+    }
+
+    Ok(phone_ids)
+}
@@ -0,0 +1,96 @@
+use super::fetch::PageFetcher;
+use super::override_with;
+use clap::Args;
+use gsmarena_scraper::budget::RequestBudget;
+use gsmarena_scraper::{AdaptiveDelay, Config, GlobalRateLimiter, MongoDBClient, Shutdown, UpcomingTracker};
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{error, info};
+
+#[derive(Debug, Args)]
+pub struct UpcomingArgs {
+    /// Collection holding tracked devices [config: collections.upcoming]
+    #[arg(long)]
+    pub collection: Option<String>,
+
+    /// Minutes between re-checks [config: upcoming.recheck_minutes]
+    #[arg(long)]
+    pub recheck_minutes: Option<u64>,
+
+    /// Run a single discover + re-check pass and exit
+    #[arg(long, env = "UPCOMING_RUN_ONCE")]
+    pub once: bool,
+
+    /// Delay between re-checked phones [config: rate_limit.delay_between_phones_ms]
+    #[arg(long)]
+    pub delay_between_phones_ms: Option<u64>,
+}
+
+impl UpcomingArgs {
+    pub fn apply(&self, config: &mut Config) {
+        override_with(&mut config.collections.upcoming, &self.collection);
+        override_with(&mut config.upcoming.recheck_minutes, &self.recheck_minutes);
+        override_with(&mut config.rate_limit.delay_between_phones_ms, &self.delay_between_phones_ms);
+    }
+}
+
+pub async fn run(args: UpcomingArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let recheck_minutes = config.upcoming.recheck_minutes;
+    let delay_between_phones_ms = config.rate_limit.delay_between_phones_ms;
+
+    info!(
+        collection = %config.collections.upcoming,
+        recheck_minutes,
+        once = args.once,
+        delay_between_phones_ms,
+        "GSMArena Scraper - Upcoming Devices Tracker"
+    );
+
+    let mongo_client = MongoDBClient::from_env().await?;
+
+    // Phone Finder and re-checked pages go through the configured provider and rate limits
+    let global_limiter = GlobalRateLimiter::from_config(config, &mongo_client.database()).await?;
+    let adaptive_delay = AdaptiveDelay::from_config(&config.rate_limit);
+    let fetcher = PageFetcher::for_provider(config)?
+        .with_budget(&RequestBudget::new(&config.budget))
+        .with_global_limiter(global_limiter.as_ref())
+        .with_adaptive_delay(adaptive_delay.as_ref());
+
+    let tracker = UpcomingTracker::new(&mongo_client, &config.collections.upcoming)
+        .with_base_url(&config.site.base_url)
+        .with_source(Arc::new(fetcher));
+    tracker.create_indexes().await.ok();
+    let shutdown = Shutdown::new().install();
+
+    loop {
+        info!("Discovering rumored and coming-soon devices...");
+        match tracker.discover().await {
+            Ok(count) => info!(count, "✓ Newly tracked devices"),
+            Err(e) => error!(error = %e, "✗ Discovery failed"),
+        }
+
+        info!("Re-checking tracked devices...");
+        match tracker.recheck(delay_between_phones_ms).await {
+            Ok(events) => {
+                // Change events go to stdout as JSON lines for downstream consumers
+                for event in &events {
+                    println!("{}", serde_json::to_string(event)?);
+                }
+                info!(count = events.len(), "✓ Status changes");
+            }
+            Err(e) => error!(error = %e, "✗ Re-check failed"),
+        }
+
+        if args.once {
+            break;
+        }
+
+        info!(minutes = recheck_minutes, "⏳ Waiting for next check");
+        if !shutdown.sleep(std::time::Duration::from_secs(recheck_minutes * 60)).await {
+            info!("✓ Tracker stopped");
+            break;
+        }
+    }
+
+    Ok(())
+}
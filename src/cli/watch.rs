@@ -0,0 +1,169 @@
+use super::CollectionArgs;
+use chrono::{DateTime, Utc};
+use clap::{Args, ValueEnum};
+use futures::stream::StreamExt;
+use gsmarena_scraper::{ChatNotifier, Config, MongoDBClient, NewDevice, PhoneDocument, PhoneEvent, Shutdown, WebhookNotifier};
+use mongodb::bson::{Bson, Document};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType};
+use serde::Serialize;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub collections: CollectionArgs,
+
+    /// Where events go; repeat for several
+    #[arg(long = "to", value_enum, default_values_t = [WatchSink::Stdout])]
+    pub sinks: Vec<WatchSink>,
+
+    /// Include the full document in stdout events
+    #[arg(long)]
+    pub full_document: bool,
+
+    /// File keeping the stream position, so a restart resumes where the last one stopped
+    #[arg(long, value_name = "PATH")]
+    pub resume_token: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WatchSink {
+    /// One JSON object per line
+    Stdout,
+    /// phone.created / phone.changed to the configured webhook URLs
+    Webhook,
+    /// Announce inserted phones on Telegram / Discord
+    Chat,
+}
+
+impl WatchArgs {
+    pub fn apply(&self, config: &mut Config) {
+        self.collections.apply(config);
+    }
+}
+
+/// One change as printed to stdout
+#[derive(Debug, Serialize)]
+struct WatchEvent<'a> {
+    operation: &'static str,
+    phone_id: Option<&'a str>,
+    at: Option<DateTime<Utc>>, // Cluster time of the write
+    updated_fields: Vec<String>,
+    removed_fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document: Option<serde_json::Value>,
+}
+
+pub async fn run(args: WatchArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let webhooks = match args.sinks.contains(&WatchSink::Webhook) {
+        true => Some(WebhookNotifier::from_config(&config.webhooks).ok_or("--to webhook needs webhooks.urls")?),
+        false => None,
+    };
+    let chat = match args.sinks.contains(&WatchSink::Chat) {
+        true => Some(ChatNotifier::from_config(&config.chat).ok_or("--to chat needs TELEGRAM_BOT_TOKEN or DISCORD_WEBHOOK_URL")?),
+        false => None,
+    };
+
+    info!("Connecting to MongoDB...");
+    let mongo_client = MongoDBClient::from_env().await?;
+    let resume_after = match &args.resume_token {
+        Some(path) if path.exists() => Some(serde_json::from_str(&std::fs::read_to_string(path)?)?),
+        _ => None,
+    };
+    let mut stream = mongo_client.watch_phones(&config.collections.phones, resume_after).await?;
+
+    let shutdown = Shutdown::new().install();
+    let run_id = format!("watch-{}", Utc::now().format("%Y%m%dT%H%M%SZ"));
+    info!(collection = %config.collections.phones, "Watching for changes (Ctrl+C to stop)");
+
+    let mut seen = 0;
+    loop {
+        let event = tokio::select! {
+            event = stream.next() => event,
+            _ = shutdown.wait() => break,
+        };
+        // The driver resumes by itself after network errors; anything else ends the watch
+        let Some(event) = event else {
+            warn!("Change stream closed by the server");
+            break;
+        };
+        let event = event?;
+        seen += 1;
+
+        let current = event.full_document.clone().and_then(|document| phone_from(document, "full document"));
+        let (updated_fields, removed_fields): (Vec<String>, Vec<String>) = match &event.update_description {
+            Some(update) => (update.updated_fields.keys().cloned().collect(), update.removed_fields.clone()),
+            None => (event.full_document.iter().flat_map(|d| d.keys().cloned()).collect(), Vec::new()),
+        };
+
+        if args.sinks.contains(&WatchSink::Stdout) {
+            let record = WatchEvent {
+                operation: operation_name(&event.operation_type),
+                phone_id: event.full_document.as_ref().and_then(|d| d.get_str("phone_id").ok()),
+                at: event.cluster_time.and_then(|t| DateTime::from_timestamp(t.time.into(), 0)),
+                updated_fields: updated_fields.clone(),
+                removed_fields,
+                document: args.full_document.then(|| event.full_document.clone().map(|d| Bson::Document(d).into_relaxed_extjson())).flatten(),
+            };
+            println!("{}", serde_json::to_string(&record)?);
+        }
+
+        if let Some(current) = &current {
+            if let Some(webhooks) = &webhooks {
+                if let Some(phone_event) = phone_event(&event, current, &updated_fields, webhooks.tracked_fields()) {
+                    webhooks.notify(&run_id, &phone_event).await;
+                }
+            }
+            if let (Some(chat), OperationType::Insert) = (&chat, &event.operation_type) {
+                chat.announce(&NewDevice::from_phone(current)).await;
+            }
+        }
+
+        if let Some(path) = &args.resume_token {
+            if let Err(e) = save_resume_token(path, &stream.resume_token()) {
+                warn!(path = %path.display(), error = %e, "Failed to save resume token");
+            }
+        }
+    }
+
+    info!(events = seen, "Stopped watching");
+    Ok(())
+}
+
+/// Webhook event for one change: exact when the pre-image is available, otherwise
+/// inferred from the fields the update touched
+fn phone_event(event: &ChangeStreamEvent<Document>, current: &PhoneDocument, updated_fields: &[String], tracked_fields: &[String]) -> Option<PhoneEvent> {
+    if let OperationType::Insert = event.operation_type {
+        return PhoneEvent::detect(None, current, tracked_fields);
+    }
+    match event.full_document_before_change.clone().and_then(|document| phone_from(document, "pre-image")) {
+        Some(before) => PhoneEvent::detect(Some(&before), current, tracked_fields),
+        None => PhoneEvent::from_update(current, updated_fields, tracked_fields),
+    }
+}
+
+fn phone_from(document: Document, what: &str) -> Option<PhoneDocument> {
+    match mongodb::bson::from_document(document) {
+        Ok(phone) => Some(phone),
+        Err(e) => {
+            warn!(error = %e, "Cannot read {} as a phone document", what);
+            None
+        }
+    }
+}
+
+fn operation_name(operation: &OperationType) -> &'static str {
+    match operation {
+        OperationType::Insert => "insert",
+        OperationType::Update => "update",
+        OperationType::Replace => "replace",
+        _ => "other",
+    }
+}
+
+fn save_resume_token(path: &Path, token: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    std::fs::write(path, serde_json::to_string(token)?)?;
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Phone-by-phone differences between two collections or snapshots (`db diff`)
+//!
+//! Documents are matched on `phone_id` and compared field by field, with nested
+//! objects flattened to dotted paths ("normalized.battery_mah"). Arrays are compared
+//! as whole values.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Fields that change on every scrape and say nothing about the data
+pub const VOLATILE_FIELDS: &[&str] = &["_id", "scraped_at", "updated_at"];
+
+/// One field whose value differs; null when missing on that side
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhoneDiff {
+    pub phone_id: String,
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionDiff {
+    pub added: Vec<String>,   // Only in the second source
+    pub removed: Vec<String>, // Only in the first source
+    pub changed: Vec<PhoneDiff>,
+    pub unchanged: usize,
+}
+
+impl CollectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// How many changed phones each field differs in, most common first
+    pub fn field_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for phone in &self.changed {
+            for field in &phone.fields {
+                *counts.entry(&field.field).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().map(|(field, count)| (field.to_string(), count)).collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+}
+
+/// Leaf values of a document keyed by dotted path, leaving out `ignore`d fields (and their children)
+fn flatten(value: &Value, ignore: &[String]) -> BTreeMap<String, Value> {
+    fn walk(prefix: &str, object: &Map<String, Value>, ignore: &[String], out: &mut BTreeMap<String, Value>) {
+        for (key, value) in object {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            if ignore.contains(&path) {
+                continue;
+            }
+            match value {
+                Value::Object(child) if !child.is_empty() && !child.keys().any(|k| k.starts_with('$')) => walk(&path, child, ignore, out),
+                _ => {
+                    out.insert(path, value.clone());
+                }
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    if let Value::Object(object) = value {
+        walk("", object, ignore, &mut out);
+    }
+    out
+}
+
+/// Field-level differences between two versions of one document
+pub fn diff_documents(before: &Value, after: &Value, ignore: &[String]) -> Vec<FieldDiff> {
+    let (before, after) = (flatten(before, ignore), flatten(after, ignore));
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).unwrap_or(&Value::Null);
+            let new = after.get(field).unwrap_or(&Value::Null);
+            (old != new).then(|| FieldDiff {
+                field: field.clone(),
+                before: old.clone(),
+                after: new.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Compare two sets of documents keyed by phone_id
+pub fn diff_collections(before: &BTreeMap<String, Value>, after: &BTreeMap<String, Value>, ignore: &[String]) -> CollectionDiff {
+    let mut diff = CollectionDiff {
+        added: after.keys().filter(|id| !before.contains_key(*id)).cloned().collect(),
+        removed: before.keys().filter(|id| !after.contains_key(*id)).cloned().collect(),
+        ..Default::default()
+    };
+
+    for (phone_id, old) in before {
+        let Some(new) = after.get(phone_id) else { continue };
+        let fields = diff_documents(old, new, ignore);
+        if fields.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.changed.push(PhoneDiff { phone_id: phone_id.clone(), fields });
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_collections() {
+        let ignore: Vec<String> = VOLATILE_FIELDS.iter().map(|f| f.to_string()).collect();
+        let keyed = |documents: Vec<Value>| -> BTreeMap<String, Value> {
+            documents.into_iter().map(|d| (d["phone_id"].as_str().unwrap().to_string(), d)).collect()
+        };
+
+        let before = keyed(vec![
+            json!({ "phone_id": "a-1", "name": "A", "scraped_at": "2024-01-01", "normalized": { "battery_mah": 5000, "has_nfc": true } }),
+            json!({ "phone_id": "b-2", "name": "B", "gallery_urls": ["x"] }),
+            json!({ "phone_id": "gone-3", "name": "Gone" }),
+        ]);
+        let after = keyed(vec![
+            json!({ "phone_id": "a-1", "name": "A", "scraped_at": "2025-01-01", "normalized": { "battery_mah": 4500, "ram_gb": 8.0, "has_nfc": true } }),
+            json!({ "phone_id": "b-2", "name": "B", "gallery_urls": ["x"] }),
+            json!({ "phone_id": "new-4", "name": "New" }),
+        ]);
+
+        let diff = diff_collections(&before, &after, &ignore);
+        assert_eq!(diff.added, vec!["new-4"]);
+        assert_eq!(diff.removed, vec!["gone-3"]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].fields,
+            vec![
+                FieldDiff { field: "normalized.battery_mah".into(), before: json!(5000), after: json!(4500) },
+                FieldDiff { field: "normalized.ram_gb".into(), before: Value::Null, after: json!(8.0) },
+            ]
+        );
+        assert_eq!(diff.field_counts()[0], ("normalized.battery_mah".to_string(), 1));
+
+        // Extended JSON values ({"$date": ...}) are compared whole
+        let dated = |date: &str| json!({ "phone_id": "a-1", "released": { "$date": date } });
+        let fields = diff_documents(&dated("2024-01-01"), &dated("2024-02-01"), &ignore);
+        assert_eq!(fields[0].field, "released");
+    }
+}
@@ -0,0 +1,408 @@
+//! Side-by-side comparison of N phones with per-category winners and a weighted score
+
+use crate::mongodb::PhoneDocument;
+use crate::normalize::NormalizedSpecs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A numeric spec extracted from the structured fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    DisplaySizeIn,
+    BrightnessNits,
+    RefreshRateHz,
+    AnnouncedYear,
+    RamGb,
+    StorageGb,
+    MainCameraMp,
+    BatteryMah,
+    ChargingW,
+    WeightG,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 10] = [
+        Metric::DisplaySizeIn,
+        Metric::BrightnessNits,
+        Metric::RefreshRateHz,
+        Metric::AnnouncedYear,
+        Metric::RamGb,
+        Metric::StorageGb,
+        Metric::MainCameraMp,
+        Metric::BatteryMah,
+        Metric::ChargingW,
+        Metric::WeightG,
+    ];
+
+    /// Name used in JSON and `--weight` ("battery_mah")
+    pub fn key(&self) -> &'static str {
+        match self {
+            Metric::DisplaySizeIn => "display_size_in",
+            Metric::BrightnessNits => "brightness_nits",
+            Metric::RefreshRateHz => "refresh_rate_hz",
+            Metric::AnnouncedYear => "announced_year",
+            Metric::RamGb => "ram_gb",
+            Metric::StorageGb => "storage_gb",
+            Metric::MainCameraMp => "main_camera_mp",
+            Metric::BatteryMah => "battery_mah",
+            Metric::ChargingW => "charging_w",
+            Metric::WeightG => "weight_g",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::DisplaySizeIn => "Display size",
+            Metric::BrightnessNits => "Peak brightness",
+            Metric::RefreshRateHz => "Refresh rate",
+            Metric::AnnouncedYear => "Announced",
+            Metric::RamGb => "RAM",
+            Metric::StorageGb => "Storage",
+            Metric::MainCameraMp => "Main camera",
+            Metric::BatteryMah => "Battery",
+            Metric::ChargingW => "Charging",
+            Metric::WeightG => "Weight",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Metric::DisplaySizeIn => "in",
+            Metric::BrightnessNits => "nits",
+            Metric::RefreshRateHz => "Hz",
+            Metric::AnnouncedYear => "",
+            Metric::RamGb | Metric::StorageGb => "GB",
+            Metric::MainCameraMp => "MP",
+            Metric::BatteryMah => "mAh",
+            Metric::ChargingW => "W",
+            Metric::WeightG => "g",
+        }
+    }
+
+    /// Spec category the metric counts towards
+    pub fn category(&self) -> &'static str {
+        match self {
+            Metric::DisplaySizeIn | Metric::BrightnessNits | Metric::RefreshRateHz => "display",
+            Metric::AnnouncedYear => "launch",
+            Metric::RamGb | Metric::StorageGb => "memory",
+            Metric::MainCameraMp => "camera",
+            Metric::BatteryMah | Metric::ChargingW => "battery",
+            Metric::WeightG => "body",
+        }
+    }
+
+    pub fn higher_is_better(&self) -> bool {
+        !matches!(self, Metric::WeightG)
+    }
+
+    /// Weight in the overall score unless overridden; display size is a preference, not a merit
+    pub fn default_weight(&self) -> f64 {
+        match self {
+            Metric::DisplaySizeIn => 0.0,
+            Metric::BatteryMah | Metric::MainCameraMp | Metric::AnnouncedYear => 2.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Read the metric from a document's normalized values (`None` when the spec is missing or unparsable)
+    pub fn extract(&self, normalized: &NormalizedSpecs) -> Option<f64> {
+        match self {
+            Metric::DisplaySizeIn => normalized.display_size_in,
+            Metric::BrightnessNits => normalized.brightness_nits,
+            Metric::RefreshRateHz => normalized.refresh_rate_hz,
+            Metric::AnnouncedYear => normalized.announced_year.map(f64::from),
+            Metric::RamGb => normalized.ram_gb,
+            Metric::StorageGb => normalized.storage_gb,
+            Metric::MainCameraMp => normalized.main_camera_mp,
+            Metric::BatteryMah => normalized.battery_mah.map(f64::from),
+            Metric::ChargingW => normalized.charging_w,
+            Metric::WeightG => normalized.weight_g,
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Metric::ALL
+            .into_iter()
+            .find(|metric| metric.key().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let keys: Vec<&str> = Metric::ALL.iter().map(Metric::key).collect();
+                format!("unknown metric '{}' (expected one of: {})", s, keys.join(", "))
+            })
+    }
+}
+
+/// Per-metric weights for the overall score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonWeights(pub BTreeMap<Metric, f64>);
+
+impl Default for ComparisonWeights {
+    fn default() -> Self {
+        Self(Metric::ALL.into_iter().map(|metric| (metric, metric.default_weight())).collect())
+    }
+}
+
+impl ComparisonWeights {
+    pub fn get(&self, metric: Metric) -> f64 {
+        self.0.get(&metric).copied().unwrap_or(0.0)
+    }
+
+    pub fn set(&mut self, metric: Metric, weight: f64) {
+        self.0.insert(metric, weight);
+    }
+
+    /// Apply a "battery_mah=3" override
+    pub fn apply_override(&mut self, assignment: &str) -> Result<(), Box<dyn Error>> {
+        let (key, value) = assignment.split_once('=').ok_or_else(|| format!("expected METRIC=WEIGHT, got '{}'", assignment))?;
+        let weight: f64 = value.trim().parse().map_err(|_| format!("invalid weight '{}'", value))?;
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(format!("weight for {} must be zero or positive", key).into());
+        }
+        self.set(key.parse()?, weight);
+        Ok(())
+    }
+}
+
+/// One phone's place in the comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedPhone {
+    pub phone_id: String,
+    pub name: String,
+    pub brand: String,
+    pub image_url: Option<String>,
+    pub score: f64, // 0-100 weighted score
+    pub rank: usize, // 1 = best
+}
+
+/// Values of one metric across the phones (same order as `ComparisonResult::phones`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub metric: Metric,
+    pub label: String,
+    pub unit: String,
+    pub values: Vec<Option<f64>>,
+    pub winner: Option<usize>, // Index of the best phone; None on a tie or without values
+    pub delta: Option<f64>,    // Best minus worst, in `unit`
+    pub weight: f64,
+}
+
+/// Which phone wins a spec category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryResult {
+    pub category: String,
+    pub winner: Option<usize>,
+    pub scores: Vec<f64>, // Sum of normalized metric scores (0-1 each)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonResult {
+    pub phones: Vec<ComparedPhone>,
+    pub metrics: Vec<MetricComparison>,
+    pub categories: Vec<CategoryResult>,
+    pub winner: Option<usize>,
+}
+
+/// Compare phones; metrics nobody has are left out
+pub fn compare(phones: &[PhoneDocument], weights: &ComparisonWeights) -> ComparisonResult {
+    // Re-derived so documents stored before normalization compare the same way
+    let normalized: Vec<NormalizedSpecs> = phones.iter().map(NormalizedSpecs::from_phone).collect();
+    let mut metrics = Vec::new();
+    let mut scaled: Vec<(Metric, Vec<f64>)> = Vec::new();
+
+    for metric in Metric::ALL {
+        let values: Vec<Option<f64>> = normalized.iter().map(|specs| metric.extract(specs)).collect();
+        let present: Vec<f64> = values.iter().flatten().copied().collect();
+        if present.is_empty() {
+            continue;
+        }
+
+        let (min, max) = present.iter().fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        let scores: Vec<f64> = values
+            .iter()
+            .map(|value| match value {
+                None => 0.0,
+                Some(_) if max == min => 1.0,
+                Some(v) if metric.higher_is_better() => (v - min) / (max - min),
+                Some(v) => (max - v) / (max - min),
+            })
+            .collect();
+
+        metrics.push(MetricComparison {
+            metric,
+            label: metric.label().to_string(),
+            unit: metric.unit().to_string(),
+            winner: best_index(&scores),
+            delta: Some(max - min),
+            values,
+            weight: weights.get(metric),
+        });
+        scaled.push((metric, scores));
+    }
+
+    let mut categories: Vec<CategoryResult> = Vec::new();
+    for (metric, scores) in &scaled {
+        let category = match categories.iter_mut().find(|c| c.category == metric.category()) {
+            Some(category) => category,
+            None => {
+                categories.push(CategoryResult {
+                    category: metric.category().to_string(),
+                    winner: None,
+                    scores: vec![0.0; phones.len()],
+                });
+                categories.last_mut().unwrap()
+            }
+        };
+        for (total, score) in category.scores.iter_mut().zip(scores) {
+            *total += score;
+        }
+    }
+    for category in &mut categories {
+        category.winner = best_index(&category.scores);
+    }
+
+    let total_weight: f64 = scaled.iter().map(|(metric, _)| weights.get(*metric)).sum();
+    let overall: Vec<f64> = (0..phones.len())
+        .map(|index| {
+            if total_weight <= 0.0 {
+                return 0.0;
+            }
+            let weighted: f64 = scaled.iter().map(|(metric, scores)| weights.get(*metric) * scores[index]).sum();
+            (weighted / total_weight * 1000.0).round() / 10.0
+        })
+        .collect();
+
+    let compared = phones
+        .iter()
+        .zip(&overall)
+        .map(|(phone, score)| ComparedPhone {
+            phone_id: phone.phone_id.clone(),
+            name: phone.name.clone(),
+            brand: phone.brand.clone(),
+            image_url: phone.image_url.clone(),
+            score: *score,
+            rank: 1 + overall.iter().filter(|other| *other > score).count(),
+        })
+        .collect();
+
+    ComparisonResult {
+        phones: compared,
+        metrics,
+        categories,
+        winner: best_index(&overall),
+    }
+}
+
+/// Index of the single highest score (None on a tie)
+fn best_index(scores: &[f64]) -> Option<usize> {
+    let best = scores.iter().copied().reduce(f64::max)?;
+    let mut leaders = scores.iter().enumerate().filter(|(_, score)| **score == best);
+    let (index, _) = leaders.next()?;
+    leaders.next().is_none().then_some(index)
+}
+
+impl ComparisonResult {
+    /// Plain-text table: one column per phone, `*` marks the winner of each row
+    pub fn to_table(&self) -> String {
+        let name_of = |index: usize| self.phones[index].name.clone();
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new()).chain(self.phones.iter().map(|p| p.name.clone())).collect()];
+
+        for metric in &self.metrics {
+            let mut row = vec![format!("{} ({})", metric.label, if metric.unit.is_empty() { "year" } else { &metric.unit })];
+            for (index, value) in metric.values.iter().enumerate() {
+                let mark = if metric.winner == Some(index) { " *" } else { "" };
+                row.push(value.map_or_else(|| "N/A".to_string(), |v| format!("{}{}", format_value(v), mark)));
+            }
+            rows.push(row);
+        }
+
+        let mut score_row = vec!["Score (0-100)".to_string()];
+        score_row.extend(self.phones.iter().map(|p| format!("{:.1} (#{})", p.score, p.rank)));
+        rows.push(score_row);
+
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|col| rows.iter().map(|row| row[col].chars().count()).max().unwrap_or(0))
+            .collect();
+
+        let mut output = String::new();
+        for (index, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+            output.push_str(cells.join("  ").trim_end());
+            output.push('\n');
+            if index == 0 || index == rows.len() - 2 {
+                output.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+                output.push('\n');
+            }
+        }
+
+        output.push('\n');
+        for category in &self.categories {
+            let winner = category.winner.map_or_else(|| "tie".to_string(), name_of);
+            output.push_str(&format!("{:<9} {}\n", category.category, winner));
+        }
+        output.push_str(&format!("\nOverall: {}\n", self.winner.map_or_else(|| "tie".to_string(), name_of)));
+        output
+    }
+}
+
+/// Whole numbers without decimals, everything else to one decimal
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(id: &str, battery: &str, weight: &str, internal: &str) -> PhoneDocument {
+        PhoneDocument::fixture(id)
+            .category("Body", &[("Weight", weight)])
+            .category("Memory", &[("Internal", internal)])
+            .category("Battery", &[("Type", battery), ("Charging", "25W wired, 15W wireless")])
+            .build()
+    }
+
+    #[test]
+    fn test_compare_phones() {
+        let phones = [
+            phone("a-1", "Li-Ion 5000 mAh", "190 g (6.70 oz)", "128GB 8GB RAM, 1TB 12GB RAM"),
+            phone("b-2", "Li-Ion 4000 mAh", "170 g (6.00 oz)", "256GB 8GB RAM"),
+        ];
+
+        let result = compare(&phones, &ComparisonWeights::default());
+        let metric = |m: Metric| result.metrics.iter().find(|c| c.metric == m).unwrap();
+
+        assert_eq!(metric(Metric::StorageGb).values, vec![Some(1024.0), Some(256.0)]);
+        assert_eq!(metric(Metric::RamGb).winner, Some(0));
+        assert_eq!(metric(Metric::BatteryMah).delta, Some(1000.0));
+        assert_eq!(metric(Metric::WeightG).winner, Some(1)); // Lighter wins
+        assert_eq!(metric(Metric::ChargingW).winner, None); // Tie
+        assert!(result.metrics.iter().all(|c| c.metric != Metric::MainCameraMp));
+
+        // Default weights: battery 2, charging 1, ram 1, storage 1, weight 1 -> a: 5/6, b: 2/6
+        assert_eq!(result.phones[0].score, 83.3);
+        assert_eq!(result.winner, Some(0));
+        assert_eq!(result.categories.iter().find(|c| c.category == "body").unwrap().winner, Some(1));
+
+        let mut weights = ComparisonWeights::default();
+        weights.apply_override("weight_g=10").unwrap();
+        assert_eq!(compare(&phones, &weights).winner, Some(1));
+        assert!(weights.apply_override("speed=1").is_err());
+    }
+}
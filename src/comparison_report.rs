@@ -0,0 +1,336 @@
+//! Markdown and standalone HTML renderings of comparisons and phone spec sheets
+
+use crate::comparison::ComparisonResult;
+use crate::mongodb::PhoneDocument;
+use std::fmt;
+use std::str::FromStr;
+
+/// Output format for `compare` and the renderers below
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    #[default]
+    Table,
+    Json,
+    Markdown,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(ReportFormat::Table),
+            "json" => Ok(ReportFormat::Json),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            other => Err(format!("unknown format '{}' (expected table, json, markdown or html)", other)),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReportFormat::Table => "table",
+            ReportFormat::Json => "json",
+            ReportFormat::Markdown => "markdown",
+            ReportFormat::Html => "html",
+        })
+    }
+}
+
+/// Spec categories in page order, with their headings
+const CATEGORIES: [(&str, &str); 13] = [
+    ("network", "Network"),
+    ("launch", "Launch"),
+    ("body", "Body"),
+    ("display", "Display"),
+    ("platform", "Platform"),
+    ("memory", "Memory"),
+    ("main_camera", "Main Camera"),
+    ("selfie_camera", "Selfie Camera"),
+    ("sound", "Sound"),
+    ("comms", "Comms"),
+    ("features", "Features"),
+    ("battery", "Battery"),
+    ("misc", "Misc"),
+];
+
+/// One table: a header row, then (label, cells) rows
+struct Table {
+    header: Vec<String>,
+    rows: Vec<(String, Vec<String>)>,
+}
+
+/// Spec rows shared by every phone set: one row per field at least one phone has
+fn spec_sections(phones: &[PhoneDocument]) -> Vec<(&'static str, Table)> {
+    let documents: Vec<serde_json::Value> = phones.iter().map(|p| serde_json::to_value(p).unwrap_or_default()).collect();
+    let header: Vec<String> = phones.iter().map(|p| p.name.clone()).collect();
+
+    CATEGORIES
+        .iter()
+        .filter_map(|(key, heading)| {
+            let fields: Vec<String> = documents
+                .iter()
+                .find_map(|doc| doc.get(*key)?.as_object().map(|o| o.keys().cloned().collect()))
+                .unwrap_or_default();
+
+            let rows: Vec<(String, Vec<String>)> = fields
+                .iter()
+                .filter_map(|field| {
+                    let cells: Vec<String> = documents
+                        .iter()
+                        .map(|doc| doc.get(*key).and_then(|c| c.get(field)).and_then(|v| v.as_str()).unwrap_or("").to_string())
+                        .collect();
+                    cells.iter().any(|c| !c.is_empty()).then(|| (field_label(field), cells))
+                })
+                .collect();
+
+            (!rows.is_empty()).then(|| (*heading, Table { header: header.clone(), rows }))
+        })
+        .collect()
+}
+
+/// "bands_2g" -> "Bands 2G", "display_type" -> "Type"
+fn field_label(field: &str) -> String {
+    let field = field.strip_prefix("display_").or_else(|| field.strip_prefix("battery_")).unwrap_or(field);
+    field
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) if word.len() <= 2 => first.to_uppercase().chain(chars.flat_map(char::to_uppercase)).collect(),
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn metric_table(result: &ComparisonResult) -> Table {
+    let rows = result
+        .metrics
+        .iter()
+        .map(|metric| {
+            let label = if metric.unit.is_empty() { metric.label.clone() } else { format!("{} ({})", metric.label, metric.unit) };
+            let cells = metric
+                .values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| match value {
+                    Some(v) if metric.winner == Some(index) => format!("{} ✓", format_number(*v)),
+                    Some(v) => format_number(*v),
+                    None => "N/A".to_string(),
+                })
+                .collect();
+            (label, cells)
+        })
+        .chain(std::iter::once((
+            "Score (0-100)".to_string(),
+            result.phones.iter().map(|p| format!("{:.1} (#{})", p.score, p.rank)).collect(),
+        )))
+        .collect();
+
+    Table {
+        header: result.phones.iter().map(|p| p.name.clone()).collect(),
+        rows,
+    }
+}
+
+fn winner_lines(result: &ComparisonResult) -> Vec<(String, String)> {
+    let name_of = |index: Option<usize>| index.map_or_else(|| "Tie".to_string(), |i| result.phones[i].name.clone());
+    let mut lines: Vec<(String, String)> =
+        result.categories.iter().map(|c| (field_label(&c.category), name_of(c.winner))).collect();
+    lines.push(("Overall".to_string(), name_of(result.winner)));
+    lines
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn md_table(table: &Table, images: Option<&[Option<String>]>) -> String {
+    let mut out = format!("| | {} |\n", table.header.iter().map(|h| md_cell(h)).collect::<Vec<_>>().join(" | "));
+    out.push_str(&format!("|---|{}\n", "---|".repeat(table.header.len())));
+    if let Some(images) = images.filter(|images| images.iter().any(Option::is_some)) {
+        let cells: Vec<String> = images
+            .iter()
+            .zip(&table.header)
+            .map(|(url, name)| url.as_ref().map_or_else(String::new, |url| format!("![{}]({})", md_cell(name), url)))
+            .collect();
+        out.push_str(&format!("| | {} |\n", cells.join(" | ")));
+    }
+    for (label, cells) in &table.rows {
+        out.push_str(&format!("| **{}** | {} |\n", md_cell(label), cells.iter().map(|c| md_cell(c)).collect::<Vec<_>>().join(" | ")));
+    }
+    out
+}
+
+/// Markdown: scored metrics, category winners, then the full spec sheet when documents are given
+pub fn comparison_markdown(result: &ComparisonResult, phones: &[PhoneDocument]) -> String {
+    let names: Vec<&str> = result.phones.iter().map(|p| p.name.as_str()).collect();
+    let images: Vec<Option<String>> = result.phones.iter().map(|p| p.image_url.clone()).collect();
+
+    let mut out = format!("# {}\n\n", names.join(" vs "));
+    out.push_str(&md_table(&metric_table(result), Some(&images)));
+    out.push_str("\n## Winners\n\n");
+    for (category, winner) in winner_lines(result) {
+        out.push_str(&format!("- **{}**: {}\n", category, md_cell(&winner)));
+    }
+    if !phones.is_empty() {
+        out.push_str("\n## Specifications\n");
+        out.push_str(&phones_markdown_sections(phones));
+    }
+    out
+}
+
+/// Markdown spec sheet for any set of phones, one table per category
+pub fn phones_markdown(phones: &[PhoneDocument]) -> String {
+    let names: Vec<&str> = phones.iter().map(|p| p.name.as_str()).collect();
+    format!("# {}\n{}", names.join(" vs "), phones_markdown_sections(phones))
+}
+
+fn phones_markdown_sections(phones: &[PhoneDocument]) -> String {
+    let images: Vec<Option<String>> = phones.iter().map(|p| p.image_url.clone()).collect();
+    spec_sections(phones)
+        .iter()
+        .enumerate()
+        .map(|(index, (heading, table))| format!("\n### {}\n\n{}", heading, md_table(table, (index == 0).then_some(images.as_slice()))))
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;color:#222}\
+table{border-collapse:collapse;width:100%;margin-bottom:1.5rem}\
+th,td{border:1px solid #ddd;padding:.4rem .6rem;text-align:left;vertical-align:top}\
+th{background:#f4f4f4}td.label{font-weight:600;width:12rem}\
+img{max-height:10rem}td.win{background:#e8f6e8}";
+
+fn html_table(table: &Table, images: Option<&[Option<String>]>, winners: Option<&[Option<usize>]>) -> String {
+    let mut out = String::from("<table>\n<tr><th></th>");
+    for name in &table.header {
+        out.push_str(&format!("<th>{}</th>", escape_html(name)));
+    }
+    out.push_str("</tr>\n");
+
+    if let Some(images) = images.filter(|images| images.iter().any(Option::is_some)) {
+        out.push_str("<tr><td></td>");
+        for (url, name) in images.iter().zip(&table.header) {
+            match url {
+                Some(url) => out.push_str(&format!("<td><img src=\"{}\" alt=\"{}\"></td>", escape_html(url), escape_html(name))),
+                None => out.push_str("<td></td>"),
+            }
+        }
+        out.push_str("</tr>\n");
+    }
+
+    for (row_index, (label, cells)) in table.rows.iter().enumerate() {
+        let winner = winners.and_then(|w| w.get(row_index).copied().flatten());
+        out.push_str(&format!("<tr><td class=\"label\">{}</td>", escape_html(label)));
+        for (index, cell) in cells.iter().enumerate() {
+            let class = if winner == Some(index) { " class=\"win\"" } else { "" };
+            out.push_str(&format!("<td{}>{}</td>", class, escape_html(cell).replace('\n', "<br>")));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+        escape_html(title),
+        HTML_STYLE,
+        escape_html(title),
+        body
+    )
+}
+
+/// Standalone HTML page: scored metrics, category winners, then the full spec sheet when documents are given
+pub fn comparison_html(result: &ComparisonResult, phones: &[PhoneDocument]) -> String {
+    let title = result.phones.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(" vs ");
+    let images: Vec<Option<String>> = result.phones.iter().map(|p| p.image_url.clone()).collect();
+    let winners: Vec<Option<usize>> = result.metrics.iter().map(|m| m.winner).chain(std::iter::once(result.winner)).collect();
+
+    let mut body = html_table(&metric_table(result), Some(&images), Some(&winners));
+    body.push_str("<h2>Winners</h2>\n<ul>\n");
+    for (category, winner) in winner_lines(result) {
+        body.push_str(&format!("<li><strong>{}</strong>: {}</li>\n", escape_html(&category), escape_html(&winner)));
+    }
+    body.push_str("</ul>\n");
+    if !phones.is_empty() {
+        body.push_str("<h2>Specifications</h2>\n");
+        body.push_str(&phones_html_sections(phones, false));
+    }
+    html_page(&title, &body)
+}
+
+/// Standalone HTML spec sheet for any set of phones
+pub fn phones_html(phones: &[PhoneDocument]) -> String {
+    let title = phones.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(" vs ");
+    html_page(&title, &phones_html_sections(phones, true))
+}
+
+fn phones_html_sections(phones: &[PhoneDocument], with_images: bool) -> String {
+    let images: Vec<Option<String>> = phones.iter().map(|p| p.image_url.clone()).collect();
+    spec_sections(phones)
+        .iter()
+        .enumerate()
+        .map(|(index, (heading, table))| {
+            let images = (with_images && index == 0).then_some(images.as_slice());
+            format!("<h3>{}</h3>\n{}", escape_html(heading), html_table(table, images, None))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparison::{compare, ComparisonWeights};
+
+    fn phone(id: &str, name: &str, battery: &str) -> PhoneDocument {
+        PhoneDocument::fixture(id)
+            .name(name)
+            .image_url(&format!("https://fdn2.gsmarena.com/vv/bigpic/{}.jpg", id))
+            .category("Platform", &[("Chipset", "Snapdragon 8 Gen 3 | 4 nm")])
+            .category("Battery", &[("Type", battery)])
+            .build()
+    }
+
+    #[test]
+    fn test_render_markdown_and_html() {
+        let phones = [phone("a-1", "Phone <A>", "Li-Ion 5000 mAh"), phone("b-2", "Phone B", "Li-Ion 4500 mAh")];
+        let result = compare(&phones, &ComparisonWeights::default());
+
+        let markdown = comparison_markdown(&result, &phones);
+        assert!(markdown.starts_with("# Phone <A> vs Phone B\n"));
+        assert!(markdown.contains("| **Battery (mAh)** | 5000 ✓ | 4500 |"));
+        assert!(markdown.contains("![Phone B](https://fdn2.gsmarena.com/vv/bigpic/b-2.jpg)"));
+        assert!(markdown.contains("| **Chipset** | Snapdragon 8 Gen 3 \\| 4 nm |"));
+        assert!(markdown.contains("- **Overall**: Phone <A>"));
+
+        let html = comparison_html(&result, &phones);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Phone &lt;A&gt; vs Phone B</title>"));
+        assert!(html.contains("<td class=\"win\">5000 ✓</td>"));
+        assert!(!html.contains("<A>"));
+
+        assert_eq!(field_label("bands_2g"), "Bands 2G");
+        assert_eq!(field_label("display_type"), "Type");
+        assert!(phones_html(&phones).contains("<h3>Platform</h3>"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Optional gzip compression for exported files
+//!
+//! Full-corpus JSON dumps run to several GB, so exports, brand directories and
+//! `db dump` can gzip what they write. Readers don't need to be told: gzip input is
+//! recognised by its magic bytes, so `.gz` and plain files load the same way.
+
+use flate2::bufread::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// First two bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileCompression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl FileCompression {
+    /// Gzip for a `.gz` path, none otherwise
+    pub fn from_path(path: &Path) -> Self {
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            FileCompression::Gzip
+        } else {
+            FileCompression::None
+        }
+    }
+
+    /// Suffix appended to compressed file names
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileCompression::None => "",
+            FileCompression::Gzip => ".gz",
+        }
+    }
+
+    /// `path` with this compression's suffix, unless it already has it
+    pub fn apply_to(&self, path: &Path) -> PathBuf {
+        if Self::from_path(path) == *self {
+            return path.to_path_buf();
+        }
+        let mut name = path.as_os_str().to_owned();
+        name.push(self.extension());
+        PathBuf::from(name)
+    }
+
+    /// Create `path` for writing; call `finish` on the writer to complete it
+    pub fn create(&self, path: &Path) -> Result<CompressedWriter, Box<dyn Error>> {
+        let file = BufWriter::new(File::create(path).map_err(|e| format!("Cannot create {}: {}", path.display(), e))?);
+        Ok(match self {
+            FileCompression::None => CompressedWriter::Plain(file),
+            FileCompression::Gzip => CompressedWriter::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+        })
+    }
+}
+
+impl fmt::Display for FileCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileCompression::None => "none",
+            FileCompression::Gzip => "gzip",
+        })
+    }
+}
+
+impl FromStr for FileCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" | "" => Ok(FileCompression::None),
+            "gzip" | "gz" => Ok(FileCompression::Gzip),
+            other => Err(format!("unknown compression '{}' (expected none or gzip)", other)),
+        }
+    }
+}
+
+/// A file being written, plain or gzipped
+pub enum CompressedWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl CompressedWriter {
+    /// Write the gzip trailer (if any) and flush to disk
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        let mut file = match self {
+            CompressedWriter::Plain(file) => file,
+            CompressedWriter::Gzip(encoder) => encoder.finish()?,
+        };
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            CompressedWriter::Plain(file) => file.write(buf),
+            CompressedWriter::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            CompressedWriter::Plain(file) => file.flush(),
+            CompressedWriter::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Open a file for reading, decompressing it when it is gzip
+pub fn open(path: &Path) -> Result<Box<dyn BufRead>, Box<dyn Error>> {
+    let mut reader = BufReader::new(File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?);
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Whole contents of a plain or gzipped file as text
+pub fn read_to_string(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut contents = String::new();
+    open(path)?.read_to_string(&mut contents).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    Ok(contents)
+}
+
+/// Write `contents` to `path` with the given compression
+pub fn write(path: &Path, contents: &[u8], compression: FileCompression) -> Result<(), Box<dyn Error>> {
+    let mut writer = compression.create(path)?;
+    writer.write_all(contents)?;
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let dir = std::env::temp_dir().join(format!("gsmarena-compression-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let contents = "{\"phone_id\":\"apple_iphone_15-12559\"}\n".repeat(200);
+
+        let plain = dir.join("phones.jsonl");
+        let gzipped = FileCompression::Gzip.apply_to(&plain);
+        assert_eq!(gzipped, dir.join("phones.jsonl.gz"));
+        assert_eq!(FileCompression::Gzip.apply_to(&gzipped), gzipped);
+        assert_eq!(FileCompression::None.apply_to(&plain), plain);
+
+        write(&plain, contents.as_bytes(), FileCompression::None).unwrap();
+        write(&gzipped, contents.as_bytes(), FileCompression::from_path(&gzipped)).unwrap();
+        assert!(std::fs::metadata(&gzipped).unwrap().len() < std::fs::metadata(&plain).unwrap().len() / 10);
+        assert_eq!(read_to_string(&plain).unwrap(), contents);
+        assert_eq!(read_to_string(&gzipped).unwrap(), contents);
+
+        // Detected by content, not by name
+        let renamed = dir.join("renamed.jsonl");
+        std::fs::rename(&gzipped, &renamed).unwrap();
+        assert_eq!(open(&renamed).unwrap().lines().count(), 200);
+
+        assert_eq!("gz".parse::<FileCompression>().unwrap(), FileCompression::Gzip);
+        assert_eq!(FileCompression::Gzip.to_string().parse::<FileCompression>().unwrap(), FileCompression::Gzip);
+        assert!("zstd".parse::<FileCompression>().is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1129 @@
+use crate::device_type::DeviceType;
+use crate::phone_finder::NetworkFilter;
+use crate::scheduler::CronSchedule;
+use crate::selectors::SelectorOverrides;
+use crate::site::SiteConfig;
+use crate::stats::ErrorPolicy;
+use crate::events::EventBackend;
+use crate::upsert::UpsertPolicy;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Config files looked up in the working directory when no path is given
+pub const DEFAULT_CONFIG_FILES: [&str; 3] = ["scraper.toml", "scraper.yaml", "scraper.yml"];
+
+/// Scraper configuration
+///
+/// Layers, lowest to highest priority: built-in defaults, config file
+/// (`scraper.toml` / `scraper.yaml`), environment variables, CLI flags.
+/// Secrets (MongoDB credentials, API keys) are only read from the environment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub site: SiteConfig,
+    pub scraping: ScrapingConfig,
+    pub rate_limit: RateLimitConfig,
+    pub collections: CollectionsConfig,
+    pub mongodb: MongoConfig,
+    pub brands: BrandFilter,
+    pub proxy: ProxySettings,
+    pub chain: ChainConfig,
+    pub images: ImagesConfig,
+    pub upcoming: UpcomingConfig,
+    pub daemon: DaemonConfig,
+    pub report: ReportConfig,
+    pub cache: CacheConfig,
+    pub budget: BudgetConfig,
+    pub user_agents: UserAgentConfig,
+    pub browser: BrowserConfig,
+    pub snapshots: SnapshotConfig,
+    pub validation: ValidationConfig,
+    pub webhooks: WebhookConfig,
+    pub chat: ChatConfig,
+    pub events: EventsConfig,
+    pub selectors: SelectorOverrides, // Element name -> CSS selectors tried before the built-in ones (see `selectors`)
+
+    /// File the configuration was loaded from, if any
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+/// How pages are fetched from GSMArena
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrapeProvider {
+    /// Direct requests with rate limiting
+    #[default]
+    Direct,
+    /// Direct requests rotated through the Appwrite proxy pool
+    Proxy,
+    /// Every request through ScrapingBee (API key rotation)
+    ScrapingBee,
+    /// Listings through ScrapingBee, specifications alternating direct/ScrapingBee batches
+    Hybrid,
+    /// Each page tries the `[chain]` providers in order until one returns it
+    Chain,
+    /// Headless Chrome, for JavaScript and challenge pages (`browser` feature)
+    Browser,
+}
+
+/// How the list of phones to scrape is discovered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscoveryMode {
+    /// Paginate every brand listing
+    #[default]
+    Brands,
+    /// Enumerate phones from the XML sitemaps in a handful of requests
+    Sitemap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScrapingConfig {
+    pub provider: ScrapeProvider,
+    pub discovery: DiscoveryMode,
+    pub sitemap_url: Option<String>, // None = sitemap.xml under site.base_url
+    pub discovery_parallelism: usize, // Brand listings fetched at once (1 = one brand after another)
+    pub max_brands: Option<usize>,       // None = all brands
+    pub phones_per_brand: Option<usize>, // None = all phones
+    pub skip_existing: bool,
+    pub batch_size: usize, // Phones per hybrid batch
+    pub hybrid_auto_tune: bool, // Shift hybrid batches towards the method that is succeeding (see `hybrid`)
+    pub fetch_brand_details: bool,
+    pub fetch_brand_years: bool, // Devices per announce year, from the brand page's year listings
+    pub fetch_gallery: bool,
+    pub download_gallery_images: bool,
+    pub record_provenance: bool, // Store the raw row behind each parsed field (see `provenance`)
+    pub self_check: bool, // Check a pinned brand and phone page parse before the run (see `self_check`)
+    pub from_dir: Option<String>, // Parse saved pages from this directory instead of fetching
+    pub max_runtime: Option<String>, // "5h30m": stop gracefully once the run has taken this long
+    pub max_phones: Option<usize>,   // Stop gracefully after fetching this many phones (skipped ones don't count)
+    pub device_type: Option<DeviceType>, // Only scrape phones, tablets or watches; None = everything listed
+    pub network_filter: Option<NetworkFilter>, // List brands through Phone Finder and keep only 5G / LTE / GSM-only devices
+    pub checkpoint_in: Option<String>,  // Continue the chain of runs this checkpoint describes (see `checkpoint`)
+    pub checkpoint_out: Option<String>, // Write where this run got to, for the next run of the chain
+    pub error_policy: ErrorPolicy, // continue, fail-fast or fail-after-N failed phones and listings (see `stats`)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub delay_between_phones_ms: u64,
+    pub delay_between_brands_ms: u64,
+    pub min_request_interval_ms: u64, // Between any two listing requests when discovering in parallel
+    pub global_max_requests: Option<u64>, // Per global_window_secs across every worker sharing the database; None = per process only
+    pub global_window_secs: u64,
+    pub global_collection: String, // Request counters of the global cap (expire on their own)
+    pub adaptive: bool, // Raise delay_between_phones_ms on 429/403 responses, lower it back when clean (see `adaptive_delay`)
+    pub adaptive_max_delay_ms: u64,
+    pub adaptive_window_secs: u64, // Sliding window the share of 429/403 responses is taken over
+    pub adaptive_threshold: f64, // Share of 429/403 responses in the window that raises the delay
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollectionsConfig {
+    pub phones: String,
+    pub brands: String,
+    pub phone_list: String,
+    pub upcoming: String,
+    pub price_history: Option<String>, // Also append each scrape's market prices here (see `pricing`)
+}
+
+/// How writes reach MongoDB (`[mongodb]`; the connection itself comes from `MONGO_DB_*`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MongoConfig {
+    pub transactions: bool, // Write a phone's specs and phone_list entry in one transaction (replica sets only)
+    pub upsert_policy: UpsertPolicy, // What a re-scrape does to the stored document
+}
+
+/// Brand names to include (empty = all) or exclude, case-insensitive
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrandFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub shard: Option<Shard>, // This worker's share of the brands, for crawls split across jobs
+}
+
+/// Worker `index` of `count` ("1/4"): takes the brands whose slug hashes to `index` modulo `count`,
+/// so concurrent workers split a crawl without overlapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Shard {
+    pub index: u64,
+    pub count: u64,
+}
+
+/// Appwrite proxy pool location; APPWRITE_API_KEY stays in the environment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProxySettings {
+    pub attempts: usize, // Proxies tried per page before giving up
+    pub appwrite_project_id: Option<String>,
+    pub appwrite_database_id: Option<String>,
+    pub appwrite_collection_id: Option<String>,
+}
+
+/// Providers of the `chain` provider, in escalation order, and how many requests each may make per run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainConfig {
+    pub providers: Vec<ScrapeProvider>,
+    pub direct_budget: Option<u64>, // None = unlimited
+    pub proxy_budget: Option<u64>,
+    pub scrapingbee_budget: Option<u64>,
+    pub browser_budget: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImagesConfig {
+    /// `local:<dir>`, `gridfs[:<bucket>]` or `s3://<bucket>[/<prefix>]`; unset disables downloads
+    pub storage: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpcomingConfig {
+    pub recheck_minutes: u64,
+}
+
+/// Schedule used by `scrape --daemon`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    /// Cron expression, 5 fields ("0 3 * * *") or 6 with seconds
+    pub schedule: Option<String>,
+    pub jitter_secs: u64, // Random delay added before each run
+}
+
+/// Where the run report of each scrape goes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportConfig {
+    pub path: String,               // JSON file; empty disables it
+    pub collection: Option<String>, // Also upsert reports into this collection (e.g. "scrape_runs")
+}
+
+/// Where raw phone pages are kept next to the parsed specifications
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnapshotConfig {
+    /// `local:<dir>`, `gridfs[:<bucket>]` or `s3://<bucket>[/<prefix>]`; unset disables snapshots
+    pub storage: Option<String>,
+}
+
+/// Data quality checks applied to every scraped document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationConfig {
+    pub min_completeness: f64, // Below this score a document is flagged for re-scrape
+    pub max_listing_shortfall: f64, // Share of a brand's device count a listing may miss before it is fetched again
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            min_completeness: crate::validator::DEFAULT_MIN_COMPLETENESS,
+            max_listing_shortfall: 0.1,
+        }
+    }
+}
+
+/// Webhooks notified when a scrape inserts a phone or changes a tracked field
+/// The signing secret is only read from `WEBHOOK_SECRET`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub urls: Vec<String>,           // Empty disables notifications
+    pub tracked_fields: Vec<String>, // "price", "status" or a "category.field" path such as "display.size"
+    pub max_attempts: usize,         // Deliveries tried per URL before giving up
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            tracked_fields: vec!["price".to_string(), "status".to_string()],
+            max_attempts: 3,
+        }
+    }
+}
+
+/// Telegram/Discord messages after each scrape (needs the `telegram` / `discord` features)
+/// Credentials are only read from `TELEGRAM_BOT_TOKEN` and `DISCORD_WEBHOOK_URL`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChatConfig {
+    pub telegram_chat_id: Option<String>,
+    pub announce_new_devices: bool, // Also post each new device with its image and key specs
+    pub max_announcements: usize,   // Per run; the summary still lists every new device
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            telegram_chat_id: None,
+            announce_new_devices: false,
+            max_announcements: 10,
+        }
+    }
+}
+
+/// A NATS or Kafka message per saved phone (needs the `nats` / `kafka` features)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventsConfig {
+    pub backend: Option<EventBackend>, // Unset disables publishing
+    pub url: Option<String>,           // NATS server or Kafka REST Proxy
+    pub topic: String,                 // NATS subject / Kafka topic
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            url: None,
+            topic: "gsmarena.phones".to_string(),
+        }
+    }
+}
+
+/// On-disk cache of fetched pages, mostly for development re-runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    pub dir: Option<String>,   // Unset disables the cache
+    pub ttl_secs: Option<u64>, // Unset = entries never expire
+    pub gzip: bool,
+}
+
+/// Hard caps per run; the run stops gracefully once one is reached (unset = unlimited)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetConfig {
+    pub max_direct_requests: Option<u64>,
+    pub max_proxy_requests: Option<u64>, // Every proxy attempt counts
+    pub max_scrapingbee_credits: Option<u64>,
+}
+
+/// User-Agent headers of direct and proxy requests (see `user_agents`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserAgentConfig {
+    pub rotation: UserAgentRotation,
+    pub file: Option<String>, // One User-Agent per line, replacing the built-in list
+}
+
+/// Headless Chrome used by the `browser` provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrowserConfig {
+    pub binary: Option<String>, // None = chromium / google-chrome from the PATH
+    pub timeout_secs: u64,      // Per page, including the browser start
+    pub settle_ms: u64,         // Time scripts (and challenges) get before the DOM is read
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            binary: None,
+            timeout_secs: 60,
+            settle_ms: 5000,
+        }
+    }
+}
+
+/// When requests switch to another User-Agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserAgentRotation {
+    /// A new User-Agent for every request
+    #[default]
+    PerRequest,
+    /// One User-Agent for the whole run
+    PerSession,
+    /// A new User-Agent for each brand
+    PerBrand,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            path: crate::run_report::DEFAULT_REPORT_PATH.to_string(),
+            collection: None,
+        }
+    }
+}
+
+impl Default for ScrapingConfig {
+    fn default() -> Self {
+        Self {
+            provider: ScrapeProvider::Direct,
+            discovery: DiscoveryMode::Brands,
+            sitemap_url: None,
+            discovery_parallelism: 1,
+            max_brands: None,
+            phones_per_brand: None,
+            skip_existing: true,
+            batch_size: 10,
+            hybrid_auto_tune: true,
+            fetch_brand_details: false,
+            fetch_brand_years: false,
+            fetch_gallery: false,
+            download_gallery_images: false,
+            record_provenance: false,
+            self_check: false,
+            from_dir: None,
+            max_runtime: None,
+            max_phones: None,
+            device_type: None,
+            network_filter: None,
+            checkpoint_in: None,
+            checkpoint_out: None,
+            error_policy: ErrorPolicy::Continue,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            delay_between_phones_ms: 500,
+            delay_between_brands_ms: 3000,
+            min_request_interval_ms: 250,
+            global_max_requests: None,
+            global_window_secs: 10,
+            global_collection: "rate_limits".to_string(),
+            adaptive: false,
+            adaptive_max_delay_ms: 30000,
+            adaptive_window_secs: 60,
+            adaptive_threshold: 0.1,
+        }
+    }
+}
+
+impl Default for CollectionsConfig {
+    fn default() -> Self {
+        Self {
+            phones: "gsmarena_phones".to_string(),
+            brands: "gsmarena_brands".to_string(),
+            phone_list: "gsmarena_phone_list".to_string(),
+            upcoming: "upcoming_phones".to_string(),
+            price_history: None,
+        }
+    }
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            attempts: 10,
+            appwrite_project_id: None,
+            appwrite_database_id: None,
+            appwrite_collection_id: None,
+        }
+    }
+}
+
+impl Default for UpcomingConfig {
+    fn default() -> Self {
+        Self { recheck_minutes: 60 }
+    }
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            providers: vec![ScrapeProvider::Direct, ScrapeProvider::Proxy, ScrapeProvider::ScrapingBee],
+            direct_budget: None,
+            proxy_budget: None,
+            scrapingbee_budget: None,
+            browser_budget: None,
+        }
+    }
+}
+
+impl ChainConfig {
+    /// Requests `provider` may make in one run
+    pub fn budget(&self, provider: ScrapeProvider) -> Option<u64> {
+        match provider {
+            ScrapeProvider::Direct => self.direct_budget,
+            ScrapeProvider::Proxy => self.proxy_budget,
+            ScrapeProvider::ScrapingBee => self.scrapingbee_budget,
+            ScrapeProvider::Browser => self.browser_budget,
+            ScrapeProvider::Hybrid | ScrapeProvider::Chain => None,
+        }
+    }
+}
+
+impl BrandFilter {
+    /// Whether a brand passes the include/exclude lists
+    pub fn matches(&self, brand_name: &str) -> bool {
+        let listed = |names: &[String]| names.iter().any(|name| name.trim().eq_ignore_ascii_case(brand_name.trim()));
+
+        (self.include.is_empty() || listed(&self.include)) && !listed(&self.exclude)
+    }
+
+    /// Whether a brand passes the lists and falls in this worker's shard
+    pub fn selects(&self, brand: &crate::brand_scraper::Brand) -> bool {
+        self.matches(&brand.name) && self.shard.is_none_or(|shard| shard.contains(&brand.slug))
+    }
+}
+
+impl Shard {
+    /// Whether `brand_slug` belongs to this shard; the hash is stable across builds and platforms
+    pub fn contains(&self, brand_slug: &str) -> bool {
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(brand_slug.as_bytes());
+        let hash = u64::from_be_bytes(digest[..8].try_into().expect("SHA-256 is 32 bytes"));
+        hash % self.count == self.index
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid shard '{}', expected N/M with 0 <= N < M", s);
+        let (index, count) = s.trim().split_once('/').ok_or_else(invalid)?;
+        let index: u64 = index.trim().parse().map_err(|_| invalid())?;
+        let count: u64 = count.trim().parse().map_err(|_| invalid())?;
+        if index >= count {
+            return Err(invalid());
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl TryFrom<String> for Shard {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Shard> for String {
+    fn from(shard: Shard) -> Self {
+        shard.to_string()
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+impl Config {
+    /// Load the config file (explicit path, `GSMARENA_CONFIG`, or `scraper.toml`/`.yaml`
+    /// in the working directory) and apply environment variable overrides
+    pub fn load(path: Option<&Path>) -> Result<Self, Box<dyn Error>> {
+        let path = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => match std::env::var("GSMARENA_CONFIG") {
+                Ok(path) if !path.trim().is_empty() => Some(PathBuf::from(path)),
+                _ => DEFAULT_CONFIG_FILES.iter().map(PathBuf::from).find(|p| p.exists()),
+            },
+        };
+
+        let mut config = match path {
+            Some(ref path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        config.apply_env_from(|key| std::env::var(key).ok())?;
+        Ok(config)
+    }
+
+    /// Read a TOML or YAML config file (chosen by extension)
+    pub fn from_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read config file {}: {}", path.display(), e))?;
+
+        let is_yaml = matches!(path.extension().and_then(|e| e.to_str()), Some("yaml") | Some("yml"));
+
+        let mut config: Config = if is_yaml {
+            serde_yaml::from_str(&contents).map_err(|e| format!("Invalid config {}: {}", path.display(), e))?
+        } else {
+            toml::from_str(&contents).map_err(|e| format!("Invalid config {}: {}", path.display(), e))?
+        };
+
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Override settings from environment variables (empty values are ignored)
+    pub fn apply_env_from<F>(&mut self, lookup: F) -> Result<(), Box<dyn Error>>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut errors = Vec::new();
+        let get = |key: &str| lookup(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        fn parse<T: FromStr>(key: &str, value: String, errors: &mut Vec<String>) -> Option<T> {
+            match value.parse() {
+                Ok(parsed) => Some(parsed),
+                Err(_) => {
+                    errors.push(format!("{}: invalid value '{}'", key, value));
+                    None
+                }
+            }
+        }
+
+        macro_rules! env_override {
+            ($key:expr, $field:expr) => {
+                if let Some(value) = get($key).and_then(|v| parse($key, v, &mut errors)) {
+                    $field = value;
+                }
+            };
+            ($key:expr, $field:expr, optional) => {
+                if let Some(value) = get($key).and_then(|v| parse($key, v, &mut errors)) {
+                    $field = Some(value);
+                }
+            };
+        }
+
+        env_override!("GSMARENA_BASE_URL", self.site.base_url);
+
+        env_override!("SCRAPE_PROVIDER", self.scraping.provider);
+        env_override!("DISCOVERY_MODE", self.scraping.discovery);
+        env_override!("SITEMAP_URL", self.scraping.sitemap_url, optional);
+        env_override!("DISCOVERY_PARALLELISM", self.scraping.discovery_parallelism);
+        env_override!("MAX_BRANDS", self.scraping.max_brands, optional);
+        env_override!("PHONES_PER_BRAND", self.scraping.phones_per_brand, optional);
+        env_override!("SKIP_EXISTING", self.scraping.skip_existing);
+        env_override!("HYBRID_BATCH_SIZE", self.scraping.batch_size);
+        env_override!("HYBRID_AUTO_TUNE", self.scraping.hybrid_auto_tune);
+        env_override!("FETCH_BRAND_DETAILS", self.scraping.fetch_brand_details);
+        env_override!("FETCH_BRAND_YEARS", self.scraping.fetch_brand_years);
+        env_override!("FETCH_GALLERY", self.scraping.fetch_gallery);
+        env_override!("DOWNLOAD_GALLERY_IMAGES", self.scraping.download_gallery_images);
+        env_override!("RECORD_PROVENANCE", self.scraping.record_provenance);
+        env_override!("SELF_CHECK", self.scraping.self_check);
+        env_override!("SCRAPE_FROM_DIR", self.scraping.from_dir, optional);
+        env_override!("MAX_RUNTIME", self.scraping.max_runtime, optional);
+        env_override!("MAX_PHONES", self.scraping.max_phones, optional);
+        env_override!("CHECKPOINT_IN", self.scraping.checkpoint_in, optional);
+        env_override!("CHECKPOINT_OUT", self.scraping.checkpoint_out, optional);
+        env_override!("DEVICE_TYPE", self.scraping.device_type, optional);
+        env_override!("NETWORK_FILTER", self.scraping.network_filter, optional);
+        env_override!("ERROR_POLICY", self.scraping.error_policy);
+
+        env_override!("DELAY_BETWEEN_PHONES_MS", self.rate_limit.delay_between_phones_ms);
+        env_override!("DELAY_BETWEEN_BRANDS_MS", self.rate_limit.delay_between_brands_ms);
+        env_override!("MIN_REQUEST_INTERVAL_MS", self.rate_limit.min_request_interval_ms);
+        env_override!("GLOBAL_MAX_REQUESTS", self.rate_limit.global_max_requests, optional);
+        env_override!("GLOBAL_WINDOW_SECS", self.rate_limit.global_window_secs);
+        env_override!("GLOBAL_RATE_LIMIT_COLLECTION", self.rate_limit.global_collection);
+        env_override!("ADAPTIVE_DELAY", self.rate_limit.adaptive);
+        env_override!("ADAPTIVE_MAX_DELAY_MS", self.rate_limit.adaptive_max_delay_ms);
+        env_override!("ADAPTIVE_WINDOW_SECS", self.rate_limit.adaptive_window_secs);
+        env_override!("ADAPTIVE_THRESHOLD", self.rate_limit.adaptive_threshold);
+
+        env_override!("COLLECTION_NAME", self.collections.phones);
+        env_override!("BRANDS_COLLECTION_NAME", self.collections.brands);
+        env_override!("PHONE_LIST_COLLECTION_NAME", self.collections.phone_list);
+        env_override!("UPCOMING_COLLECTION_NAME", self.collections.upcoming);
+        env_override!("PRICE_HISTORY_COLLECTION", self.collections.price_history, optional);
+        env_override!("MONGO_TRANSACTIONS", self.mongodb.transactions);
+        env_override!("UPSERT_POLICY", self.mongodb.upsert_policy);
+
+        if let Some(include) = get("BRANDS_INCLUDE") {
+            self.brands.include = split_list(&include);
+        }
+        if let Some(exclude) = get("BRANDS_EXCLUDE") {
+            self.brands.exclude = split_list(&exclude);
+        }
+        env_override!("BRAND_SHARD", self.brands.shard, optional);
+
+        env_override!("PROXY_ATTEMPTS", self.proxy.attempts);
+        env_override!("APPWRITE_PROJECT_ID", self.proxy.appwrite_project_id, optional);
+        env_override!("APPWRITE_DATABASE_ID", self.proxy.appwrite_database_id, optional);
+        env_override!("APPWRITE_COLLECTION_ID", self.proxy.appwrite_collection_id, optional);
+
+        if let Some(providers) = get("CHAIN_PROVIDERS") {
+            self.chain.providers = split_list(&providers)
+                .into_iter()
+                .filter_map(|provider| parse("CHAIN_PROVIDERS", provider, &mut errors))
+                .collect();
+        }
+        env_override!("CHAIN_DIRECT_BUDGET", self.chain.direct_budget, optional);
+        env_override!("CHAIN_PROXY_BUDGET", self.chain.proxy_budget, optional);
+        env_override!("CHAIN_SCRAPINGBEE_BUDGET", self.chain.scrapingbee_budget, optional);
+        env_override!("CHAIN_BROWSER_BUDGET", self.chain.browser_budget, optional);
+        env_override!("BROWSER_BINARY", self.browser.binary, optional);
+        env_override!("BROWSER_TIMEOUT_SECS", self.browser.timeout_secs);
+        env_override!("BROWSER_SETTLE_MS", self.browser.settle_ms);
+
+        env_override!("IMAGE_STORAGE", self.images.storage, optional);
+        env_override!("HTML_SNAPSHOT_STORAGE", self.snapshots.storage, optional);
+        env_override!("UPCOMING_RECHECK_MINUTES", self.upcoming.recheck_minutes);
+
+        env_override!("DAEMON_SCHEDULE", self.daemon.schedule, optional);
+        env_override!("DAEMON_JITTER_SECS", self.daemon.jitter_secs);
+
+        env_override!("RUN_REPORT_PATH", self.report.path);
+        env_override!("SCRAPE_RUNS_COLLECTION", self.report.collection, optional);
+
+        env_override!("MIN_COMPLETENESS", self.validation.min_completeness);
+        env_override!("MAX_LISTING_SHORTFALL", self.validation.max_listing_shortfall);
+
+        if let Some(urls) = get("WEBHOOK_URLS") {
+            self.webhooks.urls = split_list(&urls);
+        }
+        if let Some(fields) = get("WEBHOOK_TRACKED_FIELDS") {
+            self.webhooks.tracked_fields = split_list(&fields);
+        }
+        env_override!("WEBHOOK_MAX_ATTEMPTS", self.webhooks.max_attempts);
+
+        env_override!("TELEGRAM_CHAT_ID", self.chat.telegram_chat_id, optional);
+        env_override!("ANNOUNCE_NEW_DEVICES", self.chat.announce_new_devices);
+        env_override!("MAX_ANNOUNCEMENTS", self.chat.max_announcements);
+
+        env_override!("EVENTS_BACKEND", self.events.backend, optional);
+        env_override!("EVENTS_URL", self.events.url, optional);
+        env_override!("EVENTS_TOPIC", self.events.topic);
+
+        env_override!("PAGE_CACHE_DIR", self.cache.dir, optional);
+        env_override!("PAGE_CACHE_TTL_SECS", self.cache.ttl_secs, optional);
+
+        env_override!("MAX_DIRECT_REQUESTS", self.budget.max_direct_requests, optional);
+        env_override!("MAX_PROXY_REQUESTS", self.budget.max_proxy_requests, optional);
+        env_override!("MAX_SCRAPINGBEE_CREDITS", self.budget.max_scrapingbee_credits, optional);
+        env_override!("PAGE_CACHE_GZIP", self.cache.gzip);
+
+        env_override!("USER_AGENT_ROTATION", self.user_agents.rotation);
+        env_override!("USER_AGENTS_FILE", self.user_agents.file, optional);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid environment configuration:\n  {}", errors.join("\n  ")).into())
+        }
+    }
+
+    /// Sitemap index for sitemap discovery: `scraping.sitemap_url`, or the one under `site.base_url`
+    pub fn sitemap_url(&self) -> String {
+        self.scraping.sitemap_url.clone().unwrap_or_else(|| self.site.sitemap_url())
+    }
+
+    /// Check the configuration for values that would make a run fail or misbehave
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let scraping = &self.scraping;
+
+        if !self.site.base_url.starts_with("http://") && !self.site.base_url.starts_with("https://") {
+            problems.push(format!("site.base_url is not a URL: {}", self.site.base_url));
+        }
+        if scraping.batch_size == 0 {
+            problems.push("scraping.batch_size must be at least 1".to_string());
+        }
+        if scraping.discovery_parallelism == 0 {
+            problems.push("scraping.discovery_parallelism must be at least 1".to_string());
+        }
+        if scraping.max_brands == Some(0) || scraping.phones_per_brand == Some(0) {
+            problems.push("scraping.max_brands / phones_per_brand must be at least 1 (omit for no limit)".to_string());
+        }
+        if let (DiscoveryMode::Sitemap, Some(sitemap_url)) = (scraping.discovery, &scraping.sitemap_url) {
+            if !sitemap_url.starts_with("http") {
+                problems.push(format!("scraping.sitemap_url is not a URL: {}", sitemap_url));
+            }
+        }
+        if scraping.network_filter.is_some() && scraping.discovery == DiscoveryMode::Sitemap {
+            problems.push("scraping.network_filter lists phones through Phone Finder, so it cannot be used with discovery = \"sitemap\"".to_string());
+        }
+        if let Some(dir) = &scraping.from_dir {
+            if !Path::new(dir).is_dir() {
+                problems.push(format!("scraping.from_dir is not a directory: {}", dir));
+            }
+        }
+        if scraping.download_gallery_images && self.images.storage.is_none() {
+            problems.push("scraping.download_gallery_images needs images.storage (IMAGE_STORAGE)".to_string());
+        }
+
+        let mut collections = vec![
+            ("collections.phones", &self.collections.phones),
+            ("collections.brands", &self.collections.brands),
+            ("collections.phone_list", &self.collections.phone_list),
+            ("collections.upcoming", &self.collections.upcoming),
+        ];
+        if let Some(ref price_history) = self.collections.price_history {
+            collections.push(("collections.price_history", price_history));
+        }
+        for (index, (key, name)) in collections.iter().enumerate() {
+            if name.trim().is_empty() {
+                problems.push(format!("{} must not be empty", key));
+            } else if let Some((other, _)) = collections[..index].iter().find(|(_, other)| other == name) {
+                problems.push(format!("{} and {} both use collection '{}'", other, key, name));
+            }
+        }
+
+        for name in &self.brands.include {
+            if self.brands.exclude.iter().any(|excluded| excluded.eq_ignore_ascii_case(name)) {
+                problems.push(format!("brand '{}' is both included and excluded", name));
+            }
+        }
+
+        if self.rate_limit.global_max_requests == Some(0) {
+            problems.push("rate_limit.global_max_requests must be at least 1 (omit it to disable the cap)".to_string());
+        }
+        if self.rate_limit.global_max_requests.is_some() && self.rate_limit.global_window_secs == 0 {
+            problems.push("rate_limit.global_window_secs must be at least 1".to_string());
+        }
+        if self.rate_limit.global_collection.trim().is_empty() {
+            problems.push("rate_limit.global_collection must not be empty".to_string());
+        }
+        if self.rate_limit.adaptive {
+            if self.rate_limit.adaptive_threshold <= 0.0 || self.rate_limit.adaptive_threshold > 1.0 {
+                problems.push("rate_limit.adaptive_threshold must be above 0.0 and at most 1.0".to_string());
+            }
+            if self.rate_limit.adaptive_max_delay_ms < self.rate_limit.delay_between_phones_ms {
+                problems.push("rate_limit.adaptive_max_delay_ms must not be below delay_between_phones_ms".to_string());
+            }
+            if self.rate_limit.adaptive_window_secs == 0 {
+                problems.push("rate_limit.adaptive_window_secs must be at least 1".to_string());
+            }
+        }
+
+        if self.proxy.attempts == 0 {
+            problems.push("proxy.attempts must be at least 1".to_string());
+        }
+        if scraping.provider == ScrapeProvider::Proxy
+            && (self.proxy.appwrite_project_id.is_none()
+                || self.proxy.appwrite_database_id.is_none()
+                || self.proxy.appwrite_collection_id.is_none())
+        {
+            problems.push("provider 'proxy' needs proxy.appwrite_project_id, appwrite_database_id and appwrite_collection_id".to_string());
+        }
+
+        if self.chain.providers.is_empty() {
+            problems.push("chain.providers must list at least one provider".to_string());
+        }
+        if self.chain.providers.iter().any(|p| matches!(p, ScrapeProvider::Hybrid | ScrapeProvider::Chain)) {
+            problems.push("chain.providers can only contain direct, proxy, scrapingbee and browser".to_string());
+        }
+        if scraping.provider == ScrapeProvider::Browser && !cfg!(feature = "browser") {
+            problems.push("provider 'browser' needs a build with the `browser` feature".to_string());
+        }
+        if self.browser.timeout_secs == 0 {
+            problems.push("browser.timeout_secs must be at least 1".to_string());
+        }
+
+        problems.extend(crate::selectors::check(&self.selectors));
+
+        for (key, storage) in [("images.storage", &self.images.storage), ("snapshots.storage", &self.snapshots.storage)] {
+            if let Some(storage) = storage {
+                let known = storage.starts_with("local:") || storage.starts_with("gridfs") || storage.starts_with("s3://");
+                if !known {
+                    problems.push(format!("{} has unknown format: {}", key, storage));
+                }
+            }
+        }
+
+        if self.upcoming.recheck_minutes == 0 {
+            problems.push("upcoming.recheck_minutes must be at least 1".to_string());
+        }
+
+        if let Some(schedule) = &self.daemon.schedule {
+            if let Err(e) = CronSchedule::parse(schedule, Duration::ZERO) {
+                problems.push(format!("daemon.schedule: {}", e));
+            }
+        }
+        if let Some(Err(e)) = self.scraping.max_runtime.as_deref().map(parse_duration) {
+            problems.push(format!("scraping.max_runtime: {}", e));
+        }
+
+        if !(0.0..=1.0).contains(&self.validation.min_completeness) {
+            problems.push("validation.min_completeness must be between 0.0 and 1.0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.validation.max_listing_shortfall) {
+            problems.push("validation.max_listing_shortfall must be between 0.0 and 1.0".to_string());
+        }
+
+        for url in &self.webhooks.urls {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                problems.push(format!("webhooks.urls entry is not a URL: {}", url));
+            }
+        }
+        if self.webhooks.max_attempts == 0 {
+            problems.push("webhooks.max_attempts must be at least 1".to_string());
+        }
+
+        if let Some(backend) = self.events.backend {
+            if !backend.is_compiled_in() {
+                problems.push(format!("events.backend '{}' needs a build with the `{}` feature", backend, backend));
+            }
+            if self.events.url.as_deref().is_none_or(|url| url.trim().is_empty()) {
+                problems.push("events.url is required when events.backend is set".to_string());
+            }
+            if self.events.topic.trim().is_empty() {
+                problems.push("events.topic must not be empty".to_string());
+            }
+        }
+
+        if self.cache.dir.as_deref().is_some_and(|dir| dir.trim().is_empty()) {
+            problems.push("cache.dir must not be empty (omit it to disable the cache)".to_string());
+        }
+        if let Some(file) = &self.user_agents.file {
+            if !Path::new(file).is_file() {
+                problems.push(format!("user_agents.file does not exist: {}", file));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Render the configuration as TOML (e.g. for `config print-effective`)
+    pub fn to_toml(&self) -> Result<String, Box<dyn Error>> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+/// One "<amount><unit>" part of a duration
+static DURATION_PART: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r"(\d+)\s*([hms])").unwrap());
+
+/// Parse a duration such as "5h", "90m", "1h30m" or "45s" (a bare number is seconds)
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    if !DURATION_PART.is_match(value) || !DURATION_PART.replace_all(value, "").trim().is_empty() {
+        return Err(format!("invalid duration '{}' (e.g. 5h, 90m, 1h30m)", value));
+    }
+
+    let mut secs: u64 = 0;
+    for cap in DURATION_PART.captures_iter(value) {
+        let amount: u64 = cap[1].parse().map_err(|_| format!("invalid duration '{}'", value))?;
+        let unit = match &cap[2] {
+            "h" => 3600,
+            "m" => 60,
+            _ => 1,
+        };
+        secs = secs.saturating_add(amount.saturating_mul(unit));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Split a comma-separated list, dropping empty entries
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+impl FromStr for ScrapeProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "direct" => Ok(ScrapeProvider::Direct),
+            "proxy" => Ok(ScrapeProvider::Proxy),
+            "scrapingbee" => Ok(ScrapeProvider::ScrapingBee),
+            "hybrid" => Ok(ScrapeProvider::Hybrid),
+            "chain" => Ok(ScrapeProvider::Chain),
+            "browser" => Ok(ScrapeProvider::Browser),
+            other => Err(format!("unknown provider '{}' (direct, proxy, scrapingbee, hybrid, chain, browser)", other)),
+        }
+    }
+}
+
+impl fmt::Display for ScrapeProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScrapeProvider::Direct => "direct",
+            ScrapeProvider::Proxy => "proxy",
+            ScrapeProvider::ScrapingBee => "scrapingbee",
+            ScrapeProvider::Hybrid => "hybrid",
+            ScrapeProvider::Chain => "chain",
+            ScrapeProvider::Browser => "browser",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for DiscoveryMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "brands" => Ok(DiscoveryMode::Brands),
+            "sitemap" => Ok(DiscoveryMode::Sitemap),
+            other => Err(format!("unknown discovery mode '{}' (brands, sitemap)", other)),
+        }
+    }
+}
+
+impl fmt::Display for DiscoveryMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DiscoveryMode::Brands => "brands",
+            DiscoveryMode::Sitemap => "sitemap",
+        })
+    }
+}
+
+impl FromStr for UserAgentRotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "per_request" => Ok(UserAgentRotation::PerRequest),
+            "per_session" => Ok(UserAgentRotation::PerSession),
+            "per_brand" => Ok(UserAgentRotation::PerBrand),
+            other => Err(format!("unknown User-Agent rotation '{}' (per_request, per_session, per_brand)", other)),
+        }
+    }
+}
+
+impl fmt::Display for UserAgentRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UserAgentRotation::PerRequest => "per_request",
+            UserAgentRotation::PerSession => "per_session",
+            UserAgentRotation::PerBrand => "per_brand",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_layering_and_validation() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [scraping]
+            provider = "hybrid"
+            max_brands = 5
+
+            [rate_limit]
+            delay_between_phones_ms = 1000
+
+            [brands]
+            include = ["Apple", "Samsung"]
+
+            [user_agents]
+            rotation = "per_brand"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.scraping.provider, ScrapeProvider::Hybrid);
+        assert_eq!(config.collections.phones, "gsmarena_phones"); // Default kept
+        assert_eq!(config.user_agents.rotation, UserAgentRotation::PerBrand);
+
+        let env: HashMap<&str, &str> = [
+            ("MAX_BRANDS", "2"),
+            ("PHONES_PER_BRAND", ""),
+            ("DELAY_BETWEEN_PHONES_MS", "250"),
+            ("USER_AGENT_ROTATION", "per-session"),
+            ("UPSERT_POLICY", "merge_prefer_non_null"),
+        ]
+            .into_iter()
+            .collect();
+        config.apply_env_from(|key| env.get(key).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(config.scraping.max_brands, Some(2));
+        assert_eq!(config.scraping.phones_per_brand, None); // Empty env value ignored
+        assert_eq!(config.rate_limit.delay_between_phones_ms, 250);
+        assert_eq!(config.user_agents.rotation, UserAgentRotation::PerSession);
+        assert_eq!(config.mongodb.upsert_policy, UpsertPolicy::MergePreferNonNull);
+        assert!(config.brands.matches("samsung"));
+        assert!(!config.brands.matches("Nokia"));
+        assert!(config.validate().is_ok());
+
+        config.brands.exclude = vec!["apple".to_string()];
+        config.collections.brands = "gsmarena_phones".to_string();
+        assert_eq!(config.validate().unwrap_err().len(), 2);
+        config.collections.price_history = Some("upcoming_phones".to_string());
+        assert_eq!(config.validate().unwrap_err().len(), 3);
+
+        let mut events = Config::default();
+        events.apply_env_from(|key| (key == "EVENTS_BACKEND").then(|| "nats".to_string())).unwrap();
+        assert_eq!(events.events.backend, Some(EventBackend::Nats));
+        assert!(events.validate().unwrap_err().iter().any(|problem| problem.contains("events.url")));
+
+        let mut filtered = Config::default();
+        filtered.scraping.discovery = DiscoveryMode::Sitemap;
+        filtered.apply_env_from(|key| (key == "NETWORK_FILTER").then(|| "lte".to_string())).unwrap();
+        assert_eq!(filtered.scraping.network_filter, Some(NetworkFilter::LteOnly));
+        assert!(filtered.validate().unwrap_err().iter().any(|problem| problem.contains("network_filter")));
+
+        let mut adaptive = Config::default();
+        adaptive.apply_env_from(|key| (key == "ADAPTIVE_DELAY").then(|| "true".to_string())).unwrap();
+        assert!(adaptive.rate_limit.adaptive && adaptive.validate().is_ok());
+        adaptive.apply_env_from(|key| (key == "ERROR_POLICY").then(|| "fail-after-50".to_string())).unwrap();
+        assert_eq!(adaptive.scraping.error_policy, ErrorPolicy::FailAfter(50));
+        adaptive.rate_limit.adaptive_threshold = 0.0;
+        adaptive.rate_limit.adaptive_max_delay_ms = 100;
+        assert_eq!(adaptive.validate().unwrap_err().len(), 2);
+
+        assert!(config.apply_env_from(|key| (key == "MAX_BRANDS").then(|| "lots".to_string())).is_err());
+        assert!(toml::from_str::<Config>("[scraping]\nunknown = 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("5h"), Ok(Duration::from_secs(5 * 3600)));
+        assert_eq!(parse_duration("1h 30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("5 hours").is_err());
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn test_chain_config() {
+        let mut config = Config::default();
+        let env: HashMap<&str, &str> = [("CHAIN_PROVIDERS", "scrapingbee, direct"), ("CHAIN_SCRAPINGBEE_BUDGET", "500")].into_iter().collect();
+        config.apply_env_from(|key| env.get(key).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(config.chain.providers, vec![ScrapeProvider::ScrapingBee, ScrapeProvider::Direct]);
+        assert_eq!(config.chain.budget(ScrapeProvider::ScrapingBee), Some(500));
+        assert_eq!(config.chain.budget(ScrapeProvider::Direct), None);
+        assert!(config.validate().is_ok());
+
+        config.chain.providers.push(ScrapeProvider::Chain);
+        assert!(config.validate().is_err());
+        assert!(config.apply_env_from(|key| (key == "CHAIN_PROVIDERS").then(|| "direct,carrier-pigeon".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_shard() {
+        let shard: Shard = "1/4".parse().unwrap();
+        assert_eq!((shard.index, shard.count), (1, 4));
+        assert!("4/4".parse::<Shard>().is_err());
+        assert!("1/0".parse::<Shard>().is_err());
+        assert!("1".parse::<Shard>().is_err());
+
+        let config: Config = toml::from_str("[brands]\nshard = \"1/4\"").unwrap();
+        assert_eq!(config.brands.shard, Some(shard));
+
+        // Every slug lands in exactly one of the shards
+        let slugs = ["apple-phones-48", "samsung-phones-9", "nokia-phones-1", "sony-phones-7", "xiaomi-phones-80"];
+        for slug in slugs {
+            let owners = (0..4).filter(|&index| Shard { index, count: 4 }.contains(slug)).count();
+            assert_eq!(owners, 1, "{}", slug);
+        }
+        assert!(slugs.iter().all(|slug| Shard { index: 0, count: 1 }.contains(slug)));
+    }
+}
@@ -0,0 +1,255 @@
+//! The whole phone corpus in memory, indexed by phone_id, brand and chipset
+//!
+//! For analytics that would otherwise issue one query per phone or brand.
+//! Load once with `Dataset::load_from_mongo` or `Dataset::load_from_jsonl`
+//! (one `PhoneDocument` per line, as written by `export --format jsonl`, gzipped or not).
+
+use crate::compression::{self, FileCompression};
+use crate::mongodb::{MongoDBClient, PhoneDocument};
+use crate::query::PhoneQuery;
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::LazyLock;
+use tracing::info;
+
+#[derive(Debug, Clone, Default)]
+pub struct Dataset {
+    phones: Vec<PhoneDocument>,
+    by_id: HashMap<String, usize>,
+    by_brand: BTreeMap<String, Vec<usize>>,   // Lowercased brand
+    by_chipset: BTreeMap<String, Vec<usize>>, // See `chipset_key`
+}
+
+impl Dataset {
+    /// Index already loaded phones; a repeated phone_id keeps the last copy
+    pub fn from_phones(phones: Vec<PhoneDocument>) -> Self {
+        let mut dataset = Self::default();
+        for phone in phones {
+            dataset.insert(phone);
+        }
+        dataset
+    }
+
+    /// Load every phone matching `filter` (`doc! {}` for all), ordered by brand and name
+    pub async fn load_from_mongo(client: &MongoDBClient, collection_name: &str, filter: Document) -> Result<Self, Box<dyn Error>> {
+        let collection = client.get_collection(collection_name);
+        let options = FindOptions::builder().sort(doc! { "brand": 1, "name": 1 }).build();
+
+        let mut cursor = collection.find(filter, options).await?;
+        let mut phones = Vec::new();
+        while let Some(phone) = cursor.next().await {
+            phones.push(phone?);
+        }
+
+        info!(count = phones.len(), collection = collection_name, "Loaded dataset from MongoDB");
+        Ok(Self::from_phones(phones))
+    }
+
+    /// Load a JSON Lines file, gzipped or plain; blank lines are skipped
+    pub fn load_from_jsonl(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let reader = compression::open(path)?;
+
+        let mut phones = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let phone: PhoneDocument =
+                serde_json::from_str(&line).map_err(|e| format!("{}:{}: {}", path.display(), index + 1, e))?;
+            phones.push(phone);
+        }
+
+        info!(count = phones.len(), path = %path.display(), "Loaded dataset from JSONL");
+        Ok(Self::from_phones(phones))
+    }
+
+    /// Write the phones as JSON Lines, in dataset order; gzipped when `path` ends in `.gz`
+    pub fn write_jsonl(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut writer = FileCompression::from_path(path).create(path)?;
+        for phone in &self.phones {
+            serde_json::to_writer(&mut writer, phone)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.finish()
+    }
+
+    fn insert(&mut self, phone: PhoneDocument) {
+        if let Some(&index) = self.by_id.get(&phone.phone_id) {
+            self.phones[index] = phone;
+            self.reindex();
+            return;
+        }
+
+        let index = self.phones.len();
+        self.index_phone(index, &phone);
+        self.phones.push(phone);
+    }
+
+    fn index_phone(&mut self, index: usize, phone: &PhoneDocument) {
+        self.by_id.insert(phone.phone_id.clone(), index);
+        self.by_brand.entry(phone.brand.to_lowercase()).or_default().push(index);
+        if let Some(chipset) = phone.platform.as_ref().and_then(|p| p.chipset.as_deref()) {
+            self.by_chipset.entry(chipset_key(chipset)).or_default().push(index);
+        }
+    }
+
+    fn reindex(&mut self) {
+        self.by_id.clear();
+        self.by_brand.clear();
+        self.by_chipset.clear();
+        let phones = std::mem::take(&mut self.phones);
+        for (index, phone) in phones.iter().enumerate() {
+            self.index_phone(index, phone);
+        }
+        self.phones = phones;
+    }
+
+    pub fn len(&self) -> usize {
+        self.phones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phones.is_empty()
+    }
+
+    pub fn get(&self, phone_id: &str) -> Option<&PhoneDocument> {
+        self.by_id.get(phone_id).map(|&index| &self.phones[index])
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PhoneDocument> {
+        self.phones.iter()
+    }
+
+    /// Phones of a brand (case-insensitive)
+    pub fn by_brand<'a>(&'a self, brand: &str) -> impl Iterator<Item = &'a PhoneDocument> + 'a {
+        self.indexed(self.by_brand.get(&brand.to_lowercase()))
+    }
+
+    /// Phones with a chipset; "Snapdragon 8 Gen 3" and "Qualcomm SM8650-AB Snapdragon 8 Gen 3 (4 nm)" both work
+    pub fn by_chipset<'a>(&'a self, chipset: &str) -> impl Iterator<Item = &'a PhoneDocument> + 'a {
+        let key = chipset_key(chipset);
+        let indexes: Vec<usize> = match self.by_chipset.get(&key) {
+            Some(exact) => exact.clone(),
+            None => {
+                let mut indexes: Vec<usize> = self
+                    .by_chipset
+                    .iter()
+                    .filter(|(stored, _)| stored.contains(&key))
+                    .flat_map(|(_, indexes)| indexes.iter().copied())
+                    .collect();
+                indexes.sort_unstable();
+                indexes
+            }
+        };
+        indexes.into_iter().map(move |index| &self.phones[index])
+    }
+
+    /// Phones matching a spec query
+    pub fn query<'a>(&'a self, query: &'a PhoneQuery) -> impl Iterator<Item = &'a PhoneDocument> + 'a {
+        self.phones.iter().filter(move |phone| query.matches(phone))
+    }
+
+    /// Brand names as stored, with phone counts, alphabetically
+    pub fn brands(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.by_brand.values().map(|indexes| (self.phones[indexes[0]].brand.as_str(), indexes.len()))
+    }
+
+    /// Chipset names (without the process node) with phone counts, alphabetically
+    pub fn chipsets(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.by_chipset.iter().map(|(key, indexes)| (key.as_str(), indexes.len()))
+    }
+
+    /// Phones grouped per brand, alphabetically
+    pub fn group_by_brand(&self) -> impl Iterator<Item = (&str, Vec<&PhoneDocument>)> {
+        self.by_brand.values().map(|indexes| {
+            let phones: Vec<&PhoneDocument> = indexes.iter().map(|&index| &self.phones[index]).collect();
+            (phones[0].brand.as_str(), phones)
+        })
+    }
+
+    fn indexed<'a>(&'a self, indexes: Option<&'a Vec<usize>>) -> impl Iterator<Item = &'a PhoneDocument> + 'a {
+        indexes.into_iter().flatten().map(|&index| &self.phones[index])
+    }
+}
+
+impl<'a> IntoIterator for &'a Dataset {
+    type Item = &'a PhoneDocument;
+    type IntoIter = std::slice::Iter<'a, PhoneDocument>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.phones.iter()
+    }
+}
+
+impl IntoIterator for Dataset {
+    type Item = PhoneDocument;
+    type IntoIter = std::vec::IntoIter<PhoneDocument>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.phones.into_iter()
+    }
+}
+
+impl FromIterator<PhoneDocument> for Dataset {
+    fn from_iter<I: IntoIterator<Item = PhoneDocument>>(iter: I) -> Self {
+        Self::from_phones(iter.into_iter().collect())
+    }
+}
+
+static PROCESS_NODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\([^)]*\)\s*$").unwrap());
+
+/// "Qualcomm SM8650-AB Snapdragon 8 Gen 3 (4 nm)" -> "qualcomm sm8650-ab snapdragon 8 gen 3"
+pub fn chipset_key(chipset: &str) -> String {
+    let without_node = PROCESS_NODE.replace(chipset, "");
+    without_node.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(id: &str, brand: &str, chipset: &str) -> PhoneDocument {
+        PhoneDocument::fixture(id)
+            .brand(brand)
+            .category("Platform", &[("Chipset", chipset)])
+            .category("Battery", &[("Type", "Li-Ion 5000 mAh")])
+            .build()
+    }
+
+    #[test]
+    fn test_dataset_indexes_and_jsonl() {
+        let dataset: Dataset = vec![
+            phone("galaxy_s24-1", "Samsung", "Qualcomm SM8650-AB Snapdragon 8 Gen 3 (4 nm)"),
+            phone("xiaomi_14-2", "Xiaomi", "Qualcomm SM8650-AB Snapdragon 8 Gen 3 (4 nm)"),
+            phone("galaxy_a55-3", "Samsung", "Exynos 1480 (4 nm)"),
+            phone("galaxy_s24-1", "Samsung", "Exynos 2400 (4 nm)"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.by_brand("samsung").count(), 2);
+        assert_eq!(dataset.by_chipset("Snapdragon 8 Gen 3").map(|p| p.phone_id.as_str()).collect::<Vec<_>>(), vec!["xiaomi_14-2"]);
+        assert_eq!(dataset.by_chipset("exynos 2400").count(), 1);
+        assert_eq!(dataset.brands().collect::<Vec<_>>(), vec![("Samsung", 2), ("Xiaomi", 1)]);
+        assert_eq!(dataset.query(&PhoneQuery::new().battery_mah_gte(5000)).count(), 3);
+
+        for name in ["jsonl", "jsonl.gz"] {
+            let path = std::env::temp_dir().join(format!("gsmarena-dataset-{}.{}", std::process::id(), name));
+            dataset.write_jsonl(&path).unwrap();
+            let reloaded = Dataset::load_from_jsonl(&path).unwrap();
+            std::fs::remove_file(&path).ok();
+            assert_eq!(reloaded.len(), 3);
+            assert_eq!(reloaded.get("galaxy_a55-3").unwrap().normalized, dataset.get("galaxy_a55-3").unwrap().normalized);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Phone, tablet or watch: brand listings mix all three
+//!
+//! Listings only give a name, so `from_name` catches the obvious cases ("Galaxy Tab",
+//! "Watch GT"); `classify` also looks at the specs once a phone page has been parsed.
+
+use crate::mongodb::PhoneDocument;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+static WATCH_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(watch|band|fit|gear (s|live|sport)|wearable)\b").unwrap());
+static TABLET_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(tab|tablet|pad|ipad|matepad|mediapad|xoom)\d*\b").unwrap());
+static WATCH_BODY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(strap|wristband|smartwatch|watch)\b").unwrap());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceType {
+    #[default]
+    Phone,
+    Tablet,
+    Watch,
+}
+
+impl DeviceType {
+    /// Inferred from the model name alone; None when the name says nothing either way
+    pub fn from_name(name: &str) -> Option<Self> {
+        if WATCH_NAME.is_match(name) {
+            Some(DeviceType::Watch)
+        } else if TABLET_NAME.is_match(name) {
+            Some(DeviceType::Tablet)
+        } else {
+            None
+        }
+    }
+
+    /// Name first, then the specs: watch-only Body entries, a display under 2.2"
+    /// without a SIM card slot (feature phones have one) or a non-foldable display of 7" or more
+    pub fn classify(phone: &PhoneDocument) -> Self {
+        if let Some(device_type) = Self::from_name(&phone.name) {
+            return device_type;
+        }
+
+        // Watch pages put straps and "Smartwatch" rows (some without a key) under Body
+        let watch_body = body_values(&phone.specifications_raw).any(|value| WATCH_BODY.is_match(value));
+        let foldable = phone.display.as_ref().and_then(|d| d.display_type.as_deref()).is_some_and(|t| t.contains("Foldable"));
+
+        match phone.normalized.display_size_in {
+            _ if watch_body => DeviceType::Watch,
+            Some(size) if size < 2.2 && phone.normalized.sim_sizes.is_empty() => DeviceType::Watch,
+            Some(size) if size >= 7.0 && !foldable => DeviceType::Tablet,
+            _ => DeviceType::Phone,
+        }
+    }
+}
+
+/// Every value in the raw Body category, including rows the structured fields drop
+fn body_values(raw_specs: &serde_json::Value) -> impl Iterator<Item = &str> {
+    raw_specs
+        .get("specification")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|category| category.get("category_title").and_then(|t| t.as_str()).is_some_and(|t| t.eq_ignore_ascii_case("body")))
+        .filter_map(|category| category.get("category_spec")?.as_array())
+        .flatten()
+        .filter_map(|pair| pair.as_array()?.get(1)?.as_str())
+}
+
+impl FromStr for DeviceType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "phone" => Ok(DeviceType::Phone),
+            "tablet" => Ok(DeviceType::Tablet),
+            "watch" => Ok(DeviceType::Watch),
+            other => Err(format!("unknown device type '{}' (phone, tablet, watch)", other)),
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DeviceType::Phone => "phone",
+            DeviceType::Tablet => "tablet",
+            DeviceType::Watch => "watch",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mongodb::parse_watch_specs;
+
+    fn document(name: &str, size: &str, display_type: &str, build: &str) -> PhoneDocument {
+        PhoneDocument::fixture("test-1")
+            .name(name)
+            .category("Body", &[("Build", build)])
+            .category("Display", &[("Type", display_type), ("Size", size)])
+            .build()
+    }
+
+    #[test]
+    fn test_device_type() {
+        assert_eq!(DeviceType::from_name("Galaxy Tab S9"), Some(DeviceType::Tablet));
+        assert_eq!(DeviceType::from_name("iPad Pro 12.9 (2022)"), Some(DeviceType::Tablet));
+        assert_eq!(DeviceType::from_name("Watch GT 4"), Some(DeviceType::Watch));
+        assert_eq!(DeviceType::from_name("Galaxy S24"), None);
+        assert_eq!(DeviceType::from_name("Pixel 8 Pro"), None);
+
+        let phone = document("Galaxy S24", "6.2 inches, 94.4 cm2", "Dynamic LTPO AMOLED 2X, 120Hz", "Glass front, aluminum frame");
+        assert_eq!(phone.device_type, DeviceType::Phone);
+        let fold = document("Galaxy Z Fold5", "7.6 inches, 185.2 cm2", "Foldable Dynamic AMOLED 2X, 120Hz", "Glass front");
+        assert_eq!(fold.device_type, DeviceType::Phone);
+        let tablet = document("Xiaomi Redmi 13", "10.6 inches, 320.6 cm2", "IPS LCD, 90Hz", "Glass front, aluminum back");
+        assert_eq!(tablet.device_type, DeviceType::Tablet);
+        let watch = document("Galaxy 7 (44mm)", "1.5 inches", "Super AMOLED", "Sapphire crystal glass front, aluminum frame, silicone strap");
+        assert_eq!(watch.device_type, DeviceType::Watch);
+        assert_eq!(watch.watch.as_ref().and_then(|w| w.strap.as_deref()), Some("silicone strap"));
+        assert!(phone.watch.is_none() && tablet.watch.is_none());
+
+        assert_eq!("Tablet".parse::<DeviceType>(), Ok(DeviceType::Tablet));
+        assert!("laptop".parse::<DeviceType>().is_err());
+    }
+
+    #[test]
+    fn test_watch_specs() {
+        let spec = serde_json::json!({
+            "specification": [
+                { "category_title": "Body", "category_spec": [
+                    ["Dimensions", "44.4 x 42.8 x 9 mm (1.75 x 1.69 x 0.35 in)"],
+                    ["Build", "Glass front (Sapphire crystal), aluminum frame"],
+                    ["\u{a0}", "5ATM water resistant (50m)"],
+                    ["\u{a0}", "IP68 dust/water resistant"],
+                    ["\u{a0}", "Fluoroelastomer band"]
+                ] },
+                { "category_title": "Misc", "category_spec": [["Compatibility", "Android 11 or later"]] }
+            ]
+        });
+        let watch = parse_watch_specs(&spec, "Galaxy Watch6 (44mm)").unwrap();
+        assert_eq!(watch.case_size_mm, Some(44.0));
+        assert_eq!(watch.strap.as_deref(), Some("Fluoroelastomer band"));
+        assert_eq!(watch.water_resistance.as_deref(), Some("5ATM water resistant (50m); IP68 dust/water resistant"));
+        assert_eq!(watch.compatibility.as_deref(), Some("Android 11 or later"));
+        assert_eq!(parse_watch_specs(&spec, "Galaxy Watch6").unwrap().case_size_mm, Some(44.4));
+
+        assert!(parse_watch_specs(&serde_json::json!({ "specification": [] }), "Watch").is_none());
+    }
+}
@@ -0,0 +1,190 @@
+//! Collection snapshots as JSONL, gzipped by default (`db dump` / `db restore`)
+//!
+//! One document per line in canonical extended JSON, so ObjectIds, dates and 64-bit
+//! integers come back with their original types.
+
+use crate::compression::{self, CompressedWriter, FileCompression};
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::ReplaceOptions;
+use mongodb::Collection;
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Documents inserted at once when restoring into an emptied collection
+const INSERT_BATCH: usize = 500;
+
+/// One line of a dump
+pub fn encode_document(document: Document) -> Result<String, Box<dyn Error>> {
+    Ok(serde_json::to_string(&Bson::Document(document).into_canonical_extjson())?)
+}
+
+/// Parse one line of a dump back into a document
+pub fn decode_document(line: &str) -> Result<Document, Box<dyn Error>> {
+    match Bson::try_from(serde_json::from_str::<serde_json::Value>(line)?)? {
+        Bson::Document(document) => Ok(document),
+        other => Err(format!("dump line is not a document: {}", other).into()),
+    }
+}
+
+/// Streams documents into a JSONL file; call `finish` to complete it
+pub struct DumpWriter {
+    writer: CompressedWriter,
+    written: u64,
+}
+
+impl DumpWriter {
+    pub fn create(path: &Path, compression: FileCompression) -> Result<Self, Box<dyn Error>> {
+        Ok(Self { writer: compression.create(path)?, written: 0 })
+    }
+
+    pub fn write(&mut self, document: Document) -> Result<(), Box<dyn Error>> {
+        writeln!(self.writer, "{}", encode_document(document)?)?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Flush the file; returns the number of documents written
+    pub fn finish(self) -> Result<u64, Box<dyn Error>> {
+        self.writer.finish()?;
+        Ok(self.written)
+    }
+}
+
+/// Documents of a dump file (gzipped or plain), in order; blank lines are skipped
+pub fn read_dump(path: &Path) -> Result<impl Iterator<Item = Result<Document, Box<dyn Error>>>, Box<dyn Error>> {
+    let lines = compression::open(path)?.lines();
+    Ok(lines.filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(decode_document(&line)),
+        Err(e) => Some(Err(e.into())),
+    }))
+}
+
+/// Write every document of `collection` to `path`; returns how many
+pub async fn dump_collection(collection: &Collection<Document>, path: &Path, compression: FileCompression) -> Result<u64, Box<dyn Error>> {
+    let mut writer = DumpWriter::create(path, compression)?;
+    let mut cursor = collection.find(doc! {}, None).await?;
+    while let Some(document) = cursor.next().await {
+        writer.write(document?)?;
+    }
+    writer.finish()
+}
+
+/// Load a dump into `collection`; returns how many documents were written
+///
+/// With `replace_all` the collection is dropped first and documents are inserted in
+/// batches. Otherwise each document replaces the one with the same `_id`, so
+/// documents missing from the dump are left alone.
+pub async fn restore_collection(collection: &Collection<Document>, path: &Path, replace_all: bool) -> Result<u64, Box<dyn Error>> {
+    let mut restored = 0;
+    if replace_all {
+        collection.drop(None).await?;
+        let mut batch = Vec::with_capacity(INSERT_BATCH);
+        for document in read_dump(path)? {
+            batch.push(document?);
+            if batch.len() == INSERT_BATCH {
+                restored += batch.len() as u64;
+                collection.insert_many(std::mem::take(&mut batch), None).await?;
+            }
+        }
+        if !batch.is_empty() {
+            restored += batch.len() as u64;
+            collection.insert_many(batch, None).await?;
+        }
+        return Ok(restored);
+    }
+
+    let options = ReplaceOptions::builder().upsert(true).build();
+    for document in read_dump(path)? {
+        let document = document?;
+        let filter = match document.get("_id") {
+            Some(id) => doc! { "_id": id.clone() },
+            None => return Err("dump document has no _id; restore with --drop instead".into()),
+        };
+        collection.replace_one(filter, document, options.clone()).await?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/// Split "s3://bucket/key" into bucket and key
+pub fn parse_s3_url(url: &str) -> Option<(&str, &str)> {
+    let location = url.strip_prefix("s3://")?;
+    let (bucket, key) = location.split_once('/').unwrap_or((location, ""));
+    (!bucket.is_empty()).then_some((bucket, key.trim_matches('/')))
+}
+
+/// Upload a dump to `s3://bucket/prefix`, keeping its file name; returns the object URL
+#[cfg(feature = "s3")]
+pub async fn upload_to_s3(path: &Path, destination: &str) -> Result<String, Box<dyn Error>> {
+    let (bucket, prefix) = parse_s3_url(destination).ok_or_else(|| format!("invalid S3 location: {}", destination))?;
+    let file_name = path.file_name().and_then(|name| name.to_str()).ok_or("dump path has no file name")?;
+    let key = if prefix.is_empty() { file_name.to_string() } else { format!("{}/{}", prefix, file_name) };
+
+    let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let client = aws_sdk_s3::Client::new(&config);
+    let body = aws_sdk_s3::primitives::ByteStream::from_path(path).await?;
+    let content_type = match FileCompression::from_path(path) {
+        FileCompression::Gzip => "application/gzip",
+        FileCompression::None => "application/x-ndjson",
+    };
+    client.put_object().bucket(bucket).key(&key).content_type(content_type).body(body).send().await?;
+    Ok(format!("s3://{}/{}", bucket, key))
+}
+
+#[cfg(not(feature = "s3"))]
+pub async fn upload_to_s3(_path: &Path, destination: &str) -> Result<String, Box<dyn Error>> {
+    Err(format!("uploading to {} requires the `s3` feature", destination).into())
+}
+
+/// Download `s3://bucket/key` to `path`
+#[cfg(feature = "s3")]
+pub async fn download_from_s3(source: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+    let (bucket, key) = parse_s3_url(source).filter(|(_, key)| !key.is_empty()).ok_or_else(|| format!("invalid S3 object: {}", source))?;
+
+    let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let client = aws_sdk_s3::Client::new(&config);
+    let object = client.get_object().bucket(bucket).key(key).send().await?;
+    std::fs::write(path, object.body.collect().await?.into_bytes())?;
+    Ok(())
+}
+
+#[cfg(not(feature = "s3"))]
+pub async fn download_from_s3(source: &str, _path: &Path) -> Result<(), Box<dyn Error>> {
+    Err(format!("downloading {} requires the `s3` feature", source).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::DateTime;
+
+    #[test]
+    fn test_dump_round_trip() {
+        let documents = vec![
+            doc! { "_id": ObjectId::new(), "phone_id": "apple_iphone_15-12559", "version": 3_i64, "scraped": DateTime::from_millis(1_700_000_000_000) },
+            doc! { "_id": ObjectId::new(), "phone_id": "samsung_galaxy_s24-12773", "normalized": { "ram_gb": 8.0, "has_nfc": true } },
+        ];
+
+        let path = std::env::temp_dir().join(format!("gsmarena-dump-{}.jsonl.gz", std::process::id()));
+        for compression in [FileCompression::Gzip, FileCompression::None] {
+            let mut writer = DumpWriter::create(&path, compression).unwrap();
+            for document in documents.clone() {
+                writer.write(document).unwrap();
+            }
+            assert_eq!(writer.finish().unwrap(), 2);
+
+            let restored: Vec<Document> = read_dump(&path).unwrap().map(Result::unwrap).collect();
+            assert_eq!(restored, documents); // Types survive, not just values
+            assert!(restored[0].get_i64("version").is_ok());
+        }
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(parse_s3_url("s3://backups/gsmarena/"), Some(("backups", "gsmarena")));
+        assert_eq!(parse_s3_url("s3://backups"), Some(("backups", "")));
+        assert_eq!(parse_s3_url("/tmp/phones.jsonl.gz"), None);
+    }
+}
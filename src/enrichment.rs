@@ -0,0 +1,159 @@
+//! Data from sources other than GSMArena, attached to phone documents
+//!
+//! GSMArena stays canonical: enrichers never touch the parsed fields. Each one adds a
+//! `SourceRecord` (manufacturer spec page, FCC filing, ...) to the document's
+//! `sources`, stamped with when it was fetched. Hand them to
+//! `ScrapeRunner::builder().enricher(...)`.
+
+use crate::mongodb::PhoneDocument;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// What one secondary source had on a phone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub source: String, // Enricher name, e.g. "fcc"; one record per source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+impl SourceRecord {
+    pub fn new(source: impl Into<String>, data: serde_json::Value) -> Self {
+        Self { source: source.into(), url: None, fetched_at: Utc::now(), data }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// A secondary source; called for every phone scraped
+#[async_trait]
+pub trait Enricher: Send + Sync {
+    /// Source name stored in `SourceRecord::source`
+    fn name(&self) -> &str;
+
+    /// The source's record for this phone; None when it has nothing on it
+    async fn enrich(&self, phone: &PhoneDocument) -> Result<Option<SourceRecord>, Box<dyn Error>>;
+}
+
+/// Enrichers run in order over each scraped phone
+#[derive(Clone, Default)]
+pub struct Enrichers {
+    enrichers: Vec<Arc<dyn Enricher>>,
+}
+
+impl Enrichers {
+    pub fn push(&mut self, enricher: Arc<dyn Enricher>) {
+        self.enrichers.push(enricher);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.enrichers.is_empty()
+    }
+
+    /// Fill `phone.sources`: records of `stored` are carried over, then each enricher's
+    /// new record replaces its old one. A failing enricher keeps its old record.
+    pub async fn apply(&self, phone: &mut PhoneDocument, stored: Option<&PhoneDocument>) {
+        if let Some(stored) = stored {
+            for record in &stored.sources {
+                if phone.source_record(&record.source).is_none() {
+                    phone.sources.push(record.clone());
+                }
+            }
+        }
+
+        for enricher in &self.enrichers {
+            match enricher.enrich(phone).await {
+                Ok(Some(mut record)) => {
+                    record.source = enricher.name().to_string();
+                    phone.sources.retain(|existing| existing.source != record.source);
+                    phone.sources.push(record);
+                }
+                Ok(None) => debug!(phone = %phone.phone_id, source = enricher.name(), "No enrichment data"),
+                Err(e) => warn!(phone = %phone.phone_id, source = enricher.name(), error = %e, "Enrichment failed"),
+            }
+        }
+    }
+}
+
+impl PhoneDocument {
+    /// The record a secondary source attached, if any
+    pub fn source_record(&self, source: &str) -> Option<&SourceRecord> {
+        self.sources.iter().find(|record| record.source == source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stand-in FCC lookup: a grantee code for Apple phones only
+    struct Fcc;
+
+    #[async_trait]
+    impl Enricher for Fcc {
+        fn name(&self) -> &str {
+            "fcc"
+        }
+
+        async fn enrich(&self, phone: &PhoneDocument) -> Result<Option<SourceRecord>, Box<dyn Error>> {
+            let grantee = if phone.brand == "Apple" { "BCG" } else { return Ok(None) };
+            Ok(Some(SourceRecord::new("ignored", serde_json::json!({ "grantee_code": grantee })).with_url("https://fccid.io/BCG")))
+        }
+    }
+
+    struct Broken;
+
+    #[async_trait]
+    impl Enricher for Broken {
+        fn name(&self) -> &str {
+            "manufacturer"
+        }
+
+        async fn enrich(&self, _phone: &PhoneDocument) -> Result<Option<SourceRecord>, Box<dyn Error>> {
+            Err("HTTP 503".into())
+        }
+    }
+
+    fn phone(brand: &str) -> PhoneDocument {
+        PhoneDocument::fixture("apple_iphone_15-12559").name("iPhone 15").brand(brand).build()
+    }
+
+    #[tokio::test]
+    async fn test_enrichers() {
+        let mut enrichers = Enrichers::default();
+        enrichers.push(Arc::new(Fcc));
+        enrichers.push(Arc::new(Broken));
+
+        let mut stored = phone("Apple");
+        stored.sources = vec![
+            SourceRecord::new("manufacturer", serde_json::json!({ "colors": ["Black", "Pink"] })),
+            SourceRecord::new("fcc", serde_json::json!({ "grantee_code": "OLD" })),
+        ];
+
+        let mut scraped = phone("Apple");
+        enrichers.apply(&mut scraped, Some(&stored)).await;
+        assert_eq!(scraped.sources.len(), 2);
+        let fcc = scraped.source_record("fcc").unwrap();
+        assert_eq!((fcc.data["grantee_code"].as_str(), fcc.url.as_deref()), (Some("BCG"), Some("https://fccid.io/BCG")));
+        // The failing source keeps what it had
+        assert_eq!(scraped.source_record("manufacturer").unwrap().data["colors"][1], "Pink");
+        assert_eq!(scraped.source, "gsmarena");
+
+        let mut other = phone("Nokia");
+        enrichers.apply(&mut other, None).await;
+        assert!(other.sources.is_empty());
+
+        let json = serde_json::to_value(&scraped).unwrap();
+        let parsed: PhoneDocument = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.sources, scraped.sources);
+    }
+}
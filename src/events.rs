@@ -0,0 +1,278 @@
+//! A message per saved phone on NATS or Kafka
+//!
+//! Streaming pipelines subscribe to the topic instead of polling MongoDB. Every phone a
+//! scrape writes is published as JSON, keyed by its phone_id. NATS needs the `nats`
+//! feature; Kafka goes through a Confluent REST Proxy and needs the `kafka` feature.
+
+use crate::config::EventsConfig;
+use crate::mongodb::PhoneDocument;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use tracing::warn;
+
+/// Value of `event` in every message
+pub const PHONE_UPSERTED: &str = "phone.upserted";
+
+/// Where `[events]` messages go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventBackend {
+    /// `url` is the NATS server, `topic` the subject
+    Nats,
+    /// `url` is a Kafka REST Proxy (v2 API)
+    Kafka,
+}
+
+impl EventBackend {
+    /// Whether this build can publish to the backend
+    pub fn is_compiled_in(&self) -> bool {
+        match self {
+            EventBackend::Nats => cfg!(feature = "nats"),
+            EventBackend::Kafka => cfg!(feature = "kafka"),
+        }
+    }
+}
+
+/// One message: the full document plus enough to route it without parsing it
+#[derive(Debug, Serialize)]
+pub struct PhoneMessage<'a> {
+    pub event: &'static str,
+    pub run_id: &'a str,
+    pub published_at: DateTime<Utc>,
+    pub phone_id: &'a str,
+    pub brand: &'a str,
+    pub name: &'a str,
+    pub phone: &'a PhoneDocument,
+}
+
+impl<'a> PhoneMessage<'a> {
+    pub fn new(run_id: &'a str, phone: &'a PhoneDocument) -> Self {
+        Self {
+            event: PHONE_UPSERTED,
+            run_id,
+            published_at: Utc::now(),
+            phone_id: &phone.phone_id,
+            brand: &phone.brand,
+            name: &phone.name,
+            phone,
+        }
+    }
+}
+
+/// One event transport
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn publish(&self, topic: &str, key: &str, payload: &serde_json::Value) -> Result<(), Box<dyn Error>>;
+}
+
+/// Publishes saved phones to the configured topic
+pub struct EventPublisher {
+    sink: Box<dyn EventSink>,
+    topic: String,
+}
+
+impl EventPublisher {
+    /// `None` when no backend is configured (or the matching feature is not compiled in)
+    pub async fn from_config(config: &EventsConfig) -> Result<Option<Self>, Box<dyn Error>> {
+        let (Some(backend), Some(url)) = (config.backend, config.url.clone()) else {
+            return Ok(None);
+        };
+        let sink: Option<Box<dyn EventSink>> = match backend {
+            #[cfg(feature = "nats")]
+            EventBackend::Nats => Some(Box::new(nats::NatsSink::connect(&url).await?)),
+            #[cfg(feature = "kafka")]
+            EventBackend::Kafka => Some(Box::new(kafka::KafkaRestSink::new(url))),
+            #[allow(unreachable_patterns)] // Every backend compiled in
+            other => {
+                let _ = url;
+                warn!("events.backend is '{}' but this build lacks the `{}` feature", other, other);
+                None
+            }
+        };
+        Ok(sink.map(|sink| Self::new(sink, &config.topic)))
+    }
+
+    /// A publisher over any sink, e.g. an in-memory one in tests
+    pub fn new(sink: Box<dyn EventSink>, topic: &str) -> Self {
+        Self { sink, topic: topic.to_string() }
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.sink.name()
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Encode the message for a phone; done before the document is handed to the store
+    pub fn encode(run_id: &str, phone: &PhoneDocument) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(PhoneMessage::new(run_id, phone))
+    }
+
+    /// Publish an encoded message; failures are logged, never fatal to the run
+    pub async fn publish(&self, phone_id: &str, payload: &serde_json::Value) -> bool {
+        match self.sink.publish(&self.topic, phone_id, payload).await {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(backend = self.sink.name(), topic = %self.topic, phone = phone_id, error = %e, "✗ Failed to publish phone event");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use super::*;
+
+    /// Core NATS publish; `topic` is the subject
+    pub struct NatsSink {
+        client: async_nats::Client,
+    }
+
+    impl NatsSink {
+        pub async fn connect(url: &str) -> Result<Self, Box<dyn Error>> {
+            let client = async_nats::connect(url).await.map_err(|e| format!("Cannot connect to NATS at {}: {}", url, e))?;
+            Ok(Self { client })
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for NatsSink {
+        fn name(&self) -> &'static str {
+            "nats"
+        }
+
+        async fn publish(&self, topic: &str, _key: &str, payload: &serde_json::Value) -> Result<(), Box<dyn Error>> {
+            let body = serde_json::to_vec(payload)?;
+            self.client.publish(topic.to_string(), body.into()).await?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::*;
+    use std::time::Duration;
+
+    const CONTENT_TYPE: &str = "application/vnd.kafka.json.v2+json";
+
+    /// `POST /topics/{topic}` on a Confluent REST Proxy; the phone_id is the record key
+    pub struct KafkaRestSink {
+        client: reqwest::Client,
+        url: String,
+    }
+
+    impl KafkaRestSink {
+        pub fn new(url: String) -> Self {
+            let client = reqwest::Client::builder()
+                .user_agent(concat!("gsmarena-scraper/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client");
+            Self { client, url: url.trim_end_matches('/').to_string() }
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for KafkaRestSink {
+        fn name(&self) -> &'static str {
+            "kafka"
+        }
+
+        async fn publish(&self, topic: &str, key: &str, payload: &serde_json::Value) -> Result<(), Box<dyn Error>> {
+            let body = serde_json::json!({ "records": [{ "key": key, "value": payload }] });
+            let response = self
+                .client
+                .post(format!("{}/topics/{}", self.url, topic))
+                .header(reqwest::header::CONTENT_TYPE, CONTENT_TYPE)
+                .body(serde_json::to_vec(&body)?)
+                .send()
+                .await?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+            let text = response.text().await.unwrap_or_default();
+            Err(format!("status {}: {}", status, text.chars().take(200).collect::<String>()).into())
+        }
+    }
+}
+
+impl FromStr for EventBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "nats" => Ok(EventBackend::Nats),
+            "kafka" => Ok(EventBackend::Kafka),
+            other => Err(format!("unknown event backend '{}' (nats, kafka)", other)),
+        }
+    }
+}
+
+impl fmt::Display for EventBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventBackend::Nats => "nats",
+            EventBackend::Kafka => "kafka",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Keeps what was published; fails for one phone
+    #[derive(Clone, Default)]
+    struct Recorder {
+        published: Arc<Mutex<Vec<(String, String, serde_json::Value)>>>,
+    }
+
+    #[async_trait]
+    impl EventSink for Recorder {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        async fn publish(&self, topic: &str, key: &str, payload: &serde_json::Value) -> Result<(), Box<dyn Error>> {
+            if key == "broken-1" {
+                return Err("connection reset".into());
+            }
+            self.published.lock().unwrap().push((topic.to_string(), key.to_string(), payload.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_phone_events() {
+        let phone = PhoneDocument::fixture("apple_iphone_15-12559").name("iPhone 15").brand("Apple").build();
+
+        let recorder = Recorder::default();
+        let publisher = EventPublisher::new(Box::new(recorder.clone()), "gsmarena.phones");
+        let payload = EventPublisher::encode("run-1", &phone).unwrap();
+        assert!(publisher.publish(&phone.phone_id, &payload).await);
+        assert!(!publisher.publish("broken-1", &payload).await);
+
+        let published = recorder.published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        let (topic, key, message) = &published[0];
+        assert_eq!((topic.as_str(), key.as_str()), ("gsmarena.phones", "apple_iphone_15-12559"));
+        assert_eq!(message["event"], PHONE_UPSERTED);
+        assert_eq!(message["run_id"], "run-1");
+        assert_eq!(message["brand"], "Apple");
+        assert_eq!(message["phone"]["name"], "iPhone 15");
+
+        assert_eq!("Kafka".parse::<EventBackend>().unwrap(), EventBackend::Kafka);
+        assert!("rabbitmq".parse::<EventBackend>().is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! `PhoneStore` over a directory tree of JSON files, one directory per brand
+//!
+//! ```text
+//! scraped_data/
+//! ├── all_brands.json          brands listed so far
+//! ├── scraping_stats.json      per brand: phones listed, specs stored, directory
+//! └── Samsung/
+//!     ├── phone_list.json      the brand's listing
+//!     ├── <phone_id>.json      one PhoneDocument per phone
+//!     └── all_specs.json       every phone of the brand, rewritten by `flush`
+//! ```
+//!
+//! Every file is written to a temporary name and renamed, so an interrupted run
+//! never leaves a truncated document. Opening an existing tree indexes the phone
+//! files already there, so a run with `skip_existing` resumes where it stopped.
+//! `with_compression(FileCompression::Gzip)` writes the brand files as `.json.gz`;
+//! either kind is read back.
+
+use crate::brand_scraper::{Brand, PhoneListItem};
+use crate::compression::{self, FileCompression};
+use crate::mongodb::{BrandDocument, PhoneDocument};
+use crate::query::PhoneQuery;
+use crate::store::{PhoneFilter, PhoneStore};
+use crate::upsert::UpsertPolicy;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const ALL_BRANDS: &str = "all_brands.json";
+const STATS: &str = "scraping_stats.json";
+const PHONE_LIST: &str = "phone_list.json";
+const ALL_SPECS: &str = "all_specs.json";
+
+/// One brand's line in `scraping_stats.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrandStats {
+    pub total_phones: usize,  // In the brand's listing
+    pub specs_fetched: usize, // Phone documents in its directory
+    pub directory: String,
+}
+
+/// Brand directories of JSON files; see the module docs for the layout
+pub struct FileSystemStore {
+    root: PathBuf,
+    compression: FileCompression, // Of the files in brand directories
+    upsert_policy: UpsertPolicy,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    phones: HashMap<String, String>,     // phone_id -> brand directory
+    brands: BTreeMap<String, Brand>,     // By slug, as in all_brands.json
+    stats: BTreeMap<String, BrandStats>, // By brand name
+    dirty: BTreeSet<String>,             // Brand directories whose all_specs.json is out of date
+    current: Option<String>,             // Directory of the last stored phone
+}
+
+impl FileSystemStore {
+    /// Open (or create) a tree, indexing the phones already stored in it
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(|e| format!("Cannot create {}: {}", root.display(), e))?;
+
+        let mut state = State::default();
+        if let Some(brands) = read_json::<Vec<Brand>>(&root.join(ALL_BRANDS))? {
+            state.brands = brands.into_iter().map(|brand| (brand.slug.clone(), brand)).collect();
+        }
+        state.stats = read_json(&root.join(STATS))?.unwrap_or_default();
+        for entry in std::fs::read_dir(&root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let directory = entry.file_name().to_string_lossy().into_owned();
+            for phone_id in phone_files(&entry.path())? {
+                state.phones.insert(phone_id, directory.clone());
+            }
+        }
+
+        Ok(Self {
+            root,
+            compression: FileCompression::None,
+            upsert_policy: UpsertPolicy::default(),
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Compress the files written to brand directories from now on
+    pub fn with_compression(mut self, compression: FileCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// What `upsert_phone` does to an already stored phone (default: overwrite it)
+    pub fn with_upsert_policy(mut self, policy: UpsertPolicy) -> Self {
+        self.upsert_policy = policy;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Phones stored so far
+    pub fn phone_count(&self) -> usize {
+        self.state.lock().unwrap().phones.len()
+    }
+
+    pub fn stats(&self) -> BTreeMap<String, BrandStats> {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    fn phone_path(&self, directory: &str, phone_id: &str) -> PathBuf {
+        self.root.join(directory).join(format!("{}.json", sanitize_filename(phone_id)))
+    }
+
+    fn load_all(&self) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        let index: Vec<(String, String)> = self.state.lock().unwrap().phones.iter().map(|(id, dir)| (id.clone(), dir.clone())).collect();
+        let mut phones = Vec::with_capacity(index.len());
+        for (phone_id, directory) in index {
+            if let Some(phone) = read_json::<PhoneDocument>(&self.phone_path(&directory, &phone_id))? {
+                phones.push(phone);
+            }
+        }
+        phones.sort_by(|a, b| (&a.brand, &a.name).cmp(&(&b.brand, &b.name)));
+        Ok(phones)
+    }
+
+    /// Rewrite `all_specs.json` of one brand directory and its line in the stats
+    fn flush_brand(&self, state: &mut State, directory: &str) -> Result<(), Box<dyn Error>> {
+        let mut phones = Vec::new();
+        for phone_id in phone_files(&self.root.join(directory))? {
+            if let Some(phone) = read_json::<PhoneDocument>(&self.phone_path(directory, &phone_id))? {
+                phones.push(phone);
+            }
+        }
+        phones.sort_by(|a, b| a.name.cmp(&b.name));
+        write_json(&self.root.join(directory).join(ALL_SPECS), &phones, self.compression)?;
+
+        if let Some(phone) = phones.first() {
+            let stats = state.stats.entry(phone.brand.clone()).or_default();
+            stats.specs_fetched = phones.len();
+            stats.directory = directory.to_string();
+        }
+        write_json(&self.root.join(STATS), &state.stats, FileCompression::None)?;
+        state.dirty.remove(directory);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PhoneStore for FileSystemStore {
+    async fn get_phone(&self, phone_id: &str) -> Result<Option<PhoneDocument>, Box<dyn Error>> {
+        let directory = self.state.lock().unwrap().phones.get(phone_id).cloned();
+        match directory {
+            Some(directory) => read_json(&self.phone_path(&directory, phone_id)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_phones(&self, filter: &PhoneFilter) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        Ok(self
+            .load_all()?
+            .into_iter()
+            .filter(|phone| filter.matches(phone))
+            .skip(filter.offset)
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn query_phones(&self, query: &PhoneQuery) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        let phones = self.load_all()?;
+        Ok(query.filter(&phones).into_iter().cloned().collect())
+    }
+
+    async fn get_brand(&self, name: &str) -> Result<Option<BrandDocument>, Box<dyn Error>> {
+        let state = self.state.lock().unwrap();
+        let brand = state.brands.values().find(|brand| brand.name.eq_ignore_ascii_case(name.trim()));
+        Ok(brand.map(|brand| BrandDocument::new(brand, None)))
+    }
+
+    async fn list_brands(&self) -> Result<Vec<BrandDocument>, Box<dyn Error>> {
+        let state = self.state.lock().unwrap();
+        let mut brands: Vec<BrandDocument> = state.brands.values().map(|brand| BrandDocument::new(brand, None)).collect();
+        brands.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(brands)
+    }
+
+    /// Write `<brand>/<phone_id>.json`; the previous brand's `all_specs.json` is brought up to date
+    /// as soon as phones of another brand arrive
+    async fn upsert_phone(&self, phone: PhoneDocument) -> Result<(), Box<dyn Error>> {
+        let phone = match self.upsert_policy {
+            UpsertPolicy::Overwrite => phone,
+            policy => match policy.resolve(self.get_phone(&phone.phone_id).await?.as_ref(), phone)? {
+                Some(phone) => phone,
+                None => return Ok(()),
+            },
+        };
+        let directory = sanitize_filename(&phone.brand);
+        std::fs::create_dir_all(self.root.join(&directory))?;
+        write_json(&self.phone_path(&directory, &phone.phone_id), &phone, self.compression)?;
+
+        let mut state = self.state.lock().unwrap();
+        state.phones.insert(phone.phone_id.clone(), directory.clone());
+        state.dirty.insert(directory.clone());
+        if let Some(previous) = state.current.replace(directory.clone()).filter(|previous| *previous != directory) {
+            if state.dirty.contains(&previous) {
+                self.flush_brand(&mut state, &previous)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn save_brand_listing(&self, brand: &Brand, phones: &[PhoneListItem]) -> Result<(), Box<dyn Error>> {
+        let directory = sanitize_filename(&brand.name);
+        std::fs::create_dir_all(self.root.join(&directory))?;
+        write_json(&self.root.join(&directory).join(PHONE_LIST), &phones, self.compression)?;
+
+        let mut state = self.state.lock().unwrap();
+        state.brands.insert(brand.slug.clone(), brand.clone());
+        let brands: Vec<&Brand> = state.brands.values().collect();
+        write_json(&self.root.join(ALL_BRANDS), &brands, FileCompression::None)?;
+
+        let stats = state.stats.entry(brand.name.clone()).or_default();
+        stats.total_phones = phones.len();
+        stats.directory = directory;
+        write_json(&self.root.join(STATS), &state.stats, FileCompression::None)?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state.lock().unwrap();
+        let dirty: Vec<String> = state.dirty.iter().cloned().collect();
+        for directory in dirty {
+            self.flush_brand(&mut state, &directory)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace characters that are not allowed in file names
+pub fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            _ => c,
+        })
+        .collect()
+}
+
+/// Phone IDs of the phone documents (`.json` or `.json.gz`) in a brand directory
+fn phone_files(directory: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut phone_ids = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        let name = name.strip_suffix(".gz").unwrap_or(&name);
+        if let Some(phone_id) = name.strip_suffix(".json").filter(|_| name != PHONE_LIST && name != ALL_SPECS) {
+            phone_ids.push(phone_id.to_string());
+        }
+    }
+    phone_ids.sort();
+    phone_ids.dedup();
+    Ok(phone_ids)
+}
+
+/// `path`, or its gzipped variant, parsed; None when neither exists
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, Box<dyn Error>> {
+    let Some(path) = [FileCompression::None, FileCompression::Gzip].iter().map(|c| c.apply_to(path)).find(|path| path.is_file()) else {
+        return Ok(None);
+    };
+    let contents = compression::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&contents).map_err(|e| format!("Invalid JSON in {}: {}", path.display(), e))?))
+}
+
+/// Write then rename so an interrupted run never leaves a truncated file behind; a copy
+/// with the other compression is removed so reads never pick up a stale one
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T, compression: FileCompression) -> Result<(), Box<dyn Error>> {
+    let target = compression.apply_to(path);
+    let mut partial = target.clone().into_os_string();
+    partial.push(".partial");
+    compression::write(Path::new(&partial), serde_json::to_string_pretty(value)?.as_bytes(), compression)?;
+    std::fs::rename(&partial, &target)?;
+
+    let other = match compression {
+        FileCompression::None => FileCompression::Gzip.apply_to(path),
+        FileCompression::Gzip => path.to_path_buf(),
+    };
+    if other.is_file() {
+        std::fs::remove_file(&other)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(phone_id: &str, name: &str, brand: &str) -> PhoneDocument {
+        PhoneDocument::fixture(phone_id).name(name).brand(brand).build()
+    }
+
+    #[tokio::test]
+    async fn test_file_system_store() {
+        let root = std::env::temp_dir().join(format!("gsmarena-file-store-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let store = FileSystemStore::open(&root).unwrap();
+
+        let apple = Brand { name: "Apple".to_string(), slug: "apple-phones-48".to_string(), device_count: 2 };
+        let listing = [phone("apple_iphone_15-12559", "iPhone 15", "Apple"), phone("apple_iphone_16-13317", "iPhone 16", "Apple")];
+        let items: Vec<PhoneListItem> = listing
+            .iter()
+            .map(|p| PhoneListItem { phone_id: p.phone_id.clone(), name: p.name.clone(), url: p.url.clone(), image_url: None, device_type: None })
+            .collect();
+        store.save_brand_listing(&apple, &items).await.unwrap();
+        store.upsert_phone(listing[1].clone()).await.unwrap();
+        store.upsert_phone(listing[0].clone()).await.unwrap();
+        assert!(!root.join("Apple/all_specs.json").exists()); // Still on Apple
+        store.upsert_phone(phone("at&t_radiant_max-11321", "Radiant Max", "AT&T / Cricket")).await.unwrap();
+
+        // Switching brands brought Apple's all_specs.json up to date
+        let specs: Vec<PhoneDocument> = read_json(&root.join("Apple/all_specs.json")).unwrap().unwrap();
+        assert_eq!(specs.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["iPhone 15", "iPhone 16"]);
+        assert!(root.join("AT&T _ Cricket/at&t_radiant_max-11321.json").exists());
+        assert!(!root.join("AT&T _ Cricket/all_specs.json").exists());
+        store.flush().await.unwrap();
+        assert!(root.join("AT&T _ Cricket/all_specs.json").exists());
+
+        let stats = store.stats();
+        assert_eq!(stats["Apple"], BrandStats { total_phones: 2, specs_fetched: 2, directory: "Apple".to_string() });
+        assert_eq!(store.list_brands().await.unwrap()[0].slug, "apple-phones-48");
+
+        // Reopening resumes from what is on disk
+        drop(store);
+        let store = FileSystemStore::open(&root).unwrap();
+        assert_eq!(store.phone_count(), 3);
+        assert_eq!(store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap().name, "iPhone 15");
+        assert!(store.get_phone("apple_iphone_14-12240").await.unwrap().is_none());
+        assert_eq!(store.get_brand("apple").await.unwrap().unwrap().device_count, 2);
+        let filter = PhoneFilter { name: Some("iphone".to_string()), ..Default::default() };
+        assert_eq!(store.find_phones(&filter).await.unwrap().len(), 2);
+        assert_eq!(store.query_phones(&PhoneQuery::new().brand("at&t / cricket")).await.unwrap().len(), 1);
+        assert_eq!(store.stats()["Apple"].specs_fetched, 2);
+        assert!(std::fs::read_dir(root.join("Apple")).unwrap().all(|e| !e.unwrap().file_name().to_string_lossy().ends_with(".partial")));
+
+        // Gzipped rewrites replace the plain files and read back the same
+        let store = FileSystemStore::open(&root).unwrap().with_compression(FileCompression::Gzip);
+        store.upsert_phone(phone("apple_iphone_15-12559", "iPhone 15", "Apple")).await.unwrap();
+        store.flush().await.unwrap();
+        assert!(root.join("Apple/apple_iphone_15-12559.json.gz").exists());
+        assert!(!root.join("Apple/apple_iphone_15-12559.json").exists());
+        assert!(root.join("Apple/all_specs.json.gz").exists() && !root.join("Apple/all_specs.json").exists());
+        let store = FileSystemStore::open(&root).unwrap();
+        assert_eq!(store.phone_count(), 3);
+        assert_eq!(store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap().name, "iPhone 15");
+        assert_eq!(store.find_phones(&filter).await.unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
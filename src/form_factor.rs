@@ -0,0 +1,172 @@
+//! Body shape: bar, book or flip foldable, slider, rugged, tablet or watch
+//!
+//! Builds on `device_type` for tablets and watches. Foldables are told apart by name
+//! ("Fold", "Flip", "Razr") or by which side shrinks between the unfolded and folded
+//! dimensions; a foldable display or a cover panel marks the rest.
+
+use crate::device_type::DeviceType;
+use crate::mongodb::PhoneDocument;
+use crate::normalize::category_values;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+static FLIP_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(flip\d*|razr)\b").unwrap());
+static FOLD_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bfold\d*\b").unwrap());
+static SLIDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bslid(e|er|ing)\b").unwrap());
+static RUGGED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(\brugged\b|MIL-STD)").unwrap());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormFactor {
+    #[default]
+    Bar,
+    FoldableBook, // Opens sideways into a tablet (Galaxy Z Fold)
+    FoldableFlip, // Folds in half top to bottom (Galaxy Z Flip, Razr)
+    Slider,
+    Rugged,
+    Tablet,
+    Watch,
+}
+
+impl FormFactor {
+    /// Device type first, then foldable and slider signs, then ruggedness
+    pub fn classify(phone: &PhoneDocument) -> Self {
+        match phone.device_type {
+            DeviceType::Watch => return FormFactor::Watch,
+            DeviceType::Tablet => return FormFactor::Tablet,
+            DeviceType::Phone => {}
+        }
+
+        let name = &phone.name;
+        let body = category_values(phone, "Body").join("; ");
+        let main_type = phone.display.as_ref().and_then(|d| d.display_type.as_deref()).unwrap_or_default();
+
+        if FLIP_NAME.is_match(name) {
+            return FormFactor::FoldableFlip;
+        }
+        if FOLD_NAME.is_match(name) {
+            return FormFactor::FoldableBook;
+        }
+        let foldable = main_type.contains("Foldable") || phone.displays.iter().any(|panel| panel.role == "cover");
+        if foldable || phone.normalized.dimensions.iter().any(|d| d.state.is_some()) {
+            if let Some(form_factor) = fold_direction(phone) {
+                return form_factor;
+            }
+            if foldable {
+                return FormFactor::FoldableBook;
+            }
+        }
+
+        let name_and_body = format!("{} {}", name, body);
+        if SLIDER.is_match(&name_and_body) {
+            return FormFactor::Slider;
+        }
+        if RUGGED.is_match(&name_and_body) {
+            return FormFactor::Rugged;
+        }
+        FormFactor::Bar
+    }
+}
+
+/// Book when folding halves the width, flip when it halves the height
+fn fold_direction(phone: &PhoneDocument) -> Option<FormFactor> {
+    let state = |wanted: &str| phone.normalized.dimensions.iter().find(|d| d.state.as_deref() == Some(wanted));
+    let (unfolded, folded) = (state("unfolded")?, state("folded")?);
+    if folded.height_mm < unfolded.height_mm * 0.75 {
+        Some(FormFactor::FoldableFlip)
+    } else if folded.width_mm < unfolded.width_mm * 0.75 {
+        Some(FormFactor::FoldableBook)
+    } else {
+        None
+    }
+}
+
+impl FromStr for FormFactor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "bar" => Ok(FormFactor::Bar),
+            "foldable_book" | "book" => Ok(FormFactor::FoldableBook),
+            "foldable_flip" | "flip" => Ok(FormFactor::FoldableFlip),
+            "slider" => Ok(FormFactor::Slider),
+            "rugged" => Ok(FormFactor::Rugged),
+            "tablet" => Ok(FormFactor::Tablet),
+            "watch" => Ok(FormFactor::Watch),
+            other => Err(format!(
+                "unknown form factor '{}' (bar, foldable_book, foldable_flip, slider, rugged, tablet, watch)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FormFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FormFactor::Bar => "bar",
+            FormFactor::FoldableBook => "foldable_book",
+            FormFactor::FoldableFlip => "foldable_flip",
+            FormFactor::Slider => "slider",
+            FormFactor::Rugged => "rugged",
+            FormFactor::Tablet => "tablet",
+            FormFactor::Watch => "watch",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(name: &str, body: &[(&str, &str)], display_type: &str) -> PhoneDocument {
+        PhoneDocument::fixture("test-1")
+            .name(name)
+            .category("Body", body)
+            .category("Display", &[("Type", display_type), ("Size", "6.7 inches")])
+            .build()
+    }
+
+    #[test]
+    fn test_form_factor() {
+        let bar = document("Galaxy S24", &[("Build", "Glass front, aluminum frame")], "Dynamic AMOLED 2X");
+        assert_eq!(bar.form_factor, FormFactor::Bar);
+
+        let book = document("Magic V3", &[("Dimensions", "Unfolded: 156.6 x 145.3 x 4.4 mmFolded: 156.6 x 74 x 9.2 mm")], "Foldable LTPO OLED");
+        assert_eq!(book.form_factor, FormFactor::FoldableBook);
+        let flip = document("Find N3 Flip", &[("Dimensions", "Unfolded: 166.4 x 75.8 x 7.8 mmFolded: 85.5 x 75.8 x 16.5 mm")], "Foldable LTPO AMOLED");
+        assert_eq!(flip.form_factor, FormFactor::FoldableFlip);
+        let by_size = document("Mix Z", &[("Dimensions", "Unfolded: 167.6 x 74 x 7.6 mmFolded: 85.9 x 74 x 15 mm")], "Foldable LTPO OLED");
+        assert_eq!(by_size.form_factor, FormFactor::FoldableFlip);
+
+        let rugged = document("S62 Pro", &[("\u{a0}", "IP68/IP69 dust/water resistant"), ("\u{a0}", "MIL-STD-810H compliant")], "IPS LCD");
+        assert_eq!(rugged.form_factor, FormFactor::Rugged);
+        let cover = serde_json::json!([
+            ["Type", "Foldable Dynamic AMOLED 2X, 120Hz"],
+            ["Size", "7.6 inches, 185.2 cm2"],
+            ["Resolution", "1856 x 2160 pixels (~374 ppi density)"],
+            ["\u{a0}", "Cover display:Dynamic AMOLED 2X, 120Hz, 1600 nits (peak)6.3 inches, 968 x 2376 pixels, 21:9 ratio (410 ppi density)"]
+        ]);
+        let mut cover_only = document("Nova", &[], "");
+        cover_only.specifications_raw["specification"][1]["category_spec"] = cover;
+        cover_only.reparse();
+        assert_eq!(cover_only.displays.len(), 2);
+        assert_eq!(cover_only.displays[0].size.as_deref(), Some("7.6 inches, 185.2 cm2"));
+        let panel = &cover_only.displays[1];
+        assert_eq!(panel.role, "cover");
+        assert_eq!(panel.display_type.as_deref(), Some("Dynamic AMOLED 2X, 120Hz, 1600 nits (peak)"));
+        assert_eq!(panel.size.as_deref(), Some("6.3 inches"));
+        assert_eq!(panel.resolution.as_deref(), Some("968 x 2376 pixels, 21:9 ratio (410 ppi density)"));
+        assert_eq!(cover_only.form_factor, FormFactor::FoldableBook);
+
+        let slider = document("Xperia Pro", &[("Build", "Slide-out QWERTY keyboard")], "TFT");
+        assert_eq!(slider.form_factor, FormFactor::Slider);
+
+        assert_eq!("foldable-flip".parse::<FormFactor>(), Ok(FormFactor::FoldableFlip));
+        assert_eq!(FormFactor::FoldableBook.to_string().parse::<FormFactor>(), Ok(FormFactor::FoldableBook));
+        assert!("candybar".parse::<FormFactor>().is_err());
+    }
+}
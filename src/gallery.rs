@@ -0,0 +1,168 @@
+use crate::site::{page_url, DEFAULT_BASE_URL};
+use reqwest::blocking;
+use crate::selectors::SelectorChain;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DevicePictures {
+    pub phone_id: String,
+    pub images: Vec<String>,
+    pub spin_frames: Vec<String>,
+}
+
+impl DevicePictures {
+    /// All picture URLs: official images first, followed by 360-view frames
+    pub fn all_urls(&self) -> Vec<String> {
+        self.images
+            .iter()
+            .chain(self.spin_frames.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Build the pictures page URL for a phone ID
+/// Example: "apple_iphone_15_pro_max-12548" -> ".../apple_iphone_15_pro_max-pictures-12548.php"
+pub fn pictures_page_url(phone_id: &str) -> Option<String> {
+    pictures_page_url_from(DEFAULT_BASE_URL, phone_id)
+}
+
+/// Pictures page URL on a mirror or test server
+pub fn pictures_page_url_from(base_url: &str, phone_id: &str) -> Option<String> {
+    let (slug, numeric_id) = phone_id.rsplit_once('-')?;
+    if slug.is_empty() || numeric_id.is_empty() || !numeric_id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(page_url(base_url, &format!("{}-pictures-{}.php", slug, numeric_id)))
+}
+
+/// Fetch all official pictures (and 360-view frames when present) for a phone
+pub fn fetch_device_pictures(phone_id: &str) -> Result<DevicePictures, Box<dyn Error>> {
+    fetch_device_pictures_from(DEFAULT_BASE_URL, phone_id)
+}
+
+/// Fetch a phone's pictures from a mirror or test server
+pub fn fetch_device_pictures_from(base_url: &str, phone_id: &str) -> Result<DevicePictures, Box<dyn Error>> {
+    let url = pictures_page_url_from(base_url, phone_id)
+        .ok_or_else(|| format!("Invalid phone ID: {}", phone_id))?;
+
+    let response = blocking::get(&url)?;
+    if !response.status().is_success() {
+        return Err(format!("Pictures page returned status: {}", response.status()).into());
+    }
+
+    let body = response.text()?;
+    let mut pictures = parse_pictures_page_from(base_url, &body);
+    pictures.phone_id = phone_id.to_string();
+
+    Ok(pictures)
+}
+
+/// Parse the HTML of a pictures page into image and 360-view frame URLs
+pub fn parse_pictures_page(html: &str) -> DevicePictures {
+    parse_pictures_page_from(DEFAULT_BASE_URL, html)
+}
+
+/// Parse a pictures page served by a mirror, resolving relative URLs against `base_url`
+pub fn parse_pictures_page_from(base_url: &str, html: &str) -> DevicePictures {
+    let document = Html::parse_document(html);
+
+    let mut pictures = DevicePictures::default();
+
+    for element in SelectorChain::get("gallery_images").select_in(&document) {
+        if let Some(src) = image_source(element.value()) {
+            let url = absolute_url(base_url, src);
+            if !pictures.images.contains(&url) {
+                pictures.images.push(url);
+            }
+        }
+    }
+
+    for element in SelectorChain::get("spin_frames").select_in(&document) {
+        let src = element
+            .value()
+            .attr("data-spin-frame")
+            .or_else(|| image_source(element.value()));
+
+        if let Some(src) = src {
+            let url = absolute_url(base_url, src);
+            if !pictures.spin_frames.contains(&url) && !pictures.images.contains(&url) {
+                pictures.spin_frames.push(url);
+            }
+        }
+    }
+
+    pictures
+}
+
+/// Lazy-loaded images keep the real URL in `data-src`
+fn image_source(element: &scraper::node::Element) -> Option<&str> {
+    element
+        .attr("data-src")
+        .or_else(|| element.attr("src"))
+        .filter(|src| !src.is_empty() && !src.starts_with("data:"))
+}
+
+fn absolute_url(base_url: &str, src: &str) -> String {
+    if src.starts_with("http") {
+        src.to_string()
+    } else if let Some(rest) = src.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else {
+        page_url(base_url, src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pictures_page_url() {
+        assert_eq!(
+            pictures_page_url("apple_iphone_15_pro_max-12548").as_deref(),
+            Some("https://www.gsmarena.com/apple_iphone_15_pro_max-pictures-12548.php")
+        );
+        assert_eq!(pictures_page_url("not_a_phone_id"), None);
+    }
+
+    #[test]
+    fn test_parse_pictures_page() {
+        let html = r#"
+            <div id="pictures-list">
+                <img src="https://fdn2.gsmarena.com/vv/pics/apple/apple-iphone-15-1.jpg">
+                <img data-src="https://fdn2.gsmarena.com/vv/pics/apple/apple-iphone-15-2.jpg" src="data:image/gif;base64,AAAA">
+                <img src="https://fdn2.gsmarena.com/vv/pics/apple/apple-iphone-15-1.jpg">
+            </div>
+            <div id="spin360">
+                <img src="//fdn2.gsmarena.com/vv/spin/apple-iphone-15-01.jpg">
+            </div>
+        "#;
+
+        let pictures = parse_pictures_page(html);
+        assert_eq!(pictures.images.len(), 2);
+        assert_eq!(pictures.spin_frames, vec!["https://fdn2.gsmarena.com/vv/spin/apple-iphone-15-01.jpg"]);
+        assert_eq!(pictures.all_urls().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_pictures_page_from_mirror() {
+        let html = r#"
+            <div id="pictures-list">
+                <img src="vv/pics/apple/apple-iphone-15-1.jpg">
+                <img src="https://fdn2.gsmarena.com/vv/pics/apple/apple-iphone-15-2.jpg">
+            </div>
+        "#;
+
+        let pictures = parse_pictures_page_from("http://127.0.0.1:8080", html);
+        assert_eq!(
+            pictures.images,
+            vec![
+                "http://127.0.0.1:8080/vv/pics/apple/apple-iphone-15-1.jpg",
+                "https://fdn2.gsmarena.com/vv/pics/apple/apple-iphone-15-2.jpg",
+            ]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! One request rate for every worker sharing a MongoDB database
+//!
+//! `RateLimiter` and the configured delays only space requests within one process, so
+//! shards running side by side multiply the load on GSMArena. `GlobalRateLimiter` counts
+//! requests per time window in a collection every worker increments; a worker that finds
+//! the window full waits for the next one. Counters expire through a TTL index.
+
+use crate::config::Config;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, Database, IndexModel};
+use std::error::Error;
+use std::time::Duration;
+use tracing::debug;
+
+/// Caps requests to a site across processes (`rate_limit.global_max_requests`)
+#[derive(Clone)]
+pub struct GlobalRateLimiter {
+    collection: Collection<Document>,
+    key: String, // Counters are per site, so a mirror has its own
+    max_requests: u64,
+    window: Duration,
+    runtime: tokio::runtime::Handle, // `acquire_blocking` also runs on threads outside the runtime
+}
+
+impl GlobalRateLimiter {
+    /// `None` when no global cap is configured
+    pub async fn from_config(config: &Config, database: &Database) -> Result<Option<Self>, Box<dyn Error>> {
+        let rate_limit = &config.rate_limit;
+        let Some(max_requests) = rate_limit.global_max_requests else {
+            return Ok(None);
+        };
+        let limiter = Self::new(
+            database.collection(&rate_limit.global_collection),
+            config.site.base_url.trim_end_matches('/'),
+            max_requests,
+            Duration::from_secs(rate_limit.global_window_secs.max(1)),
+        );
+        limiter.create_index().await?;
+        Ok(Some(limiter))
+    }
+
+    /// Must be called inside the Tokio runtime the MongoDB client runs on
+    fn new(collection: Collection<Document>, key: &str, max_requests: u64, window: Duration) -> Self {
+        Self { collection, key: key.to_string(), max_requests, window, runtime: tokio::runtime::Handle::current() }
+    }
+
+    pub fn max_requests(&self) -> u64 {
+        self.max_requests
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Counters are deleted once their window is over
+    async fn create_index(&self) -> Result<(), Box<dyn Error>> {
+        let ttl = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(IndexOptions::builder().expire_after(Duration::ZERO).build())
+            .build();
+        self.collection.create_index(ttl, None).await?;
+        Ok(())
+    }
+
+    /// Wait until the current window has room for one more request, and take it
+    pub async fn acquire(&self) -> Result<(), Box<dyn Error>> {
+        loop {
+            let now = chrono::Utc::now().timestamp_millis();
+            let (start, end) = window_bounds(now, self.window);
+            if self.increment(start, end).await? <= self.max_requests {
+                return Ok(());
+            }
+            debug!(wait_ms = end - now, "Global request cap reached, waiting for the next window");
+            tokio::time::sleep(Duration::from_millis((end - now).max(0) as u64)).await;
+        }
+    }
+
+    /// `acquire` for the blocking fetch path: inside `block_in_place`, or on a plain thread
+    /// such as the workers of `fetch_brand_listings_concurrently`
+    pub fn acquire_blocking(&self) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.acquire())
+    }
+
+    /// Count one request in the window starting at `start`; returns the window's count
+    async fn increment(&self, start: i64, end: i64) -> Result<u64, Box<dyn Error>> {
+        let id = format!("{}:{}", self.key, start);
+        let update = doc! {
+            "$inc": { "count": 1_i64 },
+            "$setOnInsert": { "expires_at": mongodb::bson::DateTime::from_millis(end) },
+        };
+        let options = FindOneAndUpdateOptions::builder().upsert(true).return_document(ReturnDocument::After).build();
+
+        // Two workers creating the same counter at once: one upsert fails on the duplicate _id
+        let mut attempt = 0;
+        let counter = loop {
+            attempt += 1;
+            match self.collection.find_one_and_update(doc! { "_id": &id }, update.clone(), options.clone()).await {
+                Ok(counter) => break counter,
+                Err(_) if attempt < 2 => continue,
+                Err(e) => return Err(e.into()),
+            }
+        };
+        Ok(counter.and_then(|counter| counter.get_i64("count").ok()).unwrap_or(1) as u64)
+    }
+}
+
+/// Start and end (Unix milliseconds) of the window containing `now`
+fn window_bounds(now: i64, window: Duration) -> (i64, i64) {
+    let window = window.as_millis() as i64;
+    let start = now - now.rem_euclid(window);
+    (start, start + window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_bounds() {
+        let window = Duration::from_secs(10);
+        assert_eq!(window_bounds(1_760_000_004_321, window), (1_760_000_000_000, 1_760_000_010_000));
+        assert_eq!(window_bounds(1_760_000_010_000, window), (1_760_000_010_000, 1_760_000_020_000));
+        // Every worker maps the same instant to the same counter
+        let (start, end) = window_bounds(1_760_000_009_999, window);
+        assert_eq!((start, end - start), (1_760_000_000_000, 10_000));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_acquire_blocking_outside_runtime() {
+        // Nothing listens here: acquiring must fail with an error, not panic for lack of a reactor
+        let client = mongodb::Client::with_uri_str("mongodb://127.0.0.1:1/?serverSelectionTimeoutMS=200").await.unwrap();
+        let limiter = GlobalRateLimiter::new(client.database("test").collection("rate_limits"), "http://mock", 10, Duration::from_secs(10));
+
+        let failed = tokio::task::block_in_place(|| std::thread::spawn(move || limiter.acquire_blocking().is_err()).join());
+        assert!(failed.expect("acquire_blocking panicked on a plain thread"));
+    }
+}
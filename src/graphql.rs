@@ -0,0 +1,298 @@
+//! GraphQL schema over a `PhoneStore` (feature `graphql`)
+
+use crate::brand_scraper::YearCount;
+use crate::mongodb::{
+    BatterySpecs, BodySpecs, BrandDocument, CameraSpecs, CommsSpecs, DisplayPanel, DisplaySpecs, FeaturesSpecs, LaunchSpecs,
+    MemorySpecs, MiscSpecs, NetworkSpecs, PhoneDocument, PlatformSpecs, SoundSpecs, WatchSpecs,
+};
+use crate::store::{PhoneFilter, PhoneStore};
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema};
+use axum::extract::State;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::info;
+
+pub type PhoneSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Largest page a single `phones` query returns
+const MAX_LIMIT: usize = 500;
+
+/// Build the schema; resolvers read from `store`
+pub fn build_schema(store: Arc<dyn PhoneStore>) -> PhoneSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription).data(store).finish()
+}
+
+fn store<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a Arc<dyn PhoneStore>> {
+    ctx.data::<Arc<dyn PhoneStore>>()
+}
+
+/// Storage errors are not `Send`, so they reach clients as messages
+fn gql_error(error: Box<dyn Error>) -> async_graphql::Error {
+    async_graphql::Error::new(error.to_string())
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A phone by its GSMArena ID, e.g. "apple_iphone_15-12559"
+    async fn phone(&self, ctx: &Context<'_>, phone_id: String) -> async_graphql::Result<Option<Phone>> {
+        let phone = store(ctx)?.get_phone(&phone_id).await.map_err(gql_error)?;
+        Ok(phone.map(Phone))
+    }
+
+    /// Phones matching every given filter, ordered by brand and name
+    #[allow(clippy::too_many_arguments)]
+    async fn phones(
+        &self,
+        ctx: &Context<'_>,
+        brand: Option<String>,
+        name: Option<String>,
+        chipset: Option<String>,
+        min_battery: Option<u32>,
+        announced_year: Option<i32>,
+        #[graphql(default = 50)] limit: usize,
+        #[graphql(default = 0)] offset: usize,
+    ) -> async_graphql::Result<Vec<Phone>> {
+        let filter = PhoneFilter {
+            brand,
+            name,
+            chipset,
+            announced_year,
+            min_battery_mah: min_battery,
+            limit: Some(limit.min(MAX_LIMIT)),
+            offset,
+        };
+
+        let phones = store(ctx)?.find_phones(&filter).await.map_err(gql_error)?;
+        Ok(phones.into_iter().map(Phone).collect())
+    }
+
+    /// Phones whose Misc > Models lists this number, e.g. "SM-S918B"
+    async fn phones_by_model_number(&self, ctx: &Context<'_>, model: String) -> async_graphql::Result<Vec<Phone>> {
+        let phones = store(ctx)?.find_by_model_number(&model).await.map_err(gql_error)?;
+        Ok(phones.into_iter().map(Phone).collect())
+    }
+
+    /// A brand by name (case-insensitive)
+    async fn brand(&self, ctx: &Context<'_>, name: String) -> async_graphql::Result<Option<Brand>> {
+        let brand = store(ctx)?.get_brand(&name).await.map_err(gql_error)?;
+        Ok(brand.map(Brand))
+    }
+
+    async fn brands(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Brand>> {
+        let brands = store(ctx)?.list_brands().await.map_err(gql_error)?;
+        Ok(brands.into_iter().map(Brand).collect())
+    }
+}
+
+pub struct Phone(PhoneDocument);
+
+#[Object]
+impl Phone {
+    async fn phone_id(&self) -> &str {
+        &self.0.phone_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Brand name; select `brandInfo` for the brand's details
+    async fn brand(&self) -> &str {
+        &self.0.brand
+    }
+
+    async fn brand_info(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Brand>> {
+        let brand = store(ctx)?.get_brand(&self.0.brand).await.map_err(gql_error)?;
+        Ok(brand.map(Brand))
+    }
+
+    async fn url(&self) -> &str {
+        &self.0.url
+    }
+
+    async fn image_url(&self) -> Option<&str> {
+        self.0.image_url.as_deref()
+    }
+
+    async fn gallery_urls(&self) -> &[String] {
+        &self.0.gallery_urls
+    }
+
+    async fn battery_mah(&self) -> Option<u32> {
+        self.0.battery_capacity_mah()
+    }
+
+    async fn announced_year(&self) -> Option<i32> {
+        self.0.announced_year()
+    }
+
+    async fn network(&self) -> Option<&NetworkSpecs> {
+        self.0.network.as_ref()
+    }
+
+    async fn launch(&self) -> Option<&LaunchSpecs> {
+        self.0.launch.as_ref()
+    }
+
+    async fn body(&self) -> Option<&BodySpecs> {
+        self.0.body.as_ref()
+    }
+
+    async fn display(&self) -> Option<&DisplaySpecs> {
+        self.0.display.as_ref()
+    }
+
+    /// Every panel, main first, then cover or secondary displays
+    async fn displays(&self) -> &[DisplayPanel] {
+        &self.0.displays
+    }
+
+    async fn platform(&self) -> Option<&PlatformSpecs> {
+        self.0.platform.as_ref()
+    }
+
+    async fn memory(&self) -> Option<&MemorySpecs> {
+        self.0.memory.as_ref()
+    }
+
+    async fn main_camera(&self) -> Option<&CameraSpecs> {
+        self.0.main_camera.as_ref()
+    }
+
+    async fn selfie_camera(&self) -> Option<&CameraSpecs> {
+        self.0.selfie_camera.as_ref()
+    }
+
+    async fn sound(&self) -> Option<&SoundSpecs> {
+        self.0.sound.as_ref()
+    }
+
+    async fn comms(&self) -> Option<&CommsSpecs> {
+        self.0.comms.as_ref()
+    }
+
+    async fn features(&self) -> Option<&FeaturesSpecs> {
+        self.0.features.as_ref()
+    }
+
+    async fn battery(&self) -> Option<&BatterySpecs> {
+        self.0.battery.as_ref()
+    }
+
+    async fn misc(&self) -> Option<&MiscSpecs> {
+        self.0.misc.as_ref()
+    }
+
+    /// Case size, strap, water resistance and compatibility; watches only
+    async fn watch(&self) -> Option<&WatchSpecs> {
+        self.0.watch.as_ref()
+    }
+
+    /// "phone", "tablet" or "watch"
+    async fn device_type(&self) -> String {
+        self.0.device_type.to_string()
+    }
+
+    /// "bar", "foldable_book", "foldable_flip", "slider", "rugged", "tablet" or "watch"
+    async fn form_factor(&self) -> String {
+        self.0.form_factor.to_string()
+    }
+
+    async fn completeness_score(&self) -> Option<f64> {
+        self.0.completeness_score
+    }
+
+    async fn variant_group_id(&self) -> Option<&str> {
+        self.0.variant_group_id.as_deref()
+    }
+
+    /// RFC 3339 timestamp of the last scrape
+    async fn scraped_at(&self) -> String {
+        self.0.scraped_at.to_rfc3339()
+    }
+}
+
+pub struct Brand(BrandDocument);
+
+#[Object]
+impl Brand {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn slug(&self) -> &str {
+        &self.0.slug
+    }
+
+    async fn device_count(&self) -> u32 {
+        self.0.device_count
+    }
+
+    async fn logo_url(&self) -> Option<&str> {
+        self.0.logo_url.as_deref()
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn website(&self) -> Option<&str> {
+        self.0.website.as_deref()
+    }
+
+    /// Devices announced per year, when the breakdown has been fetched
+    async fn devices_by_year(&self) -> &[YearCount] {
+        &self.0.devices_by_year
+    }
+
+    /// The brand's phones (filters as on `Query.phones`)
+    async fn phones(
+        &self,
+        ctx: &Context<'_>,
+        chipset: Option<String>,
+        min_battery: Option<u32>,
+        announced_year: Option<i32>,
+        #[graphql(default = 50)] limit: usize,
+        #[graphql(default = 0)] offset: usize,
+    ) -> async_graphql::Result<Vec<Phone>> {
+        let filter = PhoneFilter {
+            brand: Some(self.0.name.clone()),
+            chipset,
+            announced_year,
+            min_battery_mah: min_battery,
+            limit: Some(limit.min(MAX_LIMIT)),
+            offset,
+            ..Default::default()
+        };
+
+        let phones = store(ctx)?.find_phones(&filter).await.map_err(gql_error)?;
+        Ok(phones.into_iter().map(Phone).collect())
+    }
+}
+
+async fn graphql_handler(State(schema): State<PhoneSchema>, Json(request): Json<async_graphql::Request>) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+/// Serve the schema at `/graphql` (POST queries, GET for the GraphiQL explorer)
+pub async fn serve(schema: PhoneSchema, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+    let app = Router::new()
+        .route("/graphql", get(graphiql).post(graphql_handler))
+        .with_state(schema);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(address = %addr, "✓ GraphQL endpoint listening on /graphql");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
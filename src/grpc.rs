@@ -0,0 +1,175 @@
+//! gRPC phone lookups over a `PhoneStore` (feature `grpc`, see `proto/phones.proto`)
+
+use crate::mongodb::PhoneDocument;
+use crate::store::{PhoneFilter, PhoneStore};
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+/// Generated messages, server and client
+pub mod proto {
+    tonic::include_proto!("gsmarena.phones.v1");
+}
+
+pub use proto::phones_client::PhonesClient;
+pub use proto::phones_server::PhonesServer;
+
+/// Page size when a request leaves `limit` at 0, and the largest allowed
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 500;
+
+/// `Phones` service implementation
+pub struct PhonesService {
+    store: Arc<dyn PhoneStore>,
+}
+
+impl PhonesService {
+    pub fn new(store: Arc<dyn PhoneStore>) -> Self {
+        Self { store }
+    }
+
+    async fn find(&self, filter: PhoneFilter) -> Result<Response<proto::PhoneList>, Status> {
+        let phones = self.store.find_phones(&filter).await.map_err(internal)?;
+        Ok(Response::new(proto::PhoneList { phones: phones.iter().map(proto::Phone::from).collect() }))
+    }
+}
+
+fn internal(error: Box<dyn Error>) -> Status {
+    Status::internal(error.to_string())
+}
+
+fn page_size(limit: u32) -> Option<usize> {
+    let limit = if limit == 0 { DEFAULT_LIMIT } else { limit.min(MAX_LIMIT) };
+    Some(limit as usize)
+}
+
+#[tonic::async_trait]
+impl proto::phones_server::Phones for PhonesService {
+    async fn get_phone(&self, request: Request<proto::GetPhoneRequest>) -> Result<Response<proto::Phone>, Status> {
+        let phone_id = request.into_inner().phone_id;
+        match self.store.get_phone(&phone_id).await.map_err(internal)? {
+            Some(phone) => Ok(Response::new(proto::Phone::from(&phone))),
+            None => Err(Status::not_found(format!("No phone with ID {}", phone_id))),
+        }
+    }
+
+    async fn list_phones_by_brand(
+        &self,
+        request: Request<proto::ListPhonesByBrandRequest>,
+    ) -> Result<Response<proto::PhoneList>, Status> {
+        let request = request.into_inner();
+        if request.brand.is_empty() {
+            return Err(Status::invalid_argument("brand is required"));
+        }
+
+        self.find(PhoneFilter {
+            brand: Some(request.brand),
+            limit: page_size(request.limit),
+            offset: request.offset as usize,
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn search_phones(
+        &self,
+        request: Request<proto::SearchPhonesRequest>,
+    ) -> Result<Response<proto::PhoneList>, Status> {
+        let request = request.into_inner();
+
+        self.find(PhoneFilter {
+            brand: request.brand,
+            name: request.name,
+            chipset: request.chipset,
+            announced_year: request.announced_year,
+            min_battery_mah: request.min_battery_mah,
+            limit: page_size(request.limit),
+            offset: request.offset as usize,
+        })
+        .await
+    }
+}
+
+impl From<&PhoneDocument> for proto::Phone {
+    fn from(phone: &PhoneDocument) -> Self {
+        let categories = [
+            ("network", serde_json::to_value(&phone.network)),
+            ("launch", serde_json::to_value(&phone.launch)),
+            ("body", serde_json::to_value(&phone.body)),
+            ("display", serde_json::to_value(&phone.display)),
+            ("platform", serde_json::to_value(&phone.platform)),
+            ("memory", serde_json::to_value(&phone.memory)),
+            ("main_camera", serde_json::to_value(&phone.main_camera)),
+            ("selfie_camera", serde_json::to_value(&phone.selfie_camera)),
+            ("sound", serde_json::to_value(&phone.sound)),
+            ("comms", serde_json::to_value(&phone.comms)),
+            ("features", serde_json::to_value(&phone.features)),
+            ("battery", serde_json::to_value(&phone.battery)),
+            ("misc", serde_json::to_value(&phone.misc)),
+            ("watch", serde_json::to_value(&phone.watch)),
+        ];
+
+        let specs = categories
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let fields: HashMap<String, String> = value
+                    .ok()?
+                    .as_object()?
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                    .collect();
+                (!fields.is_empty()).then(|| proto::SpecCategory { name: name.to_string(), fields })
+            })
+            .collect();
+
+        Self {
+            phone_id: phone.phone_id.clone(),
+            name: phone.name.clone(),
+            brand: phone.brand.clone(),
+            url: phone.url.clone(),
+            image_url: phone.image_url.clone(),
+            gallery_urls: phone.gallery_urls.clone(),
+            battery_mah: phone.battery_capacity_mah(),
+            announced_year: phone.announced_year(),
+            completeness_score: phone.completeness_score,
+            variant_group_id: phone.variant_group_id.clone(),
+            specs,
+            scraped_at: phone.scraped_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Serve the `Phones` service on `addr` until the process exits
+pub async fn serve(store: Arc<dyn PhoneStore>, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+    info!(address = %addr, "✓ gRPC Phones service listening");
+    tonic::transport::Server::builder()
+        .add_service(PhonesServer::new(PhonesService::new(store)))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phone_to_proto() {
+        let iphone = PhoneDocument::fixture("apple_iphone_15-12559")
+            .name("iPhone 15")
+            .brand("Apple")
+            .category("Platform", &[("Chipset", "Apple A16 Bionic (4 nm)")])
+            .category("Battery", &[("Type", "Li-Ion 3349 mAh, non-removable")])
+            .build();
+
+        let phone = proto::Phone::from(&iphone);
+        assert_eq!(phone.battery_mah, Some(3349));
+
+        let platform = phone.specs.iter().find(|category| category.name == "platform").unwrap();
+        assert_eq!(platform.fields["chipset"], "Apple A16 Bionic (4 nm)");
+        assert!(phone.specs.iter().all(|category| !category.fields.is_empty()));
+    }
+}
@@ -0,0 +1,102 @@
+//! HTTP outcomes per provider: status codes, bytes and latency of every request a run makes
+
+use crate::config::ScrapeProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// What one provider's requests came back with, as stored in the run report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderHttpStats {
+    pub requests: u64,
+    pub statuses: BTreeMap<u16, u64>, // Status code -> responses
+    pub errors: u64,                  // Requests that got no response (timeouts, refused connections)
+    pub bytes: u64,                   // Page bodies downloaded
+    pub total_latency_ms: u64,        // Request sent to body read, over all requests
+}
+
+impl ProviderHttpStats {
+    pub fn average_latency_ms(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.total_latency_ms as f64 / self.requests as f64
+    }
+
+    /// Share of responses that were 429 Too Many Requests
+    pub fn rate_limited_share(&self) -> f64 {
+        let responses: u64 = self.statuses.values().sum();
+        if responses == 0 {
+            return 0.0;
+        }
+        self.statuses.get(&429).copied().unwrap_or(0) as f64 / responses as f64
+    }
+}
+
+/// Live counters, shared by every fetcher of a run (clones count into the same totals)
+#[derive(Debug, Clone, Default)]
+pub struct HttpStats {
+    providers: Arc<Mutex<BTreeMap<String, ProviderHttpStats>>>,
+}
+
+impl HttpStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A response with `status` whose body of `bytes` took `latency` to arrive
+    pub fn record_response(&self, provider: ScrapeProvider, status: u16, bytes: usize, latency: Duration) {
+        self.update(provider, latency, |stats| {
+            *stats.statuses.entry(status).or_default() += 1;
+            stats.bytes += bytes as u64;
+        });
+    }
+
+    /// A request that failed before any response arrived
+    pub fn record_error(&self, provider: ScrapeProvider, latency: Duration) {
+        self.update(provider, latency, |stats| stats.errors += 1);
+    }
+
+    fn update(&self, provider: ScrapeProvider, latency: Duration, apply: impl FnOnce(&mut ProviderHttpStats)) {
+        let mut providers = self.providers.lock().unwrap();
+        let stats = providers.entry(provider.to_string()).or_default();
+        stats.requests += 1;
+        stats.total_latency_ms += latency.as_millis() as u64;
+        apply(stats);
+    }
+
+    /// Provider name -> its stats so far
+    pub fn snapshot(&self) -> BTreeMap<String, ProviderHttpStats> {
+        self.providers.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_stats() {
+        let stats = HttpStats::new();
+        let shared = stats.clone();
+        stats.record_response(ScrapeProvider::Direct, 200, 50_000, Duration::from_millis(300));
+        shared.record_response(ScrapeProvider::Direct, 429, 0, Duration::from_millis(100));
+        stats.record_response(ScrapeProvider::Direct, 200, 30_000, Duration::from_millis(200));
+        stats.record_error(ScrapeProvider::Proxy, Duration::from_secs(10));
+
+        let snapshot = stats.snapshot();
+        let direct = &snapshot["direct"];
+        assert_eq!(direct.requests, 3);
+        assert_eq!(direct.statuses, BTreeMap::from([(200, 2), (429, 1)]));
+        assert_eq!(direct.bytes, 80_000);
+        assert_eq!(direct.average_latency_ms(), 200.0);
+        assert!((direct.rate_limited_share() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!((snapshot["proxy"].errors, snapshot["proxy"].rate_limited_share()), (1, 0.0));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains(r#""statuses":{"200":2,"429":1}"#), "{}", json);
+        let parsed: BTreeMap<String, ProviderHttpStats> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+    }
+}
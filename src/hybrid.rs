@@ -0,0 +1,199 @@
+//! Which method the hybrid provider uses for each phone, and how each one did
+//!
+//! Phones go out in batches of `scraping.batch_size`, each batch either rate-limited
+//! (direct) or through ScrapingBee. Strict alternation spends credits on half the
+//! phones even while direct requests succeed, so with `scraping.hybrid_auto_tune` the
+//! share of ScrapingBee batches moves after every batch: down while direct fetches
+//! succeed, up while they fail, and down again when ScrapingBee fails too. The share
+//! stays within 10-90%, so both methods keep being tried.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Lowest and highest share of batches sent through ScrapingBee when auto-tuning
+const MIN_SHARE: f64 = 0.1;
+const MAX_SHARE: f64 = 0.9;
+/// Change of the share after a batch
+const STEP: f64 = 0.1;
+/// Success rate from which direct requests are considered to be working
+const HEALTHY: f64 = 0.9;
+/// Success rate below which a method is considered to be failing
+const FAILING: f64 = 0.5;
+
+/// Phone fetches through one method
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MethodStats {
+    pub attempts: u64,
+    pub successes: u64,
+    pub failures: u64, // Errors and pages without specifications (block pages)
+    pub total_latency_ms: u64,
+}
+
+impl MethodStats {
+    pub fn average_latency_ms(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.total_latency_ms as f64 / self.attempts as f64
+    }
+
+    /// None before the first attempt
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.successes as f64 / self.attempts as f64)
+    }
+
+    fn record(&mut self, success: bool, latency: Duration) {
+        self.attempts += 1;
+        if success {
+            self.successes += 1;
+        } else {
+            self.failures += 1;
+        }
+        self.total_latency_ms += latency.as_millis() as u64;
+    }
+}
+
+/// Both methods of a hybrid run, as stored in the run report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HybridStats {
+    pub direct: MethodStats,
+    pub scrapingbee: MethodStats,
+    pub scrapingbee_share: f64, // Share of batches going through ScrapingBee at the end of the run
+}
+
+/// Picks the method of each phone in hybrid mode
+#[derive(Debug, Clone)]
+pub struct HybridSchedule {
+    batch_size: usize,
+    counter: usize,
+    use_scrapingbee: bool, // Method of the current batch
+    scrapingbee_available: bool,
+    auto_tune: bool,
+    share: f64,  // Share of batches through ScrapingBee
+    credit: f64, // Accumulated share; a batch goes through ScrapingBee each time it reaches 1
+    batch: HybridStats, // Outcomes of the current batch
+    totals: HybridStats,
+}
+
+impl HybridSchedule {
+    /// Starts rate-limited, with half the batches through ScrapingBee
+    pub fn new(batch_size: usize, auto_tune: bool) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            counter: 0,
+            use_scrapingbee: false,
+            scrapingbee_available: true,
+            auto_tune,
+            share: 0.5,
+            credit: 0.5,
+            batch: HybridStats::default(),
+            totals: HybridStats::default(),
+        }
+    }
+
+    /// Whether the next phone should go through ScrapingBee
+    pub fn next_uses_scrapingbee(&mut self) -> bool {
+        if self.counter >= self.batch_size {
+            self.end_batch();
+            self.counter = 0;
+        }
+        self.counter += 1;
+
+        self.use_scrapingbee && self.scrapingbee_available
+    }
+
+    /// Every ScrapingBee key is exhausted: rate-limited only from now on
+    pub fn disable_scrapingbee(&mut self) {
+        self.scrapingbee_available = false;
+    }
+
+    /// Outcome of one phone fetch; a block page counts as a failure
+    pub fn record(&mut self, via_scrapingbee: bool, success: bool, latency: Duration) {
+        for stats in [&mut self.batch, &mut self.totals] {
+            let method = if via_scrapingbee { &mut stats.scrapingbee } else { &mut stats.direct };
+            method.record(success, latency);
+        }
+    }
+
+    pub fn scrapingbee_share(&self) -> f64 {
+        self.share
+    }
+
+    /// Totals of the run so far
+    pub fn stats(&self) -> HybridStats {
+        HybridStats { scrapingbee_share: self.share, ..self.totals.clone() }
+    }
+
+    /// Adjust the share from the finished batch, then pick the next batch's method
+    fn end_batch(&mut self) {
+        if !self.auto_tune {
+            self.use_scrapingbee = !self.use_scrapingbee;
+            return;
+        }
+
+        let batch = std::mem::take(&mut self.batch);
+        match batch.direct.success_rate() {
+            Some(rate) if rate >= HEALTHY => self.share -= STEP,
+            Some(rate) if rate < FAILING => self.share += STEP,
+            _ => {}
+        }
+        if batch.scrapingbee.success_rate().is_some_and(|rate| rate < FAILING) {
+            self.share -= STEP;
+        }
+        self.share = self.share.clamp(MIN_SHARE, MAX_SHARE);
+
+        self.credit += self.share;
+        self.use_scrapingbee = self.credit >= 1.0;
+        if self.use_scrapingbee {
+            self.credit -= 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Methods of `batches` batches of 2 phones, each fetch succeeding as `succeeds` says
+    fn run(schedule: &mut HybridSchedule, batches: usize, succeeds: impl Fn(bool) -> bool) -> Vec<bool> {
+        let mut methods = Vec::new();
+        for _ in 0..batches * 2 {
+            let via_scrapingbee = schedule.next_uses_scrapingbee();
+            schedule.record(via_scrapingbee, succeeds(via_scrapingbee), Duration::from_millis(if via_scrapingbee { 3000 } else { 500 }));
+            methods.push(via_scrapingbee);
+        }
+        methods.chunks(2).map(|batch| batch[0]).collect()
+    }
+
+    #[test]
+    fn test_fixed_alternation() {
+        let mut schedule = HybridSchedule::new(2, false);
+        assert_eq!(run(&mut schedule, 4, |_| true), vec![false, true, false, true]);
+        let stats = schedule.stats();
+        assert_eq!((stats.direct.attempts, stats.scrapingbee.successes), (4, 4));
+        assert_eq!(stats.scrapingbee.average_latency_ms(), 3000.0);
+        assert_eq!(stats.scrapingbee_share, 0.5);
+    }
+
+    #[test]
+    fn test_auto_tune() {
+        // Direct requests work: ScrapingBee drops to the minimum share
+        let mut schedule = HybridSchedule::new(2, true);
+        let methods = run(&mut schedule, 20, |_| true);
+        assert_eq!(schedule.scrapingbee_share(), MIN_SHARE);
+        assert!(methods.iter().filter(|via_scrapingbee| **via_scrapingbee).count() <= 3, "{:?}", methods);
+
+        // Direct requests are blocked: most batches move to ScrapingBee
+        let mut schedule = HybridSchedule::new(2, true);
+        let methods = run(&mut schedule, 20, |via_scrapingbee| via_scrapingbee);
+        assert!((schedule.scrapingbee_share() - MAX_SHARE).abs() < 1e-9);
+        assert!(methods[10..].iter().filter(|via_scrapingbee| **via_scrapingbee).count() >= 8, "{:?}", methods);
+        let stats = schedule.stats();
+        assert_eq!(stats.direct.success_rate(), Some(0.0));
+        assert_eq!(stats.direct.failures, stats.direct.attempts);
+
+        // Exhausted keys: rate-limited only, whatever the share
+        schedule.disable_scrapingbee();
+        assert!(!run(&mut schedule, 5, |_| true).contains(&true));
+    }
+}
@@ -0,0 +1,387 @@
+use crate::config::ImagesConfig;
+use mongodb::bson::doc;
+use mongodb::options::{GridFsBucketOptions, GridFsUploadOptions};
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Reference to a downloaded image, recorded on `PhoneDocument`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoredImage {
+    pub source_url: String,
+    pub content_hash: String, // SHA-256 of the image bytes (hex)
+    pub storage_ref: String,  // e.g. "file:///data/images/ab/abcd.jpg", "gridfs://images/abcd.jpg", "s3://bucket/key"
+    pub content_type: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// Where downloaded images are stored
+pub enum ImageStorage {
+    /// Local directory tree, sharded by the first two hash characters
+    Local(PathBuf),
+    /// MongoDB GridFS bucket
+    GridFs { database: Database, bucket: String },
+    /// S3 bucket with an optional key prefix
+    #[cfg(feature = "s3")]
+    S3 {
+        client: aws_sdk_s3::Client,
+        bucket: String,
+        prefix: String,
+    },
+}
+
+impl ImageStorage {
+    /// Build storage from the `IMAGE_STORAGE` environment variable
+    ///
+    /// Accepted values: `local:<dir>`, `gridfs[:<bucket>]`, `s3://<bucket>[/<prefix>]`.
+    /// Returns `None` when the variable is not set (image downloading disabled).
+    pub async fn from_env(database: &Database) -> Result<Option<Self>, Box<dyn Error>> {
+        let spec = match std::env::var("IMAGE_STORAGE") {
+            Ok(spec) if !spec.trim().is_empty() => spec,
+            _ => return Ok(None),
+        };
+
+        Self::parse(spec.trim(), database).await.map(Some)
+    }
+
+    /// Build storage from the `[images]` config section (`None` disables downloads)
+    pub async fn from_config(config: &ImagesConfig, database: &Database) -> Result<Option<Self>, Box<dyn Error>> {
+        match config.storage.as_deref().map(str::trim) {
+            Some(spec) if !spec.is_empty() => Self::parse(spec, database).await.map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse a storage specification string (see `from_env`)
+    pub async fn parse(spec: &str, database: &Database) -> Result<Self, Box<dyn Error>> {
+        Self::parse_with_bucket(spec, database, "phone_images").await
+    }
+
+    /// Parse a storage specification, using `default_bucket` for a bare `gridfs`
+    pub async fn parse_with_bucket(spec: &str, database: &Database, default_bucket: &str) -> Result<Self, Box<dyn Error>> {
+        if let Some(dir) = spec.strip_prefix("local:") {
+            return Ok(ImageStorage::Local(PathBuf::from(dir)));
+        }
+
+        if spec == "gridfs" || spec.starts_with("gridfs:") {
+            let bucket = spec
+                .strip_prefix("gridfs:")
+                .filter(|b| !b.is_empty())
+                .unwrap_or(default_bucket);
+            return Ok(ImageStorage::GridFs {
+                database: database.clone(),
+                bucket: bucket.to_string(),
+            });
+        }
+
+        if let Some(location) = spec.strip_prefix("s3://") {
+            #[cfg(feature = "s3")]
+            {
+                let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+                let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+                return Ok(ImageStorage::S3 {
+                    client: aws_sdk_s3::Client::new(&config),
+                    bucket: bucket.to_string(),
+                    prefix: prefix.trim_end_matches('/').to_string(),
+                });
+            }
+
+            #[cfg(not(feature = "s3"))]
+            return Err(format!("S3 image storage ({}) requires the `s3` feature", location).into());
+        }
+
+        Err(format!("Unknown storage value: {}", spec).into())
+    }
+
+    /// Write an object unless one with the same (content-addressed) file name exists
+    ///
+    /// `filename` must start with the content hash; local files and S3 keys are
+    /// sharded by its first two characters. Returns the storage reference.
+    pub async fn store(
+        &self,
+        filename: &str,
+        bytes: &[u8],
+        source_url: &str,
+        content_type: Option<&str>,
+    ) -> Result<String, Box<dyn Error>> {
+        let shard = &filename[..2.min(filename.len())];
+
+        match self {
+            ImageStorage::Local(root) => {
+                let dir = root.join(shard);
+                let path = dir.join(filename);
+
+                if !path.exists() {
+                    tokio::fs::create_dir_all(&dir).await?;
+                    tokio::fs::write(&path, bytes).await?;
+                }
+
+                Ok(format!("file://{}", path.display()))
+            }
+            ImageStorage::GridFs { database, bucket } => {
+                let gridfs = database.gridfs_bucket(
+                    GridFsBucketOptions::builder().bucket_name(bucket.clone()).build(),
+                );
+
+                let existing = gridfs
+                    .find(doc! { "filename": filename }, None)
+                    .await?
+                    .advance()
+                    .await?;
+
+                if !existing {
+                    let options = GridFsUploadOptions::builder()
+                        .metadata(doc! {
+                            "source_url": source_url,
+                            "content_type": content_type,
+                        })
+                        .build();
+                    gridfs
+                        .upload_from_futures_0_3_reader(filename, bytes, options)
+                        .await?;
+                }
+
+                Ok(format!("gridfs://{}/{}", bucket, filename))
+            }
+            #[cfg(feature = "s3")]
+            ImageStorage::S3 { client, bucket, prefix } => {
+                let key = if prefix.is_empty() {
+                    format!("{}/{}", shard, filename)
+                } else {
+                    format!("{}/{}/{}", prefix, shard, filename)
+                };
+
+                let exists = client.head_object().bucket(bucket).key(&key).send().await.is_ok();
+
+                if !exists {
+                    let mut request = client
+                        .put_object()
+                        .bucket(bucket)
+                        .key(&key)
+                        .body(bytes.to_vec().into());
+                    if let Some(content_type) = content_type {
+                        request = request.content_type(content_type);
+                    }
+                    request.send().await?;
+                }
+
+                Ok(format!("s3://{}/{}", bucket, key))
+            }
+        }
+    }
+
+    /// Read back an object written by `store`, given its storage reference
+    pub async fn load(&self, storage_ref: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        if let Some(path) = storage_ref.strip_prefix("file://") {
+            return Ok(tokio::fs::read(path).await?);
+        }
+
+        if let Some(location) = storage_ref.strip_prefix("gridfs://") {
+            let (bucket, filename) = location
+                .split_once('/')
+                .ok_or_else(|| format!("Invalid GridFS reference: {}", storage_ref))?;
+            let ImageStorage::GridFs { database, .. } = self else {
+                return Err(format!("{} needs GridFS storage", storage_ref).into());
+            };
+
+            let gridfs = database.gridfs_bucket(
+                GridFsBucketOptions::builder().bucket_name(bucket.to_string()).build(),
+            );
+            let mut stream = gridfs.open_download_stream_by_name(filename, None).await?;
+            let mut bytes = Vec::new();
+            futures::AsyncReadExt::read_to_end(&mut stream, &mut bytes).await?;
+            return Ok(bytes);
+        }
+
+        #[cfg(feature = "s3")]
+        if let Some(location) = storage_ref.strip_prefix("s3://") {
+            let (bucket, key) = location
+                .split_once('/')
+                .ok_or_else(|| format!("Invalid S3 reference: {}", storage_ref))?;
+            let ImageStorage::S3 { client, .. } = self else {
+                return Err(format!("{} needs S3 storage", storage_ref).into());
+            };
+
+            let object = client.get_object().bucket(bucket).key(key).send().await?;
+            return Ok(object.body.collect().await?.into_bytes().to_vec());
+        }
+
+        Err(format!("Unsupported storage reference: {}", storage_ref).into())
+    }
+
+    /// Short label for configuration output
+    pub fn describe(&self) -> String {
+        match self {
+            ImageStorage::Local(dir) => format!("local ({})", dir.display()),
+            ImageStorage::GridFs { bucket, .. } => format!("GridFS ({})", bucket),
+            #[cfg(feature = "s3")]
+            ImageStorage::S3 { bucket, prefix, .. } => format!("S3 (s3://{}/{})", bucket, prefix),
+        }
+    }
+}
+
+/// Downloads phone images and stores them deduplicated by content hash
+pub struct ImageDownloader {
+    client: reqwest::Client,
+    storage: ImageStorage,
+}
+
+impl ImageDownloader {
+    pub fn new(storage: ImageStorage) -> Result<Self, Box<dyn Error>> {
+        let client = reqwest::Client::builder()
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self { client, storage })
+    }
+
+    pub fn storage(&self) -> &ImageStorage {
+        &self.storage
+    }
+
+    /// Download a single image and store it (skips the write if the content already exists)
+    pub async fn download(&self, url: &str) -> Result<StoredImage, Box<dyn Error>> {
+        let response = self.client.get(url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Image download returned status: {}", response.status()).into());
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+
+        let bytes = response.bytes().await?;
+        let content_hash = format!("{:x}", Sha256::digest(&bytes));
+        let filename = format!("{}.{}", content_hash, image_extension(url, content_type.as_deref()));
+
+        let storage_ref = self
+            .storage
+            .store(&filename, &bytes, url, content_type.as_deref())
+            .await?;
+
+        Ok(StoredImage {
+            source_url: url.to_string(),
+            content_hash,
+            storage_ref,
+            content_type,
+            size_bytes: bytes.len() as u64,
+        })
+    }
+
+    /// Download the thumbnail and (optionally) gallery images for a phone
+    /// Failures are reported and skipped so one bad image doesn't fail the phone
+    pub async fn download_phone_images(
+        &self,
+        image_url: Option<&str>,
+        gallery_urls: &[String],
+        include_gallery: bool,
+    ) -> Vec<StoredImage> {
+        let mut urls: Vec<&str> = image_url.into_iter().collect();
+        if include_gallery {
+            urls.extend(gallery_urls.iter().map(|u| u.as_str()));
+        }
+
+        let mut stored: Vec<StoredImage> = Vec::new();
+
+        for url in urls {
+            match self.download(url).await {
+                Ok(image) => {
+                    if !stored.iter().any(|s| s.content_hash == image.content_hash) {
+                        stored.push(image);
+                    }
+                }
+                Err(e) => warn!(url = %url, error = %e, "Image download failed"),
+            }
+        }
+
+        stored
+    }
+}
+
+/// Pick a file extension from the URL, falling back to the content type
+fn image_extension(url: &str, content_type: Option<&str>) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+
+    for ext in ["jpg", "jpeg", "png", "gif", "webp"] {
+        if path.ends_with(&format!(".{}", ext)) {
+            return if ext == "jpeg" { "jpg" } else { ext };
+        }
+    }
+
+    match content_type {
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        _ => "jpg",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(image_extension("https://fdn2.gsmarena.com/vv/bigpic/apple-iphone-15.JPEG?v=2", None), "jpg");
+        assert_eq!(image_extension("https://fdn2.gsmarena.com/vv/pics/apple-iphone-15-1.png", Some("image/jpeg")), "png");
+        assert_eq!(image_extension("https://example.com/image", Some("image/webp")), "webp");
+        assert_eq!(image_extension("https://example.com/image", None), "jpg");
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_layout() {
+        let root = std::env::temp_dir().join(format!("gsmarena-images-layout-{}", std::process::id()));
+        let storage = ImageStorage::Local(root.clone());
+
+        let storage_ref = storage.store("abcd1234.jpg", b"first", "https://example.com/a.jpg", None).await.unwrap();
+        let path = root.join("ab").join("abcd1234.jpg");
+        assert_eq!(storage_ref, format!("file://{}", path.display()));
+        assert_eq!(storage.load(&storage_ref).await.unwrap(), b"first");
+
+        // Content-addressed: an existing object is not rewritten
+        let again = storage.store("abcd1234.jpg", b"second", "https://example.com/b.jpg", None).await.unwrap();
+        assert_eq!(again, storage_ref);
+        assert_eq!(std::fs::read(&path).unwrap(), b"first");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_dedupes_by_content_hash() {
+        let mut server = mockito::Server::new_async().await;
+        let bigpic = server.mock("GET", "/bigpic/iphone.jpg").with_header("content-type", "image/jpeg").with_body("jpeg bytes").create_async().await;
+        server.mock("GET", "/pics/iphone-1.jpg").with_header("content-type", "image/jpeg").with_body("jpeg bytes").create_async().await;
+        server.mock("GET", "/pics/iphone-2").with_header("content-type", "image/png").with_body("png bytes").create_async().await;
+        server.mock("GET", "/pics/missing.jpg").with_status(404).create_async().await;
+
+        let root = std::env::temp_dir().join(format!("gsmarena-images-download-{}", std::process::id()));
+        let downloader = ImageDownloader::new(ImageStorage::Local(root.clone())).unwrap();
+
+        let image = downloader.download(&format!("{}/bigpic/iphone.jpg", server.url())).await.unwrap();
+        bigpic.assert_async().await;
+        let hash = format!("{:x}", Sha256::digest(b"jpeg bytes"));
+        assert_eq!(image.content_hash, hash);
+        assert_eq!(image.content_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(image.size_bytes, 10);
+        assert!(root.join(&hash[..2]).join(format!("{}.jpg", hash)).exists());
+
+        // The gallery repeats the thumbnail and has one broken link: both are dropped
+        let gallery: Vec<String> = ["pics/iphone-1.jpg", "pics/iphone-2", "pics/missing.jpg"].iter().map(|path| format!("{}/{}", server.url(), path)).collect();
+        let thumbnail = format!("{}/bigpic/iphone.jpg", server.url());
+        let stored = downloader.download_phone_images(Some(&thumbnail), &gallery, true).await;
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].content_hash, hash);
+        assert!(stored[1].storage_ref.ends_with(".png"));
+
+        assert_eq!(downloader.download_phone_images(Some(&thumbnail), &gallery, false).await.len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
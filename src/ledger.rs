@@ -0,0 +1,143 @@
+//! A local record of what earlier runs scraped, for runs without MongoDB
+//!
+//! A `ScrapeRunner` that only writes files (or hands phones to `on_phone_scraped`)
+//! cannot ask a database what it already has. The ledger is a small SQLite file of
+//! phone_ids and specs hashes: with it, `skip_existing` skips phones scraped by an
+//! earlier run, and re-scraped phones whose specifications did not change are not
+//! passed on again. Needs the `ledger` feature.
+
+use crate::mongodb::PhoneDocument;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::error::Error;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS scraped_phones (
+    phone_id   TEXT PRIMARY KEY,
+    brand      TEXT NOT NULL,
+    specs_hash TEXT,
+    scraped_at TEXT NOT NULL
+)";
+
+/// What the ledger knows about one phone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub phone_id: String,
+    pub brand: String,
+    pub specs_hash: Option<String>,
+    pub scraped_at: DateTime<Utc>,
+}
+
+/// Phone_ids and content hashes of scraped phones, in a SQLite file
+/// Cloning shares the connection.
+#[derive(Clone)]
+pub struct Ledger {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Ledger {
+    /// Open (or create) the ledger file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path).map_err(|e| format!("Cannot open ledger {}: {}", path.display(), e))?;
+        Self::with_connection(connection)
+    }
+
+    /// A ledger that lives only as long as the process, e.g. for tests
+    pub fn in_memory() -> Result<Self, Box<dyn Error>> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, Box<dyn Error>> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection: Arc::new(Mutex::new(connection)) })
+    }
+
+    pub fn get(&self, phone_id: &str) -> Result<Option<LedgerEntry>, Box<dyn Error>> {
+        let connection = self.connection.lock().unwrap();
+        let row = connection
+            .query_row(
+                "SELECT brand, specs_hash, scraped_at FROM scraped_phones WHERE phone_id = ?1",
+                params![phone_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, String>(2)?)),
+            )
+            .optional()?;
+        let Some((brand, specs_hash, scraped_at)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(LedgerEntry {
+            phone_id: phone_id.to_string(),
+            brand,
+            specs_hash,
+            scraped_at: DateTime::parse_from_rfc3339(&scraped_at)?.with_timezone(&Utc),
+        }))
+    }
+
+    pub fn contains(&self, phone_id: &str) -> Result<bool, Box<dyn Error>> {
+        Ok(self.get(phone_id)?.is_some())
+    }
+
+    /// Record a phone as scraped now, replacing what the ledger had for it
+    pub fn record(&self, phone: &PhoneDocument) -> Result<(), Box<dyn Error>> {
+        self.connection.lock().unwrap().execute(
+            "INSERT INTO scraped_phones (phone_id, brand, specs_hash, scraped_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(phone_id) DO UPDATE SET brand = excluded.brand, specs_hash = excluded.specs_hash, scraped_at = excluded.scraped_at",
+            params![phone.phone_id, phone.brand, phone.specs_hash, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a phone, so the next run scrapes it again
+    pub fn remove(&self, phone_id: &str) -> Result<bool, Box<dyn Error>> {
+        let removed = self.connection.lock().unwrap().execute("DELETE FROM scraped_phones WHERE phone_id = ?1", params![phone_id])?;
+        Ok(removed > 0)
+    }
+
+    pub fn len(&self) -> Result<usize, Box<dyn Error>> {
+        let count: i64 = self.connection.lock().unwrap().query_row("SELECT COUNT(*) FROM scraped_phones", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+        Ok(self.len()? == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(chipset: &str) -> PhoneDocument {
+        PhoneDocument::fixture("apple_iphone_15-12559").brand("Apple").category("Platform", &[("Chipset", chipset)]).build()
+    }
+
+    #[test]
+    fn test_ledger() {
+        let path = std::env::temp_dir().join(format!("gsmarena-ledger-{}", std::process::id())).join("ledger.sqlite");
+        let ledger = Ledger::open(&path).unwrap();
+        assert!(ledger.is_empty().unwrap());
+
+        let first = phone("Apple A16 Bionic (4 nm)");
+        ledger.record(&first).unwrap();
+        ledger.record(&first).unwrap();
+        assert_eq!(ledger.len().unwrap(), 1);
+
+        // Survives reopening, like a second run
+        drop(ledger);
+        let ledger = Ledger::open(&path).unwrap();
+        let entry = ledger.get(&first.phone_id).unwrap().unwrap();
+        assert_eq!((entry.brand.as_str(), &entry.specs_hash), ("Apple", &first.specs_hash));
+
+        let changed = phone("Apple A16");
+        ledger.record(&changed).unwrap();
+        assert_eq!(ledger.get(&first.phone_id).unwrap().unwrap().specs_hash, changed.specs_hash);
+
+        assert!(ledger.remove(&first.phone_id).unwrap());
+        assert!(!ledger.contains(&first.phone_id).unwrap());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
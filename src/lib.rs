@@ -2,14 +2,124 @@ pub mod scraper;
 pub mod models;
 pub mod utils;
 pub mod brand_scraper;
+#[cfg(feature = "browser")]
+pub mod browser;
+pub mod config;
+pub mod migrations;
 pub mod mongodb;
 pub mod proxy_manager;
 pub mod scrapingbee_client;
+pub mod adaptive_delay;
+pub mod analytics;
+pub mod archive;
+pub mod budget;
+pub mod chat;
+pub mod checkpoint;
+pub mod collection_diff;
+pub mod comparison;
+pub mod comparison_report;
+pub mod compression;
+pub mod dataset;
+pub mod device_type;
+pub mod dump;
+pub mod enrichment;
+pub mod events;
+pub mod file_store;
+pub mod form_factor;
+pub mod gallery;
+pub mod global_limiter;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod http_stats;
+pub mod hybrid;
+pub mod images;
+#[cfg(feature = "ledger")]
+pub mod ledger;
+pub mod lifecycle;
+pub mod normalize;
+pub mod notifications;
+pub mod page_cache;
+pub mod phone_finder;
+pub mod pricing;
+pub mod provenance;
+pub mod query;
+pub mod rate_limiter;
+pub mod resolve;
+pub mod run_report;
+pub mod runner;
+pub mod saved_pages;
+pub mod scheduler;
+pub mod search;
+pub mod secrets;
+pub mod selectors;
+pub mod self_check;
+pub mod service;
+pub mod shutdown;
+pub mod site;
+pub mod sitemap;
+pub mod snapshots;
+pub mod stats;
+pub mod store;
+#[cfg(test)]
+mod test_support;
+pub mod upcoming;
+pub mod upsert;
+pub mod user_agents;
+pub mod validator;
+pub mod variants;
 
 // Re-export main types
 pub use scraper::GsmArenaScraper;
 pub use gsmarena::{DeviceSpecification, Category, SingleSpecification};
-pub use brand_scraper::{Brand, PhoneListItem, fetch_all_brands, fetch_phones_by_brand, fetch_phones_by_brand_paginated, fetch_all_phones};
-pub use mongodb::{MongoDBClient, PhoneDocument, parse_specifications};
+pub use brand_scraper::{Brand, BrandDetails, PhoneListItem, PhonePages, YearCount, fetch_all_brands, fetch_brand_details, fetch_brand_year_breakdown, fetch_phones_by_brand, fetch_phones_by_brand_paginated, fetch_phones_by_brand_from_page, fetch_all_phones, phone_pages};
+pub use adaptive_delay::{AdaptiveDelay, AdaptiveDelayStats};
+pub use budget::{BudgetUsage, RequestBudget};
+pub use chat::{ChatNotifier, NewDevice};
+pub use checkpoint::Checkpoint;
+pub use comparison::{compare, ComparisonResult, ComparisonWeights, Metric};
+pub use compression::FileCompression;
+pub use config::{Config, DiscoveryMode, ScrapeProvider, UserAgentRotation};
+pub use dataset::Dataset;
+pub use enrichment::{Enricher, Enrichers, SourceRecord};
+pub use events::{EventBackend, EventPublisher, EventSink};
+pub use device_type::DeviceType;
+pub use file_store::FileSystemStore;
+pub use form_factor::FormFactor;
+pub use mongodb::{BrandDocument, MongoDBClient, PhoneDocument, parse_specifications};
+pub use provenance::Provenance;
 pub use proxy_manager::{ProxyManager, ProxyConfig};
 pub use scrapingbee_client::ScrapingBeeClient;
+pub use gallery::{DevicePictures, fetch_device_pictures};
+pub use global_limiter::GlobalRateLimiter;
+pub use http_stats::{HttpStats, ProviderHttpStats};
+pub use images::{ImageDownloader, ImageStorage, StoredImage};
+#[cfg(feature = "ledger")]
+pub use ledger::{Ledger, LedgerEntry};
+pub use lifecycle::{OsUpgrade, StatusTransition};
+pub use normalize::{NormalizedSpecs, OsVersion};
+pub use notifications::{FieldChange, PhoneEvent, WebhookNotifier};
+pub use page_cache::{PageCache, Validators};
+pub use phone_finder::{Availability, NetworkFilter, PhoneFinderQuery, search_phone_finder};
+pub use pricing::{MarketPrice, PriceSnapshot};
+pub use query::PhoneQuery;
+pub use rate_limiter::RateLimiter;
+pub use resolve::{resolve_phone_id, resolve_phone_id_with_db, score_name};
+pub use run_report::{FailedPhone, RunReport};
+pub use runner::{ScrapeError, ScrapeRunner, ScrapeRunnerBuilder, ScrapeSummary};
+pub use saved_pages::SavedPages;
+pub use scheduler::CronSchedule;
+pub use search::{SearchFilters, SearchResult, search, search_with_filters};
+pub use service::{DirectSource, PageSource, PhoneService, PhoneSource};
+pub use shutdown::Shutdown;
+pub use site::SiteConfig;
+pub use snapshots::{HtmlSnapshot, SnapshotStore};
+pub use stats::{ErrorPolicy, FailureCategory, FailureStats, SkipReason};
+pub use store::{MongoPhoneStore, PhoneFilter, PhoneStore};
+pub use sitemap::{discover_phones_from_sitemap, group_phones_by_brand};
+pub use upcoming::{StatusChangeEvent, UpcomingPhone, UpcomingTracker};
+pub use upsert::UpsertPolicy;
+pub use user_agents::UserAgentPool;
+pub use validator::{validate, Validation};
+pub use variants::{group_variants, VariantCandidate, VariantGroup};
@@ -0,0 +1,175 @@
+//! Launch.status over time: when a device went Rumored -> Available -> Discontinued
+//!
+//! Each scrape compares the parsed status with the last transition stored on the
+//! document and appends one when it moved, so the history builds up run by run.
+//! OS upgrades ("upgradable to Android 15") are recorded the same way.
+
+use crate::mongodb::PhoneDocument;
+use crate::normalize::OsVersion;
+use crate::phone_finder::Availability;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// One change of Launch.status, as seen by a scrape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub from: Option<Availability>, // None for the first status seen
+    pub to: Availability,
+    pub at: DateTime<Utc>, // When the scrape noticed, not when GSMArena changed it
+}
+
+/// A newer OS version showing up in Platform > OS, as seen by a scrape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsUpgrade {
+    pub from: Option<OsVersion>, // Newest version listed before; None for the first one seen
+    pub to: OsVersion,
+    pub at: DateTime<Utc>,
+}
+
+impl PhoneDocument {
+    /// Parsed Launch.status ("Discontinued" -> Discontinued)
+    pub fn availability(&self) -> Option<Availability> {
+        Availability::from_status(self.launch.as_ref()?.status.as_deref()?)
+    }
+
+    /// When the device last moved to `status`, if it ever did
+    pub fn became(&self, status: Availability) -> Option<DateTime<Utc>> {
+        self.lifecycle.iter().rev().find(|t| t.to == status).map(|t| t.at)
+    }
+}
+
+/// Carry the stored history over to a freshly scraped document and append a
+/// transition when its status differs from the last one recorded
+///
+/// Documents stored before tracking have no history; their parsed status stands in
+/// for the last one, so the first transition recorded for them is a real change.
+/// Returns the new transition, if any.
+pub fn track(previous: Option<&PhoneDocument>, phone: &mut PhoneDocument, now: DateTime<Utc>) -> Option<StatusTransition> {
+    if let Some(upgrade) = track_os(previous, phone, now) {
+        info!(phone = %phone.phone_id, from = ?upgrade.from.map(|v| v.to_string()), to = %upgrade.to, "OS upgrade listed");
+    }
+    if let Some(previous) = previous {
+        phone.lifecycle = previous.lifecycle.clone();
+    }
+    let status = phone.availability()?;
+    let last = match phone.lifecycle.last() {
+        Some(transition) => Some(transition.to),
+        None => previous.and_then(PhoneDocument::availability),
+    };
+    if last == Some(status) && !phone.lifecycle.is_empty() {
+        return None;
+    }
+
+    let transition = StatusTransition {
+        from: last.filter(|&from| from != status),
+        to: status,
+        at: now,
+    };
+    phone.lifecycle.push(transition.clone());
+    Some(transition).filter(|t| t.from.is_some())
+}
+
+/// Carry the stored OS upgrades over and append one when the newest listed OS
+/// version went up; a lower version (GSMArena correcting itself) is not recorded
+///
+/// As with `track`, the first version seen is recorded but not returned.
+pub fn track_os(previous: Option<&PhoneDocument>, phone: &mut PhoneDocument, now: DateTime<Utc>) -> Option<OsUpgrade> {
+    if let Some(previous) = previous {
+        phone.os_upgrades = previous.os_upgrades.clone();
+    }
+    let version = phone.normalized.os_latest()?;
+    let last = match phone.os_upgrades.last() {
+        Some(upgrade) => Some(upgrade.to),
+        None => previous.and_then(|p| p.normalized.os_latest()),
+    };
+    if last.is_some_and(|last| last >= version) && !phone.os_upgrades.is_empty() {
+        return None;
+    }
+
+    let upgrade = OsUpgrade {
+        from: last.filter(|&from| from < version),
+        to: version,
+        at: now,
+    };
+    phone.os_upgrades.push(upgrade.clone());
+    Some(upgrade).filter(|u| u.from.is_some())
+}
+
+/// Timestamps as stored in documents (chrono's serde format), comparable as strings
+pub fn stored_timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scraped(status: &str) -> PhoneDocument {
+        PhoneDocument::fixture("nothing_phone_(3)-13000").name("Phone (3)").brand("Nothing").category("Launch", &[("Status", status)]).build()
+    }
+
+    fn with_os(os: &str) -> PhoneDocument {
+        let mut phone = scraped("Available");
+        phone.specifications_raw = serde_json::json!({
+            "specification": [{ "category_title": "Platform", "category_spec": [["OS", os]] }]
+        });
+        phone.reparse();
+        phone
+    }
+
+    #[test]
+    fn test_track_os_upgrades() {
+        let day = |d: i64| DateTime::<Utc>::from_timestamp(1_700_000_000 + d * 86_400, 0).unwrap();
+
+        let mut first = with_os("Android 13, One UI 5.1");
+        assert_eq!(track_os(None, &mut first, day(0)), None);
+        assert_eq!(first.os_upgrades[0].to, OsVersion::new(13, 0));
+
+        let mut same = with_os("Android 13, One UI 5.1");
+        assert_eq!(track_os(Some(&first), &mut same, day(30)), None);
+
+        let mut upgraded = with_os("Android 13, upgradable to Android 15, One UI 7");
+        track(Some(&same), &mut upgraded, day(400)); // Status tracking records OS upgrades too
+        let upgrade = upgraded.os_upgrades.last().unwrap();
+        assert_eq!((upgrade.from, upgrade.to, upgrade.at), (Some(OsVersion::new(13, 0)), OsVersion::new(15, 0), day(400)));
+
+        let mut corrected = with_os("Android 13, upgradable to Android 14");
+        assert_eq!(track_os(Some(&upgraded), &mut corrected, day(401)), None);
+        assert_eq!(corrected.os_upgrades.len(), 2);
+    }
+
+    #[test]
+    fn test_track_lifecycle() {
+        let day = |d: i64| DateTime::<Utc>::from_timestamp(1_700_000_000 + d * 86_400, 0).unwrap();
+
+        let mut first = scraped("Rumored");
+        assert_eq!(track(None, &mut first, day(0)), None); // First sighting is recorded, not announced
+        assert_eq!(first.lifecycle.len(), 1);
+
+        let mut same = scraped("Rumored");
+        assert_eq!(track(Some(&first), &mut same, day(10)), None);
+        assert_eq!(same.lifecycle, first.lifecycle);
+
+        let mut released = scraped("Available. Released 2025, July 15");
+        let transition = track(Some(&same), &mut released, day(40)).unwrap();
+        assert_eq!((transition.from, transition.to), (Some(Availability::Rumored), Availability::Available));
+
+        let mut discontinued = scraped("Discontinued");
+        track(Some(&released), &mut discontinued, day(400));
+        assert_eq!(discontinued.lifecycle.len(), 3);
+        assert_eq!(discontinued.became(Availability::Available), Some(day(40)));
+        assert_eq!(discontinued.became(Availability::Discontinued), Some(day(400)));
+
+        // Stored before tracking: the old status is the starting point
+        let mut untracked = scraped("Available. Released 2020, March 01");
+        untracked.lifecycle.clear();
+        let mut now_discontinued = scraped("Discontinued");
+        let transition = track(Some(&untracked), &mut now_discontinued, day(5)).unwrap();
+        assert_eq!(transition.from, Some(Availability::Available));
+
+        assert_eq!(discontinued.os_upgrades.len(), 0); // No Platform > OS in these pages
+
+        assert_eq!(stored_timestamp(day(0)), serde_json::to_value(day(0)).unwrap().as_str().unwrap());
+    }
+}
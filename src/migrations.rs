@@ -0,0 +1,199 @@
+use crate::config::Config;
+use crate::mongodb::MongoDBClient;
+use crate::validator::validate;
+use chrono::{DateTime, Utc};
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use tracing::{info, warn};
+
+/// Collection recording which migrations have been applied
+pub const META_COLLECTION: &str = "_meta";
+
+/// A numbered, idempotent upgrade of the stored collections
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Every migration, in the order they are applied; append new ones with the next version
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "indexes",
+        description: "Create the specs collection indexes and a unique phone_id index on the phone list",
+    },
+    Migration {
+        version: 2,
+        name: "phone_document_defaults",
+        description: "Add gallery_urls, stored_images and html_snapshot to documents scraped before they existed",
+    },
+    Migration {
+        version: 3,
+        name: "reparse_structured_specs",
+        description: "Re-derive structured fields from specifications_raw with the current parser",
+    },
+    Migration {
+        version: 4,
+        name: "completeness_scores",
+        description: "Score every document and flag incomplete ones for re-scrape",
+    },
+    Migration {
+        version: 5,
+        name: "normalized_specs",
+        description: "Parse battery, price, weight and other numbers into the `normalized` fields queries filter on",
+    },
+];
+
+/// Record of an applied migration in the `_meta` collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    #[serde(rename = "_id")]
+    pub id: String, // "migration:<version>"
+    pub version: u32,
+    pub name: String,
+    pub applied_at: DateTime<Utc>,
+    pub documents_modified: u64,
+}
+
+/// Applies pending migrations to the configured collections
+pub struct Migrator<'a> {
+    client: &'a MongoDBClient,
+    config: &'a Config,
+}
+
+impl<'a> Migrator<'a> {
+    pub fn new(client: &'a MongoDBClient, config: &'a Config) -> Self {
+        Self { client, config }
+    }
+
+    /// Migrations already applied, oldest first
+    pub async fn applied(&self) -> Result<Vec<AppliedMigration>, Box<dyn Error>> {
+        let meta = self.client.database().collection::<AppliedMigration>(META_COLLECTION);
+        let options = mongodb::options::FindOptions::builder().sort(doc! { "version": 1 }).build();
+
+        let mut cursor = meta.find(doc! { "_id": { "$regex": "^migration:" } }, options).await?;
+        let mut applied = Vec::new();
+        while let Some(record) = cursor.next().await {
+            applied.push(record?);
+        }
+        Ok(applied)
+    }
+
+    /// Migrations not yet applied, up to `target` (all when `None`)
+    pub async fn pending(&self, target: Option<u32>) -> Result<Vec<&'static Migration>, Box<dyn Error>> {
+        let applied = self.applied().await?;
+
+        Ok(MIGRATIONS
+            .iter()
+            .filter(|m| target.is_none_or(|target| m.version <= target))
+            .filter(|m| !applied.iter().any(|a| a.version == m.version))
+            .collect())
+    }
+
+    /// Run one migration and record it as applied
+    pub async fn apply(&self, migration: &Migration) -> Result<AppliedMigration, Box<dyn Error>> {
+        info!(version = migration.version, name = migration.name, "Applying migration");
+        let documents_modified = self.run(migration.version).await?;
+
+        let record = AppliedMigration {
+            id: format!("migration:{}", migration.version),
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: Utc::now(),
+            documents_modified,
+        };
+
+        let meta = self.client.database().collection::<AppliedMigration>(META_COLLECTION);
+        meta.replace_one(
+            doc! { "_id": &record.id },
+            &record,
+            mongodb::options::ReplaceOptions::builder().upsert(true).build(),
+        )
+        .await?;
+
+        info!(version = migration.version, documents_modified, "✓ Migration applied");
+        Ok(record)
+    }
+
+    /// Returns the number of documents modified
+    async fn run(&self, version: u32) -> Result<u64, Box<dyn Error>> {
+        let database = self.client.database();
+        let phones = &self.config.collections.phones;
+
+        match version {
+            1 => {
+                self.client.create_indexes(phones).await?;
+
+                let phone_list = database.collection::<Document>(&self.config.collections.phone_list);
+                let index = IndexModel::builder()
+                    .keys(doc! { "phone_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build();
+                phone_list.create_index(index, None).await?;
+                Ok(0)
+            }
+            2 => {
+                let collection = database.collection::<Document>(phones);
+                let defaults = [
+                    ("gallery_urls", mongodb::bson::Bson::Array(Vec::new())),
+                    ("stored_images", mongodb::bson::Bson::Array(Vec::new())),
+                    ("html_snapshot", mongodb::bson::Bson::Null),
+                ];
+
+                let mut modified = 0;
+                for (field, value) in defaults {
+                    let result = collection
+                        .update_many(doc! { field: { "$exists": false } }, doc! { "$set": { field: value } }, None)
+                        .await?;
+                    modified += result.modified_count;
+                }
+                Ok(modified)
+            }
+            3..=5 => {
+                let min_completeness = self.config.validation.min_completeness;
+                let mut cursor = self.client.get_collection(phones).find(doc! {}, None).await?;
+                let mut modified = 0;
+
+                while let Some(result) = cursor.next().await {
+                    let mut phone = match result {
+                        Ok(phone) => phone,
+                        Err(e) => {
+                            warn!(error = %e, "Skipping unreadable document");
+                            continue;
+                        }
+                    };
+
+                    let changed = match version {
+                        3 | 5 => phone.reparse(),
+                        _ => phone.apply_validation(&validate(&phone, min_completeness)),
+                    };
+                    if changed {
+                        self.client.upsert_phone(phones, phone).await?;
+                        modified += 1;
+                    }
+                }
+                Ok(modified)
+            }
+            other => Err(format!("Unknown migration version {}", other).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_numbered_in_order() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1, "{} is out of order", migration.name);
+            assert_eq!(MIGRATIONS.iter().filter(|m| m.name == migration.name).count(), 1);
+        }
+    }
+}
@@ -0,0 +1,995 @@
+//! Typed values parsed from the spec strings, stored as `PhoneDocument.normalized`
+//!
+//! Structured fields keep GSMArena's text ("Li-Ion 5000 mAh, non-removable");
+//! these are the numbers and flags queries filter and sort on.
+
+use crate::mongodb::PhoneDocument;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Patterns compiled once; normalizing runs over every phone of a scrape or migration
+static INCHES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*inches").unwrap());
+static NITS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)\s*nits").unwrap());
+static HERTZ: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)\s*Hz").unwrap());
+static RAM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*GB\s+RAM").unwrap());
+static STORAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*(GB|TB)(\s+RAM)?").unwrap());
+static MEGAPIXELS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*MP").unwrap());
+static WATTS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*W\b").unwrap());
+static LEADING_NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(\d+(?:\.\d+)?)").unwrap());
+static USD_SYMBOL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\s*([\d,]+(?:\.\d+)?)").unwrap());
+static USD_ABOUT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)about\s+([\d,]+(?:\.\d+)?)\s*USD").unwrap());
+static EUR_SYMBOL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"€\s*([\d,]+(?:\.\d+)?)").unwrap());
+static EUR_ABOUT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)about\s+([\d,]+(?:\.\d+)?)\s*EUR").unwrap());
+static VERSION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+(?:\.\d+)*").unwrap());
+static UPGRADABLE_TO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)upgradable to\s+[A-Za-z ]*?(\d+(?:\.\d+)*)").unwrap());
+static SIM_SIZE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(nano|micro|mini)-sim").unwrap());
+static SIM_MAX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"max (\d+) at a time").unwrap());
+static IP_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bIP([0-6X])([0-9X]K?)\b").unwrap());
+static BUILD_PARTS: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
+    ["front", "back", "frame", "unibody"]
+        .into_iter()
+        .map(|side| (side, Regex::new(&format!(r"(?i)(?:^|,)\s*([a-z][a-z -]*?)\s+{}\b\s*(?:\(([^)]*)\))?", side)).unwrap()))
+        .collect()
+});
+static CHARGING_LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"([a-z)])(\d+(?:\.\d+)?\s*W\b)").unwrap());
+static CHARGE_TIME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+%\s+in\s+\d+(?:\.\d+)?\s*(?:min|hr|h)\b").unwrap());
+static GNSS_SYSTEM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b([a-z][a-z-]*)\b\s*(?:\(([^)]*)\))?").unwrap());
+static DUAL_FREQUENCY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)dual[- ](band|frequency)").unwrap());
+static USB4: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\busb\s*4\b").unwrap());
+static USB_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:type-c|usb-c|usb|micro-?usb|mini-?usb|lightning)[ ,]*([1-3]\.\d(?:\s+gen\s*\d(?:x\d)?)?)").unwrap());
+static USB_GENERATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\s+gen\s*").unwrap());
+static WIFI_STANDARDS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"802\.11\s*([a-z0-9/ ]+)").unwrap());
+static HI_RES_BITS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(24|32)-bit").unwrap());
+static SAR_HEAD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(\d+(?:\.\d+)?)\s*W/kg\s*\(head\)").unwrap());
+static SAR_BODY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(\d+(?:\.\d+)?)\s*W/kg\s*\(body\)").unwrap());
+static DIMENSIONS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:(unfolded|folded)\s*:?\s*)?(\d+(?:\.\d+)?)\s*x\s*(\d+(?:\.\d+)?)\s*x\s*(\d+(?:\.\d+)?)\s*mm").unwrap());
+static WEIGHT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*g\b\s*(?:\(([^)]*)\))?").unwrap());
+static BATTERY_CAPACITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:^|[^x\d])(\d{3,5})\s*mAh\b\s*(?:\(([^)]*)\))?").unwrap());
+static MODEL_SEPARATOR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[,;](\s+|$)").unwrap());
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[serde(default)]
+pub struct NormalizedSpecs {
+    pub announced_year: Option<i32>,
+    pub display_size_in: Option<f64>,
+    pub brightness_nits: Option<f64>, // Highest figure quoted (usually peak)
+    pub refresh_rate_hz: Option<f64>,
+    pub ram_gb: Option<f64>,     // Largest RAM option
+    pub storage_gb: Option<f64>, // Largest storage option
+    pub main_camera_mp: Option<f64>,
+    pub battery_mah: Option<u32>,
+    pub charging_w: Option<f64>, // Fastest charging figure quoted
+    pub weight_g: Option<f64>, // First weight listed
+    pub has_nfc: Option<bool>,
+    pub has_5g: Option<bool>,
+    pub price_usd: Option<f64>, // Only when GSMArena lists a USD price
+    pub price_eur: Option<f64>, // Only when GSMArena lists a EUR price
+    pub model_numbers: Vec<String>, // Misc > Models as listed: "SM-S918B", "SM-S918B/DS", "iPhone15,4"
+    pub os_name: Option<String>,       // "Android", "iOS", "HarmonyOS"
+    pub os_version: Option<OsVersion>,       // Version the device shipped with
+    pub os_upgradable_to: Option<OsVersion>, // Latest upgrade GSMArena lists; None when only promised
+    pub sim_count: Option<u32>,        // SIMs usable at once, eSIMs included
+    pub sim_sizes: Vec<String>,        // Physical card sizes: "nano", "micro", "mini"
+    pub has_esim: Option<bool>,
+    pub dual_standby: Option<bool>,    // Several SIMs share one radio ("dual stand-by")
+    pub ip_rating: Option<String>,     // Highest listed, e.g. "IP68"
+    pub ip_water: Option<u32>,         // Water digit of `ip_rating` (8 for IP68)
+    pub front_protection: Option<String>, // "Gorilla Glass Victus 2", "Ceramic Shield glass"
+    pub back_material: Option<String>,    // Lowercase: "glass", "plastic", "eco leather"
+    pub frame_material: Option<String>,   // Lowercase: "aluminum", "titanium", "stainless steel"
+    pub wired_watts: Option<f64>,
+    pub wireless_watts: Option<f64>,
+    pub reverse_wireless_watts: Option<f64>,
+    pub charge_time_claims: Vec<String>, // As advertised: "50% in 30 min"
+    pub has_3_5mm_jack: Option<bool>,
+    pub stereo_speakers: Option<bool>,
+    pub hi_res_audio: Option<bool>,      // "Hi-Res", or 24/32-bit audio listed under Sound
+    pub bluetooth_codecs: Vec<String>,   // Named in Comms > Bluetooth: "aptX HD", "LDAC"
+    pub wifi_generations: Vec<String>,   // From Wi-Fi 4 on: "4", "5", "6", "6E", "7"
+    pub wifi_generation: Option<u32>,    // Newest generation
+    pub wifi_6ghz: Option<bool>,         // 6E or 7 listed
+    pub wifi_bands: Option<u32>,         // 2 for "dual-band", 3 for "tri-band"
+    pub bluetooth_version: Option<f64>,
+    pub bluetooth_profiles: Vec<String>, // "A2DP", "LE", "LE Audio", ...
+    pub gnss_systems: Vec<String>,       // "GPS", "GLONASS", "Galileo", "BDS", "QZSS", "NavIC"
+    pub gps_bands: Vec<String>,          // "L1", "L5"
+    pub dual_frequency_gnss: Option<bool>, // Some constellation is received on two bands
+    pub usb_connector: Option<String>,   // "USB-C", "microUSB", "miniUSB", "Lightning"
+    pub usb_version: Option<String>,     // As listed: "2.0", "3.2 Gen 2"
+    pub usb_speed_gbps: Option<f64>,     // Signalling rate of `usb_version`
+    pub has_otg: Option<bool>,
+    pub usb_displayport: Option<bool>,   // Video out over the port (DisplayPort alt mode)
+    pub sar_us_head: Option<f64>,        // W/kg, Misc > SAR (US, averaged over 1 g)
+    pub sar_us_body: Option<f64>,
+    pub sar_eu_head: Option<f64>,        // W/kg, Misc > SAR EU (averaged over 10 g)
+    pub sar_eu_body: Option<f64>,
+    pub colors: Vec<String>,             // Misc > Colors as named: "Titanium Gray", "Onyx Black"
+    pub color_families: Vec<String>,     // Lowercase families: "black", "blue", "titanium"
+    pub height_mm: Option<f64>,          // Folded size for foldables (the pocket size)
+    pub width_mm: Option<f64>,
+    pub thickness_mm: Option<f64>,
+    pub volume_cm3: Option<f64>,
+    pub dimensions: Vec<Dimensions>,     // One entry per state; "unfolded" and "folded" for foldables
+    pub weight_variants: Vec<WeightVariant>, // Every weight listed, with its variant label
+    pub battery_chemistry: Option<String>,   // "Si/C", "Li-Po", "Li-Ion", "NiMH"
+    pub battery_removable: Option<bool>,
+    pub battery_variants: Vec<BatteryVariant>, // Every capacity listed, e.g. China and Global models
+}
+
+/// One capacity from Battery > Type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct BatteryVariant {
+    pub variant: Option<String>, // "China", "Global"; None when unlabelled
+    pub mah: u32,
+}
+
+/// One weight from Body > Weight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct WeightVariant {
+    pub variant: Option<String>, // "Sub6", "mmWave", "glass back"; None when unlabelled
+    pub grams: f64,
+}
+
+/// Size in one state of the device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct Dimensions {
+    pub state: Option<String>, // "unfolded", "folded"; None for ordinary phones
+    pub height_mm: f64,
+    pub width_mm: f64,
+    pub thickness_mm: f64,
+    pub volume_cm3: f64,
+}
+
+impl NormalizedSpecs {
+    /// Parse from the structured fields of a document
+    pub fn from_phone(phone: &PhoneDocument) -> Self {
+        let display = phone.display.as_ref();
+        let internal = phone.memory.as_ref().and_then(|m| m.internal.as_deref());
+        let (price_usd, price_eur) = phone.misc.as_ref().and_then(|m| m.price.as_deref()).map(parse_price).unwrap_or_default();
+        let sim = phone.body.as_ref().and_then(|b| parse_sim(b.sim.as_deref()?));
+        let charging = phone.battery.as_ref().and_then(|b| b.charging.as_deref()).map(parse_charging).unwrap_or_default();
+        let sound = phone.sound.as_ref();
+        let sound_values = category_values(phone, "Sound");
+        let comms = phone.comms.as_ref();
+        let wifi = comms.and_then(|c| c.wlan.as_deref()).map(parse_wifi);
+        let bluetooth = comms.and_then(|c| c.bluetooth.as_deref());
+        let gnss = comms.and_then(|c| c.positioning.as_deref()).map(parse_gnss);
+        let usb = comms.and_then(|c| c.usb.as_deref()).map(parse_usb);
+        let misc = phone.misc.as_ref();
+        let (sar_us_head, sar_us_body) = misc.and_then(|m| m.sar.as_deref()).map(parse_sar).unwrap_or_default();
+        let (sar_eu_head, sar_eu_body) = misc.and_then(|m| m.sar_eu.as_deref()).map(parse_sar).unwrap_or_default();
+        let colors = misc.and_then(|m| m.colors.as_deref()).map(parse_colors).unwrap_or_default();
+        let dimensions = phone.body.as_ref().and_then(|b| b.dimensions.as_deref()).map(parse_dimensions).unwrap_or_default();
+        let pocket = dimensions.iter().find(|d| d.state.as_deref() == Some("folded")).or(dimensions.first());
+        let weight_variants = phone.body.as_ref().and_then(|b| b.weight.as_deref()).map(parse_weights).unwrap_or_default();
+        let battery_type = phone.battery.as_ref().and_then(|b| b.battery_type.as_deref());
+        let build = phone.body.as_ref().and_then(|b| b.build.as_deref()).unwrap_or_default();
+        let ip_rating = category_values(phone, "Body").into_iter().filter_map(parse_ip_rating).max_by_key(|ip| (ip_digit(ip, 3), ip_digit(ip, 2)));
+        let os = phone.platform.as_ref().and_then(|p| p.os.as_deref()).map(parse_os).unwrap_or_default();
+
+        Self {
+            announced_year: phone.announced_year(),
+            display_size_in: display.and_then(|d| first_number(d.size.as_deref()?, &INCHES)),
+            brightness_nits: display.and_then(|d| max_number(d.display_type.as_deref()?, &NITS)),
+            refresh_rate_hz: display.and_then(|d| max_number(d.display_type.as_deref()?, &HERTZ)),
+            ram_gb: internal.and_then(|text| max_number(text, &RAM)),
+            storage_gb: internal.and_then(max_storage_gb),
+            main_camera_mp: phone.main_camera.as_ref().and_then(|c| max_number(c.modules.as_deref()?, &MEGAPIXELS)),
+            battery_mah: phone.battery_capacity_mah(),
+            charging_w: phone.battery.as_ref().and_then(|b| max_number(b.charging.as_deref()?, &WATTS)),
+            weight_g: weight_variants.first().map(|w| w.grams),
+            has_nfc: phone.comms.as_ref().and_then(|c| yes_no(c.nfc.as_deref()?)),
+            has_5g: phone.network.as_ref().and_then(|n| Some(n.technology.as_deref()?.contains("5G"))),
+            price_usd,
+            price_eur,
+            model_numbers: phone.misc.as_ref().and_then(|m| m.models.as_deref()).map(parse_model_numbers).unwrap_or_default(),
+            os_name: os.name,
+            os_version: os.version,
+            os_upgradable_to: os.upgradable_to,
+            sim_count: sim.as_ref().map(|s| s.count),
+            has_esim: sim.as_ref().map(|s| s.esim),
+            dual_standby: sim.as_ref().map(|s| s.dual_standby),
+            sim_sizes: sim.map(|s| s.sizes).unwrap_or_default(),
+            ip_water: ip_rating.as_deref().and_then(|ip| ip_digit(ip, 3)),
+            ip_rating,
+            front_protection: phone.display.as_ref().and_then(|d| d.protection.as_deref()).and_then(protection_name).or_else(|| build_part(build, "front").and_then(|(_, detail)| detail)),
+            back_material: build_part(build, "back").map(|(material, _)| material),
+            frame_material: build_part(build, "frame").map(|(material, _)| material),
+            wired_watts: charging.wired,
+            wireless_watts: charging.wireless,
+            reverse_wireless_watts: charging.reverse_wireless,
+            charge_time_claims: charging.claims,
+            has_3_5mm_jack: sound.and_then(|s| yes_no(s.jack_3_5mm.as_deref()?)),
+            stereo_speakers: sound.and_then(|s| Some(s.loudspeaker.as_deref()?.to_lowercase().contains("stereo"))),
+            hi_res_audio: (!sound_values.is_empty()).then(|| sound_values.iter().any(|value| is_hi_res(value))),
+            bluetooth_codecs: bluetooth.map(bluetooth_codecs).unwrap_or_default(),
+            wifi_generation: wifi.as_ref().and_then(|w| w.generations.iter().filter_map(|g| g.trim_end_matches('E').parse().ok()).max()),
+            wifi_6ghz: wifi.as_ref().filter(|w| !w.generations.is_empty()).map(|w| w.generations.iter().any(|g| g == "6E" || g == "7")),
+            wifi_bands: wifi.as_ref().and_then(|w| w.bands),
+            wifi_generations: wifi.map(|w| w.generations).unwrap_or_default(),
+            bluetooth_version: bluetooth.and_then(|b| first_number(b, &LEADING_NUMBER)),
+            bluetooth_profiles: bluetooth.map(|b| known_terms(b, &BLUETOOTH_PROFILES)).unwrap_or_default(),
+            dual_frequency_gnss: gnss.as_ref().filter(|g| !g.systems.is_empty()).map(|g| g.dual_frequency),
+            gps_bands: gnss.as_ref().map(|g| g.gps_bands.clone()).unwrap_or_default(),
+            gnss_systems: gnss.map(|g| g.systems).unwrap_or_default(),
+            usb_connector: usb.as_ref().and_then(|u| u.connector.clone()),
+            usb_speed_gbps: usb.as_ref().and_then(|u| usb_speed_gbps(u.version.as_deref()?)),
+            usb_version: usb.as_ref().and_then(|u| u.version.clone()),
+            has_otg: usb.as_ref().map(|u| u.otg),
+            usb_displayport: usb.as_ref().map(|u| u.displayport),
+            sar_us_head,
+            sar_us_body,
+            sar_eu_head,
+            sar_eu_body,
+            color_families: color_families(&colors),
+            colors,
+            height_mm: pocket.map(|d| d.height_mm),
+            width_mm: pocket.map(|d| d.width_mm),
+            thickness_mm: pocket.map(|d| d.thickness_mm),
+            volume_cm3: pocket.map(|d| d.volume_cm3),
+            dimensions,
+            weight_variants,
+            battery_chemistry: battery_type.and_then(battery_chemistry),
+            battery_removable: battery_type.and_then(battery_removable),
+            battery_variants: battery_type.map(parse_battery_variants).unwrap_or_default(),
+        }
+    }
+
+    /// Newest OS version the device runs: the listed upgrade, else the shipped one
+    pub fn os_latest(&self) -> Option<OsVersion> {
+        self.os_upgradable_to.or(self.os_version)
+    }
+}
+
+/// Every value listed under a category of the raw specification, unlabelled rows included
+pub fn category_values<'a>(phone: &'a PhoneDocument, category: &str) -> Vec<&'a str> {
+    let categories = phone.specifications_raw.get("specification").and_then(|s| s.as_array());
+    categories
+        .into_iter()
+        .flatten()
+        .filter(|c| c.get("category_title").and_then(|t| t.as_str()).is_some_and(|t| t.eq_ignore_ascii_case(category)))
+        .filter_map(|c| c.get("category_spec")?.as_array())
+        .flatten()
+        .filter_map(|pair| pair.get(1)?.as_str())
+        .collect()
+}
+
+/// First number captured by `pattern`
+pub fn first_number(text: &str, pattern: &Regex) -> Option<f64> {
+    pattern.captures(text)?[1].parse().ok()
+}
+
+/// Largest number captured by `pattern`
+pub fn max_number(text: &str, pattern: &Regex) -> Option<f64> {
+    pattern
+        .captures_iter(text)
+        .filter_map(|c| c[1].parse::<f64>().ok())
+        .reduce(f64::max)
+}
+
+/// Largest storage option in "128GB 8GB RAM, 1TB 12GB RAM" (RAM sizes skipped)
+pub fn max_storage_gb(internal: &str) -> Option<f64> {
+    STORAGE
+        .captures_iter(internal)
+        .filter(|c| c.get(3).is_none())
+        .filter_map(|c| {
+            let size: f64 = c[1].parse().ok()?;
+            Some(if &c[2] == "TB" { size * 1024.0 } else { size })
+        })
+        .reduce(f64::max)
+}
+
+/// "Yes", "Yes (market dependent)" -> true, "No" -> false
+fn yes_no(text: &str) -> Option<bool> {
+    let text = text.trim().to_lowercase();
+    if text.starts_with("yes") {
+        Some(true)
+    } else if text.starts_with("no") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// USD and EUR amounts from "$ 799.99 / € 689.00 / £ 649.00" or "About 150 EUR"
+pub fn parse_price(text: &str) -> (Option<f64>, Option<f64>) {
+    let amount = |pattern: &Regex| pattern.captures(text).and_then(|c| c[1].replace(',', "").parse::<f64>().ok());
+
+    let usd = amount(&USD_SYMBOL).or_else(|| amount(&USD_ABOUT));
+    let eur = amount(&EUR_SYMBOL).or_else(|| amount(&EUR_ABOUT));
+    (usd, eur)
+}
+
+/// An OS version compared part by part, so 17.10 is newer than 17.9
+///
+/// Only major and minor are kept: "17.5.1" is 17.5.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[serde(from = "StoredOsVersion")]
+pub struct OsVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl OsVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl fmt::Display for OsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for OsVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid OS version '{}'", s);
+        let mut parts = s.trim().split('.');
+        let major = parts.next().unwrap_or_default().parse().map_err(|_| invalid())?;
+        let minor = match parts.next() {
+            Some(minor) => minor.parse().map_err(|_| invalid())?,
+            None => 0,
+        };
+        Ok(Self { major, minor })
+    }
+}
+
+/// Versions were stored as floats (13.0) before they became pairs; both still load
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredOsVersion {
+    Pair { major: u32, minor: u32 },
+    Float(f64),
+}
+
+impl From<StoredOsVersion> for OsVersion {
+    fn from(stored: StoredOsVersion) -> Self {
+        match stored {
+            StoredOsVersion::Pair { major, minor } => Self { major, minor },
+            StoredOsVersion::Float(version) => version.to_string().parse().unwrap_or_default(),
+        }
+    }
+}
+
+/// Name, shipped version and "upgradable to" version from Platform > OS
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Os {
+    pub name: Option<String>,
+    pub version: Option<OsVersion>,
+    pub upgradable_to: Option<OsVersion>,
+}
+
+/// Parse "Android 13, upgradable to Android 15, One UI 7"
+pub fn parse_os(os: &str) -> Os {
+    let shipped = os.split(',').next().unwrap_or_default().trim();
+    let version = VERSION.find(shipped);
+    let name = match version {
+        Some(version) => shipped[..version.start()].trim(),
+        None => shipped.split('(').next().unwrap_or_default().trim(),
+    };
+    Os {
+        name: (!name.is_empty()).then(|| name.to_string()),
+        version: version.and_then(|v| v.as_str().parse().ok()),
+        upgradable_to: UPGRADABLE_TO.captures_iter(os).filter_map(|c| c[1].parse().ok()).max(),
+    }
+}
+
+/// SIM count, card sizes, eSIM and dual stand-by from Body > SIM
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sim {
+    pub count: u32, // SIMs usable at once, eSIMs included
+    pub sizes: Vec<String>,
+    pub esim: bool,
+    pub dual_standby: bool,
+}
+
+/// Parse "Nano-SIM and eSIM or Dual SIM (Nano-SIM, dual stand-by)"; None for "No"
+pub fn parse_sim(sim: &str) -> Option<Sim> {
+    let lower = sim.to_lowercase();
+    if !lower.contains("sim") {
+        return None;
+    }
+
+    let mut sizes: Vec<String> = Vec::new();
+    for size in SIM_SIZE.captures_iter(&lower) {
+        if !sizes.contains(&size[1].to_string()) {
+            sizes.push(size[1].to_string());
+        }
+    }
+
+    // Every alternative ("X or Y") is one configuration; the count is the largest of them
+    let count = match first_number(&lower, &SIM_MAX) {
+        Some(max) => max as u32,
+        None => lower
+            .split(" or ")
+            .map(|option| {
+                let named = [("single", 1), ("dual", 2), ("triple", 3), ("quad", 4)]
+                    .iter()
+                    .find(|(word, _)| option.contains(&format!("{} sim", word)))
+                    .map(|&(_, count)| count);
+                let listed = option.split('(').next().unwrap_or_default().split(['+', ',']).flat_map(|part| part.split(" and ")).filter(|part| part.contains("sim")).count() as u32;
+                named.unwrap_or(0).max(listed)
+            })
+            .max()
+            .unwrap_or(1)
+            .max(1),
+    };
+
+    Some(Sim {
+        count,
+        sizes,
+        esim: lower.contains("esim"),
+        dual_standby: lower.contains("stand-by") || lower.contains("standby"),
+    })
+}
+
+/// Highest IP code in a line like "IP68 dust/water resistant (up to 6m for 30 min)"
+pub fn parse_ip_rating(text: &str) -> Option<String> {
+    IP_CODE
+        .captures_iter(text)
+        .map(|c| format!("IP{}{}", &c[1], &c[2]))
+        .max_by_key(|ip| (ip_digit(ip, 3), ip_digit(ip, 2)))
+}
+
+/// Digit of an IP code at `index` ("IP68": 2 is dust, 3 is water); None for "X"
+fn ip_digit(ip: &str, index: usize) -> Option<u32> {
+    ip.chars().nth(index)?.to_digit(10)
+}
+
+/// Protection name from Display > Protection: "Corning Gorilla Glass Victus 2, Mohs level 4" -> "Gorilla Glass Victus 2"
+fn protection_name(protection: &str) -> Option<String> {
+    let name = protection.split(',').next()?.trim().trim_start_matches("Corning ").trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Material and parenthesised detail of the Build part naming `side` ("front", "back", "frame")
+/// "Glass front (Gorilla Glass 5), plastic back, aluminum frame": "back" -> ("plastic", None)
+/// "Aluminum unibody" answers for the back and frame.
+pub fn build_part(build: &str, side: &str) -> Option<(String, Option<String>)> {
+    let part = |side: &str| BUILD_PARTS.iter().find(|(name, _)| *name == side).map(|(_, pattern)| pattern);
+    let captures = part(side)?.captures(build).or_else(|| (side != "front").then(|| part("unibody")?.captures(build)).flatten())?;
+    let detail = captures.get(2).map(|d| d.as_str().trim().trim_start_matches("Corning ").to_string()).filter(|d| !d.is_empty());
+    Some((captures[1].to_lowercase(), detail))
+}
+
+/// Charging figures from Battery > Charging, fastest of each kind
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Charging {
+    pub wired: Option<f64>,
+    pub wireless: Option<f64>,
+    pub reverse_wireless: Option<f64>,
+    pub claims: Vec<String>,
+}
+
+/// Parse "25W wired, PD3.0, 50% in 30 min (advertised)15W wireless (Qi/PMA)4.5W reverse wireless"
+///
+/// The page's line breaks are lost when cells are read, so a line starting right after
+/// ")" is split off first. Each clause is then classed by its wording; reverse wired
+/// charging is left out.
+pub fn parse_charging(charging: &str) -> Charging {
+    // Lines lost their breaks: "(advertised)15W wireless", "120W wired50W wireless"
+    let lines = CHARGING_LINE.replace_all(charging, "$1\n$2");
+    let mut parsed = Charging::default();
+    for clause in lines.split([',', '\n']) {
+        let Some(watts) = first_number(clause, &WATTS) else { continue };
+        let clause = clause.to_lowercase();
+        let slot = match (clause.contains("reverse"), clause.contains("wireless")) {
+            (true, true) => &mut parsed.reverse_wireless,
+            (true, false) => continue,
+            (false, true) => &mut parsed.wireless,
+            (false, false) => &mut parsed.wired,
+        };
+        *slot = Some(slot.map_or(watts, |current: f64| current.max(watts)));
+    }
+    parsed.claims = CHARGE_TIME
+        .find_iter(charging)
+        .map(|claim| claim.as_str().to_string())
+        .collect();
+    parsed
+}
+
+/// Codecs recognised in Comms > Bluetooth, most specific first so "aptX HD" is not also "aptX"
+static BLUETOOTH_CODECS: LazyLock<Vec<(&str, Regex)>> =
+    LazyLock::new(|| term_patterns(&["aptX Lossless", "aptX Adaptive", "aptX HD", "aptX LL", "aptX", "LDAC", "LHDC", "AAC", "LC3"]));
+
+/// Profiles recognised in Comms > Bluetooth, longest first like the codecs
+static BLUETOOTH_PROFILES: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| term_patterns(&["LE Audio", "A2DP", "AVRCP", "EDR", "HFP", "HSP", "HID", "LE"]));
+
+/// Codecs named in "5.3, A2DP, LE, aptX HD, aptX Adaptive, LDAC", in `BLUETOOTH_CODECS` order
+pub fn bluetooth_codecs(bluetooth: &str) -> Vec<String> {
+    known_terms(bluetooth, &BLUETOOTH_CODECS)
+}
+
+/// Each term with a whole-word pattern for its lowercase form
+fn term_patterns(terms: &[&'static str]) -> Vec<(&'static str, Regex)> {
+    terms.iter().map(|&term| (term, Regex::new(&format!(r"\b{}\b", regex::escape(&term.to_lowercase()))).unwrap())).collect()
+}
+
+/// Which of `terms` appear in `text` as whole words, in `terms` order; each match is
+/// removed before looking for the next term, so list longer terms first
+fn known_terms(text: &str, terms: &[(&str, Regex)]) -> Vec<String> {
+    let mut rest = text.to_lowercase();
+    let mut found = Vec::new();
+    for (term, pattern) in terms {
+        if pattern.is_match(&rest) {
+            rest = pattern.replace_all(&rest, "").into_owned();
+            found.push(term.to_string());
+        }
+    }
+    found
+}
+
+/// Satellite systems in Comms > Positioning, with the spellings GSMArena uses
+const GNSS_SYSTEMS: &[(&str, &[&str])] = &[
+    ("GPS", &["gps"]),
+    ("GLONASS", &["glonass"]),
+    ("Galileo", &["galileo"]),
+    ("BDS", &["bds", "beidou"]),
+    ("QZSS", &["qzss"]),
+    ("NavIC", &["navic", "irnss"]),
+];
+
+/// Constellations and bands from Comms > Positioning
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gnss {
+    pub systems: Vec<String>,
+    pub gps_bands: Vec<String>,
+    pub dual_frequency: bool,
+}
+
+/// Parse "GPS (L1+L5), GLONASS (G1), BDS (B1I+B1c+B2a), GALILEO (E1+E5a), QZSS (L1+L5), NavIC (L5)"
+pub fn parse_gnss(positioning: &str) -> Gnss {
+    let mut gnss = Gnss::default();
+    for captures in GNSS_SYSTEM.captures_iter(positioning) {
+        let name = captures[1].to_lowercase();
+        let Some((canonical, _)) = GNSS_SYSTEMS.iter().find(|(_, spellings)| spellings.contains(&name.as_str())) else { continue };
+        if !gnss.systems.iter().any(|s| s == canonical) {
+            gnss.systems.push(canonical.to_string());
+        }
+        let bands: Vec<&str> = captures.get(2).map(|b| b.as_str().split('+').map(str::trim).filter(|b| !b.is_empty()).collect()).unwrap_or_default();
+        gnss.dual_frequency |= bands.len() > 1;
+        if *canonical == "GPS" {
+            gnss.gps_bands = bands.iter().map(|b| b.to_uppercase()).collect();
+        }
+    }
+    gnss.dual_frequency |= DUAL_FREQUENCY.is_match(positioning);
+    gnss
+}
+
+/// Connector, version and extras from Comms > USB
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Usb {
+    pub connector: Option<String>,
+    pub version: Option<String>,
+    pub otg: bool,
+    pub displayport: bool,
+}
+
+/// Parse "USB Type-C 3.2 Gen 2, DisplayPort 1.2, OTG"
+pub fn parse_usb(usb: &str) -> Usb {
+    let lower = usb.to_lowercase();
+    let connector = [("type-c", "USB-C"), ("usb-c", "USB-C"), ("microusb", "microUSB"), ("micro-usb", "microUSB"), ("miniusb", "miniUSB"), ("mini-usb", "miniUSB"), ("lightning", "Lightning")]
+        .iter()
+        .find(|(spelling, _)| lower.contains(spelling))
+        .map(|(_, connector)| connector.to_string());
+    let version = if USB4.is_match(usb) {
+        Some("4".to_string())
+    } else {
+        USB_VERSION.captures(usb).map(|c| USB_GENERATION.replace(&c[1], " Gen ").into_owned())
+    };
+    Usb {
+        connector,
+        version,
+        otg: lower.contains("otg") || lower.contains("on-the-go"),
+        displayport: lower.contains("displayport") || lower.contains("video out"),
+    }
+}
+
+/// Signalling rate of a USB version: "2.0" -> 0.48, "3.2 Gen 2" -> 10
+/// Plain 3.x without a generation is taken as the 5 Gbps first generation.
+pub fn usb_speed_gbps(version: &str) -> Option<f64> {
+    let (number, generation) = version.split_once(" Gen ").unwrap_or((version, ""));
+    let speed = match (number, generation) {
+        ("4", _) => 40.0,
+        (_, "2x2") => 20.0,
+        ("3.1" | "3.2", "2") => 10.0,
+        (number, _) if number.starts_with('3') => 5.0,
+        ("2.0", _) => 0.48,
+        ("1.1", _) => 0.012,
+        _ => return None,
+    };
+    Some(speed)
+}
+
+/// Wi-Fi generations and band count from Comms > WLAN
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Wifi {
+    pub generations: Vec<String>,
+    pub bands: Option<u32>,
+}
+
+/// Parse "Wi-Fi 802.11 a/b/g/n/ac/6e, tri-band, Wi-Fi Direct"
+/// Letters are mapped to generations (n: 4, ac: 5, ax: 6, be: 7); pre-Wi-Fi 4 letters are dropped.
+pub fn parse_wifi(wlan: &str) -> Wifi {
+    let lower = wlan.to_lowercase();
+    let mut generations: Vec<String> = Vec::new();
+    if let Some(standards) = WIFI_STANDARDS.captures(&lower) {
+        for standard in standards[1].split('/') {
+            let generation = match standard.trim() {
+                "n" => "4",
+                "ac" => "5",
+                "ax" | "6" => "6",
+                "6e" => "6E",
+                "be" | "7" => "7",
+                _ => continue,
+            };
+            if !generations.iter().any(|g| g == generation) {
+                generations.push(generation.to_string());
+            }
+        }
+    }
+    let bands = if lower.contains("tri-band") {
+        Some(3)
+    } else if lower.contains("dual-band") {
+        Some(2)
+    } else {
+        None
+    };
+    Wifi { generations, bands }
+}
+
+/// A Sound line promising high-resolution playback
+fn is_hi_res(line: &str) -> bool {
+    let line = line.to_lowercase();
+    line.contains("hi-res") || line.contains("hi res") || HI_RES_BITS.is_match(&line)
+}
+
+/// Head and body values in W/kg from "1.19 W/kg (head)     0.99 W/kg (body)"
+/// Hotspot and limb figures are skipped.
+pub fn parse_sar(sar: &str) -> (Option<f64>, Option<f64>) {
+    (first_number(sar, &SAR_HEAD), first_number(sar, &SAR_BODY))
+}
+
+/// Color names from Misc > Colors, in listed order without duplicates
+pub fn parse_colors(colors: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in colors.split([',', ';']) {
+        let name = name.trim().trim_end_matches('.').trim();
+        if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Words that put a marketing color name in a family; checked in order, so
+/// "Titanium Black" is both "titanium" and "black"
+const COLOR_FAMILIES: &[(&str, &[&str])] = &[
+    ("black", &["black", "onyx", "obsidian", "midnight", "jet", "ebony", "charcoal", "noir"]),
+    ("white", &["white", "starlight", "pearl", "ivory", "snow", "cream", "porcelain"]),
+    ("gray", &["gray", "grey", "graphite", "slate"]),
+    ("silver", &["silver", "platinum", "chrome"]),
+    ("gold", &["gold", "champagne"]),
+    ("blue", &["blue", "navy", "ocean", "sky", "sapphire", "cyan", "teal", "azure", "ice"]),
+    ("green", &["green", "mint", "emerald", "jade", "olive", "sage", "lime"]),
+    ("red", &["red", "crimson", "burgundy", "ruby", "coral"]),
+    ("pink", &["pink", "rose", "blush", "peach"]),
+    ("purple", &["purple", "violet", "lavender", "lilac", "amethyst"]),
+    ("yellow", &["yellow", "lemon", "amber"]),
+    ("orange", &["orange"]),
+    ("brown", &["brown", "bronze", "copper", "mocha", "sand", "beige"]),
+    ("titanium", &["titanium"]),
+];
+
+/// Each family with one whole-word pattern matching any of its keywords
+static COLOR_FAMILY_PATTERNS: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
+    COLOR_FAMILIES.iter().map(|(family, keywords)| (*family, Regex::new(&format!(r"\b({})\b", keywords.join("|"))).unwrap())).collect()
+});
+
+/// Families of the named colors, in `COLOR_FAMILIES` order; names matching none are left out
+pub fn color_families(colors: &[String]) -> Vec<String> {
+    let words: Vec<String> = colors.iter().map(|c| c.to_lowercase()).collect();
+    COLOR_FAMILY_PATTERNS
+        .iter()
+        .filter(|(_, pattern)| words.iter().any(|name| pattern.is_match(name)))
+        .map(|(family, _)| family.to_string())
+        .collect()
+}
+
+/// Millimetre sizes from Body > Dimensions, one per "Unfolded:" / "Folded:" state
+/// "146.6 x 70.6 x 7.6 mm (5.77 x 2.78 x 0.30 in)" gives a single entry without a state.
+pub fn parse_dimensions(dimensions: &str) -> Vec<Dimensions> {
+    DIMENSIONS
+        .captures_iter(dimensions)
+        .filter_map(|c| {
+            let (height_mm, width_mm, thickness_mm): (f64, f64, f64) = (c[2].parse().ok()?, c[3].parse().ok()?, c[4].parse().ok()?);
+            Some(Dimensions {
+                state: c.get(1).map(|state| state.as_str().to_lowercase()),
+                height_mm,
+                width_mm,
+                thickness_mm,
+                volume_cm3: (height_mm * width_mm * thickness_mm / 100.0).round() / 10.0,
+            })
+        })
+        .collect()
+}
+
+/// Weights from Body > Weight: "221 g (Sub6), 233 g (mmWave) (7.80 oz)"
+/// Ounce conversions are not variant labels.
+pub fn parse_weights(weight: &str) -> Vec<WeightVariant> {
+    WEIGHT
+        .captures_iter(weight)
+        .filter_map(|c| {
+            Some(WeightVariant {
+                variant: c.get(2).map(|v| v.as_str().trim()).filter(|v| !v.is_empty() && !v.contains("oz")).map(str::to_string),
+                grams: c[1].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Cell chemistry in Battery > Type; silicon-carbon is checked first as it is also written "Si/C Li-Ion"
+pub fn battery_chemistry(battery: &str) -> Option<String> {
+    let lower = battery.to_lowercase();
+    let chemistry = if lower.contains("si/c") || lower.contains("silicon") {
+        "Si/C"
+    } else if lower.contains("li-po") || lower.contains("lipo") || lower.contains("polymer") {
+        "Li-Po"
+    } else if lower.contains("li-ion") || lower.contains("lithium") {
+        "Li-Ion"
+    } else if lower.contains("ni-mh") || lower.contains("nimh") {
+        "NiMH"
+    } else {
+        return None;
+    };
+    Some(chemistry.to_string())
+}
+
+/// "non-removable" -> false, "Removable Li-Ion 1500 mAh battery" -> true
+fn battery_removable(battery: &str) -> Option<bool> {
+    let lower = battery.to_lowercase();
+    if lower.contains("non-removable") || lower.contains("non removable") {
+        Some(false)
+    } else if lower.contains("removable") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Capacities from "Si/C 6000 mAh (China), 5500 mAh (Global), non-removable"
+/// Cell breakdowns such as "(2x2500 mAh)" are not capacities of their own.
+pub fn parse_battery_variants(battery: &str) -> Vec<BatteryVariant> {
+    BATTERY_CAPACITY
+        .captures_iter(battery)
+        .filter_map(|c| {
+            Some(BatteryVariant {
+                variant: c.get(2).map(|v| v.as_str().trim()).filter(|v| !v.is_empty() && !v.contains("mAh")).map(str::to_string),
+                mah: c[1].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Model numbers from "SM-S918B, SM-S918B/DS, SM-S918U", in listed order without duplicates
+/// Only a comma followed by a space separates, so Apple identifiers like "iPhone15,4" stay whole
+pub fn parse_model_numbers(models: &str) -> Vec<String> {
+    let mut numbers: Vec<String> = Vec::new();
+    for model in MODEL_SEPARATOR.split(models) {
+        let model = model.trim().trim_end_matches('.').trim();
+        if model.chars().any(|c| c.is_ascii_digit()) && !numbers.iter().any(|n| n.eq_ignore_ascii_case(model)) {
+            numbers.push(model.to_string());
+        }
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_protection() {
+        assert_eq!(parse_ip_rating("IP68 dust/water resistant (up to 6m for 30 min)"), Some("IP68".into()));
+        assert_eq!(parse_ip_rating("IP68/IP69 dust tight and water resistant"), Some("IP69".into()));
+        assert_eq!(parse_ip_rating("IP54, splash resistant"), Some("IP54".into()));
+        assert_eq!(parse_ip_rating("Apple Pay (Visa, MasterCard, AMEX certified)"), None);
+
+        let build = "Glass front (Corning Gorilla Glass Victus 2), glass back (Gorilla Glass Victus 2), titanium frame (grade 2)";
+        assert_eq!(build_part(build, "front"), Some(("glass".into(), Some("Gorilla Glass Victus 2".into()))));
+        assert_eq!(build_part(build, "back").map(|b| b.0), Some("glass".into()));
+        assert_eq!(build_part(build, "frame"), Some(("titanium".into(), Some("grade 2".into()))));
+        assert_eq!(build_part("Glass front, eco leather back, stainless steel frame", "back").map(|b| b.0), Some("eco leather".into()));
+        assert_eq!(build_part("Aluminum unibody", "frame").map(|b| b.0), Some("aluminum".into()));
+        assert_eq!(build_part("Aluminum unibody", "front"), None);
+        assert_eq!(protection_name("Corning Gorilla Glass Victus 2, Mohs level 4"), Some("Gorilla Glass Victus 2".into()));
+    }
+
+    #[test]
+    fn test_parse_charging() {
+        let galaxy = parse_charging("25W wired, PD3.0, 50% in 30 min (advertised)15W wireless (Qi/PMA)4.5W reverse wireless");
+        assert_eq!((galaxy.wired, galaxy.wireless, galaxy.reverse_wireless), (Some(25.0), Some(15.0), Some(4.5)));
+        assert_eq!(galaxy.claims, vec!["50% in 30 min"]);
+
+        let iphone = parse_charging("Wired, PD2.0, 50% in 30 min (advertised)15W wireless (MagSafe)15W wireless (Qi2)4.5W reverse wired");
+        assert_eq!((iphone.wired, iphone.wireless, iphone.reverse_wireless), (None, Some(15.0), None));
+
+        let older = parse_charging("Fast charging 18W, 100% in 1 hr (advertised), Fast wireless charging 10W");
+        assert_eq!((older.wired, older.wireless), (Some(18.0), Some(10.0)));
+        assert_eq!(older.claims, vec!["100% in 1 hr"]);
+        let glued = parse_charging("120W wired50W wireless");
+        assert_eq!((glued.wired, glued.wireless), (Some(120.0), Some(50.0)));
+        assert_eq!(parse_charging("Yes"), Charging::default());
+    }
+
+    #[test]
+    fn test_parse_audio() {
+        assert_eq!(bluetooth_codecs("5.3, A2DP, LE, aptX HD, aptX Adaptive, LDAC"), vec!["aptX Adaptive", "aptX HD", "LDAC"]);
+        assert_eq!(bluetooth_codecs("5.0, A2DP, LE, aptX"), vec!["aptX"]);
+        assert!(bluetooth_codecs("5.3, A2DP, LE").is_empty());
+        assert!(is_hi_res("32-bit/384kHz audio"));
+        assert!(is_hi_res("Hi-Res & Hi-Res Wireless audio"));
+        assert!(!is_hi_res("Tuned by AKG"));
+    }
+
+    #[test]
+    fn test_parse_connectivity() {
+        let wifi = parse_wifi("Wi-Fi 802.11 a/b/g/n/ac/6e, tri-band, Wi-Fi Direct");
+        assert_eq!(wifi.generations, vec!["4", "5", "6E"]);
+        assert_eq!(wifi.bands, Some(3));
+        assert_eq!(parse_wifi("Wi-Fi 802.11 a/b/g/n/ac/6/7, dual-band").generations, vec!["4", "5", "6", "7"]);
+        assert_eq!(parse_wifi("Wi-Fi 802.11 b/g/n, hotspot"), Wifi { generations: vec!["4".into()], bands: None });
+        assert_eq!(parse_wifi("No"), Wifi::default());
+        let gnss = parse_gnss("GPS (L1+L5), GLONASS (G1), BDS (B1I+B1c+B2a), GALILEO (E1+E5a), QZSS (L1+L5), NavIC (L5)");
+        assert_eq!(gnss.systems, vec!["GPS", "GLONASS", "BDS", "Galileo", "QZSS", "NavIC"]);
+        assert_eq!(gnss.gps_bands, vec!["L1", "L5"]);
+        assert!(gnss.dual_frequency);
+        let single = parse_gnss("GPS, A-GPS, GLONASS, BeiDou");
+        assert_eq!((single.systems, single.dual_frequency), (vec!["GPS".into(), "GLONASS".into(), "BDS".into()], false));
+        assert_eq!(parse_gnss("No"), Gnss::default());
+
+        let usb = parse_usb("USB Type-C 3.2 Gen 2, DisplayPort 1.2, OTG");
+        assert_eq!((usb.connector.as_deref(), usb.version.as_deref(), usb.otg, usb.displayport), (Some("USB-C"), Some("3.2 Gen 2"), true, true));
+        assert_eq!(usb_speed_gbps("3.2 Gen 2"), Some(10.0));
+        let micro = parse_usb("microUSB 2.0, USB On-The-Go");
+        assert_eq!((micro.connector.as_deref(), micro.version.as_deref(), micro.otg), (Some("microUSB"), Some("2.0"), true));
+        assert_eq!(parse_usb("Lightning, USB 2.0").version.as_deref(), Some("2.0"));
+        assert_eq!(usb_speed_gbps("3.1"), Some(5.0));
+
+        assert_eq!(known_terms("5.4, A2DP, LE, LE Audio, aptX HD", &BLUETOOTH_PROFILES), vec!["LE Audio", "A2DP", "LE"]);
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let colors = parse_colors("Titanium Gray, Titanium Black, Titanium Violet, Titanium Yellow, titanium gray");
+        assert_eq!(colors, vec!["Titanium Gray", "Titanium Black", "Titanium Violet", "Titanium Yellow"]);
+        assert_eq!(color_families(&colors), vec!["black", "gray", "purple", "yellow", "titanium"]);
+        assert_eq!(color_families(&parse_colors("Midnight, Starlight, Product Red")), vec!["black", "white", "red"]);
+        assert!(color_families(&parse_colors("Bora Purple")).contains(&"purple".to_string()));
+        assert!(color_families(&parse_colors("Hazel")).is_empty());
+    }
+
+    #[test]
+    fn test_parse_dimensions() {
+        let phone = parse_dimensions("146.6 x 70.6 x 7.6 mm (5.77 x 2.78 x 0.30 in)");
+        assert_eq!(phone, vec![Dimensions { state: None, height_mm: 146.6, width_mm: 70.6, thickness_mm: 7.6, volume_cm3: 78.7 }]);
+
+        // Line breaks are lost when cells are read
+        let fold = parse_dimensions("Unfolded: 153.5 x 132.6 x 5.6 mmFolded: 153.5 x 68.1 x 12.1 mm");
+        assert_eq!(fold.len(), 2);
+        assert_eq!((fold[0].state.as_deref(), fold[0].width_mm), (Some("unfolded"), 132.6));
+        assert_eq!((fold[1].state.as_deref(), fold[1].thickness_mm), (Some("folded"), 12.1));
+        assert!(parse_dimensions("-").is_empty());
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights = parse_weights("221 g (Sub6), 233 g (mmWave) (7.80 oz)");
+        assert_eq!(
+            weights,
+            vec![
+                WeightVariant { variant: Some("Sub6".into()), grams: 221.0 },
+                WeightVariant { variant: Some("mmWave".into()), grams: 233.0 },
+            ]
+        );
+        assert_eq!(parse_weights("167 g (5.89 oz)"), vec![WeightVariant { variant: None, grams: 167.0 }]);
+        assert_eq!(parse_weights("187 g or 190 g (6.60 oz)").len(), 2);
+        assert!(parse_weights("-").is_empty());
+    }
+
+    #[test]
+    fn test_parse_battery() {
+        let variants = parse_battery_variants("Si/C Li-Ion 6000 mAh (China), 5500 mAh (Global), non-removable");
+        assert_eq!(
+            variants,
+            vec![
+                BatteryVariant { variant: Some("China".into()), mah: 6000 },
+                BatteryVariant { variant: Some("Global".into()), mah: 5500 },
+            ]
+        );
+        assert_eq!(parse_battery_variants("Li-Ion 5000 mAh (2x2500 mAh), non-removable"), vec![BatteryVariant { variant: None, mah: 5000 }]);
+        assert_eq!(battery_chemistry("Si/C Li-Ion 6000 mAh"), Some("Si/C".into()));
+        assert_eq!(battery_chemistry("Li-Po 4500 mAh, non-removable"), Some("Li-Po".into()));
+        assert_eq!(battery_chemistry("Removable Li-Ion 1500 mAh battery"), Some("Li-Ion".into()));
+        assert_eq!(battery_removable("Li-Po 4500 mAh, non-removable"), Some(false));
+        assert_eq!(battery_removable("Removable Li-Ion 1500 mAh battery"), Some(true));
+        assert_eq!(battery_chemistry("5000 mAh"), None);
+    }
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(parse_price("$ 799.99 / € 689.00 / £ 649.00 / ₹ 79,900"), (Some(799.99), Some(689.0)));
+        assert_eq!(parse_price("About 150 EUR"), (None, Some(150.0)));
+        assert_eq!(parse_price("About 1,300 USD"), (Some(1300.0), None));
+        assert_eq!(parse_price("£ 649.00"), (None, None));
+        assert_eq!(max_storage_gb("128GB 8GB RAM, 1TB 12GB RAM"), Some(1024.0));
+        assert_eq!(yes_no("Yes (market/region dependent)"), Some(true));
+    }
+
+    #[test]
+    fn test_parse_os() {
+        let os = |name: &str, version: Option<(u32, u32)>, upgradable_to: Option<(u32, u32)>| Os {
+            name: Some(name.to_string()),
+            version: version.map(|(major, minor)| OsVersion::new(major, minor)),
+            upgradable_to: upgradable_to.map(|(major, minor)| OsVersion::new(major, minor)),
+        };
+        assert_eq!(parse_os("Android 13, upgradable to Android 15, One UI 7"), os("Android", Some((13, 0)), Some((15, 0))));
+        assert_eq!(parse_os("iOS 17, upgradable to iOS 18.3"), os("iOS", Some((17, 0)), Some((18, 3))));
+        assert_eq!(parse_os("Android 14, up to 4 major Android upgrades, HyperOS"), os("Android", Some((14, 0)), None));
+        assert_eq!(parse_os("Android 4.4.2 (KitKat)"), os("Android", Some((4, 4)), None));
+        assert_eq!(parse_os("Wear OS 4"), os("Wear OS", Some((4, 0)), None));
+        assert_eq!(parse_os("Proprietary OS"), os("Proprietary OS", None, None));
+
+        // 17.10 is newer than 17.9, not equal to 17.1
+        assert!(parse_os("iOS 17.10").version > parse_os("iOS 17.9").version);
+        assert_ne!(parse_os("iOS 17.10").version, parse_os("iOS 17.1").version);
+        assert_eq!(OsVersion::new(17, 5).to_string(), "17.5");
+
+        let stored: OsVersion = serde_json::from_value(serde_json::json!(18.1)).unwrap();
+        assert_eq!(stored, OsVersion::new(18, 1));
+        let stored: OsVersion = serde_json::from_value(serde_json::json!({ "major": 18, "minor": 10 })).unwrap();
+        assert_eq!(stored, OsVersion::new(18, 10));
+    }
+
+    #[test]
+    fn test_parse_model_numbers() {
+        assert_eq!(
+            parse_model_numbers("SM-S918B, SM-S918B/DS, SM-S918U; sm-s918b, SM-S9180."),
+            vec!["SM-S918B", "SM-S918B/DS", "SM-S918U", "SM-S9180"]
+        );
+        assert_eq!(parse_model_numbers("A2846, A3089, iPhone15,4"), vec!["A2846", "A3089", "iPhone15,4"]);
+        assert!(parse_model_numbers("Unknown").is_empty());
+    }
+
+    #[test]
+    fn test_parse_sar() {
+        assert_eq!(parse_sar("1.19 W/kg (head)     0.99 W/kg (body)"), (Some(1.19), Some(0.99)));
+        assert_eq!(parse_sar("0.98 W/kg (head)"), (Some(0.98), None));
+        assert_eq!(parse_sar("1.07 W/kg (body)     1.20 W/kg (hotspot)"), (None, Some(1.07)));
+    }
+
+    #[test]
+    fn test_parse_sim() {
+        let sim = |count, sizes: &[&str], esim, dual_standby| Some(Sim { count, sizes: sizes.iter().map(|s| s.to_string()).collect(), esim, dual_standby });
+        assert_eq!(parse_sim("Nano-SIM and eSIM or Dual SIM (Nano-SIM, dual stand-by)"), sim(2, &["nano"], true, true));
+        assert_eq!(parse_sim("Nano-SIM + Nano-SIM + eSIM + eSIM (max 2 at a time)"), sim(2, &["nano"], true, false));
+        assert_eq!(parse_sim("Single SIM (Micro-SIM)"), sim(1, &["micro"], false, false));
+        assert_eq!(parse_sim("Dual SIM (Mini-SIM/ Micro-SIM, dual stand-by)"), sim(2, &["mini", "micro"], false, true));
+        assert_eq!(parse_sim("Nano-SIM, eSIM"), sim(2, &["nano"], true, false));
+        assert_eq!(parse_sim("eSIM"), sim(1, &[], true, false));
+        assert_eq!(parse_sim("No"), None);
+    }
+}
@@ -0,0 +1,320 @@
+//! Webhook notifications for new phones and changes to tracked fields
+
+use crate::config::WebhookConfig;
+use crate::mongodb::PhoneDocument;
+use crate::secrets::env_secret;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::error::Error;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Header carrying `sha256=<hex HMAC of the body>` when `WEBHOOK_SECRET` is set
+pub const SIGNATURE_HEADER: &str = "X-GSMArena-Signature";
+/// Header carrying the event name, e.g. `phone.created`
+pub const EVENT_HEADER: &str = "X-GSMArena-Event";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PhoneEventKind {
+    #[serde(rename = "phone.created")]
+    Created,
+    #[serde(rename = "phone.changed")]
+    Changed,
+}
+
+impl PhoneEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhoneEventKind::Created => "phone.created",
+            PhoneEventKind::Changed => "phone.changed",
+        }
+    }
+}
+
+/// A tracked field whose value differs from the stored document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// What a webhook is told about one phone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhoneEvent {
+    pub event: PhoneEventKind,
+    pub phone_id: String,
+    pub name: String,
+    pub brand: String,
+    pub url: String,
+    pub image_url: Option<String>,
+    pub changes: Vec<FieldChange>, // Empty for phone.created
+}
+
+impl PhoneEvent {
+    /// Compare a freshly scraped document with the stored one (`None` = new phone)
+    /// Returns `None` when the phone exists and no tracked field changed
+    pub fn detect(previous: Option<&PhoneDocument>, current: &PhoneDocument, tracked_fields: &[String]) -> Option<Self> {
+        let (event, changes) = match previous {
+            None => (PhoneEventKind::Created, Vec::new()),
+            Some(previous) => {
+                let old_json = serde_json::to_value(previous).ok()?;
+                let new_json = serde_json::to_value(current).ok()?;
+
+                let changes: Vec<FieldChange> = tracked_fields
+                    .iter()
+                    .filter_map(|field| {
+                        let old = tracked_value(&old_json, field);
+                        let new = tracked_value(&new_json, field);
+                        (old != new).then(|| FieldChange { field: field.clone(), old, new })
+                    })
+                    .collect();
+
+                if changes.is_empty() {
+                    return None;
+                }
+                (PhoneEventKind::Changed, changes)
+            }
+        };
+
+        Some(Self {
+            event,
+            phone_id: current.phone_id.clone(),
+            name: current.name.clone(),
+            brand: current.brand.clone(),
+            url: current.url.clone(),
+            image_url: current.image_url.clone(),
+            changes,
+        })
+    }
+}
+
+impl PhoneEvent {
+    /// Event for a stored document updated elsewhere (a change stream), given the
+    /// dotted paths the update touched; the old values are unknown, so `old` is None
+    /// Returns `None` when no tracked field was touched
+    pub fn from_update(current: &PhoneDocument, updated_fields: &[String], tracked_fields: &[String]) -> Option<Self> {
+        let current_json = serde_json::to_value(current).ok()?;
+        let touched = |path: &str| {
+            updated_fields
+                .iter()
+                .any(|field| field == path || path.starts_with(&format!("{}.", field)) || field.starts_with(&format!("{}.", path)))
+        };
+
+        let changes: Vec<FieldChange> = tracked_fields
+            .iter()
+            .filter(|field| touched(tracked_path(field)))
+            .map(|field| FieldChange {
+                field: field.clone(),
+                old: None,
+                new: tracked_value(&current_json, field),
+            })
+            .collect();
+
+        if changes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            event: PhoneEventKind::Changed,
+            phone_id: current.phone_id.clone(),
+            name: current.name.clone(),
+            brand: current.brand.clone(),
+            url: current.url.clone(),
+            image_url: current.image_url.clone(),
+            changes,
+        })
+    }
+}
+
+/// Document path of a tracked field; "price" and "status" are shorthands for misc.price and launch.status
+fn tracked_path(field: &str) -> &str {
+    match field {
+        "price" => "misc.price",
+        "status" => "launch.status",
+        other => other,
+    }
+}
+
+/// Value of a tracked field
+fn tracked_value(document: &serde_json::Value, field: &str) -> Option<String> {
+    let path = tracked_path(field);
+
+    let pointer = format!("/{}", path.replace('.', "/"));
+    match document.pointer(&pointer)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(value) => Some(value.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Body POSTed to every webhook URL
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    run_id: &'a str,
+    sent_at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a PhoneEvent,
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `body`, as sent in `X-GSMArena-Signature`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delivers phone events to the configured webhook URLs
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    urls: Vec<String>,
+    secret: Option<String>,
+    tracked_fields: Vec<String>,
+    max_attempts: usize,
+}
+
+impl WebhookNotifier {
+    /// `None` when no webhook URLs are configured; the secret comes from `WEBHOOK_SECRET`
+    pub fn from_config(config: &WebhookConfig) -> Option<Self> {
+        if config.urls.is_empty() {
+            return None;
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("gsmarena-scraper/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Some(Self {
+            client,
+            urls: config.urls.clone(),
+            secret: env_secret("WEBHOOK_SECRET").unwrap_or_else(|e| {
+                warn!(error = %e, "Webhooks will not be signed");
+                None
+            }).filter(|s| !s.trim().is_empty()),
+            tracked_fields: config.tracked_fields.clone(),
+            max_attempts: config.max_attempts.max(1),
+        })
+    }
+
+    pub fn tracked_fields(&self) -> &[String] {
+        &self.tracked_fields
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.secret.is_some()
+    }
+
+    /// Send an event to every URL; failures are logged, never fatal to the run
+    /// Returns how many URLs could not be reached
+    pub async fn notify(&self, run_id: &str, event: &PhoneEvent) -> usize {
+        let payload = WebhookPayload { run_id, sent_at: Utc::now(), event };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(error = %e, "Cannot encode webhook payload");
+                return self.urls.len();
+            }
+        };
+
+        let mut failed = 0;
+        for url in &self.urls {
+            match self.deliver(url, event.event, &body).await {
+                Ok(()) => debug!(url = %url, event = event.event.as_str(), "✓ Webhook delivered"),
+                Err(e) => {
+                    warn!(url = %url, event = event.event.as_str(), phone = %event.phone_id, error = %e, "✗ Webhook failed");
+                    failed += 1;
+                }
+            }
+        }
+        failed
+    }
+
+    /// POST one body, retrying network errors, 429 and 5xx with exponential backoff
+    async fn deliver(&self, url: &str, kind: PhoneEventKind, body: &[u8]) -> Result<(), Box<dyn Error>> {
+        let mut last_error = String::new();
+
+        for attempt in 1..=self.max_attempts {
+            if attempt > 1 {
+                tokio::time::sleep(Duration::from_secs(1 << (attempt - 2).min(5))).await;
+            }
+
+            let mut request = self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, kind.as_str())
+                .body(body.to_vec());
+            if let Some(ref secret) = self.secret {
+                request = request.header(SIGNATURE_HEADER, sign(secret, body));
+            }
+
+            match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    last_error = format!("status {}", status);
+                    if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                        break; // The receiver rejected it; retrying won't help
+                    }
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+            debug!(url, attempt, error = %last_error, "Webhook attempt failed");
+        }
+
+        Err(last_error.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(price: &str) -> PhoneDocument {
+        PhoneDocument::fixture("samsung_galaxy_a55-12824")
+            .name("Galaxy A55")
+            .brand("Samsung")
+            .category("Launch", &[("Status", "Available. Released 2024, March 15")])
+            .category("Misc", &[("Price", price)])
+            .build()
+    }
+
+    #[test]
+    fn test_detect_events_and_sign() {
+        let tracked = vec!["price".to_string(), "status".to_string()];
+        let stored = phone("€ 399.00");
+
+        let created = PhoneEvent::detect(None, &stored, &tracked).unwrap();
+        assert_eq!(created.event, PhoneEventKind::Created);
+        assert!(PhoneEvent::detect(Some(&stored), &phone("€ 399.00"), &tracked).is_none());
+
+        let changed = PhoneEvent::detect(Some(&stored), &phone("€ 349.00"), &tracked).unwrap();
+        assert_eq!(changed.event, PhoneEventKind::Changed);
+        assert_eq!(
+            changed.changes,
+            vec![FieldChange {
+                field: "price".to_string(),
+                old: Some("€ 399.00".to_string()),
+                new: Some("€ 349.00".to_string()),
+            }]
+        );
+
+        // Change streams only say which paths an update touched
+        let updated = |fields: &[&str]| fields.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        let from_stream = PhoneEvent::from_update(&stored, &updated(&["misc.price", "updated_at"]), &tracked).unwrap();
+        assert_eq!(from_stream.event, PhoneEventKind::Changed);
+        assert_eq!(from_stream.changes.len(), 1);
+        assert_eq!((from_stream.changes[0].old.as_deref(), from_stream.changes[0].new.as_deref()), (None, Some("€ 399.00")));
+        assert_eq!(PhoneEvent::from_update(&stored, &updated(&["launch"]), &tracked).unwrap().changes[0].field, "status");
+        assert!(PhoneEvent::from_update(&stored, &updated(&["scraped_at", "normalized.price_eur"]), &tracked).is_none());
+
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
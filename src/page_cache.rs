@@ -0,0 +1,221 @@
+use crate::config::CacheConfig;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::header::{HeaderMap, HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Fetched HTML stored on disk, keyed by URL
+///
+/// Each page is one file named after the SHA-256 of its URL (`.html`, or
+/// `.html.gz` when compressed). Entries older than the TTL count as misses,
+/// but are kept with their validators (`.meta.json`) so they can be revalidated.
+#[derive(Debug, Clone)]
+pub struct PageCache {
+    dir: PathBuf,
+    ttl: Option<Duration>, // None = entries never expire
+    gzip: bool,
+}
+
+/// HTTP validators of a cached page, sent back as conditional request headers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name: HeaderName| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Add If-None-Match / If-Modified-Since to a request
+    pub fn apply(&self, mut request: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+        if let Some(ref etag) = self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref last_modified) = self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
+
+impl PageCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Option<Duration>, gzip: bool) -> Result<Self, Box<dyn Error>> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Cannot create page cache {}: {}", dir.display(), e))?;
+        Ok(Self { dir, ttl, gzip })
+    }
+
+    /// Cache described by the config, or `None` when `cache.dir` is unset
+    pub fn from_config(config: &CacheConfig) -> Result<Option<Self>, Box<dyn Error>> {
+        match config.dir {
+            Some(ref dir) => Ok(Some(Self::new(dir, config.ttl_secs.map(Duration::from_secs), config.gzip)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cached body of a URL, if present and fresh
+    pub fn get(&self, url: &str) -> Option<String> {
+        // Look at both forms so toggling gzip keeps existing entries usable
+        let (path, gzip) = [(self.path_for(url, self.gzip), self.gzip), (self.path_for(url, !self.gzip), !self.gzip)]
+            .into_iter()
+            .find(|(path, _)| self.is_fresh(path))?;
+
+        Self::read_body(&path, gzip)
+    }
+
+    /// Whether a fresh entry exists for the URL
+    pub fn contains(&self, url: &str) -> bool {
+        self.is_fresh(&self.path_for(url, self.gzip)) || self.is_fresh(&self.path_for(url, !self.gzip))
+    }
+
+    /// Validators of an entry (fresh or expired) whose body is still on disk
+    pub fn validators(&self, url: &str) -> Option<Validators> {
+        let contents = std::fs::read_to_string(self.meta_path_for(url)).ok()?;
+        let validators: Validators = serde_json::from_str(&contents).ok()?;
+        (!validators.is_empty() && self.stored_body(url).is_some()).then_some(validators)
+    }
+
+    /// Body of an entry regardless of its age (after a 304 Not Modified)
+    pub fn stored_body(&self, url: &str) -> Option<String> {
+        let (path, gzip) = [(self.path_for(url, self.gzip), self.gzip), (self.path_for(url, !self.gzip), !self.gzip)]
+            .into_iter()
+            .find(|(path, _)| path.exists())?;
+        Self::read_body(&path, gzip)
+    }
+
+    /// Mark an entry as fresh again after the server confirmed it is unchanged
+    pub fn touch(&self, url: &str) -> Result<(), Box<dyn Error>> {
+        for path in [self.path_for(url, self.gzip), self.path_for(url, !self.gzip)] {
+            if path.exists() {
+                std::fs::File::options().append(true).open(&path)?.set_modified(SystemTime::now())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store a page body with its validators, replacing any previous entry
+    pub fn put_with_validators(&self, url: &str, body: &str, validators: &Validators) -> Result<(), Box<dyn Error>> {
+        self.put(url, body)?;
+
+        let meta_path = self.meta_path_for(url);
+        if validators.is_empty() {
+            let _ = std::fs::remove_file(meta_path);
+        } else {
+            std::fs::write(meta_path, serde_json::to_string(validators)?)?;
+        }
+        Ok(())
+    }
+
+    /// Store a page body, replacing any previous entry
+    pub fn put(&self, url: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        let bytes = if self.gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body.as_bytes())?;
+            encoder.finish()?
+        } else {
+            body.as_bytes().to_vec()
+        };
+
+        // Write then rename so an interrupted run never leaves a truncated page behind
+        let path = self.path_for(url, self.gzip);
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(&partial, &path)?;
+
+        let _ = std::fs::remove_file(self.path_for(url, !self.gzip));
+        Ok(())
+    }
+
+    fn read_body(path: &Path, gzip: bool) -> Option<String> {
+        let bytes = std::fs::read(path).ok()?;
+        if gzip {
+            let mut body = String::new();
+            GzDecoder::new(bytes.as_slice()).read_to_string(&mut body).ok()?;
+            Some(body)
+        } else {
+            String::from_utf8(bytes).ok()
+        }
+    }
+
+    fn hash(url: &str) -> String {
+        format!("{:x}", Sha256::digest(url.as_bytes()))
+    }
+
+    fn path_for(&self, url: &str, gzip: bool) -> PathBuf {
+        let hash = Self::hash(url);
+        self.dir.join(if gzip { format!("{}.html.gz", hash) } else { format!("{}.html", hash) })
+    }
+
+    fn meta_path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.meta.json", Self::hash(url)))
+    }
+
+    fn is_fresh(&self, path: &Path) -> bool {
+        let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+            return false;
+        };
+
+        match self.ttl {
+            Some(ttl) => SystemTime::now().duration_since(modified).map_or(true, |age| age <= ttl),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("gsmarena-page-cache-{}", std::process::id()));
+        let url = "https://www.gsmarena.com/apple-phones-48.php";
+
+        let cache = PageCache::new(&dir, None, false).unwrap();
+        assert!(cache.get(url).is_none());
+        cache.put(url, "<html>Apple</html>").unwrap();
+        assert_eq!(cache.get(url).as_deref(), Some("<html>Apple</html>"));
+
+        // A gzip cache still serves (and then replaces) the plain entry
+        let gzip = PageCache::new(&dir, None, true).unwrap();
+        assert_eq!(gzip.get(url).as_deref(), Some("<html>Apple</html>"));
+        gzip.put(url, "<html>Apple v2</html>").unwrap();
+        assert_eq!(cache.get(url).as_deref(), Some("<html>Apple v2</html>"));
+        assert!(!cache.path_for(url, false).exists());
+
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        cache.put_with_validators(url, "<html>Apple v3</html>", &validators).unwrap();
+
+        // Expired entries are misses but keep their body and validators for revalidation
+        let expired = PageCache::new(&dir, Some(Duration::ZERO), true).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(!expired.contains(url));
+        assert_eq!(expired.validators(url), Some(validators));
+        assert_eq!(expired.stored_body(url).as_deref(), Some("<html>Apple v3</html>"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use crate::brand_scraper::{parse_phone_list_page, PhoneListItem};
+use crate::site::{page_url, DEFAULT_BASE_URL};
+use reqwest::blocking;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Device availability as used by Phone Finder and the Launch.status spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Availability {
+    Available,
+    ComingSoon,
+    Discontinued,
+    Rumored,
+    Cancelled,
+}
+
+impl Availability {
+    /// Value of the `sAvailabilities` Phone Finder parameter
+    pub fn finder_param(&self) -> u8 {
+        match self {
+            Availability::Available => 1,
+            Availability::ComingSoon => 2,
+            Availability::Discontinued => 3,
+            Availability::Rumored => 4,
+            Availability::Cancelled => 5,
+        }
+    }
+
+    /// Classify a Launch.status string
+    /// Example: "Available. Released 2023, September 22" -> Available
+    pub fn from_status(status: &str) -> Option<Self> {
+        let status = status.trim().to_lowercase();
+
+        if status.starts_with("available") {
+            Some(Availability::Available)
+        } else if status.starts_with("coming soon") {
+            Some(Availability::ComingSoon)
+        } else if status.starts_with("discontinued") {
+            Some(Availability::Discontinued)
+        } else if status.starts_with("rumored") || status.starts_with("rumoured") {
+            Some(Availability::Rumored)
+        } else if status.starts_with("cancelled") || status.starts_with("canceled") {
+            Some(Availability::Cancelled)
+        } else {
+            None
+        }
+    }
+}
+
+/// Newest network technology a device may have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NetworkFilter {
+    #[serde(rename = "5g-only")]
+    FiveGOnly,
+    #[serde(rename = "lte-only")]
+    LteOnly, // LTE, no 5G
+    #[serde(rename = "gsm-only")]
+    GsmOnly, // 2G only (GSM / CDMA), e.g. feature phones
+}
+
+impl NetworkFilter {
+    /// Phone Finder checkbox for the technology; results also include devices with newer ones
+    pub fn finder_param(&self) -> &'static str {
+        match self {
+            NetworkFilter::FiveGOnly => "chk5G",
+            NetworkFilter::LteOnly => "chk4G",
+            NetworkFilter::GsmOnly => "chk2G",
+        }
+    }
+
+    /// Whether a Network > Technology value passes the filter
+    /// Example: "GSM / HSPA / LTE" passes lte-only but not 5g-only or gsm-only
+    pub fn matches(&self, technology: &str) -> bool {
+        let technologies: Vec<String> = technology.split('/').map(|t| t.trim().to_uppercase()).filter(|t| !t.is_empty()).collect();
+        let has = |name: &str| technologies.iter().any(|t| t == name);
+        match self {
+            NetworkFilter::FiveGOnly => has("5G"),
+            NetworkFilter::LteOnly => has("LTE") && !has("5G"),
+            NetworkFilter::GsmOnly => !technologies.is_empty() && technologies.iter().all(|t| t == "GSM" || t == "CDMA"),
+        }
+    }
+}
+
+/// Query for GSMArena's Phone Finder (results.php3)
+#[derive(Debug, Clone, Default)]
+pub struct PhoneFinderQuery {
+    pub availabilities: Vec<Availability>,
+    pub makers: Vec<u32>,       // Maker IDs (`Brand::maker_id`); empty = every brand
+    pub year_min: Option<i32>,  // Announced in or after
+    pub year_max: Option<i32>,  // Announced in or before
+    pub network: Option<NetworkFilter>,
+}
+
+impl PhoneFinderQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict results to devices with the given availability (may be called repeatedly)
+    pub fn availability(mut self, availability: Availability) -> Self {
+        if !self.availabilities.contains(&availability) {
+            self.availabilities.push(availability);
+        }
+        self
+    }
+
+    /// Restrict results to one brand (may be called repeatedly)
+    pub fn maker(mut self, maker_id: u32) -> Self {
+        if !self.makers.contains(&maker_id) {
+            self.makers.push(maker_id);
+        }
+        self
+    }
+
+    /// Restrict results to devices announced from `min` to `max`, both included
+    pub fn years(mut self, min: i32, max: i32) -> Self {
+        self.year_min = Some(min);
+        self.year_max = Some(max);
+        self
+    }
+
+    /// Restrict results to devices with the filter's network technology (a superset, see `NetworkFilter`)
+    pub fn network(mut self, filter: NetworkFilter) -> Self {
+        self.network = Some(filter);
+        self
+    }
+
+    /// Build the results.php3 URL for this query
+    pub fn to_url(&self) -> String {
+        self.url_from(DEFAULT_BASE_URL)
+    }
+
+    /// Build the results.php3 URL on a mirror or test server
+    pub fn url_from(&self, base_url: &str) -> String {
+        let mut params = Vec::new();
+
+        if !self.availabilities.is_empty() {
+            let values = self
+                .availabilities
+                .iter()
+                .map(|a| a.finder_param().to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            params.push(format!("sAvailabilities={}", values));
+        }
+        if !self.makers.is_empty() {
+            let values = self.makers.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+            params.push(format!("sMakers={}", values));
+        }
+        if let Some(year) = self.year_min {
+            params.push(format!("nYearMin={}", year));
+        }
+        if let Some(year) = self.year_max {
+            params.push(format!("nYearMax={}", year));
+        }
+        if let Some(network) = self.network {
+            params.push(format!("{}=selected", network.finder_param()));
+        }
+
+        page_url(base_url, &format!("results.php3?{}", params.join("&")))
+    }
+}
+
+/// Run a Phone Finder query and return the matching devices
+pub fn search_phone_finder(query: &PhoneFinderQuery) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
+    search_phone_finder_from(DEFAULT_BASE_URL, query)
+}
+
+/// Run a Phone Finder query against a mirror or test server
+pub fn search_phone_finder_from(base_url: &str, query: &PhoneFinderQuery) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
+    search_phone_finder_with(base_url, query, |url| {
+        let response = blocking::get(url)?;
+        if !response.status().is_success() {
+            return Err(format!("Phone Finder returned status: {}", response.status()).into());
+        }
+        Ok(response.text()?)
+    })
+}
+
+/// Run a Phone Finder query using a custom page fetcher (proxy, ScrapingBee, ...)
+pub fn search_phone_finder_with<F>(base_url: &str, query: &PhoneFinderQuery, fetch: F) -> Result<Vec<PhoneListItem>, Box<dyn Error>>
+where
+    F: FnOnce(&str) -> Result<String, Box<dyn Error>>,
+{
+    Ok(parse_phone_list_page(&fetch(&query.url_from(base_url))?))
+}
+
+impl FromStr for NetworkFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "5g-only" | "5g" => Ok(NetworkFilter::FiveGOnly),
+            "lte-only" | "lte" | "4g" => Ok(NetworkFilter::LteOnly),
+            "gsm-only" | "gsm" | "2g" => Ok(NetworkFilter::GsmOnly),
+            other => Err(format!("unknown network filter '{}' (5g-only, lte-only, gsm-only)", other)),
+        }
+    }
+}
+
+impl fmt::Display for NetworkFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NetworkFilter::FiveGOnly => "5g-only",
+            NetworkFilter::LteOnly => "lte-only",
+            NetworkFilter::GsmOnly => "gsm-only",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_availability_from_status() {
+        assert_eq!(Availability::from_status("Available. Released 2023, September 22"), Some(Availability::Available));
+        assert_eq!(Availability::from_status("Coming soon. Exp. release 2025, March"), Some(Availability::ComingSoon));
+        assert_eq!(Availability::from_status("Rumored"), Some(Availability::Rumored));
+        assert_eq!(Availability::from_status("Unknown"), None);
+    }
+
+    #[test]
+    fn test_query_url() {
+        let query = PhoneFinderQuery::new()
+            .availability(Availability::Rumored)
+            .availability(Availability::ComingSoon);
+        assert_eq!(query.to_url(), "https://www.gsmarena.com/results.php3?sAvailabilities=4,2");
+
+        let query = PhoneFinderQuery::new().maker(48).years(2024, 2024);
+        assert_eq!(query.to_url(), "https://www.gsmarena.com/results.php3?sMakers=48&nYearMin=2024&nYearMax=2024");
+
+        let query = PhoneFinderQuery::new().maker(48).network(NetworkFilter::FiveGOnly);
+        assert_eq!(query.to_url(), "https://www.gsmarena.com/results.php3?sMakers=48&chk5G=selected");
+    }
+
+    #[test]
+    fn test_network_filter() {
+        let lte = "GSM / HSPA / LTE";
+        let five_g = "GSM / CDMA / HSPA / EVDO / LTE / 5G";
+        assert!(NetworkFilter::LteOnly.matches(lte) && !NetworkFilter::LteOnly.matches(five_g));
+        assert!(NetworkFilter::FiveGOnly.matches(five_g) && !NetworkFilter::FiveGOnly.matches(lte));
+        assert!(NetworkFilter::GsmOnly.matches("GSM") && !NetworkFilter::GsmOnly.matches(lte) && !NetworkFilter::GsmOnly.matches(""));
+        assert_eq!("LTE".parse::<NetworkFilter>().unwrap(), NetworkFilter::LteOnly);
+        assert_eq!(NetworkFilter::FiveGOnly.to_string(), "5g-only");
+        assert!("wifi-only".parse::<NetworkFilter>().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Store prices per region from the pricing widget of a phone page
+//!
+//! Misc > Price is one launch price. The pricing widget lists what stores currently ask,
+//! one table per region with a column per variant. Every scrape parses it into
+//! `PhoneDocument::market_prices`; with `collections.price_history` set, each scrape also
+//! appends a `PriceSnapshot`, so prices can be charted over time even while the
+//! specifications (and so the stored document) stay the same.
+
+use crate::mongodb::PhoneDocument;
+use crate::selectors::SelectorChain;
+use crate::site::{page_url, DEFAULT_BASE_URL};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// One store's price for one variant in one region
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketPrice {
+    pub store: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>, // Table caption or data-region, e.g. "United States"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>, // Column header, e.g. "128GB 6GB RAM"
+    pub price: String, // As shown, e.g. "$ 729.00"
+    pub amount: Option<f64>,
+    pub currency: Option<String>, // ISO code when the symbol is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>, // Store offer
+    pub scraped_at: DateTime<Utc>,
+}
+
+/// All prices of one phone from one scrape (a `collections.price_history` document)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceSnapshot {
+    pub phone_id: String,
+    pub brand: String,
+    pub scraped_at: DateTime<Utc>,
+    pub prices: Vec<MarketPrice>,
+}
+
+impl PriceSnapshot {
+    /// None when the phone has no market prices
+    pub fn of(phone: &PhoneDocument) -> Option<Self> {
+        let first = phone.market_prices.first()?;
+        Some(Self {
+            phone_id: phone.phone_id.clone(),
+            brand: phone.brand.clone(),
+            scraped_at: first.scraped_at,
+            prices: phone.market_prices.clone(),
+        })
+    }
+}
+
+/// Parse the pricing widget of a phone page; empty when the page has none
+pub fn parse_market_prices(html: &str) -> Vec<MarketPrice> {
+    let document = Html::parse_document(html);
+    let scraped_at = Utc::now();
+    let mut prices = Vec::new();
+
+    for table in SelectorChain::get("price_tables").select_in(&document) {
+        let region = table
+            .value()
+            .attr("data-region")
+            .map(str::to_string)
+            .or_else(|| SelectorChain::get("price_region").first(table).map(text))
+            .filter(|region| !region.is_empty());
+        let variants: Vec<String> = SelectorChain::get("price_variants").select(table).into_iter().map(text).collect();
+
+        for row in SelectorChain::get("price_rows").select(table) {
+            let cells: Vec<ElementRef> = row.children().filter_map(ElementRef::wrap).filter(|cell| matches!(cell.value().name(), "td" | "th")).collect();
+            let Some((store_cell, offers)) = cells.split_first() else {
+                continue;
+            };
+            let Some(store) = store_name(*store_cell) else {
+                continue;
+            };
+
+            for (column, cell) in offers.iter().enumerate() {
+                let price = text(*cell);
+                if !price.chars().any(|c| c.is_ascii_digit()) {
+                    continue; // "-" or empty: not sold in this variant
+                }
+                let (amount, currency) = parse_amount(&price);
+                let url = SelectorChain::get("price_links")
+                    .first(*cell)
+                    .and_then(|link| link.value().attr("href"))
+                    .map(absolute_url);
+                prices.push(MarketPrice {
+                    store: store.clone(),
+                    region: region.clone(),
+                    // Header cells line up with the row's, the first one over the store column
+                    variant: (variants.len() == cells.len()).then(|| variants[column + 1].clone()),
+                    price,
+                    amount,
+                    currency,
+                    url,
+                    scraped_at,
+                });
+            }
+        }
+    }
+
+    prices
+}
+
+static AMOUNT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d[\d,\s\u{a0}]*(?:\.\d+)?").unwrap());
+static CURRENCY_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b([A-Z]{3})\b").unwrap());
+
+/// Amount and ISO currency of a displayed price
+/// "$ 729.00" -> (729.0, "USD"), "₹ 79,900" -> (79900.0, "INR"), "1 099 CHF" -> (1099.0, "CHF")
+pub fn parse_amount(price: &str) -> (Option<f64>, Option<String>) {
+    const SYMBOLS: [(&str, &str); 7] = [("US$", "USD"), ("$", "USD"), ("€", "EUR"), ("£", "GBP"), ("₹", "INR"), ("¥", "JPY"), ("₩", "KRW")];
+
+    let amount = AMOUNT
+        .find(price)
+        .and_then(|m| m.as_str().chars().filter(|c| c.is_ascii_digit() || *c == '.').collect::<String>().parse().ok());
+    let currency = SYMBOLS
+        .iter()
+        .find(|(symbol, _)| price.contains(symbol))
+        .map(|(_, code)| code.to_string())
+        .or_else(|| CURRENCY_CODE.captures(price).map(|c| c[1].to_string()));
+    (amount, currency)
+}
+
+/// Stores are shown as a logo (alt text) or a name
+fn store_name(cell: ElementRef) -> Option<String> {
+    let name = SelectorChain::get("price_store_logo")
+        .first(cell)
+        .and_then(|logo| logo.value().attr("alt"))
+        .map(|alt| alt.trim().to_string())
+        .filter(|alt| !alt.is_empty())
+        .unwrap_or_else(|| text(cell));
+    (!name.is_empty()).then_some(name)
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn absolute_url(href: &str) -> String {
+    if href.starts_with("http") {
+        href.to_string()
+    } else if let Some(rest) = href.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else {
+        page_url(DEFAULT_BASE_URL, href)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDGET: &str = r#"<div id="specs-list"></div>
+        <div id="pricing-widget">
+          <table class="pricing" data-region="United States">
+            <thead><tr><th>Store</th><th>128GB 6GB RAM</th><th>256GB 6GB RAM</th></tr></thead>
+            <tbody>
+              <tr><td><img src="amazon.png" alt="Amazon"></td><td><a href="https://amazon.com/dp/1">$ 699.00</a></td><td><a href="//amazon.com/dp/2">$ 799.00</a></td></tr>
+              <tr><td>Best Buy</td><td><a href="/go/bestbuy-1">$ 729.99</a></td><td>-</td></tr>
+            </tbody>
+          </table>
+          <table class="pricing">
+            <caption>India</caption>
+            <tbody><tr><td><img alt="Flipkart"></td><td>₹ 79,900</td></tr></tbody>
+          </table>
+        </div>"#;
+
+    #[test]
+    fn test_parse_market_prices() {
+        let prices = parse_market_prices(WIDGET);
+        assert_eq!(prices.len(), 4, "{:#?}", prices);
+
+        let amazon = &prices[1];
+        assert_eq!((amazon.store.as_str(), amazon.region.as_deref(), amazon.variant.as_deref()), ("Amazon", Some("United States"), Some("256GB 6GB RAM")));
+        assert_eq!((amazon.amount, amazon.currency.as_deref()), (Some(799.0), Some("USD")));
+        assert_eq!(amazon.url.as_deref(), Some("https://amazon.com/dp/2"));
+
+        let best_buy = &prices[2];
+        assert_eq!((best_buy.store.as_str(), best_buy.price.as_str()), ("Best Buy", "$ 729.99"));
+        assert_eq!(best_buy.url.as_deref(), Some("https://www.gsmarena.com/go/bestbuy-1"));
+
+        let flipkart = &prices[3];
+        assert_eq!((flipkart.region.as_deref(), flipkart.variant.as_deref(), flipkart.url.as_deref()), (Some("India"), None, None));
+        assert_eq!((flipkart.amount, flipkart.currency.as_deref()), (Some(79900.0), Some("INR")));
+
+        assert_eq!(parse_amount("1 099 CHF"), (Some(1099.0), Some("CHF".to_string())));
+        assert!(parse_market_prices("<table><tr><td>Amazon</td><td>$ 1</td></tr></table>").is_empty());
+    }
+}
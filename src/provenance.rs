@@ -0,0 +1,243 @@
+//! Where each parsed value came from, stored as `PhoneDocument.provenance` when enabled
+//!
+//! A compact map from field path ("display/size", "normalized/battery_mah") to the raw
+//! rows it was read from ("Display > Size", "Battery > Type"), plus the parser version.
+//! Paths use "/" because MongoDB keys cannot contain dots. Only fields that hold a value
+//! are listed.
+
+use crate::mongodb::PhoneDocument;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bumped whenever `parse_specifications` or `normalize` read different rows
+pub const PARSER_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub parser_version: u32,
+    pub fields: BTreeMap<String, String>, // "display/size" -> "Display > Size"
+}
+
+/// Structured field -> raw category and the keys it is read from, first present wins
+const STRUCTURED: &[(&str, &str, &[&str])] = &[
+    ("network/technology", "Network", &["technology"]),
+    ("network/bands_2g", "Network", &["2g bands"]),
+    ("network/bands_3g", "Network", &["3g bands"]),
+    ("network/bands_4g", "Network", &["4g bands"]),
+    ("network/bands_5g", "Network", &["5g bands"]),
+    ("network/speed", "Network", &["speed"]),
+    ("launch/announced", "Launch", &["announced"]),
+    ("launch/status", "Launch", &["status"]),
+    ("body/dimensions", "Body", &["dimensions"]),
+    ("body/weight", "Body", &["weight"]),
+    ("body/build", "Body", &["build"]),
+    ("body/sim", "Body", &["sim"]),
+    ("display/display_type", "Display", &["type"]),
+    ("display/size", "Display", &["size"]),
+    ("display/resolution", "Display", &["resolution"]),
+    ("display/protection", "Display", &["protection"]),
+    ("platform/os", "Platform", &["os"]),
+    ("platform/chipset", "Platform", &["chipset"]),
+    ("platform/cpu", "Platform", &["cpu"]),
+    ("platform/gpu", "Platform", &["gpu"]),
+    ("memory/card_slot", "Memory", &["card slot"]),
+    ("memory/internal", "Memory", &["internal"]),
+    ("main_camera/modules", "Main Camera", &["single", "dual", "triple", "quad", "penta"]),
+    ("main_camera/features", "Main Camera", &["features"]),
+    ("main_camera/video", "Main Camera", &["video"]),
+    ("selfie_camera/modules", "Selfie camera", &["single", "dual"]),
+    ("selfie_camera/features", "Selfie camera", &["features"]),
+    ("selfie_camera/video", "Selfie camera", &["video"]),
+    ("sound/loudspeaker", "Sound", &["loudspeaker"]),
+    ("sound/jack_3_5mm", "Sound", &["3.5mm jack"]),
+    ("comms/wlan", "Comms", &["wlan"]),
+    ("comms/bluetooth", "Comms", &["bluetooth"]),
+    ("comms/positioning", "Comms", &["positioning"]),
+    ("comms/nfc", "Comms", &["nfc"]),
+    ("comms/radio", "Comms", &["radio"]),
+    ("comms/usb", "Comms", &["usb"]),
+    ("features/sensors", "Features", &["sensors"]),
+    ("battery/battery_type", "Battery", &["type"]),
+    ("battery/charging", "Battery", &["charging"]),
+    ("misc/colors", "Misc", &["colors"]),
+    ("misc/models", "Misc", &["models"]),
+    ("misc/sar", "Misc", &["sar"]),
+    ("misc/sar_eu", "Misc", &["sar eu"]),
+    ("misc/price", "Misc", &["price"]),
+];
+
+/// Normalized field -> the structured fields (or whole categories, as "Body > *") it is parsed from
+const NORMALIZED: &[(&str, &[&str])] = &[
+    ("announced_year", &["launch/announced"]),
+    ("display_size_in", &["display/size"]),
+    ("brightness_nits", &["display/display_type"]),
+    ("refresh_rate_hz", &["display/display_type"]),
+    ("ram_gb", &["memory/internal"]),
+    ("storage_gb", &["memory/internal"]),
+    ("main_camera_mp", &["main_camera/modules"]),
+    ("battery_mah", &["battery/battery_type"]),
+    ("charging_w", &["battery/charging"]),
+    ("weight_g", &["body/weight"]),
+    ("has_nfc", &["comms/nfc"]),
+    ("has_5g", &["network/technology"]),
+    ("price_usd", &["misc/price"]),
+    ("price_eur", &["misc/price"]),
+    ("model_numbers", &["misc/models"]),
+    ("os_name", &["platform/os"]),
+    ("os_version", &["platform/os"]),
+    ("os_upgradable_to", &["platform/os"]),
+    ("sim_count", &["body/sim"]),
+    ("sim_sizes", &["body/sim"]),
+    ("has_esim", &["body/sim"]),
+    ("dual_standby", &["body/sim"]),
+    ("ip_rating", &["Body > *"]),
+    ("ip_water", &["Body > *"]),
+    ("front_protection", &["display/protection", "body/build"]),
+    ("back_material", &["body/build"]),
+    ("frame_material", &["body/build"]),
+    ("wired_watts", &["battery/charging"]),
+    ("wireless_watts", &["battery/charging"]),
+    ("reverse_wireless_watts", &["battery/charging"]),
+    ("charge_time_claims", &["battery/charging"]),
+    ("has_3_5mm_jack", &["sound/jack_3_5mm"]),
+    ("stereo_speakers", &["sound/loudspeaker"]),
+    ("hi_res_audio", &["Sound > *"]),
+    ("bluetooth_codecs", &["comms/bluetooth"]),
+    ("wifi_generations", &["comms/wlan"]),
+    ("wifi_generation", &["comms/wlan"]),
+    ("wifi_6ghz", &["comms/wlan"]),
+    ("wifi_bands", &["comms/wlan"]),
+    ("bluetooth_version", &["comms/bluetooth"]),
+    ("bluetooth_profiles", &["comms/bluetooth"]),
+    ("gnss_systems", &["comms/positioning"]),
+    ("gps_bands", &["comms/positioning"]),
+    ("dual_frequency_gnss", &["comms/positioning"]),
+    ("usb_connector", &["comms/usb"]),
+    ("usb_version", &["comms/usb"]),
+    ("usb_speed_gbps", &["comms/usb"]),
+    ("has_otg", &["comms/usb"]),
+    ("usb_displayport", &["comms/usb"]),
+    ("sar_us_head", &["misc/sar"]),
+    ("sar_us_body", &["misc/sar"]),
+    ("sar_eu_head", &["misc/sar_eu"]),
+    ("sar_eu_body", &["misc/sar_eu"]),
+    ("colors", &["misc/colors"]),
+    ("color_families", &["misc/colors"]),
+    ("height_mm", &["body/dimensions"]),
+    ("width_mm", &["body/dimensions"]),
+    ("thickness_mm", &["body/dimensions"]),
+    ("volume_cm3", &["body/dimensions"]),
+    ("dimensions", &["body/dimensions"]),
+    ("weight_variants", &["body/weight"]),
+    ("battery_chemistry", &["battery/battery_type"]),
+    ("battery_removable", &["battery/battery_type"]),
+    ("battery_variants", &["battery/battery_type"]),
+];
+
+impl Provenance {
+    /// Trace every populated structured and normalized field back to its raw rows
+    pub fn trace(phone: &PhoneDocument) -> Self {
+        let document = serde_json::to_value(phone).unwrap_or_default();
+        let mut fields = BTreeMap::new();
+
+        for (path, category, keys) in STRUCTURED {
+            if !has_value(document.pointer(&format!("/{}", path))) {
+                continue;
+            }
+            if let Some(row) = keys.iter().find_map(|key| raw_row(&phone.specifications_raw, category, key)) {
+                fields.insert(path.to_string(), row);
+            }
+        }
+
+        for (field, sources) in NORMALIZED {
+            if !has_value(document.pointer(&format!("/normalized/{}", field))) {
+                continue;
+            }
+            let rows: Vec<&str> = sources
+                .iter()
+                .filter_map(|source| if source.contains(" > ") { Some(*source) } else { fields.get(*source).map(String::as_str) })
+                .collect();
+            if !rows.is_empty() {
+                fields.insert(format!("normalized/{}", field), rows.join(", "));
+            }
+        }
+
+        Provenance { parser_version: PARSER_VERSION, fields }
+    }
+
+    /// Raw rows a field was read from ("normalized/battery_mah" -> "Battery > Type")
+    pub fn source(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(String::as_str)
+    }
+
+    /// Whether this was recorded by an older parser than the running one
+    pub fn is_stale(&self) -> bool {
+        self.parser_version < PARSER_VERSION
+    }
+}
+
+fn has_value(value: Option<&serde_json::Value>) -> bool {
+    match value {
+        None | Some(serde_json::Value::Null) => false,
+        Some(serde_json::Value::Array(items)) => !items.is_empty(),
+        Some(_) => true,
+    }
+}
+
+/// "Category > Key" with the page's own spelling, when the raw specification has that row
+fn raw_row(raw_specs: &serde_json::Value, category: &str, key: &str) -> Option<String> {
+    raw_specs
+        .get("specification")?
+        .as_array()?
+        .iter()
+        .filter(|c| c.get("category_title").and_then(|t| t.as_str()).is_some_and(|t| t.eq_ignore_ascii_case(category)))
+        .find_map(|c| {
+            let title = c.get("category_title")?.as_str()?;
+            let raw_key = c
+                .get("category_spec")?
+                .as_array()?
+                .iter()
+                .filter_map(|pair| pair.get(0)?.as_str())
+                .find(|raw_key| raw_key.eq_ignore_ascii_case(key))?;
+            Some(format!("{} > {}", title, raw_key))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        let mut phone = PhoneDocument::fixture("test-1")
+            .name("Galaxy S24 Ultra")
+            .brand("Samsung")
+            .category("Body", &[
+                ("Build", "Glass front (Gorilla Armor), titanium frame"),
+                ("\u{a0}", "IP68 dust/water resistant (up to 1.5m for 30 min)"),
+            ])
+            .category("Display", &[("Size", "6.8 inches"), ("Protection", "Corning Gorilla Armor")])
+            .category("Main Camera", &[("Quad", "200 MP, f/1.7, 24mm (wide)")])
+            .category("Battery", &[("Type", "Li-Ion 5000 mAh, non-removable")])
+            .build();
+        assert!(phone.provenance.is_none());
+        phone.record_provenance();
+
+        let provenance = phone.provenance.as_ref().unwrap();
+        assert_eq!(provenance.parser_version, PARSER_VERSION);
+        assert!(!provenance.is_stale());
+        assert_eq!(provenance.source("display/size"), Some("Display > Size"));
+        assert_eq!(provenance.source("main_camera/modules"), Some("Main Camera > Quad"));
+        assert_eq!(provenance.source("normalized/main_camera_mp"), Some("Main Camera > Quad"));
+        assert_eq!(provenance.source("normalized/battery_mah"), Some("Battery > Type"));
+        assert_eq!(provenance.source("normalized/front_protection"), Some("Display > Protection, Body > Build"));
+        assert_eq!(provenance.source("normalized/ip_rating"), Some("Body > *"));
+        assert_eq!(provenance.source("display/resolution"), None);
+        assert_eq!(provenance.source("normalized/has_nfc"), None);
+
+        phone.specifications_raw["specification"][1]["category_spec"] = serde_json::json!([["Size", "6.8 inches"]]);
+        phone.reparse();
+        let provenance = phone.provenance.as_ref().unwrap();
+        assert_eq!(provenance.source("normalized/front_protection"), Some("Body > Build"));
+    }
+}
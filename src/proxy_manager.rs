@@ -3,6 +3,10 @@ use std::sync::{Arc, Mutex};
 use rand::seq::SliceRandom;
 use reqwest::blocking::Client as ReqwestClient;
 use reqwest::Proxy;
+use crate::config::ProxySettings;
+use crate::secrets::required_secret;
+use crate::user_agents::UserAgentPool;
+use tracing::info;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyDocument {
@@ -60,19 +64,32 @@ pub struct ProxyManager {
     api_key: String,
     database_id: String,
     collection_id: String,
+    user_agents: UserAgentPool,
 }
 
 impl ProxyManager {
     /// Create a new ProxyManager from environment variables
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
         let project_id = std::env::var("APPWRITE_PROJECT_ID")?;
-        let api_key = std::env::var("APPWRITE_API_KEY")?;
+        let api_key = required_secret("APPWRITE_API_KEY")?;
         let database_id = std::env::var("APPWRITE_DATABASE_ID")?;
         let collection_id = std::env::var("APPWRITE_COLLECTION_ID")?;
 
         Ok(Self::new(project_id, api_key, database_id, collection_id))
     }
 
+    /// Create a ProxyManager from configured Appwrite IDs (API key from `APPWRITE_API_KEY` or `APPWRITE_API_KEY_FILE`)
+    pub fn from_settings(settings: &ProxySettings) -> Result<Self, Box<dyn std::error::Error>> {
+        let missing = |key: &str| format!("proxy.{} is not configured", key);
+
+        let project_id = settings.appwrite_project_id.clone().ok_or_else(|| missing("appwrite_project_id"))?;
+        let database_id = settings.appwrite_database_id.clone().ok_or_else(|| missing("appwrite_database_id"))?;
+        let collection_id = settings.appwrite_collection_id.clone().ok_or_else(|| missing("appwrite_collection_id"))?;
+        let api_key = required_secret("APPWRITE_API_KEY")?;
+
+        Ok(Self::new(project_id, api_key, database_id, collection_id))
+    }
+
     /// Create a new ProxyManager with explicit credentials
     pub fn new(
         project_id: String,
@@ -87,9 +104,15 @@ impl ProxyManager {
             api_key,
             database_id,
             collection_id,
+            user_agents: UserAgentPool::default(),
         }
     }
 
+    /// User-Agents sent by the proxy clients (built-in pool, rotated per request, by default)
+    pub fn set_user_agents(&mut self, user_agents: &UserAgentPool) {
+        self.user_agents = user_agents.clone();
+    }
+
     /// Fetch proxies from Appwrite
     pub fn fetch_proxies(&self) -> Result<usize, Box<dyn std::error::Error>> {
         let url = format!(
@@ -134,7 +157,7 @@ impl ProxyManager {
         *self.proxies.lock().unwrap() = proxies;
         *self.current_index.lock().unwrap() = 0;
 
-        println!("✓ Loaded {} active proxies from Appwrite", count);
+        info!(count, "✓ Loaded active proxies from Appwrite");
         
         Ok(count)
     }
@@ -184,7 +207,7 @@ impl ProxyManager {
         } else {
             // No proxy available, return client without proxy
             Ok(ReqwestClient::builder()
-                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+                .user_agent(self.user_agents.user_agent())
                 .timeout(std::time::Duration::from_secs(30))
                 .danger_accept_invalid_certs(true) // Accept self-signed certificates from proxies
                 .build()?)
@@ -225,7 +248,7 @@ impl ProxyManager {
 
         Ok(ReqwestClient::builder()
             .proxy(proxy)
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+            .user_agent(self.user_agents.user_agent())
             .timeout(std::time::Duration::from_secs(15)) // Shorter timeout for proxies
             .danger_accept_invalid_certs(true) // Accept self-signed certificates
             .build()?)
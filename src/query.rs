@@ -0,0 +1,683 @@
+//! Builder for spec-based phone queries, run against MongoDB or an in-memory list
+//!
+//! ```no_run
+//! # use gsmarena_scraper::PhoneQuery;
+//! let query = PhoneQuery::new().battery_mah_gte(5000).has_nfc().announced_after(2023).price_usd_lte(400.0);
+//! ```
+//!
+//! Numeric conditions use the `normalized` fields, so stored documents need
+//! migration 5 (`normalized_specs`) before they match.
+
+use crate::device_type::DeviceType;
+use crate::form_factor::FormFactor;
+use crate::lifecycle::stored_timestamp;
+use crate::mongodb::PhoneDocument;
+use crate::normalize::{NormalizedSpecs, OsVersion};
+use crate::phone_finder::Availability;
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, Bson, Document};
+
+/// A numeric field under `normalized`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericField {
+    AnnouncedYear,
+    DisplaySizeIn,
+    RefreshRateHz,
+    RamGb,
+    StorageGb,
+    MainCameraMp,
+    BatteryMah,
+    ChargingW,
+    WeightG,
+    PriceUsd,
+    PriceEur,
+    SimCount,
+    IpWater,
+    WiredW,
+    WirelessW,
+    ReverseWirelessW,
+    WifiGeneration,
+    BluetoothVersion,
+    UsbSpeedGbps,
+    SarUsHead,
+    SarUsBody,
+    SarEuHead,
+    SarEuBody,
+    HeightMm,
+    WidthMm,
+    ThicknessMm,
+    VolumeCm3,
+}
+
+impl NumericField {
+    /// Field name under `normalized`
+    pub fn key(&self) -> &'static str {
+        match self {
+            NumericField::AnnouncedYear => "announced_year",
+            NumericField::DisplaySizeIn => "display_size_in",
+            NumericField::RefreshRateHz => "refresh_rate_hz",
+            NumericField::RamGb => "ram_gb",
+            NumericField::StorageGb => "storage_gb",
+            NumericField::MainCameraMp => "main_camera_mp",
+            NumericField::BatteryMah => "battery_mah",
+            NumericField::ChargingW => "charging_w",
+            NumericField::WeightG => "weight_g",
+            NumericField::PriceUsd => "price_usd",
+            NumericField::PriceEur => "price_eur",
+            NumericField::SimCount => "sim_count",
+            NumericField::IpWater => "ip_water",
+            NumericField::WiredW => "wired_watts",
+            NumericField::WirelessW => "wireless_watts",
+            NumericField::ReverseWirelessW => "reverse_wireless_watts",
+            NumericField::WifiGeneration => "wifi_generation",
+            NumericField::BluetoothVersion => "bluetooth_version",
+            NumericField::UsbSpeedGbps => "usb_speed_gbps",
+            NumericField::SarUsHead => "sar_us_head",
+            NumericField::SarUsBody => "sar_us_body",
+            NumericField::SarEuHead => "sar_eu_head",
+            NumericField::SarEuBody => "sar_eu_body",
+            NumericField::HeightMm => "height_mm",
+            NumericField::WidthMm => "width_mm",
+            NumericField::ThicknessMm => "thickness_mm",
+            NumericField::VolumeCm3 => "volume_cm3",
+        }
+    }
+
+    fn value(&self, specs: &NormalizedSpecs) -> Option<f64> {
+        match self {
+            NumericField::AnnouncedYear => specs.announced_year.map(f64::from),
+            NumericField::DisplaySizeIn => specs.display_size_in,
+            NumericField::RefreshRateHz => specs.refresh_rate_hz,
+            NumericField::RamGb => specs.ram_gb,
+            NumericField::StorageGb => specs.storage_gb,
+            NumericField::MainCameraMp => specs.main_camera_mp,
+            NumericField::BatteryMah => specs.battery_mah.map(f64::from),
+            NumericField::ChargingW => specs.charging_w,
+            NumericField::WeightG => specs.weight_g,
+            NumericField::PriceUsd => specs.price_usd,
+            NumericField::PriceEur => specs.price_eur,
+            NumericField::SimCount => specs.sim_count.map(f64::from),
+            NumericField::IpWater => specs.ip_water.map(f64::from),
+            NumericField::WiredW => specs.wired_watts,
+            NumericField::WirelessW => specs.wireless_watts,
+            NumericField::ReverseWirelessW => specs.reverse_wireless_watts,
+            NumericField::WifiGeneration => specs.wifi_generation.map(f64::from),
+            NumericField::BluetoothVersion => specs.bluetooth_version,
+            NumericField::UsbSpeedGbps => specs.usb_speed_gbps,
+            NumericField::SarUsHead => specs.sar_us_head,
+            NumericField::SarUsBody => specs.sar_us_body,
+            NumericField::SarEuHead => specs.sar_eu_head,
+            NumericField::SarEuBody => specs.sar_eu_body,
+            NumericField::HeightMm => specs.height_mm,
+            NumericField::WidthMm => specs.width_mm,
+            NumericField::ThicknessMm => specs.thickness_mm,
+            NumericField::VolumeCm3 => specs.volume_cm3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Gte,
+    Lte,
+    Gt,
+    Lt,
+    Eq,
+}
+
+impl Comparison {
+    fn operator(&self) -> &'static str {
+        match self {
+            Comparison::Gte => "$gte",
+            Comparison::Lte => "$lte",
+            Comparison::Gt => "$gt",
+            Comparison::Lt => "$lt",
+            Comparison::Eq => "$eq",
+        }
+    }
+
+    fn holds(&self, value: f64, bound: f64) -> bool {
+        match self {
+            Comparison::Gte => value >= bound,
+            Comparison::Lte => value <= bound,
+            Comparison::Gt => value > bound,
+            Comparison::Lt => value < bound,
+            Comparison::Eq => value == bound,
+        }
+    }
+}
+
+/// A boolean field under `normalized`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagField {
+    Nfc,
+    FiveG,
+    Esim,
+    HeadphoneJack,
+    StereoSpeakers,
+    HiResAudio,
+    Wifi6Ghz,
+    DualFrequencyGnss,
+    Otg,
+    UsbDisplayPort,
+}
+
+impl FlagField {
+    pub fn key(&self) -> &'static str {
+        match self {
+            FlagField::Nfc => "has_nfc",
+            FlagField::FiveG => "has_5g",
+            FlagField::Esim => "has_esim",
+            FlagField::HeadphoneJack => "has_3_5mm_jack",
+            FlagField::StereoSpeakers => "stereo_speakers",
+            FlagField::HiResAudio => "hi_res_audio",
+            FlagField::Wifi6Ghz => "wifi_6ghz",
+            FlagField::DualFrequencyGnss => "dual_frequency_gnss",
+            FlagField::Otg => "has_otg",
+            FlagField::UsbDisplayPort => "usb_displayport",
+        }
+    }
+
+    fn value(&self, specs: &NormalizedSpecs) -> Option<bool> {
+        match self {
+            FlagField::Nfc => specs.has_nfc,
+            FlagField::FiveG => specs.has_5g,
+            FlagField::Esim => specs.has_esim,
+            FlagField::HeadphoneJack => specs.has_3_5mm_jack,
+            FlagField::StereoSpeakers => specs.stereo_speakers,
+            FlagField::HiResAudio => specs.hi_res_audio,
+            FlagField::Wifi6Ghz => specs.wifi_6ghz,
+            FlagField::DualFrequencyGnss => specs.dual_frequency_gnss,
+            FlagField::Otg => specs.has_otg,
+            FlagField::UsbDisplayPort => specs.usb_displayport,
+        }
+    }
+}
+
+/// One condition; a query matches when all of them hold
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Brand(String),        // Case-insensitive exact brand
+    NameContains(String), // Case-insensitive substring
+    ChipsetContains(String),
+    Number(NumericField, Comparison, f64),
+    Flag(FlagField, bool),
+    DeviceType(DeviceType),
+    FormFactor(FormFactor),
+    StatusSince(Availability, DateTime<Utc>), // Launch.status moved to this at or after the time
+    ModelNumber(String), // Case-insensitive; "SM-S918B" also matches "SM-S918B/DS"
+    OsName(String),      // Case-insensitive exact, e.g. "Android"
+    OsLatestGte(OsVersion), // Shipped with or upgradable to at least this version
+    OsUpgradedSince(DateTime<Utc>), // A newer OS version was first seen at or after the time
+    BluetoothCodec(String), // Case-insensitive, e.g. "LDAC"
+    GnssSystem(String),     // Case-insensitive, e.g. "NavIC"
+    ColorFamily(String),    // Lowercase family, e.g. "blue"
+    BatteryChemistry(String), // Case-insensitive: "Si/C", "Li-Po", "Li-Ion"
+}
+
+impl Condition {
+    fn to_document(&self) -> Document {
+        let case_insensitive = |pattern: String| doc! { "$regex": pattern, "$options": "i" };
+        match self {
+            Condition::Brand(brand) => doc! { "brand": case_insensitive(format!("^{}$", regex::escape(brand))) },
+            Condition::NameContains(name) => doc! { "name": case_insensitive(regex::escape(name)) },
+            Condition::ChipsetContains(chipset) => doc! { "platform.chipset": case_insensitive(regex::escape(chipset)) },
+            Condition::Number(field, comparison, bound) => {
+                doc! { format!("normalized.{}", field.key()): { comparison.operator(): Bson::Double(*bound) } }
+            }
+            Condition::Flag(field, value) => doc! { format!("normalized.{}", field.key()): *value },
+            Condition::DeviceType(device_type) => doc! { "device_type": device_type.to_string() },
+            Condition::FormFactor(form_factor) => doc! { "form_factor": form_factor.to_string() },
+            Condition::StatusSince(status, since) => doc! {
+                "lifecycle": { "$elemMatch": { "to": format!("{:?}", status), "at": { "$gte": stored_timestamp(*since) } } }
+            },
+            Condition::ModelNumber(model) => {
+                doc! { "normalized.model_numbers": case_insensitive(format!("^{}(/.*)?$", regex::escape(model.trim()))) }
+            }
+            Condition::OsName(name) => doc! { "normalized.os_name": case_insensitive(format!("^{}$", regex::escape(name))) },
+            Condition::OsLatestGte(version) => {
+                // Versions are stored as { major, minor }: a newer major, or the same major and a minor at least as new
+                let at_least = |field: &str| {
+                    doc! {
+                        "$or": [
+                            { format!("{}.major", field): { "$gt": Bson::Int64(version.major.into()) } },
+                            {
+                                format!("{}.major", field): Bson::Int64(version.major.into()),
+                                format!("{}.minor", field): { "$gte": Bson::Int64(version.minor.into()) },
+                            },
+                        ]
+                    }
+                };
+                doc! { "$or": [at_least("normalized.os_version"), at_least("normalized.os_upgradable_to")] }
+            }
+            Condition::OsUpgradedSince(since) => doc! {
+                "os_upgrades": { "$elemMatch": { "from": { "$ne": Bson::Null }, "at": { "$gte": stored_timestamp(*since) } } }
+            },
+            Condition::BluetoothCodec(codec) => {
+                doc! { "normalized.bluetooth_codecs": case_insensitive(format!("^{}$", regex::escape(codec))) }
+            }
+            Condition::GnssSystem(system) => {
+                doc! { "normalized.gnss_systems": case_insensitive(format!("^{}$", regex::escape(system))) }
+            }
+            Condition::ColorFamily(family) => doc! { "normalized.color_families": family.to_lowercase() },
+            Condition::BatteryChemistry(chemistry) => {
+                doc! { "normalized.battery_chemistry": case_insensitive(format!("^{}$", regex::escape(chemistry))) }
+            }
+        }
+    }
+
+    fn matches(&self, phone: &PhoneDocument) -> bool {
+        let contains = |text: Option<&str>, needle: &str| text.is_some_and(|t| t.to_lowercase().contains(&needle.to_lowercase()));
+        match self {
+            Condition::Brand(brand) => phone.brand.eq_ignore_ascii_case(brand),
+            Condition::NameContains(name) => contains(Some(&phone.name), name),
+            Condition::ChipsetContains(chipset) => contains(phone.platform.as_ref().and_then(|p| p.chipset.as_deref()), chipset),
+            Condition::Number(field, comparison, bound) => {
+                field.value(&phone.normalized).is_some_and(|value| comparison.holds(value, *bound))
+            }
+            Condition::Flag(field, value) => field.value(&phone.normalized) == Some(*value),
+            Condition::DeviceType(device_type) => phone.device_type == *device_type,
+            Condition::FormFactor(form_factor) => phone.form_factor == *form_factor,
+            Condition::StatusSince(status, since) => phone.lifecycle.iter().any(|t| t.to == *status && t.at >= *since),
+            Condition::ModelNumber(model) => phone.normalized.model_numbers.iter().any(|number| {
+                let (base, _) = number.split_once('/').unwrap_or((number, ""));
+                number.eq_ignore_ascii_case(model.trim()) || base.eq_ignore_ascii_case(model.trim())
+            }),
+            Condition::OsName(name) => phone.normalized.os_name.as_deref().is_some_and(|os| os.eq_ignore_ascii_case(name)),
+            Condition::OsLatestGte(version) => phone.normalized.os_latest().is_some_and(|latest| latest >= *version),
+            Condition::OsUpgradedSince(since) => phone.os_upgrades.iter().any(|u| u.from.is_some() && u.at >= *since),
+            Condition::BluetoothCodec(codec) => phone.normalized.bluetooth_codecs.iter().any(|c| c.eq_ignore_ascii_case(codec)),
+            Condition::GnssSystem(system) => phone.normalized.gnss_systems.iter().any(|s| s.eq_ignore_ascii_case(system)),
+            Condition::ColorFamily(family) => phone.normalized.color_families.iter().any(|f| f.eq_ignore_ascii_case(family)),
+            Condition::BatteryChemistry(chemistry) => {
+                phone.normalized.battery_chemistry.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(chemistry))
+            }
+        }
+    }
+}
+
+/// Conditions plus paging; build with the chained methods
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhoneQuery {
+    pub conditions: Vec<Condition>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl PhoneQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    fn number(self, field: NumericField, comparison: Comparison, bound: f64) -> Self {
+        self.with(Condition::Number(field, comparison, bound))
+    }
+
+    pub fn brand(self, brand: &str) -> Self {
+        self.with(Condition::Brand(brand.to_string()))
+    }
+
+    pub fn name_contains(self, name: &str) -> Self {
+        self.with(Condition::NameContains(name.to_string()))
+    }
+
+    pub fn chipset_contains(self, chipset: &str) -> Self {
+        self.with(Condition::ChipsetContains(chipset.to_string()))
+    }
+
+    pub fn battery_mah_gte(self, mah: u32) -> Self {
+        self.number(NumericField::BatteryMah, Comparison::Gte, mah as f64)
+    }
+
+    pub fn charging_w_gte(self, watts: f64) -> Self {
+        self.number(NumericField::ChargingW, Comparison::Gte, watts)
+    }
+
+    pub fn wired_w_gte(self, watts: f64) -> Self {
+        self.number(NumericField::WiredW, Comparison::Gte, watts)
+    }
+
+    pub fn wireless_w_gte(self, watts: f64) -> Self {
+        self.number(NumericField::WirelessW, Comparison::Gte, watts)
+    }
+
+    /// Can charge other devices wirelessly
+    pub fn has_reverse_wireless(self) -> Self {
+        self.number(NumericField::ReverseWirelessW, Comparison::Gt, 0.0)
+    }
+
+    /// Announced in a later year than `year`
+    pub fn announced_after(self, year: i32) -> Self {
+        self.number(NumericField::AnnouncedYear, Comparison::Gt, year as f64)
+    }
+
+    /// Announced in an earlier year than `year`
+    pub fn announced_before(self, year: i32) -> Self {
+        self.number(NumericField::AnnouncedYear, Comparison::Lt, year as f64)
+    }
+
+    pub fn announced_in(self, year: i32) -> Self {
+        self.number(NumericField::AnnouncedYear, Comparison::Eq, year as f64)
+    }
+
+    pub fn price_usd_lte(self, usd: f64) -> Self {
+        self.number(NumericField::PriceUsd, Comparison::Lte, usd)
+    }
+
+    pub fn price_eur_lte(self, eur: f64) -> Self {
+        self.number(NumericField::PriceEur, Comparison::Lte, eur)
+    }
+
+    pub fn weight_g_lte(self, grams: f64) -> Self {
+        self.number(NumericField::WeightG, Comparison::Lte, grams)
+    }
+
+    pub fn display_size_between(self, min_inches: f64, max_inches: f64) -> Self {
+        self.number(NumericField::DisplaySizeIn, Comparison::Gte, min_inches)
+            .number(NumericField::DisplaySizeIn, Comparison::Lte, max_inches)
+    }
+
+    pub fn refresh_rate_gte(self, hz: f64) -> Self {
+        self.number(NumericField::RefreshRateHz, Comparison::Gte, hz)
+    }
+
+    pub fn ram_gb_gte(self, gb: f64) -> Self {
+        self.number(NumericField::RamGb, Comparison::Gte, gb)
+    }
+
+    pub fn storage_gb_gte(self, gb: f64) -> Self {
+        self.number(NumericField::StorageGb, Comparison::Gte, gb)
+    }
+
+    pub fn main_camera_mp_gte(self, mp: f64) -> Self {
+        self.number(NumericField::MainCameraMp, Comparison::Gte, mp)
+    }
+
+    pub fn has_nfc(self) -> Self {
+        self.with(Condition::Flag(FlagField::Nfc, true))
+    }
+
+    pub fn has_5g(self) -> Self {
+        self.with(Condition::Flag(FlagField::FiveG, true))
+    }
+
+    pub fn has_esim(self) -> Self {
+        self.with(Condition::Flag(FlagField::Esim, true))
+    }
+
+    pub fn has_headphone_jack(self) -> Self {
+        self.with(Condition::Flag(FlagField::HeadphoneJack, true))
+    }
+
+    pub fn stereo_speakers(self) -> Self {
+        self.with(Condition::Flag(FlagField::StereoSpeakers, true))
+    }
+
+    pub fn hi_res_audio(self) -> Self {
+        self.with(Condition::Flag(FlagField::HiResAudio, true))
+    }
+
+    /// Wi-Fi 6E or 7 (the 6 GHz band)
+    pub fn wifi_6e(self) -> Self {
+        self.with(Condition::Flag(FlagField::Wifi6Ghz, true))
+    }
+
+    pub fn wifi_generation_gte(self, generation: u32) -> Self {
+        self.number(NumericField::WifiGeneration, Comparison::Gte, generation as f64)
+    }
+
+    /// `PhoneQuery::new().wifi_6e().bluetooth_version_gte(5.3)`
+    pub fn bluetooth_version_gte(self, version: f64) -> Self {
+        self.number(NumericField::BluetoothVersion, Comparison::Gte, version)
+    }
+
+    /// Comms > Bluetooth names this codec, e.g. "LDAC" or "aptX HD"
+    pub fn bluetooth_codec(self, codec: &str) -> Self {
+        self.with(Condition::BluetoothCodec(codec.to_string()))
+    }
+
+    /// Comms > Positioning lists this constellation: "GPS", "GLONASS", "Galileo", "BDS", "QZSS", "NavIC"
+    pub fn gnss(self, system: &str) -> Self {
+        self.with(Condition::GnssSystem(system.to_string()))
+    }
+
+    /// Receives some constellation on two bands, e.g. GPS L1+L5
+    pub fn dual_frequency_gnss(self) -> Self {
+        self.with(Condition::Flag(FlagField::DualFrequencyGnss, true))
+    }
+
+    /// Thickness (folded, for foldables) at most `mm`
+    pub fn thickness_mm_lte(self, mm: f64) -> Self {
+        self.number(NumericField::ThicknessMm, Comparison::Lte, mm)
+    }
+
+    /// US head SAR at most `w_kg`
+    pub fn sar_us_head_lte(self, w_kg: f64) -> Self {
+        self.number(NumericField::SarUsHead, Comparison::Lte, w_kg)
+    }
+
+    /// EU head SAR at most `w_kg`
+    pub fn sar_eu_head_lte(self, w_kg: f64) -> Self {
+        self.number(NumericField::SarEuHead, Comparison::Lte, w_kg)
+    }
+
+    /// Battery cells of this chemistry: "Si/C" (silicon-carbon), "Li-Po", "Li-Ion"
+    pub fn battery_chemistry(self, chemistry: &str) -> Self {
+        self.with(Condition::BatteryChemistry(chemistry.to_string()))
+    }
+
+    /// Comes in a color of this family: "black", "blue", "titanium", ...
+    pub fn color(self, family: &str) -> Self {
+        self.with(Condition::ColorFamily(family.to_string()))
+    }
+
+    /// Video out over USB (DisplayPort alt mode)
+    pub fn usb_video_out(self) -> Self {
+        self.with(Condition::Flag(FlagField::UsbDisplayPort, true))
+    }
+
+    pub fn has_otg(self) -> Self {
+        self.with(Condition::Flag(FlagField::Otg, true))
+    }
+
+    /// USB signalling rate at least this many Gbps; `usb_speed_gbps_gte(10.0)` is 3.2 Gen 2 and up
+    pub fn usb_speed_gbps_gte(self, gbps: f64) -> Self {
+        self.number(NumericField::UsbSpeedGbps, Comparison::Gte, gbps)
+    }
+
+    /// Takes two or more SIMs at once, physical or eSIM
+    pub fn dual_sim(self) -> Self {
+        self.number(NumericField::SimCount, Comparison::Gte, 2.0)
+    }
+
+    /// Water digit of the IP rating at least `level`; `ip_water_gte(8)` finds IP68 and up
+    pub fn ip_water_gte(self, level: u32) -> Self {
+        self.number(NumericField::IpWater, Comparison::Gte, level as f64)
+    }
+
+    pub fn device_type(self, device_type: DeviceType) -> Self {
+        self.with(Condition::DeviceType(device_type))
+    }
+
+    pub fn form_factor(self, form_factor: FormFactor) -> Self {
+        self.with(Condition::FormFactor(form_factor))
+    }
+
+    /// Launch.status changed to `status` at or after `since` (needs lifecycle history)
+    pub fn status_since(self, status: Availability, since: DateTime<Utc>) -> Self {
+        self.with(Condition::StatusSince(status, since))
+    }
+
+    /// Misc > Models lists this number; "SM-S918B" also finds phones listing only "SM-S918B/DS"
+    pub fn model_number(self, model: impl Into<String>) -> Self {
+        self.with(Condition::ModelNumber(model.into()))
+    }
+
+    /// Platform > OS names this system, e.g. "Android", "iOS"
+    pub fn os(self, name: &str) -> Self {
+        self.with(Condition::OsName(name.to_string()))
+    }
+
+    /// Runs at least this OS version, shipped or as a listed upgrade: `.os("Android").os_version_gte(OsVersion::new(15, 0))`
+    pub fn os_version_gte(self, version: OsVersion) -> Self {
+        self.with(Condition::OsLatestGte(version))
+    }
+
+    /// Got a newer OS version listed at or after `since` (needs upgrade history)
+    pub fn os_upgraded_since(self, since: DateTime<Utc>) -> Self {
+        self.with(Condition::OsUpgradedSince(since))
+    }
+
+    /// Discontinued at or after `since`, e.g. in the last quarter
+    pub fn discontinued_since(self, since: DateTime<Utc>) -> Self {
+        self.status_since(Availability::Discontinued, since)
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// MongoDB filter for the specs collection (paging is applied by the caller)
+    pub fn to_document(&self) -> Document {
+        match self.conditions.len() {
+            0 => Document::new(),
+            1 => self.conditions[0].to_document(),
+            _ => doc! { "$and": self.conditions.iter().map(Condition::to_document).collect::<Vec<_>>() },
+        }
+    }
+
+    /// Whether one document satisfies every condition
+    pub fn matches(&self, phone: &PhoneDocument) -> bool {
+        self.conditions.iter().all(|condition| condition.matches(phone))
+    }
+
+    /// Evaluate against an in-memory list, applying offset and limit
+    pub fn filter<'a, I>(&self, phones: I) -> Vec<&'a PhoneDocument>
+    where
+        I: IntoIterator<Item = &'a PhoneDocument>,
+    {
+        phones
+            .into_iter()
+            .filter(|phone| self.matches(phone))
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone(id: &str, battery: &str, nfc: &str, announced: &str, price: &str) -> PhoneDocument {
+        PhoneDocument::fixture(id)
+            .brand("Xiaomi")
+            .category("Launch", &[("Announced", announced)])
+            .category("Comms", &[("NFC", nfc)])
+            .category("Battery", &[("Type", battery)])
+            .category("Misc", &[("Price", price)])
+            .build()
+    }
+
+    #[test]
+    fn test_query_in_memory_and_mongo() {
+        let phones = vec![
+            phone("match-1", "Li-Po 5160 mAh", "Yes", "2024, March 04", "$ 329.99 / € 299.00"),
+            phone("old-2", "Li-Po 5000 mAh", "Yes", "2022, March 15", "$ 199.00"),
+            phone("no_nfc-3", "Li-Po 5000 mAh", "No", "2024, May 02", "$ 249.00"),
+            phone("pricey-4", "Li-Po 5500 mAh", "Yes", "2025, January 10", "$ 899.00"),
+            phone("eur_only-5", "Li-Po 6000 mAh", "Yes", "2024, June 01", "About 300 EUR"),
+        ];
+
+        let query = PhoneQuery::new().battery_mah_gte(5000).has_nfc().announced_after(2023).price_usd_lte(400.0);
+        let found: Vec<&str> = query.filter(&phones).iter().map(|p| p.phone_id.as_str()).collect();
+        assert_eq!(found, vec!["match-1"]);
+
+        assert_eq!(PhoneQuery::new().brand("xiaomi").offset(1).limit(2).filter(&phones).len(), 2);
+
+        let filter = query.to_document();
+        let conditions = filter.get_array("$and").unwrap();
+        assert_eq!(conditions.len(), 4);
+        assert_eq!(conditions[0].as_document().unwrap(), &doc! { "normalized.battery_mah": { "$gte": 5000.0 } });
+        assert_eq!(conditions[1].as_document().unwrap(), &doc! { "normalized.has_nfc": true });
+        assert_eq!(PhoneQuery::new().to_document(), Document::new());
+
+        assert_eq!(PhoneQuery::new().device_type(DeviceType::Phone).filter(&phones).len(), 5);
+        assert_eq!(PhoneQuery::new().device_type(DeviceType::Watch).to_document(), doc! { "device_type": "watch" });
+        assert_eq!(PhoneQuery::new().form_factor(FormFactor::Bar).filter(&phones).len(), 5);
+        assert_eq!(PhoneQuery::new().form_factor(FormFactor::FoldableFlip).to_document(), doc! { "form_factor": "foldable_flip" });
+
+        let since = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(
+            PhoneQuery::new().discontinued_since(since).to_document(),
+            doc! { "lifecycle": { "$elemMatch": { "to": "Discontinued", "at": { "$gte": "2023-11-14T22:13:20Z" } } } }
+        );
+        assert!(PhoneQuery::new().discontinued_since(since).filter(&phones).is_empty());
+
+        let mut galaxy = phone("samsung_galaxy_s23_ultra-12024", "Li-Ion 5000 mAh", "Yes", "2023, February 01", "$ 1199.99");
+        galaxy.normalized.model_numbers = vec!["SM-S918B/DS".into(), "SM-S918U".into()];
+        let with_galaxy = [phones, vec![galaxy]].concat();
+        assert_eq!(PhoneQuery::new().model_number("sm-s918b").filter(&with_galaxy).len(), 1);
+        assert_eq!(PhoneQuery::new().model_number("SM-S918U").filter(&with_galaxy).len(), 1);
+        assert!(PhoneQuery::new().model_number("SM-S918").filter(&with_galaxy).is_empty());
+
+        let mut upgraded = with_galaxy.clone();
+        upgraded[5].normalized.os_name = Some("Android".into());
+        upgraded[5].normalized.os_version = Some(OsVersion::new(13, 0));
+        upgraded[5].normalized.os_upgradable_to = Some(OsVersion::new(15, 0));
+        upgraded[5].os_upgrades.push(crate::lifecycle::OsUpgrade { from: Some(OsVersion::new(14, 0)), to: OsVersion::new(15, 0), at: since });
+        let android_15 = PhoneQuery::new().os("android").os_version_gte(OsVersion::new(15, 0));
+        assert_eq!(android_15.filter(&upgraded).len(), 1);
+        assert_eq!(PhoneQuery::new().os_upgraded_since(since).filter(&upgraded).len(), 1);
+        assert!(PhoneQuery::new().os_version_gte(OsVersion::new(16, 0)).filter(&upgraded).is_empty());
+        assert!(PhoneQuery::new().os_version_gte(OsVersion::new(15, 1)).filter(&upgraded).is_empty());
+        assert_eq!(
+            PhoneQuery::new().os_version_gte(OsVersion::new(15, 1)).to_document(),
+            doc! { "$or": [
+                { "$or": [
+                    { "normalized.os_version.major": { "$gt": 15_i64 } },
+                    { "normalized.os_version.major": 15_i64, "normalized.os_version.minor": { "$gte": 1_i64 } },
+                ] },
+                { "$or": [
+                    { "normalized.os_upgradable_to.major": { "$gt": 15_i64 } },
+                    { "normalized.os_upgradable_to.major": 15_i64, "normalized.os_upgradable_to.minor": { "$gte": 1_i64 } },
+                ] },
+            ] }
+        );
+        assert_eq!(android_15.to_document().get_array("$and").unwrap().len(), 2);
+
+        upgraded[0].normalized.has_esim = Some(true);
+        upgraded[0].normalized.sim_count = Some(2);
+        assert_eq!(PhoneQuery::new().has_esim().dual_sim().filter(&upgraded).len(), 1);
+        assert_eq!(PhoneQuery::new().has_esim().to_document(), doc! { "normalized.has_esim": true });
+
+        upgraded[1].normalized.has_3_5mm_jack = Some(true);
+        upgraded[1].normalized.bluetooth_codecs = vec!["aptX HD".into(), "LDAC".into()];
+        assert_eq!(PhoneQuery::new().has_headphone_jack().bluetooth_codec("ldac").filter(&upgraded).len(), 1);
+        assert!(PhoneQuery::new().bluetooth_codec("aptX").filter(&upgraded).is_empty());
+
+        upgraded[2].normalized.gnss_systems = vec!["GPS".into(), "NavIC".into()];
+        upgraded[2].normalized.dual_frequency_gnss = Some(true);
+        assert_eq!(PhoneQuery::new().gnss("navic").dual_frequency_gnss().filter(&upgraded).len(), 1);
+
+        upgraded[3].normalized.color_families = vec!["blue".into(), "titanium".into()];
+        assert_eq!(PhoneQuery::new().color("Blue").filter(&upgraded).len(), 1);
+        assert_eq!(PhoneQuery::new().battery_chemistry("li-po").filter(&upgraded).len(), 5);
+        assert_eq!(PhoneQuery::new().color("Blue").to_document(), doc! { "normalized.color_families": "blue" });
+        assert_eq!(
+            PhoneQuery::new().model_number("SM-S918B").to_document(),
+            doc! { "normalized.model_numbers": { "$regex": "^SM\\-S918B(/.*)?$", "$options": "i" } }
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Request spacing shared between threads
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Hands out request slots at least `interval` apart, however many threads ask
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Block until the caller's slot comes up
+    pub fn wait(&self) {
+        let now = Instant::now();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + self.interval;
+            slot
+        };
+        std::thread::sleep(slot.saturating_duration_since(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_are_spaced_across_threads() {
+        let limiter = RateLimiter::new(Duration::from_millis(20));
+        let started = Instant::now();
+        std::thread::scope(|scope| {
+            for _ in 0..3 {
+                scope.spawn(|| {
+                    limiter.wait();
+                    limiter.wait();
+                });
+            }
+        });
+        // Six slots: the first is immediate, the other five 20 ms apart
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::mongodb::MongoDBClient;
+use crate::search::{search_with_filters_from, SearchFilters};
+use crate::site::DEFAULT_BASE_URL;
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use std::collections::HashMap;
+use std::error::Error;
+use tracing::warn;
+
+/// Maximum number of candidates returned by the resolvers
+const MAX_CANDIDATES: usize = 10;
+
+/// Resolve a human-readable phone name to GSMArena phone IDs using the site search
+/// Returns `(phone_id, score)` pairs sorted by descending score (1.0 = exact match)
+pub fn resolve_phone_id(query: &str) -> Result<Vec<(String, f64)>, Box<dyn Error>> {
+    resolve_phone_id_from(DEFAULT_BASE_URL, query)
+}
+
+/// Resolve a phone name using the search of a mirror or test server
+pub fn resolve_phone_id_from(base_url: &str, query: &str) -> Result<Vec<(String, f64)>, Box<dyn Error>> {
+    let candidates = search_with_filters_from(base_url, query, &SearchFilters::default())?
+        .into_iter()
+        .map(|result| (result.phone_id, score_name(query, &result.name)))
+        .collect();
+
+    Ok(rank(candidates))
+}
+
+/// Resolve a phone name using both the site search and the names already stored in MongoDB
+pub async fn resolve_phone_id_with_db(
+    query: &str,
+    mongo_client: &MongoDBClient,
+    collection_name: &str,
+) -> Result<Vec<(String, f64)>, Box<dyn Error>> {
+    let owned_query = query.to_string();
+    let site_results = tokio::task::spawn_blocking(move || {
+        resolve_phone_id(&owned_query).map_err(|e| e.to_string())
+    })
+    .await?;
+
+    let mut candidates = match site_results {
+        Ok(results) => results,
+        Err(e) => {
+            warn!(error = %e, "Site search failed, using local names only");
+            Vec::new()
+        }
+    };
+
+    // Stored names come from brand listings and usually lack the brand prefix
+    let collection = mongo_client.database().collection::<Document>(collection_name);
+    let options = FindOptions::builder()
+        .projection(doc! { "phone_id": 1, "name": 1, "brand": 1, "_id": 0 })
+        .build();
+    let mut cursor = collection.find(doc! {}, options).await?;
+
+    while let Some(result) = cursor.next().await {
+        let document = result?;
+        let (Ok(phone_id), Ok(name)) = (document.get_str("phone_id"), document.get_str("name")) else {
+            continue;
+        };
+
+        let score = match document.get_str("brand") {
+            Ok(brand) => score_name(query, name).max(score_name(query, &format!("{} {}", brand, name))),
+            Err(_) => score_name(query, name),
+        };
+
+        candidates.push((phone_id.to_string(), score));
+    }
+
+    Ok(rank(candidates))
+}
+
+/// Similarity between a query and a device name (0.0 - 1.0)
+/// Jaro-Winkler over normalized names, with a bonus when every query token appears in the name
+pub fn score_name(query: &str, name: &str) -> f64 {
+    let query = normalize_name(query);
+    let name = normalize_name(name);
+
+    if query.is_empty() || name.is_empty() {
+        return 0.0;
+    }
+    if query == name {
+        return 1.0;
+    }
+
+    let similarity = strsim::jaro_winkler(&query, &name);
+
+    let name_tokens: Vec<&str> = name.split(' ').collect();
+    let all_tokens_present = query.split(' ').all(|token| name_tokens.contains(&token));
+
+    if all_tokens_present {
+        // Penalize extra tokens so "iphone 15" ranks "iPhone 15" above "iPhone 15 Pro Max"
+        let extra_tokens = name_tokens.len().saturating_sub(query.split(' ').count()) as f64;
+        (0.9 + 0.1 * similarity - 0.02 * extra_tokens).clamp(similarity, 0.99)
+    } else {
+        similarity
+    }
+}
+
+/// Lowercase, strip punctuation and collapse whitespace
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Deduplicate by phone ID (keeping the best score) and sort best-first
+fn rank(candidates: Vec<(String, f64)>) -> Vec<(String, f64)> {
+    let mut best: HashMap<String, f64> = HashMap::new();
+
+    for (phone_id, score) in candidates {
+        let entry = best.entry(phone_id).or_insert(score);
+        if score > *entry {
+            *entry = score;
+        }
+    }
+
+    let mut ranked: Vec<(String, f64)> = best.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(MAX_CANDIDATES);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_name_prefers_exact_model() {
+        let exact = score_name("iphone 15 pro max", "Apple iPhone 15 Pro Max");
+        let shorter = score_name("iphone 15 pro max", "Apple iPhone 15 Pro");
+        assert!(exact > shorter);
+
+        assert_eq!(score_name("Galaxy S24", "galaxy s24"), 1.0);
+        assert!(score_name("iphone 15", "iPhone 15") > score_name("iphone 15", "iPhone 15 Pro Max"));
+    }
+
+    #[test]
+    fn test_rank_deduplicates() {
+        let ranked = rank(vec![
+            ("a-1".to_string(), 0.5),
+            ("b-2".to_string(), 0.9),
+            ("a-1".to_string(), 0.7),
+        ]);
+        assert_eq!(ranked, vec![("b-2".to_string(), 0.9), ("a-1".to_string(), 0.7)]);
+    }
+}
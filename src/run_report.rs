@@ -0,0 +1,261 @@
+use crate::adaptive_delay::AdaptiveDelayStats;
+use crate::budget::BudgetUsage;
+use crate::config::Config;
+use crate::http_stats::ProviderHttpStats;
+use crate::hybrid::HybridStats;
+use crate::stats::{FailureCategory, FailureStats, SkipReason};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+/// Default file written at the end of every scrape
+pub const DEFAULT_REPORT_PATH: &str = "run_report.json";
+
+/// Machine-readable summary of one scrape run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub run_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_secs: Option<f64>,
+    pub interrupted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>, // The budget or limit that ended the run early
+    pub config: Config,
+    pub counts: RunCounts,
+    pub brands: Vec<BrandOutcome>,
+    pub failed_phones: Vec<FailedPhone>,
+    pub scrapingbee_credits: u64,
+    #[serde(default)]
+    pub budget: BudgetUsage, // Requests and credits counted against `[budget]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub http: BTreeMap<String, ProviderHttpStats>, // Provider -> status codes, bytes and latency of its requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hybrid: Option<HybridStats>, // Phone fetches per method, for the hybrid provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive_delay: Option<AdaptiveDelayStats>, // How the phone delay moved, with `rate_limit.adaptive`
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunCounts {
+    pub brands_processed: usize,
+    pub brands_failed: usize,
+    pub phones_found: usize,
+    pub phones_inserted: usize,
+    pub phones_skipped: usize,
+    pub phones_failed: usize,
+    #[serde(default)]
+    pub phones_unchanged: usize, // Scraped again into identical specifications, not rewritten
+    #[serde(default)]
+    pub brands_short: usize, // Listings well below the brand's device count
+    #[serde(default)]
+    pub phones_stale: usize, // phone_list entries newly marked stale (no longer listed)
+    #[serde(default)]
+    pub failures: FailureStats, // Failed and skipped phones by category
+    pub initial_count: u64,
+    pub final_count: u64,
+}
+
+/// What happened to one brand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrandOutcome {
+    pub name: String,
+    pub slug: String,
+    pub phones_found: usize,
+    pub phones_inserted: usize,
+    pub phones_skipped: usize,
+    pub phones_failed: usize,
+    #[serde(default)]
+    pub phones_unchanged: usize,
+    pub error: Option<String>, // Phone list could not be fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_page: Option<usize>, // Listing page the error happened on; `retry-failed` resumes from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_phones: Option<usize>, // Device count from makers.php3, when the listing fell short of it
+}
+
+/// A phone whose specifications were not saved, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedPhone {
+    pub phone_id: String,
+    pub name: String,
+    pub brand: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<FailureCategory>, // None in reports written before categories
+    pub error: String,
+}
+
+impl RunReport {
+    pub fn new(config: &Config) -> Self {
+        let started_at = Utc::now();
+
+        Self {
+            run_id: started_at.format("%Y%m%dT%H%M%SZ").to_string(),
+            started_at,
+            finished_at: None,
+            duration_secs: None,
+            interrupted: false,
+            stop_reason: None,
+            config: config.clone(),
+            counts: RunCounts::default(),
+            brands: Vec::new(),
+            failed_phones: Vec::new(),
+            scrapingbee_credits: 0,
+            budget: BudgetUsage::default(),
+            http: BTreeMap::new(),
+            hybrid: None,
+            adaptive_delay: None,
+        }
+    }
+
+    /// Start tracking a brand; later `record_*` calls apply to it
+    pub fn start_brand(&mut self, name: &str, slug: &str) {
+        self.brands.push(BrandOutcome {
+            name: name.to_string(),
+            slug: slug.to_string(),
+            phones_found: 0,
+            phones_inserted: 0,
+            phones_skipped: 0,
+            phones_failed: 0,
+            phones_unchanged: 0,
+            error: None,
+            failed_page: None,
+            expected_phones: None,
+        });
+    }
+
+    pub fn record_brand_phones(&mut self, found: usize) {
+        self.counts.brands_processed += 1;
+        self.counts.phones_found += found;
+        if let Some(brand) = self.brands.last_mut() {
+            brand.phones_found = found;
+        }
+    }
+
+    /// phone_list entries of the current brand that its complete listing no longer has
+    pub fn record_stale(&mut self, count: usize) {
+        self.counts.phones_stale += count;
+    }
+
+    /// The listing still fell short of the brand's device count after a retry
+    pub fn record_brand_shortfall(&mut self, expected: usize) {
+        self.counts.brands_short += 1;
+        if let Some(brand) = self.brands.last_mut() {
+            brand.expected_phones = Some(expected);
+        }
+    }
+
+    /// The listing failed at `page` (1 when no phones could be listed at all)
+    pub fn record_brand_error(&mut self, page: usize, error: &str) {
+        self.counts.brands_failed += 1;
+        if let Some(brand) = self.brands.last_mut() {
+            brand.error = Some(error.to_string());
+            brand.failed_page = Some(page);
+        }
+    }
+
+    /// Brands whose listing stopped at a failed page
+    pub fn unfinished_listings(&self) -> impl Iterator<Item = &BrandOutcome> {
+        self.brands.iter().filter(|brand| brand.failed_page.is_some())
+    }
+
+    pub fn record_inserted(&mut self) {
+        self.counts.phones_inserted += 1;
+        if let Some(brand) = self.brands.last_mut() {
+            brand.phones_inserted += 1;
+        }
+    }
+
+    /// Fetched again, but `specifications_raw` hashed the same as the stored document's
+    pub fn record_unchanged(&mut self) {
+        self.counts.phones_unchanged += 1;
+        if let Some(brand) = self.brands.last_mut() {
+            brand.phones_unchanged += 1;
+        }
+    }
+
+    pub fn record_skipped(&mut self, reason: SkipReason) {
+        self.counts.phones_skipped += 1;
+        self.counts.failures.record_skip(reason);
+        if let Some(brand) = self.brands.last_mut() {
+            brand.phones_skipped += 1;
+        }
+    }
+
+    pub fn record_failed(&mut self, phone_id: &str, name: &str, brand_name: &str, category: FailureCategory, error: &str) {
+        self.counts.phones_failed += 1;
+        self.counts.failures.record_failure(category);
+        if let Some(brand) = self.brands.last_mut() {
+            brand.phones_failed += 1;
+        }
+
+        self.failed_phones.push(FailedPhone {
+            phone_id: phone_id.to_string(),
+            name: name.to_string(),
+            brand: brand_name.to_string(),
+            category: Some(category),
+            error: error.to_string(),
+        });
+    }
+
+    /// Stamp the end time and duration
+    pub fn finish(&mut self, interrupted: bool) {
+        let finished_at = Utc::now();
+        self.duration_secs = Some((finished_at - self.started_at).num_milliseconds() as f64 / 1000.0);
+        self.finished_at = Some(finished_at);
+        self.interrupted = interrupted;
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn read_json(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read run report {}: {}", path.display(), e))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_report_counts() {
+        let mut report = RunReport::new(&Config::default());
+
+        report.start_brand("Apple", "apple-phones-48");
+        report.record_brand_phones(3);
+        report.record_inserted();
+        report.record_skipped(SkipReason::Complete);
+        report.record_unchanged();
+        report.record_failed("apple_iphone_15-12559", "iPhone 15", "Apple", FailureCategory::Network, "timeout");
+
+        report.record_brand_shortfall(40);
+
+        report.start_brand("Nokia", "nokia-phones-1");
+        report.record_brand_error(7, "status 429");
+        report.finish(false);
+
+        assert_eq!(report.counts.phones_found, 3);
+        assert_eq!(report.counts.brands_processed, 1);
+        assert_eq!(report.counts.brands_failed, 1);
+        assert_eq!(report.brands[0].phones_failed, 1);
+        assert_eq!((report.counts.phones_unchanged, report.brands[0].phones_unchanged), (1, 1));
+        assert_eq!(report.brands[1].error.as_deref(), Some("status 429"));
+        let unfinished: Vec<_> = report.unfinished_listings().map(|brand| (brand.slug.as_str(), brand.failed_page)).collect();
+        assert_eq!(unfinished, vec![("nokia-phones-1", Some(7))]);
+        assert_eq!((report.counts.brands_short, report.brands[0].expected_phones), (1, Some(40)));
+        assert_eq!(report.failed_phones[0].phone_id, "apple_iphone_15-12559");
+        assert_eq!((report.counts.failures.network, report.counts.failures.skipped_complete), (1, 1));
+
+        let json = serde_json::to_string(&report).unwrap();
+        let parsed: RunReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.failed_phones.len(), 1);
+        assert!(parsed.duration_secs.is_some());
+    }
+}
@@ -0,0 +1,633 @@
+//! The whole pipeline behind one call, for programs that embed the crate
+//!
+//! `ScrapeRunner` walks makers -> brand listings -> phone pages through any
+//! `PageSource`, and hands every parsed phone to a `PhoneStore` and/or the
+//! `on_phone_scraped` hook. The `scrape` command layers providers, budgets, reports
+//! and notifications on top of the same steps: `listing_excludes` and `build_phone_document`.
+
+use crate::brand_scraper::{fetch_phones_by_brand_with, parse_brands_page, Brand, PhoneListItem};
+use crate::config::{BrandFilter, Config};
+use crate::device_type::DeviceType;
+use crate::enrichment::{Enricher, Enrichers};
+#[cfg(feature = "ledger")]
+use crate::ledger::Ledger;
+use crate::lifecycle;
+use crate::mongodb::PhoneDocument;
+use crate::pricing::parse_market_prices;
+use crate::scraper::parse_specification_html;
+use crate::self_check::self_check;
+use crate::service::{DirectSource, PageSource};
+use crate::shutdown::Shutdown;
+use crate::stats::{ErrorPolicy, FailureCategory, FailureStats, SkipReason};
+use crate::site::SiteConfig;
+use crate::store::PhoneStore;
+use crate::validator::{validate, DEFAULT_MIN_COMPLETENESS};
+use chrono::Utc;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Something that went wrong for one brand or phone; the run carries on
+#[derive(Debug, Clone)]
+pub struct ScrapeError {
+    pub brand: String,
+    pub phone_id: Option<String>, // None when the brand listing failed
+    pub category: FailureCategory,
+    pub message: String,
+}
+
+/// What a run did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrapeSummary {
+    pub brands: usize,
+    pub phones_scraped: usize,
+    pub phones_skipped: usize, // Already stored, or not the requested device type
+    pub phones_unchanged: usize, // Scraped again into identical specifications, so not rewritten
+    pub errors: usize,
+    pub failures: FailureStats, // Errors and skipped phones by category
+    pub stopped: bool, // Shutdown was requested, or the error policy ended the run, before the end
+}
+
+/// How a fetched phone page becomes a document, for `build_phone_document`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhonePageOptions {
+    pub min_completeness: f64,
+    pub record_provenance: bool,
+    pub device_type: Option<DeviceType>,
+}
+
+impl PhonePageOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            min_completeness: config.validation.min_completeness,
+            record_provenance: config.scraping.record_provenance,
+            device_type: config.scraping.device_type,
+        }
+    }
+}
+
+/// A phone page turned into a document
+pub enum PhonePage {
+    Document(Box<PhoneDocument>),
+    OtherType(DeviceType), // What the specs say it is, when not the requested device type
+}
+
+/// Whether a listing entry is left out for the requested device type without fetching it
+/// Listings only hint at the type from the name; names that say nothing count as phones.
+pub fn listing_excludes(phone: &PhoneListItem, device_type: Option<DeviceType>) -> bool {
+    device_type.is_some_and(|wanted| phone.device_type.unwrap_or_default() != wanted)
+}
+
+/// Build the document of a parsed phone page: store prices, provenance, validation and
+/// the device type from the specs. A page without specifications (a block page parses
+/// "successfully" into nothing) is a `Blocked` error, so it never overwrites real data.
+pub fn build_phone_document(
+    phone: &PhoneListItem,
+    brand: &str,
+    spec_json: serde_json::Value,
+    html: &str,
+    options: &PhonePageOptions,
+) -> Result<PhonePage, (FailureCategory, Box<dyn Error>)> {
+    let mut document = PhoneDocument::new(phone, brand, spec_json);
+    document.market_prices = parse_market_prices(html);
+    if options.record_provenance {
+        document.record_provenance();
+    }
+
+    let validation = validate(&document, options.min_completeness);
+    if !validation.has_specifications {
+        return Err((FailureCategory::Blocked, "no specifications on page (blocked or empty)".into()));
+    }
+    if validation.needs_rescrape {
+        warn!(phone = %phone.phone_id, completeness = validation.completeness_score, "Incomplete specifications, flagged for re-scrape");
+    }
+    document.apply_validation(&validation);
+
+    if options.device_type.is_some_and(|wanted| document.device_type != wanted) {
+        return Ok(PhonePage::OtherType(document.device_type));
+    }
+    Ok(PhonePage::Document(Box::new(document)))
+}
+
+/// What became of one fetched phone
+enum PhoneOutcome {
+    Saved(Box<PhoneDocument>),
+    Unchanged, // Same specifications_raw as the stored document
+    OtherType, // Not the requested device type
+}
+
+type BrandHook = Arc<dyn Fn(&Brand, usize) + Send + Sync>;
+type PhoneHook = Arc<dyn Fn(&PhoneDocument) + Send + Sync>;
+type ErrorHook = Arc<dyn Fn(&ScrapeError) + Send + Sync>;
+
+/// Configures a `ScrapeRunner`; every option has a default
+pub struct ScrapeRunnerBuilder {
+    site: SiteConfig,
+    source: Arc<dyn PageSource>,
+    store: Option<Arc<dyn PhoneStore>>,
+    brands: BrandFilter,
+    max_brands: Option<usize>,
+    phones_per_brand: Option<usize>,
+    skip_existing: bool,
+    page: PhonePageOptions,
+    delay_between_phones: Duration,
+    self_check: bool,
+    error_policy: ErrorPolicy,
+    enrichers: Enrichers,
+    #[cfg(feature = "ledger")]
+    ledger: Option<Ledger>,
+    shutdown: Shutdown,
+    on_brand_start: Option<BrandHook>,
+    on_phone_scraped: Option<PhoneHook>,
+    on_error: Option<ErrorHook>,
+}
+
+impl ScrapeRunnerBuilder {
+    /// Site, brand filter, limits, device type, delay, completeness threshold, provenance, self-check and error policy from a loaded config
+    pub fn config(mut self, config: &Config) -> Self {
+        self.site = config.site.clone();
+        self.brands = config.brands.clone();
+        self.max_brands = config.scraping.max_brands;
+        self.phones_per_brand = config.scraping.phones_per_brand;
+        self.skip_existing = config.scraping.skip_existing;
+        self.page = PhonePageOptions::from_config(config);
+        self.delay_between_phones = Duration::from_millis(config.rate_limit.delay_between_phones_ms);
+        self.self_check = config.scraping.self_check;
+        self.error_policy = config.scraping.error_policy;
+        self
+    }
+
+    pub fn site(mut self, site: SiteConfig) -> Self {
+        self.site = site;
+        self
+    }
+
+    /// Where pages come from (default: plain GETs)
+    pub fn source(mut self, source: Arc<dyn PageSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Where phones are written; without one, `on_phone_scraped` is the only output
+    pub fn store(mut self, store: Arc<dyn PhoneStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn brands(mut self, brands: BrandFilter) -> Self {
+        self.brands = brands;
+        self
+    }
+
+    pub fn max_brands(mut self, max_brands: usize) -> Self {
+        self.max_brands = Some(max_brands);
+        self
+    }
+
+    pub fn phones_per_brand(mut self, phones_per_brand: usize) -> Self {
+        self.phones_per_brand = Some(phones_per_brand);
+        self
+    }
+
+    /// Skip phones the store already has, unless flagged for re-scrape (needs a store)
+    pub fn skip_existing(mut self, skip_existing: bool) -> Self {
+        self.skip_existing = skip_existing;
+        self
+    }
+
+    pub fn device_type(mut self, device_type: DeviceType) -> Self {
+        self.page.device_type = Some(device_type);
+        self
+    }
+
+    pub fn delay_between_phones(mut self, delay: Duration) -> Self {
+        self.delay_between_phones = delay;
+        self
+    }
+
+    pub fn min_completeness(mut self, min_completeness: f64) -> Self {
+        self.page.min_completeness = min_completeness;
+        self
+    }
+
+    /// Store which raw row each parsed field came from (see `provenance`)
+    pub fn record_provenance(mut self, record_provenance: bool) -> Self {
+        self.page.record_provenance = record_provenance;
+        self
+    }
+
+    /// Check a pinned brand and phone page before listing brands; `run` fails if the layout changed
+    pub fn self_check(mut self, self_check: bool) -> Self {
+        self.self_check = self_check;
+        self
+    }
+
+    /// How many failed listings and phones to put up with; `run` fails once the policy stops it
+    pub fn error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Attach data from another source to every phone scraped (repeatable; run in order)
+    pub fn enricher(mut self, enricher: Arc<dyn Enricher>) -> Self {
+        self.enrichers.push(enricher);
+        self
+    }
+
+    /// Remember scraped phones across runs without a readable store (`ledger` feature)
+    /// `skip_existing` then also skips phones in the ledger, and unchanged re-scrapes are not passed on.
+    #[cfg(feature = "ledger")]
+    pub fn ledger(mut self, ledger: Ledger) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Stop between phones once this is requested
+    pub fn shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Called before a brand's phones are fetched, with how many its listing returned
+    pub fn on_brand_start(mut self, hook: impl Fn(&Brand, usize) + Send + Sync + 'static) -> Self {
+        self.on_brand_start = Some(Arc::new(hook));
+        self
+    }
+
+    /// Called with every phone parsed, after it is stored
+    pub fn on_phone_scraped(mut self, hook: impl Fn(&PhoneDocument) + Send + Sync + 'static) -> Self {
+        self.on_phone_scraped = Some(Arc::new(hook));
+        self
+    }
+
+    /// Called for every failed listing or phone
+    pub fn on_error(mut self, hook: impl Fn(&ScrapeError) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Arc::new(hook));
+        self
+    }
+
+    pub fn build(self) -> ScrapeRunner {
+        ScrapeRunner { options: self }
+    }
+}
+
+/// Scrapes brands, listings and phone pages with the options of its builder
+pub struct ScrapeRunner {
+    options: ScrapeRunnerBuilder,
+}
+
+impl ScrapeRunner {
+    pub fn builder() -> ScrapeRunnerBuilder {
+        ScrapeRunnerBuilder {
+            site: SiteConfig::default(),
+            source: Arc::new(DirectSource),
+            store: None,
+            brands: BrandFilter::default(),
+            max_brands: None,
+            phones_per_brand: None,
+            skip_existing: true,
+            page: PhonePageOptions { min_completeness: DEFAULT_MIN_COMPLETENESS, record_provenance: false, device_type: None },
+            delay_between_phones: Duration::from_millis(500),
+            self_check: false,
+            error_policy: ErrorPolicy::Continue,
+            enrichers: Enrichers::default(),
+            #[cfg(feature = "ledger")]
+            ledger: None,
+            shutdown: Shutdown::new(),
+            on_brand_start: None,
+            on_phone_scraped: None,
+            on_error: None,
+        }
+    }
+
+    /// Run the pipeline; failing to list the brands is an error, the rest goes to `on_error`
+    /// (and is an error too once `error_policy` stops the run)
+    /// Page fetches block in `block_in_place`, so this needs a multi-threaded runtime.
+    pub async fn run(&self) -> Result<ScrapeSummary, Box<dyn Error>> {
+        let options = &self.options;
+        if options.self_check {
+            self_check(&options.site, |url| self.fetch(url))?;
+        }
+        let makers = self.fetch(&options.site.makers_url())?;
+        let brands: Vec<Brand> = parse_brands_page(&makers)
+            .into_iter()
+            .filter(|brand| options.brands.selects(brand))
+            .take(options.max_brands.unwrap_or(usize::MAX))
+            .collect();
+        info!(brands = brands.len(), "Scraping brands");
+
+        let mut summary = ScrapeSummary::default();
+        for brand in &brands {
+            if options.shutdown.is_requested() || self.policy_stops(&summary) {
+                summary.stopped = true;
+                break;
+            }
+            summary.brands += 1;
+            self.scrape_brand(brand, &mut summary).await;
+        }
+        if let Some(store) = &options.store {
+            store.flush().await?;
+        }
+
+        info!(
+            brands = summary.brands,
+            scraped = summary.phones_scraped,
+            skipped = summary.phones_skipped,
+            unchanged = summary.phones_unchanged,
+            errors = summary.errors,
+            failures = %summary.failures,
+            "✓ Scrape finished"
+        );
+        if let Some(reason) = options.error_policy.stop_reason(summary.errors as u64) {
+            return Err(format!("Run aborted: {}", reason).into());
+        }
+        Ok(summary)
+    }
+
+    fn policy_stops(&self, summary: &ScrapeSummary) -> bool {
+        self.options.error_policy.stop_reason(summary.errors as u64).is_some()
+    }
+
+    async fn scrape_brand(&self, brand: &Brand, summary: &mut ScrapeSummary) {
+        let options = &self.options;
+        let limit = options.phones_per_brand.unwrap_or(usize::MAX);
+        let listing = tokio::task::block_in_place(|| {
+            fetch_phones_by_brand_with(&options.site.base_url, &brand.slug, limit, |url| options.source.fetch_page(url))
+        });
+        let phones = match listing {
+            Ok(phones) => phones,
+            Err(e) => {
+                self.report(summary, brand, None, FailureCategory::classify(&e.to_string()), e.as_ref());
+                return;
+            }
+        };
+        if let Some(store) = &options.store {
+            if let Err(e) = store.save_brand_listing(brand, &phones).await {
+                self.report(summary, brand, None, FailureCategory::Storage, e.as_ref());
+            }
+        }
+        if let Some(hook) = &options.on_brand_start {
+            hook(brand, phones.len());
+        }
+
+        let mut fetched_any = false;
+        for phone in &phones {
+            if self.policy_stops(summary) {
+                summary.stopped = true;
+                return;
+            }
+            if listing_excludes(phone, options.page.device_type) {
+                skip(summary, SkipReason::Filtered);
+                continue;
+            }
+            let previous = match self.stored(&phone.phone_id).await {
+                Ok(previous) => previous,
+                Err(e) => {
+                    self.report(summary, brand, Some(phone), FailureCategory::Storage, e.as_ref());
+                    continue;
+                }
+            };
+            if options.skip_existing && previous.as_ref().is_some_and(|stored| !stored.needs_rescrape) {
+                debug!(phone = %phone.phone_id, "Already stored, skipping");
+                skip(summary, SkipReason::Complete);
+                continue;
+            }
+            let scraped_before = if previous.is_none() { self.ledger_hash(&phone.phone_id) } else { None };
+            if options.skip_existing && scraped_before.is_some() {
+                debug!(phone = %phone.phone_id, "In the ledger, skipping");
+                skip(summary, SkipReason::Existing);
+                continue;
+            }
+
+            if fetched_any && !options.shutdown.sleep(options.delay_between_phones).await {
+                summary.stopped = true;
+                return;
+            }
+            fetched_any = true;
+
+            match self.scrape_phone(brand, phone, previous.as_ref(), scraped_before.flatten()).await {
+                Ok(PhoneOutcome::Saved(document)) => {
+                    summary.phones_scraped += 1;
+                    if let Some(hook) = &options.on_phone_scraped {
+                        hook(&document);
+                    }
+                }
+                Ok(PhoneOutcome::Unchanged) => summary.phones_unchanged += 1,
+                Ok(PhoneOutcome::OtherType) => skip(summary, SkipReason::Filtered),
+                Err((category, e)) => self.report(summary, brand, Some(phone), category, e.as_ref()),
+            }
+        }
+    }
+
+    /// Fetch, parse, validate and store one phone; errors come with their category
+    /// `ledger_hash` is the specs hash the ledger recorded when nothing is stored.
+    async fn scrape_phone(
+        &self,
+        brand: &Brand,
+        phone: &PhoneListItem,
+        previous: Option<&PhoneDocument>,
+        ledger_hash: Option<String>,
+    ) -> Result<PhoneOutcome, (FailureCategory, Box<dyn Error>)> {
+        let options = &self.options;
+        let url = options.site.phone_page_url(&phone.phone_id);
+        let classified = |e: Box<dyn Error>| (FailureCategory::classify(&e.to_string()), e);
+        let html = self.fetch(&url).map_err(classified)?;
+        let spec = parse_specification_html(&phone.phone_id, &html).map_err(classified)?;
+        let spec_json = serde_json::to_value(&spec).map_err(|e| (FailureCategory::Parse, e.into()))?;
+        let mut document = match build_phone_document(phone, &brand.name, spec_json, &html, &options.page)? {
+            PhonePage::Document(document) => *document,
+            PhonePage::OtherType(_) => return Ok(PhoneOutcome::OtherType),
+        };
+        // Keep the stored document (and its updated_at) when the page has not changed
+        if previous.is_some_and(|stored| document.is_unchanged_from(stored)) || (ledger_hash.is_some() && ledger_hash == document.specs_hash) {
+            debug!(phone = %phone.phone_id, "Specifications unchanged, not rewritten");
+            // Store prices move on their own: keep the stored ones current
+            if let (Some(store), Some(_)) = (&options.store, previous) {
+                store.update_market_prices(&phone.phone_id, &document.market_prices).await.map_err(|e| (FailureCategory::Storage, e))?;
+            }
+            return Ok(PhoneOutcome::Unchanged);
+        }
+        lifecycle::track(previous, &mut document, Utc::now());
+        options.enrichers.apply(&mut document, previous).await;
+
+        if let Some(store) = &options.store {
+            store.upsert_phone(document.clone()).await.map_err(|e| (FailureCategory::Storage, e))?;
+        }
+        self.record_in_ledger(&document);
+        Ok(PhoneOutcome::Saved(Box::new(document)))
+    }
+
+    /// The specs hash a ledger recorded for a phone: None when it is not in the ledger
+    /// (or there is none), Some(None) when it was recorded without a hash
+    fn ledger_hash(&self, phone_id: &str) -> Option<Option<String>> {
+        #[cfg(feature = "ledger")]
+        if let Some(ledger) = &self.options.ledger {
+            match ledger.get(phone_id) {
+                Ok(entry) => return entry.map(|entry| entry.specs_hash),
+                Err(e) => warn!(phone = phone_id, error = %e, "Cannot read the ledger"),
+            }
+        }
+        let _ = phone_id;
+        None
+    }
+
+    fn record_in_ledger(&self, document: &PhoneDocument) {
+        #[cfg(feature = "ledger")]
+        if let Some(ledger) = &self.options.ledger {
+            if let Err(e) = ledger.record(document) {
+                warn!(phone = %document.phone_id, error = %e, "Cannot record phone in the ledger");
+            }
+        }
+        let _ = document;
+    }
+
+    async fn stored(&self, phone_id: &str) -> Result<Option<PhoneDocument>, Box<dyn Error>> {
+        match &self.options.store {
+            Some(store) => store.get_phone(phone_id).await,
+            None => Ok(None),
+        }
+    }
+
+    fn fetch(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        tokio::task::block_in_place(|| self.options.source.fetch_page(url))
+    }
+
+    fn report(&self, summary: &mut ScrapeSummary, brand: &Brand, phone: Option<&PhoneListItem>, category: FailureCategory, error: &dyn Error) {
+        summary.errors += 1;
+        summary.failures.record_failure(category);
+        let error = ScrapeError {
+            brand: brand.name.clone(),
+            phone_id: phone.map(|phone| phone.phone_id.clone()),
+            category,
+            message: error.to_string(),
+        };
+        warn!(brand = %error.brand, phone = ?error.phone_id, category = %error.category, error = %error.message, "✗ Scrape error");
+        if let Some(hook) = &self.options.on_error {
+            hook(&error);
+        }
+    }
+}
+
+fn skip(summary: &mut ScrapeSummary, reason: SkipReason) {
+    summary.phones_skipped += 1;
+    summary.failures.record_skip(reason);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FixtureSource, MemoryStore, FIXTURE_BASE_URL};
+    use std::sync::Mutex;
+
+    /// A runner over the fixture pages of Apple and Samsung, logging every hook call to `events`
+    fn runner(source: &Arc<FixtureSource>, store: &Arc<MemoryStore>, events: &Arc<Mutex<Vec<String>>>) -> ScrapeRunnerBuilder {
+        let (on_brand, on_phone, on_error) = (events.clone(), events.clone(), events.clone());
+        ScrapeRunner::builder()
+            .site(SiteConfig::new(FIXTURE_BASE_URL))
+            .source(source.clone())
+            .store(store.clone())
+            .brands(BrandFilter { include: vec!["apple".to_string(), "samsung".to_string()], ..Default::default() })
+            .phones_per_brand(2)
+            .delay_between_phones(Duration::ZERO)
+            .on_brand_start(move |brand, phones| on_brand.lock().unwrap().push(format!("brand {} {}", brand.name, phones)))
+            .on_phone_scraped(move |phone| on_phone.lock().unwrap().push(format!("phone {}", phone.phone_id)))
+            .on_error(move |error| on_error.lock().unwrap().push(format!("error {} {}", error.phone_id.as_deref().unwrap_or("-"), error.category)))
+    }
+
+    fn phone_pages(source: &FixtureSource) -> Vec<String> {
+        source.requested.lock().unwrap().iter().filter(|url| !url.contains("-phones-") && !url.ends_with("makers.php3")).cloned().collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_calls_hooks() {
+        let (source, store, events) = (Arc::new(FixtureSource::default()), Arc::new(MemoryStore::default()), Arc::default());
+
+        let summary = runner(&source, &store, &events).build().run().await.unwrap();
+        assert_eq!((summary.brands, summary.phones_scraped, summary.errors), (2, 2, 1));
+        assert_eq!(summary.failures.network, 1);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "brand Apple 2",
+                "error apple_iphone_15_pro_max-12548 network", // No fixture: a 404
+                "phone apple_iphone_15-12559",
+                "brand Samsung 1",
+                "phone samsung_galaxy_s24-12773",
+            ]
+        );
+        assert_eq!(store.phone_ids(), vec!["apple_iphone_15-12559", "samsung_galaxy_s24-12773"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_stops_on_error_policy() {
+        let (source, store, events) = (Arc::new(FixtureSource::default()), Arc::new(MemoryStore::default()), Arc::default());
+
+        let error = runner(&source, &store, &events).error_policy(ErrorPolicy::FailFast).build().run().await.unwrap_err();
+        assert!(error.to_string().contains("fail-fast"), "{}", error);
+        // Neither the second Apple phone nor Samsung's listing was fetched
+        assert_eq!(*events.lock().unwrap(), vec!["brand Apple 2", "error apple_iphone_15_pro_max-12548 network"]);
+        assert_eq!(phone_pages(&source), vec!["http://mirror.local/apple_iphone_15_pro_max-12548.php"]);
+        assert!(store.phone_ids().is_empty());
+
+        let summary = runner(&source, &store, &events).error_policy(ErrorPolicy::FailAfter(2)).build().run().await.unwrap();
+        assert!(!summary.stopped);
+        assert_eq!((summary.phones_scraped, summary.errors), (2, 1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_filters_device_type() {
+        let (source, store, events) = (Arc::new(FixtureSource::default()), Arc::new(MemoryStore::default()), Arc::default());
+
+        // The names say nothing, so every listed phone counts as a phone and is never fetched
+        let summary = runner(&source, &store, &events).device_type(DeviceType::Tablet).build().run().await.unwrap();
+        assert_eq!((summary.phones_scraped, summary.phones_skipped), (0, 3));
+        assert_eq!(summary.failures.skipped_filtered, 3);
+        assert!(phone_pages(&source).is_empty());
+        assert_eq!(*events.lock().unwrap(), vec!["brand Apple 2", "brand Samsung 1"]);
+
+        let summary = runner(&source, &store, &events).device_type(DeviceType::Phone).build().run().await.unwrap();
+        assert_eq!((summary.phones_scraped, summary.phones_skipped), (2, 0));
+        assert!(store.get_phone("samsung_galaxy_s24-12773").await.unwrap().is_some_and(|phone| phone.device_type == DeviceType::Phone));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unchanged_page_updates_market_prices() {
+        let (source, store, events) = (Arc::new(FixtureSource::default()), Arc::new(MemoryStore::default()), Arc::default());
+        runner(&source, &store, &events).build().run().await.unwrap();
+
+        // Same specifications, prices from an earlier scrape
+        let mut stored = store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap();
+        let scraped_prices = std::mem::take(&mut stored.market_prices);
+        assert_eq!(scraped_prices.len(), 5);
+        store.upsert_phone(stored.clone()).await.unwrap();
+
+        let summary = runner(&source, &store, &events).skip_existing(false).build().run().await.unwrap();
+        assert_eq!((summary.phones_scraped, summary.phones_unchanged), (0, 2));
+        let refreshed = store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap();
+        assert_eq!(refreshed.market_prices.len(), 5);
+        assert!(refreshed.market_prices[0].scraped_at > scraped_prices[0].scraped_at);
+        assert_eq!((refreshed.updated_at, refreshed.specs_hash), (stored.updated_at, stored.specs_hash));
+    }
+
+    #[test]
+    fn test_build_phone_document() {
+        let item = PhoneListItem {
+            phone_id: "apple_ipad_air_11_(2024)-12986".to_string(),
+            name: "iPad Air 11 (2024)".to_string(),
+            url: String::new(),
+            image_url: None,
+            device_type: DeviceType::from_name("iPad Air 11 (2024)"),
+        };
+        let options = PhonePageOptions { min_completeness: 0.0, record_provenance: false, device_type: Some(DeviceType::Phone) };
+        assert!(listing_excludes(&item, options.device_type));
+
+        let blocked = build_phone_document(&item, "Apple", serde_json::json!({ "specification": [] }), "<html></html>", &options);
+        assert!(matches!(blocked, Err((FailureCategory::Blocked, _))));
+
+        let html = std::fs::read_to_string(format!("{}/tests/fixtures/{}.php.html", env!("CARGO_MANIFEST_DIR"), item.phone_id)).unwrap();
+        let spec_json = serde_json::to_value(parse_specification_html(&item.phone_id, &html).unwrap()).unwrap();
+        let built = build_phone_document(&item, "Apple", spec_json, &html, &options);
+        assert!(matches!(built, Ok(PhonePage::OtherType(DeviceType::Tablet))));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::compression;
+use crate::page_cache::PageCache;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Previously saved GSMArena pages, read back instead of fetching them
+///
+/// A URL is looked up by its file name (`makers.php3`, `apple-phones-48.php`,
+/// `apple_iphone_15-12559.php`), optionally with `.html` and/or `.gz` appended,
+/// and then as a page cache entry, so a `cache.dir` can be replayed as well.
+#[derive(Debug, Clone)]
+pub struct SavedPages {
+    dir: PathBuf,
+    cache: PageCache,
+}
+
+impl SavedPages {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let dir = dir.into();
+        if !dir.is_dir() {
+            return Err(format!("Saved pages directory {} does not exist", dir.display()).into());
+        }
+
+        let cache = PageCache::new(&dir, None, false)?;
+        Ok(Self { dir, cache })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Body of the saved page for a URL
+    pub fn get(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        let name = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| format!("Cannot map {} to a saved page", url))?;
+
+        for candidate in [name.to_string(), format!("{}.html", name), format!("{}.gz", name), format!("{}.html.gz", name)] {
+            let path = self.dir.join(&candidate);
+            if path.is_file() {
+                return compression::read_to_string(&path);
+            }
+        }
+
+        self.cache
+            .stored_body(url)
+            .ok_or_else(|| format!("{} is not among the saved pages in {}", name, self.dir.display()).into())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_page_lookup() {
+        let dir = std::env::temp_dir().join(format!("gsmarena-saved-pages-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("makers.php3"), "<html>makers</html>").unwrap();
+        std::fs::write(dir.join("apple-phones-48.php.html"), "<html>apple</html>").unwrap();
+        compression::write(&dir.join("samsung-phones-9.php.gz"), b"<html>samsung</html>", compression::FileCompression::Gzip).unwrap();
+        PageCache::new(&dir, None, true)
+            .unwrap()
+            .put("https://www.gsmarena.com/apple_iphone_15-12559.php", "<html>iPhone 15</html>")
+            .unwrap();
+
+        let pages = SavedPages::new(&dir).unwrap();
+        assert_eq!(pages.get("https://www.gsmarena.com/makers.php3").unwrap(), "<html>makers</html>");
+        assert_eq!(pages.get("https://www.gsmarena.com/apple-phones-48.php").unwrap(), "<html>apple</html>");
+        assert_eq!(pages.get("https://www.gsmarena.com/samsung-phones-9.php").unwrap(), "<html>samsung</html>");
+        assert_eq!(pages.get("https://www.gsmarena.com/apple_iphone_15-12559.php").unwrap(), "<html>iPhone 15</html>");
+        assert!(pages.get("https://www.gsmarena.com/apple-phones-48-p2.php").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
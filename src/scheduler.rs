@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use rand::Rng;
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Cron schedule for daemon mode, with optional random jitter
+///
+/// Accepts standard 5-field expressions ("0 3 * * *") as well as the
+/// 6/7-field form with seconds (and year) used by the `cron` crate.
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    expression: String,
+    schedule: Schedule,
+    jitter: Duration,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str, jitter: Duration) -> Result<Self, Box<dyn Error>> {
+        let expression = expression.trim();
+        let fields = expression.split_whitespace().count();
+
+        // Standard cron has no seconds field; run at second 0
+        let normalized = match fields {
+            5 => format!("0 {}", expression),
+            6 | 7 => expression.to_string(),
+            _ => return Err(format!("Invalid cron expression '{}': expected 5 to 7 fields", expression).into()),
+        };
+
+        let schedule = Schedule::from_str(&normalized)
+            .map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))?;
+
+        Ok(Self {
+            expression: expression.to_string(),
+            schedule,
+            jitter,
+        })
+    }
+
+    /// The expression as given
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Next fire time strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule.after(&after).next()
+    }
+
+    /// Number of fire times in `(from, to]`, i.e. runs missed while a cycle was busy
+    pub fn missed_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> usize {
+        self.schedule.after(&from).take_while(|t| *t <= to).count()
+    }
+
+    /// Random delay in `[0, jitter]` added to each run so fleets don't hit the site in lockstep
+    pub fn jitter_delay(&self) -> Duration {
+        let max_ms = self.jitter.as_millis() as u64;
+        if max_ms == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(0..=max_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_cron_schedule() {
+        let schedule = CronSchedule::parse("30 3 * * *", Duration::ZERO).unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+
+        let next = schedule.next_after(start).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2024, 5, 2, 3, 30, 0).unwrap());
+
+        // A cycle that ran for three days skipped two later runs
+        let finished = Utc.with_ymd_and_hms(2024, 5, 4, 12, 0, 0).unwrap();
+        assert_eq!(schedule.missed_between(next, finished), 2);
+
+        assert_eq!(schedule.jitter_delay(), Duration::ZERO);
+        assert!(CronSchedule::parse("0 */6 * * * *", Duration::from_secs(60)).unwrap().jitter_delay() <= Duration::from_secs(60));
+        assert!(CronSchedule::parse("every day", Duration::ZERO).is_err());
+    }
+}
@@ -1,5 +1,10 @@
+use crate::site::{page_url, DEFAULT_BASE_URL};
 use gsmarena::{DeviceSpecification};
+use reqwest::blocking;
+use crate::selectors::SelectorChain;
+use scraper::Html;
 use std::error::Error;
+use tracing::error;
 
 /// Wrapper around the gsmarena crate for easier usage
 pub struct GsmArenaScraper;
@@ -30,7 +35,7 @@ impl GsmArenaScraper {
         for phone_id in phone_ids {
             match self.get_phone_details(phone_id) {
                 Ok(phone) => phones.push(phone),
-                Err(e) => eprintln!("Error fetching phone {}: {}", phone_id, e),
+                Err(e) => error!(phone = %phone_id, error = %e, "Error fetching phone"),
             }
         }
 
@@ -38,6 +43,83 @@ impl GsmArenaScraper {
     }
 }
 
+/// URL of a phone's specification page
+pub fn phone_page_url(phone_id: &str) -> String {
+    phone_page_url_from(DEFAULT_BASE_URL, phone_id)
+}
+
+/// URL of a phone's specification page on a mirror or test server
+pub fn phone_page_url_from(base_url: &str, phone_id: &str) -> String {
+    page_url(base_url, &format!("{}.php", phone_id))
+}
+
+/// Fetch and parse a phone page without panicking on network errors
+/// (unlike `gsmarena::get_specification`)
+pub fn fetch_specification(phone_id: &str) -> Result<DeviceSpecification, Box<dyn Error>> {
+    fetch_specification_from(DEFAULT_BASE_URL, phone_id)
+}
+
+/// Fetch and parse a phone page from a mirror or test server
+pub fn fetch_specification_from(base_url: &str, phone_id: &str) -> Result<DeviceSpecification, Box<dyn Error>> {
+    let response = blocking::get(phone_page_url_from(base_url, phone_id))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Phone page returned status: {}", response.status()).into());
+    }
+
+    parse_specification_html(phone_id, &response.text()?)
+}
+
+/// Parse a phone page fetched by any means (direct, proxy, ScrapingBee)
+/// Produces the same structure as the gsmarena crate: one category per `#specs-list` table
+pub fn parse_specification_html(phone_id: &str, html: &str) -> Result<DeviceSpecification, Box<dyn Error>> {
+    let document = Html::parse_document(html);
+
+    let tables = SelectorChain::get("spec_tables");
+    let titles = SelectorChain::get("spec_title");
+    let rows = SelectorChain::get("spec_rows");
+    let cells = SelectorChain::get("spec_cells");
+
+    let mut categories = Vec::new();
+
+    for table in tables.select_in(&document) {
+        let title = titles
+            .first(table)
+            .map(|th| th.text().collect::<String>().trim().to_string())
+            .unwrap_or_default();
+
+        let mut specs = Vec::new();
+        for row in rows.select(table) {
+            let row_cells = cells.select(row);
+            if row_cells.len() < 2 {
+                continue;
+            }
+
+            let key = row_cells[0].text().next().unwrap_or("").to_string();
+            let value = row_cells[1]
+                .text()
+                .filter(|t| *t != "\n" && *t != "\n\n")
+                .collect::<String>();
+
+            specs.push(serde_json::json!([key, value]));
+        }
+
+        categories.push(serde_json::json!({ "category_title": title, "category_spec": specs }));
+    }
+
+    if categories.is_empty() {
+        return Err(format!("No specifications found on page for {}", phone_id).into());
+    }
+
+    // DeviceSpecification fields are private, so build it through its JSON shape
+    let spec = serde_json::from_value(serde_json::json!({
+        "name": phone_id,
+        "specification": categories,
+    }))?;
+
+    Ok(spec)
+}
+
 impl Default for GsmArenaScraper {
     fn default() -> Self {
         Self::new()
@@ -48,6 +130,25 @@ impl Default for GsmArenaScraper {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_specification_html() {
+        let html = r##"
+            <div id="specs-list">
+                <table><tr><th rowspan="2">Launch</th><td class="ttl"><a href="#">Announced</a></td><td class="nfo">2023, September 12</td></tr>
+                <tr><td class="ttl"><a href="#">Status</a></td><td class="nfo">Available. Released 2023, September 22</td></tr></table>
+                <table><tr><th>Battery</th><td class="ttl"><a href="#">Type</a></td><td class="nfo">Li-Ion 3349 mAh</td></tr></table>
+            </div>
+        "##;
+
+        let spec = parse_specification_html("apple_iphone_15-12559", html).unwrap();
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json["specification"][0]["category_title"], "Launch");
+        assert_eq!(json["specification"][0]["category_spec"][1][0], "Status");
+        assert_eq!(json["specification"][1]["category_spec"][0][1], "Li-Ion 3349 mAh");
+
+        assert!(parse_specification_html("x-1", "<html></html>").is_err());
+    }
+
     #[test]
     fn test_get_phone() {
         let scraper = GsmArenaScraper::new();
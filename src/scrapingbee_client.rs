@@ -1,18 +1,38 @@
+use crate::config::ScrapeProvider;
+use crate::http_stats::HttpStats;
+use crate::secrets::required_secret;
 use reqwest::blocking::Client;
+use serde::Deserialize;
 use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+/// Credits of one API key, as reported by the usage endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyUsage {
+    pub max_api_credit: u64,
+    pub used_api_credit: u64,
+}
+
+impl KeyUsage {
+    pub fn credits_left(&self) -> u64 {
+        self.max_api_credit.saturating_sub(self.used_api_credit)
+    }
+}
 
 pub struct ScrapingBeeClient {
     client: Client,
     api_keys: Arc<Mutex<Vec<String>>>,
     current_index: Arc<Mutex<usize>>,
+    credits_used: Arc<AtomicU64>,
+    stats: HttpStats,
 }
 
 impl ScrapingBeeClient {
-    /// Create a new ScrapingBee client from comma-separated API keys in env
+    /// Create a new ScrapingBee client from comma-separated API keys in env (or `SCRAPINGBEE_API_KEYS_FILE`)
     pub fn from_env() -> Result<Self, Box<dyn Error>> {
-        let api_keys_str = std::env::var("SCRAPINGBEE_API_KEYS")
-            .map_err(|_| "SCRAPINGBEE_API_KEYS not set in environment")?;
+        let api_keys_str = required_secret("SCRAPINGBEE_API_KEYS")?;
         
         let api_keys: Vec<String> = api_keys_str
             .split(',')
@@ -24,7 +44,7 @@ impl ScrapingBeeClient {
             return Err("No valid ScrapingBee API keys found".into());
         }
         
-        println!("✓ Loaded {} ScrapingBee API key(s)", api_keys.len());
+        info!(count = api_keys.len(), "✓ Loaded ScrapingBee API keys");
         
         Ok(Self::new(api_keys))
     }
@@ -40,6 +60,8 @@ impl ScrapingBeeClient {
             client,
             api_keys: Arc::new(Mutex::new(api_keys)),
             current_index: Arc::new(Mutex::new(0)),
+            credits_used: Arc::new(AtomicU64::new(0)),
+            stats: HttpStats::default(),
         }
     }
     
@@ -59,8 +81,23 @@ impl ScrapingBeeClient {
         Ok(key)
     }
     
+    /// Count every API response (including 429s from rotated keys) into a run's stats
+    pub fn set_stats(&mut self, stats: &HttpStats) {
+        self.stats = stats.clone();
+    }
+
+    /// API credits consumed by this client so far
+    pub fn credits_used(&self) -> u64 {
+        self.credits_used.load(Ordering::Relaxed)
+    }
+
     /// Fetch a URL through ScrapingBee with automatic API key rotation
     pub fn fetch(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        self.fetch_billed(url).map(|(body, _)| body)
+    }
+
+    /// Fetch a URL, also returning the credits it cost (failed requests are not billed)
+    pub fn fetch_billed(&self, url: &str) -> Result<(String, u64), Box<dyn Error>> {
         let keys_len = self.api_keys.lock().unwrap().len();
         
         // Try all API keys before giving up
@@ -73,16 +110,30 @@ impl ScrapingBeeClient {
                 urlencoding::encode(url)
             );
             
+            let started = std::time::Instant::now();
             match self.client.get(&scrapingbee_url).send() {
                 Ok(response) => {
                     let status = response.status();
                     
                     if status.is_success() {
-                        return response.text().map_err(|e| e.into());
-                    } else if status.as_u16() == 429 || status.as_u16() == 403 {
+                        // Successful requests are billed; the cost header is authoritative when present
+                        let cost = response
+                            .headers()
+                            .get("spb-cost")
+                            .and_then(|v| v.to_str().ok())
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(1);
+                        self.credits_used.fetch_add(cost, Ordering::Relaxed);
+
+                        let body = response.text()?;
+                        self.stats.record_response(ScrapeProvider::ScrapingBee, status.as_u16(), body.len(), started.elapsed());
+                        return Ok((body, cost));
+                    }
+
+                    self.stats.record_response(ScrapeProvider::ScrapingBee, status.as_u16(), 0, started.elapsed());
+                    if status.as_u16() == 429 || status.as_u16() == 403 {
                         // API key exhausted or blocked, try next key
-                        println!("  ⚠ API key {} exhausted/blocked (status {}), switching to next key...", 
-                                 attempt, status);
+                        warn!(key = attempt, status = %status, "API key exhausted/blocked, switching to next key");
                         
                         if attempt < keys_len {
                             std::thread::sleep(std::time::Duration::from_millis(500));
@@ -95,8 +146,11 @@ impl ScrapingBeeClient {
                     }
                 }
                 Err(e) => {
+                    // The request URL carries the API key
+                    let e = e.without_url();
+                    self.stats.record_error(ScrapeProvider::ScrapingBee, started.elapsed());
                     if attempt < keys_len {
-                        println!("  ⚠ Request failed ({}), trying next API key...", e);
+                        warn!(error = %e, "Request failed, trying next API key");
                         std::thread::sleep(std::time::Duration::from_millis(500));
                         continue;
                     } else {
@@ -109,6 +163,20 @@ impl ScrapingBeeClient {
         Err("Failed to fetch after trying all API keys".into())
     }
     
+    /// Look up every key on the usage endpoint, which costs no credits; an error marks an invalid key
+    pub fn key_usage(&self) -> Vec<Result<KeyUsage, Box<dyn Error>>> {
+        let keys = self.api_keys.lock().unwrap().clone();
+        keys.iter()
+            .map(|api_key| {
+                let response = self.client.get("https://app.scrapingbee.com/api/v1/usage").query(&[("api_key", api_key)]).send().map_err(|e| e.without_url())?;
+                if !response.status().is_success() {
+                    return Err(format!("ScrapingBee returned status: {}", response.status()).into());
+                }
+                Ok(response.json()?)
+            })
+            .collect()
+    }
+
     /// Get the number of API keys loaded
     pub fn api_key_count(&self) -> usize {
         self.api_keys.lock().unwrap().len()
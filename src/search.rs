@@ -0,0 +1,170 @@
+use crate::site::{page_url, DEFAULT_BASE_URL};
+use reqwest::blocking;
+use crate::selectors::SelectorChain;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub name: String,
+    pub phone_id: String,       // e.g. "samsung_galaxy_s24-12771"
+    pub device_id: Option<u32>, // Numeric GSMArena device ID, e.g. 12771
+    pub url: String,
+    pub image_url: Option<String>,
+    pub description: Option<String>, // Listing blurb ("... Announced Jan 2024. Features ...")
+    pub announced_year: Option<i32>,
+}
+
+/// Optional filters applied to search results
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    pub brand: Option<String>,
+    pub year: Option<i32>,
+}
+
+impl SearchFilters {
+    /// Check whether a result passes all configured filters
+    pub fn matches(&self, result: &SearchResult) -> bool {
+        if let Some(brand) = &self.brand {
+            let brand_prefix = format!("{}_", brand.trim().to_lowercase().replace(' ', "_"));
+            if !result.phone_id.to_lowercase().starts_with(&brand_prefix) {
+                return false;
+            }
+        }
+
+        if let Some(year) = self.year {
+            if result.announced_year != Some(year) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Search GSMArena for phones matching a query
+pub fn search(query: &str) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    search_with_filters(query, &SearchFilters::default())
+}
+
+/// Search GSMArena and keep only results passing the given filters
+pub fn search_with_filters(query: &str, filters: &SearchFilters) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    search_with_filters_from(DEFAULT_BASE_URL, query, filters)
+}
+
+/// Search a mirror or test server
+pub fn search_with_filters_from(base_url: &str, query: &str, filters: &SearchFilters) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    let url = page_url(base_url, &format!("res.php3?sSearch={}", urlencoding::encode(query)));
+
+    let response = blocking::get(&url)?;
+    if !response.status().is_success() {
+        return Err(format!("Search returned status: {}", response.status()).into());
+    }
+
+    let body = response.text()?;
+
+    Ok(parse_search_results(&body)
+        .into_iter()
+        .filter(|result| filters.matches(result))
+        .collect())
+}
+
+/// Parse the res.php3 results page
+pub fn parse_search_results(html: &str) -> Vec<SearchResult> {
+    let document = Html::parse_document(html);
+
+    let images = SelectorChain::get("phone_image");
+
+    let mut results = Vec::new();
+
+    for element in SelectorChain::get("phone_links").select_in(&document) {
+        let href = match element.value().attr("href") {
+            Some(href) => href,
+            None => continue,
+        };
+
+        // Names are split across lines ("Samsung<br>Galaxy S24")
+        let name = element
+            .text()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let phone_id = href.trim_end_matches(".php").to_string();
+        let img = images.first(element);
+
+        let image_url = img
+            .and_then(|img| img.value().attr("src"))
+            .map(|src| {
+                if src.starts_with("http") {
+                    src.to_string()
+                } else {
+                    page_url(DEFAULT_BASE_URL, src)
+                }
+            });
+
+        let description = img
+            .and_then(|img| img.value().attr("title"))
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+
+        let announced_year = description.as_deref().and_then(announced_year_from_description);
+
+        results.push(SearchResult {
+            name,
+            device_id: device_id_from_phone_id(&phone_id),
+            url: page_url(DEFAULT_BASE_URL, href),
+            phone_id,
+            image_url,
+            description,
+            announced_year,
+        });
+    }
+
+    results
+}
+
+/// Extract the numeric device ID from a phone ID
+/// Example: "apple_iphone_15_pro_max-12548" -> 12548
+pub fn device_id_from_phone_id(phone_id: &str) -> Option<u32> {
+    phone_id.rsplit_once('-').and_then(|(_, id)| id.parse().ok())
+}
+
+/// Extract the year from a listing blurb such as "Announced Jan 2024."
+fn announced_year_from_description(description: &str) -> Option<i32> {
+    let rest = &description[description.find("Announced")? + "Announced".len()..];
+
+    rest.split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 4)
+        .and_then(|year| year.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_results() {
+        let html = r#"
+            <div class="makers"><ul>
+                <li><a href="samsung_galaxy_s24-12773.php">
+                    <img src="https://fdn2.gsmarena.com/vv/bigpic/samsung-galaxy-s24-5g-sm-s921.jpg"
+                         title="Samsung Galaxy S24 Android smartphone. Announced Jan 2024. Features 6.2″ display.">
+                    <strong><span>Samsung<br>Galaxy S24</span></strong></a></li>
+                <li><a href="apple_iphone_15-12559.php"><img src="x.jpg"><strong><span>Apple<br>iPhone 15</span></strong></a></li>
+            </ul></div>
+        "#;
+
+        let results = parse_search_results(html);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "Samsung Galaxy S24");
+        assert_eq!(results[0].device_id, Some(12773));
+        assert_eq!(results[0].announced_year, Some(2024));
+
+        let filters = SearchFilters { brand: Some("Samsung".to_string()), year: Some(2024) };
+        assert!(filters.matches(&results[0]));
+        assert!(!filters.matches(&results[1]));
+    }
+}
@@ -0,0 +1,187 @@
+//! Every CSS selector the page parsers use, with fallbacks for older or changed markup
+//!
+//! Each element has a chain of selectors tried in order; the first one that matches
+//! anything wins. A fallback match is logged once per element, so a GSMArena layout
+//! change shows up as a warning instead of silently empty results. The `[selectors]`
+//! config section puts its selectors in front of the built-in chain:
+//!
+//! ```toml
+//! [selectors]
+//! phone_links = ["div.makers-v2 li a"]
+//! ```
+
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, RwLock};
+use tracing::warn;
+
+/// Element name -> selectors, primary first
+pub const DEFAULTS: &[(&str, &[&str])] = &[
+    // Phone page
+    ("spec_tables", &["#specs-list table", "div.specs-list table", "table.specs"]),
+    ("spec_title", &["th"]),
+    ("spec_rows", &["tr"]),
+    ("spec_cells", &["td"]),
+    // Pricing widget on the phone page
+    ("price_tables", &["#pricing-widget table", "div.pricing table", "table.pricing"]),
+    ("price_region", &["caption", "th.region"]),
+    ("price_variants", &["thead th"]),
+    ("price_rows", &["tbody tr", "tr"]),
+    ("price_store_logo", &["img"]),
+    ("price_links", &["a[href]"]),
+    // Makers page
+    ("brand_links", &["div.st-text table td a", "div.brandmenu-v2 ul li a", "div.brandmenu ul li a"]),
+    ("brand_count", &["span"]),
+    // Brand page header
+    ("brand_name", &["h1.article-info-name", ".article-info-name"]),
+    ("brand_logo", &[".article-info img", ".brand-logo img", ".review-header img"]),
+    ("brand_description", &[".article-info .st-text p", ".brand-description", ".article-info-meta p"]),
+    ("brand_website", &[".article-info a[href^='http']", ".brand-description a[href^='http']", "a.brand-website"]),
+    ("brand_year_links", &["a[href*='nYearMin=']"]),
+    // Listings (brand pages, Phone Finder and search results)
+    ("listing_pages", &["div.nav-pages strong, div.nav-pages a", "div.review-nav-v2 a"]),
+    ("phone_links", &["div.makers ul li a", "div.makers li a"]),
+    ("phone_image", &["img"]),
+    // Pictures page
+    ("gallery_images", &["#pictures-list img", ".pictures-list img"]),
+    ("spin_frames", &["#spin360 img, .spin360 img, [data-spin-frame]"]),
+];
+
+/// Selectors from config, tried before the built-in ones
+pub type SelectorOverrides = BTreeMap<String, Vec<String>>;
+
+static OVERRIDES: RwLock<SelectorOverrides> = RwLock::new(BTreeMap::new());
+static WARNED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Install config overrides for every parser in the process; rejected as a whole when any entry is invalid
+pub fn configure(overrides: &SelectorOverrides) -> Result<(), Vec<String>> {
+    let problems = check(overrides);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+    *OVERRIDES.write().unwrap() = overrides.clone();
+    Ok(())
+}
+
+/// Unknown element names and selectors that do not parse
+pub fn check(overrides: &SelectorOverrides) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (name, selectors) in overrides {
+        if !DEFAULTS.iter().any(|(known, _)| known == name) {
+            let names: Vec<&str> = DEFAULTS.iter().map(|(known, _)| *known).collect();
+            problems.push(format!("selectors.{} is not a known element ({})", name, names.join(", ")));
+        }
+        for selector in selectors {
+            if Selector::parse(selector).is_err() {
+                problems.push(format!("selectors.{}: invalid CSS selector '{}'", name, selector));
+            }
+        }
+    }
+    problems
+}
+
+/// The compiled chain for one element
+pub struct SelectorChain {
+    name: &'static str,
+    selectors: Vec<(String, Selector)>,
+}
+
+impl SelectorChain {
+    /// Overrides for `name` followed by its built-in selectors
+    pub fn get(name: &'static str) -> Self {
+        let overrides = OVERRIDES.read().unwrap().get(name).cloned().unwrap_or_default();
+        Self::with_overrides(name, &overrides)
+    }
+
+    fn with_overrides(name: &'static str, overrides: &[String]) -> Self {
+        let defaults = DEFAULTS
+            .iter()
+            .find(|(known, _)| *known == name)
+            .unwrap_or_else(|| panic!("no selectors for '{}'", name))
+            .1;
+        let selectors = overrides
+            .iter()
+            .map(String::as_str)
+            .chain(defaults.iter().copied())
+            .filter_map(|css| Some((css.to_string(), Selector::parse(css).ok()?)))
+            .collect();
+        Self { name, selectors }
+    }
+
+    /// Matches of the first selector in the chain that matches anything under `scope`
+    pub fn select<'a>(&self, scope: ElementRef<'a>) -> Vec<ElementRef<'a>> {
+        for (index, (css, selector)) in self.selectors.iter().enumerate() {
+            let found: Vec<ElementRef<'a>> = scope.select(selector).collect();
+            if found.is_empty() {
+                continue;
+            }
+            if index > 0 {
+                self.warn_fallback(css);
+            }
+            return found;
+        }
+        Vec::new()
+    }
+
+    /// Matches anywhere in the document
+    pub fn select_in<'a>(&self, document: &'a Html) -> Vec<ElementRef<'a>> {
+        self.select(document.root_element())
+    }
+
+    pub fn first<'a>(&self, scope: ElementRef<'a>) -> Option<ElementRef<'a>> {
+        self.select(scope).into_iter().next()
+    }
+
+    pub fn first_in<'a>(&self, document: &'a Html) -> Option<ElementRef<'a>> {
+        self.first(document.root_element())
+    }
+
+    /// How many elements the chain finds in a document, and whether a fallback found them (no warning)
+    pub fn probe(&self, document: &Html) -> (usize, bool) {
+        self.selectors
+            .iter()
+            .enumerate()
+            .map(|(index, (_, selector))| (document.select(selector).count(), index > 0))
+            .find(|(found, _)| *found > 0)
+            .unwrap_or((0, false))
+    }
+
+    /// Once per element and selector, so a changed layout doesn't flood the log
+    fn warn_fallback(&self, css: &str) {
+        if WARNED.lock().unwrap().insert(format!("{} {}", self.name, css)) {
+            let primary = &self.selectors[0].0;
+            warn!(element = self.name, primary = %primary, fallback = %css, "Primary selector matched nothing, using fallback (page layout changed?)");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_chain() {
+        let document = Html::parse_document(r#"<div class="makers"><li><a href="a-1.php">A</a></li></div>"#);
+        let phones = SelectorChain::get("phone_links");
+        assert_eq!(phones.select_in(&document).len(), 1); // Through "div.makers li a"
+
+        let overridden = SelectorChain::with_overrides("phone_links", &["a[href$='.php']".to_string(), "[".to_string()]);
+        assert_eq!(overridden.selectors.len(), 3); // The invalid override is dropped
+        assert_eq!(overridden.first_in(&document).unwrap().value().attr("href"), Some("a-1.php"));
+        assert!(SelectorChain::get("spec_tables").select_in(&document).is_empty());
+
+        let overrides = SelectorOverrides::from([
+            ("phone_links".to_string(), vec!["div.makers-v2 a".to_string()]),
+            ("phone_link".to_string(), vec!["a".to_string()]),
+            ("spec_rows".to_string(), vec!["tr[".to_string()]),
+        ]);
+        let problems = check(&overrides);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].starts_with("selectors.phone_link is not a known element"));
+        assert!(problems[1].contains("invalid CSS selector 'tr['"));
+        assert!(configure(&overrides).is_err());
+        for (name, chain) in DEFAULTS {
+            assert!(chain.iter().all(|css| Selector::parse(css).is_ok()), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Preflight canary: is the site still laid out the way the parsers expect?
+//!
+//! Fetches one known brand listing and one known phone page before a run and counts
+//! what the selectors find on them. A changed layout then stops the run with an error
+//! naming the elements that came up short, instead of writing a night's worth of
+//! empty documents.
+
+use crate::brand_scraper::PhoneListItem;
+use crate::mongodb::PhoneDocument;
+use crate::scraper::parse_specification_html;
+use crate::selectors::SelectorChain;
+use crate::site::SiteConfig;
+use scraper::Html;
+use std::error::Error;
+use tracing::{info, warn};
+
+/// Brand listing fetched by the check (Apple: large, always listed)
+pub const CANARY_BRAND: &str = "apple-phones-48";
+/// Phone page fetched by the check, pinned so its spec sheet does not change
+pub const CANARY_PHONE: &str = "apple_iphone_15-12559";
+
+/// Least each element must match on the brand listing
+const BRAND_PAGE: &[(&str, usize)] = &[("phone_links", 1), ("brand_name", 1)];
+/// Least each element must match on the phone page (the live page has 13 categories)
+const PHONE_PAGE: &[(&str, usize)] = &[("spec_tables", 8), ("spec_title", 8), ("spec_rows", 12), ("spec_cells", 24)];
+
+/// What one selector chain found on a canary page
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementCount {
+    pub page: String,
+    pub element: &'static str,
+    pub found: usize,
+    pub expected: usize, // At least
+    pub fallback: bool,  // Only a fallback selector matched
+}
+
+impl ElementCount {
+    pub fn passed(&self) -> bool {
+        self.found >= self.expected
+    }
+}
+
+/// Counts from both canary pages, plus fields the parsed phone came out without
+#[derive(Debug, Clone, Default)]
+pub struct SelfCheck {
+    pub counts: Vec<ElementCount>,
+    pub missing_fields: Vec<&'static str>,
+}
+
+impl SelfCheck {
+    pub fn passed(&self) -> bool {
+        self.counts.iter().all(ElementCount::passed) && self.missing_fields.is_empty()
+    }
+
+    /// One line per problem, for the error that aborts the run
+    pub fn problems(&self) -> Vec<String> {
+        let counts = self.counts.iter().filter(|count| !count.passed()).map(|count| {
+            format!("{}: '{}' matched {} element(s), expected at least {}", count.page, count.element, count.found, count.expected)
+        });
+        let fields = self.missing_fields.iter().map(|field| format!("{}: parsed without {}", CANARY_PHONE, field));
+        counts.chain(fields).collect()
+    }
+}
+
+/// Check the pinned brand listing and phone page; Err when either can't be fetched or the layout changed
+pub fn self_check<F>(site: &SiteConfig, mut fetch: F) -> Result<SelfCheck, Box<dyn Error>>
+where
+    F: FnMut(&str) -> Result<String, Box<dyn Error>>,
+{
+    let brand_url = site.brand_page_url(CANARY_BRAND, 1);
+    let phone_url = site.phone_page_url(CANARY_PHONE);
+    let brand_html = fetch(&brand_url).map_err(|e| format!("Self-check could not fetch {}: {}", brand_url, e))?;
+    let phone_html = fetch(&phone_url).map_err(|e| format!("Self-check could not fetch {}: {}", phone_url, e))?;
+
+    let check = check_pages(&brand_html, &phone_html);
+    for count in check.counts.iter().filter(|count| count.fallback && count.passed()) {
+        warn!(page = %count.page, element = count.element, "Self-check: only a fallback selector matched");
+    }
+    if !check.passed() {
+        return Err(format!(
+            "Self-check failed, the site layout seems to have changed (fix or override the selectors, see [selectors]):\n  {}",
+            check.problems().join("\n  ")
+        )
+        .into());
+    }
+    info!(brand = CANARY_BRAND, phone = CANARY_PHONE, "✓ Self-check passed: site layout as expected");
+    Ok(check)
+}
+
+/// Count the selector matches on already fetched canary pages
+pub fn check_pages(brand_html: &str, phone_html: &str) -> SelfCheck {
+    let mut check = SelfCheck::default();
+    for (page, html, expected) in [(CANARY_BRAND, brand_html, BRAND_PAGE), (CANARY_PHONE, phone_html, PHONE_PAGE)] {
+        let document = Html::parse_document(html);
+        for (element, at_least) in expected {
+            let (found, fallback) = SelectorChain::get(element).probe(&document);
+            check.counts.push(ElementCount { page: page.to_string(), element, found, expected: *at_least, fallback });
+        }
+    }
+
+    // Tables can still be there with the rows no longer where the parser reads them
+    let item = PhoneListItem {
+        phone_id: CANARY_PHONE.to_string(),
+        name: "iPhone 15".to_string(),
+        url: crate::scraper::phone_page_url(CANARY_PHONE),
+        image_url: None,
+        device_type: None,
+    };
+    match parse_specification_html(CANARY_PHONE, phone_html).map(|spec| serde_json::to_value(&spec)) {
+        Ok(Ok(spec)) => {
+            let phone = PhoneDocument::new(&item, "Apple", spec);
+            let fields = [
+                ("launch.announced", phone.launch.as_ref().and_then(|l| l.announced.as_ref()).is_some()),
+                ("display.size", phone.display.as_ref().and_then(|d| d.size.as_ref()).is_some()),
+                ("platform.chipset", phone.platform.as_ref().and_then(|p| p.chipset.as_ref()).is_some()),
+                ("battery.battery_type", phone.battery.as_ref().and_then(|b| b.battery_type.as_ref()).is_some()),
+            ];
+            check.missing_fields = fields.into_iter().filter(|(_, present)| !present).map(|(field, _)| field).collect();
+        }
+        _ => check.missing_fields.push("specifications"),
+    }
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(page: &str) -> String {
+        std::fs::read_to_string(format!("{}/tests/fixtures/{}.html", env!("CARGO_MANIFEST_DIR"), page)).unwrap()
+    }
+
+    #[test]
+    fn test_self_check() {
+        let site = SiteConfig::new("http://mirror.local");
+        let mut requested = Vec::new();
+        let check = self_check(&site, |url| {
+            requested.push(url.to_string());
+            Ok(fixture(url.trim_start_matches("http://mirror.local/")))
+        })
+        .unwrap();
+        assert!(check.passed());
+        assert_eq!(requested, vec!["http://mirror.local/apple-phones-48.php", "http://mirror.local/apple_iphone_15-12559.php"]);
+
+        // A redesigned phone page: the spec tables moved and nothing parses
+        let redesigned = fixture("apple_iphone_15-12559.php").replace("id=\"specs-list\"", "id=\"specs\"");
+        let check = check_pages(&fixture("apple-phones-48.php"), &redesigned);
+        assert!(!check.passed());
+        let problems = check.problems();
+        assert!(problems[0].starts_with("apple_iphone_15-12559: 'spec_tables' matched 0 element(s), expected at least 8"), "{:?}", problems);
+        assert!(problems.iter().any(|p| p.ends_with("parsed without specifications")), "{:?}", problems);
+
+        let error = self_check(&site, |url| {
+            if url.ends_with("apple-phones-48.php") {
+                Ok(fixture("apple-phones-48.php"))
+            } else {
+                Ok("<html><body>Too many requests</body></html>".to_string())
+            }
+        })
+        .unwrap_err();
+        assert!(error.to_string().starts_with("Self-check failed"), "{}", error);
+        assert!(self_check(&site, |_| Err("connection refused".into())).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Read-through access to single phones: the store first, GSMArena when missing or stale
+//!
+//! `PhoneService::get` makes the stored collection behave like a cache in front of the
+//! site. Phones fetched live are parsed, validated and written back before returning.
+
+use crate::lifecycle;
+use crate::mongodb::PhoneDocument;
+use crate::scraper::parse_specification_html;
+use crate::site::SiteConfig;
+use crate::sitemap::{brand_from_name, phone_from_url};
+use crate::store::PhoneStore;
+use crate::validator::{validate, DEFAULT_MIN_COMPLETENESS};
+use chrono::{Duration, Utc};
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Fetches a page body for `PhoneService`; the CLI plugs in its page fetcher
+/// (cache, proxies, ScrapingBee). Called from `block_in_place`, so it may block.
+pub trait PageSource: Send + Sync {
+    fn fetch_page(&self, url: &str) -> Result<String, Box<dyn Error>>;
+}
+
+/// Plain blocking GET, for programs without a fetch strategy of their own
+pub struct DirectSource;
+
+impl PageSource for DirectSource {
+    fn fetch_page(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        let response = reqwest::blocking::get(url)?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned status: {}", url, response.status()).into());
+        }
+        Ok(response.text()?)
+    }
+}
+
+/// Where `PhoneService::get` found a phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneSource {
+    Store,
+    Live,
+    StaleStore, // The live fetch failed, so the stale stored copy was returned
+}
+
+/// Single-phone lookups backed by a `PhoneStore`, falling back to a live fetch
+pub struct PhoneService {
+    store: Arc<dyn PhoneStore>,
+    source: Arc<dyn PageSource>,
+    site: SiteConfig,
+    max_age: Option<Duration>, // None: stored phones only go stale when flagged needs_rescrape
+    min_completeness: f64,
+}
+
+impl PhoneService {
+    pub fn new(store: Arc<dyn PhoneStore>, source: Arc<dyn PageSource>, site: SiteConfig) -> Self {
+        Self {
+            store,
+            source,
+            site,
+            max_age: None,
+            min_completeness: DEFAULT_MIN_COMPLETENESS,
+        }
+    }
+
+    /// Refetch stored phones scraped longer ago than `max_age`
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Completeness score below which fetched phones are flagged for re-scrape
+    pub fn with_min_completeness(mut self, min_completeness: f64) -> Self {
+        self.min_completeness = min_completeness;
+        self
+    }
+
+    /// Whether a stored phone should be fetched again
+    pub fn is_stale(&self, phone: &PhoneDocument) -> bool {
+        phone.needs_rescrape || self.max_age.is_some_and(|max_age| Utc::now() - phone.scraped_at > max_age)
+    }
+
+    /// The stored phone when fresh; otherwise fetched live, stored and returned
+    pub async fn get(&self, phone_id: &str) -> Result<PhoneDocument, Box<dyn Error>> {
+        self.get_with_source(phone_id).await.map(|(phone, _)| phone)
+    }
+
+    /// Like `get`, also saying where the phone came from
+    pub async fn get_with_source(&self, phone_id: &str) -> Result<(PhoneDocument, PhoneSource), Box<dyn Error>> {
+        let stored = self.store.get_phone(phone_id).await?;
+        if let Some(phone) = stored.as_ref().filter(|phone| !self.is_stale(phone)) {
+            debug!(phone = %phone_id, "Served from the store");
+            return Ok((phone.clone(), PhoneSource::Store));
+        }
+
+        match self.fetch(phone_id, stored.as_ref()).await {
+            Ok(phone) => {
+                self.store.upsert_phone(phone.clone()).await?;
+                info!(phone = %phone_id, refreshed = stored.is_some(), "✓ Fetched live and stored");
+                Ok((phone, PhoneSource::Live))
+            }
+            Err(e) => match stored {
+                Some(phone) => {
+                    warn!(phone = %phone_id, error = %e, "Live fetch failed, serving the stale copy");
+                    Ok((phone, PhoneSource::StaleStore))
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Fetch and parse a phone page, keeping the name, brand and history of a stored copy
+    async fn fetch(&self, phone_id: &str, stored: Option<&PhoneDocument>) -> Result<PhoneDocument, Box<dyn Error>> {
+        let mut item = phone_from_url(&SiteConfig::default(), &crate::scraper::phone_page_url(phone_id)).ok_or("invalid phone ID")?;
+        let mut brand = brand_from_name(&item);
+        if let Some(stored) = stored {
+            item.name = stored.name.clone();
+            item.image_url = stored.image_url.clone();
+            brand = stored.brand.clone();
+        }
+
+        let url = self.site.phone_page_url(phone_id);
+        let html = tokio::task::block_in_place(|| self.source.fetch_page(&url))?;
+        let spec = parse_specification_html(phone_id, &html)?;
+        let mut phone = PhoneDocument::new(&item, &brand, serde_json::to_value(&spec)?);
+
+        let validation = validate(&phone, self.min_completeness);
+        if !validation.has_specifications {
+            return Err(format!("no specifications on {} (blocked or empty)", url).into());
+        }
+        phone.apply_validation(&validation);
+        lifecycle::track(stored, &mut phone, Utc::now());
+        Ok(phone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FixtureSource, MemoryStore, FIXTURE_BASE_URL};
+
+    fn service(store: &Arc<MemoryStore>, source: &Arc<FixtureSource>) -> PhoneService {
+        PhoneService::new(store.clone(), source.clone(), SiteConfig::new(FIXTURE_BASE_URL))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_serves_stored_phone() {
+        let (store, source) = (Arc::new(MemoryStore::default()), Arc::new(FixtureSource::default()));
+        let stored = PhoneDocument::fixture("apple_iphone_15-12559").name("iPhone 15").brand("Apple").build();
+        store.upsert_phone(stored).await.unwrap();
+
+        let (phone, found_in) = service(&store, &source).get_with_source("apple_iphone_15-12559").await.unwrap();
+        assert_eq!((found_in, phone.name.as_str()), (PhoneSource::Store, "iPhone 15"));
+        assert!(source.requested.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_fetches_missing_phone() {
+        let (store, source) = (Arc::new(MemoryStore::default()), Arc::new(FixtureSource::default()));
+
+        let (phone, found_in) = service(&store, &source).get_with_source("apple_iphone_15-12559").await.unwrap();
+        assert_eq!(found_in, PhoneSource::Live);
+        assert_eq!((phone.name.as_str(), phone.brand.as_str()), ("Apple Iphone 15", "Apple"));
+        assert_eq!(*source.requested.lock().unwrap(), vec!["http://mirror.local/apple_iphone_15-12559.php"]);
+        assert_eq!(store.phone_ids(), vec!["apple_iphone_15-12559"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_unknown_phone_returns_nothing() {
+        let (store, source) = (Arc::new(MemoryStore::default()), Arc::new(FixtureSource::default()));
+
+        // What `specs --cached` sees for a phone neither stored nor on the site
+        assert!(service(&store, &source).get("removed_phone-1").await.is_err());
+        assert_eq!(source.requested.lock().unwrap().len(), 1);
+        assert!(store.phone_ids().is_empty());
+    }
+}
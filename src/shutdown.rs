@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{error, warn};
+
+/// Cooperative shutdown flag set by SIGINT/SIGTERM
+///
+/// Long-running loops check `is_requested()` between phones so the in-flight
+/// phone is finished (specs and phone list entry both written) before stopping.
+/// A second signal exits immediately.
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    requested: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listen for Ctrl+C and SIGTERM in the background (requires a tokio runtime)
+    pub fn install(&self) -> Self {
+        let shutdown = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                wait_for_signal().await;
+
+                if shutdown.is_requested() {
+                    error!("Second signal received, exiting immediately");
+                    std::process::exit(130);
+                }
+
+                warn!("Shutdown requested, finishing the current phone (signal again to force)...");
+                shutdown.request();
+            }
+        });
+
+        self.clone()
+    }
+
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// Wait until shutdown is requested
+    pub async fn wait(&self) {
+        let notified = self.notify.notified();
+        if !self.is_requested() {
+            notified.await;
+        }
+    }
+
+    /// Sleep for `duration`, waking early on shutdown; returns false if interrupted
+    pub async fn sleep(&self, duration: Duration) -> bool {
+        let notified = self.notify.notified();
+        if self.is_requested() {
+            return false;
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => !self.is_requested(),
+            _ = notified => false,
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = wait_for_ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+        }
+        Err(_) => wait_for_ctrl_c().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    wait_for_ctrl_c().await
+}
+
+async fn wait_for_ctrl_c() {
+    // Without a handler, never report a signal (rather than spinning on the error)
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
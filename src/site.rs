@@ -0,0 +1,93 @@
+//! Where GSMArena pages are fetched from
+
+use serde::{Deserialize, Serialize};
+
+/// The live site; mirrors and test servers are passed to the `*_from` fetch functions instead
+pub const DEFAULT_BASE_URL: &str = "https://www.gsmarena.com/";
+
+/// Join a page path onto a base URL, with or without a trailing slash
+/// ("http://127.0.0.1:1234" + "makers.php3" -> "http://127.0.0.1:1234/makers.php3")
+pub fn page_url(base_url: &str, path: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+/// The site pages are fetched from (`[site]` in the config file, `GSMARENA_BASE_URL`)
+///
+/// Only fetching goes through `base_url`; stored `url` fields keep pointing at gsmarena.com,
+/// except for phones found through sitemap discovery, which record their page under `base_url`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteConfig {
+    pub base_url: String,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+        }
+    }
+}
+
+impl SiteConfig {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self { base_url: base_url.into() }
+    }
+
+    /// Whether this points somewhere other than the live site
+    pub fn is_mirror(&self) -> bool {
+        self.base_url.trim_end_matches('/') != DEFAULT_BASE_URL.trim_end_matches('/')
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        page_url(&self.base_url, path)
+    }
+
+    pub fn makers_url(&self) -> String {
+        self.url("makers.php3")
+    }
+
+    pub fn sitemap_url(&self) -> String {
+        self.url("sitemap.xml")
+    }
+
+    pub fn brand_page_url(&self, brand_slug: &str, page: usize) -> String {
+        crate::brand_scraper::brand_page_url_from(&self.base_url, brand_slug, page)
+    }
+
+    pub fn phone_page_url(&self, phone_id: &str) -> String {
+        crate::scraper::phone_page_url_from(&self.base_url, phone_id)
+    }
+
+    pub fn pictures_page_url(&self, phone_id: &str) -> Option<String> {
+        crate::gallery::pictures_page_url_from(&self.base_url, phone_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_url() {
+        assert_eq!(page_url(DEFAULT_BASE_URL, "makers.php3"), "https://www.gsmarena.com/makers.php3");
+        assert_eq!(page_url("http://127.0.0.1:1234", "/apple-phones-48.php"), "http://127.0.0.1:1234/apple-phones-48.php");
+    }
+
+    #[test]
+    fn test_site_config_urls() {
+        let live = SiteConfig::default();
+        assert!(!live.is_mirror());
+        assert_eq!(live.makers_url(), "https://www.gsmarena.com/makers.php3");
+        assert_eq!(live.sitemap_url(), "https://www.gsmarena.com/sitemap.xml");
+
+        let mirror = SiteConfig::new("http://mirror.local:8080");
+        assert!(mirror.is_mirror());
+        assert_eq!(mirror.brand_page_url("apple-phones-48", 2), "http://mirror.local:8080/apple-phones-48-p2.php");
+        assert_eq!(mirror.phone_page_url("apple_iphone_15-12559"), "http://mirror.local:8080/apple_iphone_15-12559.php");
+        assert_eq!(
+            mirror.pictures_page_url("apple_iphone_15-12559").as_deref(),
+            Some("http://mirror.local:8080/apple_iphone_15-pictures-12559.php")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::brand_scraper::{Brand, PhoneListItem};
+use crate::device_type::DeviceType;
+use crate::site::SiteConfig;
+use regex::Regex;
+use reqwest::blocking;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// Contents of a single sitemap document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// `<sitemapindex>` listing child sitemaps
+    Index(Vec<String>),
+    /// `<urlset>` listing page URLs
+    UrlSet(Vec<String>),
+}
+
+/// Sitemap index URL from the environment, falling back to the site's own sitemap
+pub fn sitemap_url_from_env(site: &SiteConfig) -> String {
+    std::env::var("SITEMAP_URL").unwrap_or_else(|_| site.sitemap_url())
+}
+
+/// Enumerate all phone pages listed in the sitemaps (direct requests)
+pub fn discover_phones_from_sitemap(site: &SiteConfig, index_url: &str) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
+    discover_phones_from_sitemap_with(site, index_url, |url| {
+        let response = blocking::get(url)?;
+        if !response.status().is_success() {
+            return Err(format!("Sitemap returned status: {}", response.status()).into());
+        }
+        Ok(response.text()?)
+    })
+}
+
+/// Enumerate all phone pages listed in the sitemaps using a custom fetcher
+/// (e.g. ScrapingBee). Follows one level of sitemap index.
+/// Only URLs under `site.base_url` are recognised as phone pages.
+pub fn discover_phones_from_sitemap_with<F>(site: &SiteConfig, index_url: &str, mut fetch: F) -> Result<Vec<PhoneListItem>, Box<dyn Error>>
+where
+    F: FnMut(&str) -> Result<String, Box<dyn Error>>,
+{
+    let page_urls = match parse_sitemap(&fetch(index_url)?) {
+        Sitemap::UrlSet(urls) => urls,
+        Sitemap::Index(children) => {
+            // Only follow device sitemaps when the index names them, news/reviews are huge
+            let device_sitemaps: Vec<&String> = children
+                .iter()
+                .filter(|url| url.contains("phone") || url.contains("device"))
+                .collect();
+            let to_follow = if device_sitemaps.is_empty() {
+                children.iter().collect()
+            } else {
+                device_sitemaps
+            };
+
+            let mut urls = Vec::new();
+            for child in to_follow {
+                match parse_sitemap(&fetch(child)?) {
+                    Sitemap::UrlSet(child_urls) => urls.extend(child_urls),
+                    Sitemap::Index(_) => warn!(url = %child, "Skipping nested sitemap index"),
+                }
+            }
+            urls
+        }
+    };
+
+    let mut seen = HashSet::new();
+    Ok(page_urls
+        .iter()
+        .filter_map(|url| phone_from_url(site, url))
+        .filter(|phone| seen.insert(phone.phone_id.clone()))
+        .collect())
+}
+
+static LOC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<loc>\s*(.*?)\s*</loc>").unwrap());
+static PHONE_PATH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([a-z0-9_]+-\d+)\.php$").unwrap());
+
+/// Parse a sitemap or sitemap index document
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let locs = LOC
+        .captures_iter(xml)
+        .map(|cap| cap[1].replace("&amp;", "&"))
+        .collect();
+
+    if xml.contains("<sitemapindex") {
+        Sitemap::Index(locs)
+    } else {
+        Sitemap::UrlSet(locs)
+    }
+}
+
+/// Build a phone list item from a device page URL under `site.base_url`
+/// Returns None for anything that isn't a device page (pictures, reviews, news, brand listings)
+/// or lives on another host
+/// Sitemaps carry no display names, so the name is derived from the slug ("Samsung Galaxy S24")
+pub fn phone_from_url(site: &SiteConfig, url: &str) -> Option<PhoneListItem> {
+    let path = url.trim().strip_prefix(site.base_url.trim_end_matches('/'))?.strip_prefix('/')?;
+    let phone_id = PHONE_PATH.captures(path)?[1].to_string();
+
+    let slug = phone_id.rsplit_once('-')?.0;
+    let name = slug
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(PhoneListItem {
+        device_type: DeviceType::from_name(&name),
+        name,
+        url: site.phone_page_url(&phone_id),
+        phone_id,
+        image_url: None,
+    })
+}
+
+/// Best-effort brand for phones never seen in a brand listing ("Samsung Galaxy S24" -> "Samsung")
+pub fn brand_from_name(phone: &PhoneListItem) -> String {
+    phone.name.split_whitespace().next().unwrap_or_default().to_string()
+}
+
+/// Group sitemap phones under their brands by phone ID prefix
+/// ("samsung_galaxy_s24-12771" -> Samsung), keyed by brand slug
+/// The longest matching brand wins, so "sony_ericsson_*" doesn't land under Sony
+pub fn group_phones_by_brand(brands: &[Brand], phones: Vec<PhoneListItem>) -> HashMap<String, Vec<PhoneListItem>> {
+    let mut prefixes: Vec<(String, &Brand)> = brands
+        .iter()
+        .map(|brand| (format!("{}_", brand.name.trim().to_lowercase().replace(' ', "_")), brand))
+        .collect();
+    prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+    let mut grouped: HashMap<String, Vec<PhoneListItem>> = HashMap::new();
+
+    for phone in phones {
+        if let Some((_, brand)) = prefixes.iter().find(|(prefix, _)| phone.phone_id.starts_with(prefix.as_str())) {
+            grouped.entry(brand.slug.clone()).or_default().push(phone);
+        }
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sitemap() {
+        let index = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>https://www.gsmarena.com/sitemap-phones.xml</loc></sitemap>
+                <sitemap><loc>https://www.gsmarena.com/sitemap-news.xml</loc></sitemap>
+            </sitemapindex>"#;
+        assert_eq!(
+            parse_sitemap(index),
+            Sitemap::Index(vec![
+                "https://www.gsmarena.com/sitemap-phones.xml".to_string(),
+                "https://www.gsmarena.com/sitemap-news.xml".to_string(),
+            ])
+        );
+
+        let urlset = r#"<urlset>
+                <url><loc>https://www.gsmarena.com/samsung_galaxy_s24-12773.php</loc></url>
+                <url><loc>https://www.gsmarena.com/samsung_galaxy_s24-pictures-12773.php</loc></url>
+                <url><loc>https://www.gsmarena.com/sony_ericsson_w995-2696.php</loc></url>
+                <url><loc>https://www.gsmarena.com/samsung-phones-9.php</loc></url>
+            </urlset>"#;
+
+        let site = SiteConfig::default();
+        let phones = discover_phones_from_sitemap_with(&site, "index", |_| Ok(urlset.to_string())).unwrap();
+        assert_eq!(phones.len(), 2);
+        assert_eq!(phones[0].phone_id, "samsung_galaxy_s24-12773");
+        assert_eq!(phones[0].name, "Samsung Galaxy S24");
+        assert_eq!(phones[0].url, "https://www.gsmarena.com/samsung_galaxy_s24-12773.php");
+
+        let mirror = SiteConfig::new("http://mirror.local:8080/");
+        assert!(phone_from_url(&mirror, "https://www.gsmarena.com/samsung_galaxy_s24-12773.php").is_none());
+        let phone = phone_from_url(&mirror, "http://mirror.local:8080/samsung_galaxy_s24-12773.php").unwrap();
+        assert_eq!(phone.url, "http://mirror.local:8080/samsung_galaxy_s24-12773.php");
+
+        let brands = vec![
+            Brand { name: "Samsung".to_string(), slug: "samsung-phones-9".to_string(), device_count: 1 },
+            Brand { name: "Sony".to_string(), slug: "sony-phones-7".to_string(), device_count: 1 },
+            Brand { name: "Sony Ericsson".to_string(), slug: "sony_ericsson-phones-19".to_string(), device_count: 1 },
+        ];
+        let grouped = group_phones_by_brand(&brands, phones);
+        assert_eq!(grouped["samsung-phones-9"].len(), 1);
+        assert_eq!(grouped["sony_ericsson-phones-19"].len(), 1);
+        assert!(!grouped.contains_key("sony-phones-7"));
+    }
+}
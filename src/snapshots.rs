@@ -0,0 +1,99 @@
+use crate::config::SnapshotConfig;
+use crate::images::ImageStorage;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::{Read, Write};
+
+/// Reference to the gzip-compressed phone page a `PhoneDocument` was parsed from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HtmlSnapshot {
+    pub source_url: String,
+    pub content_hash: String, // SHA-256 of the uncompressed HTML (hex)
+    pub storage_ref: String,  // Same formats as `StoredImage::storage_ref`
+    pub size_bytes: u64,      // Compressed size
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Stores raw phone pages so specifications can be re-derived without re-scraping
+pub struct SnapshotStore {
+    storage: ImageStorage,
+}
+
+impl SnapshotStore {
+    pub fn new(storage: ImageStorage) -> Self {
+        Self { storage }
+    }
+
+    /// Build the store from the `[snapshots]` config section (`None` disables snapshots)
+    ///
+    /// A bare `gridfs` uses the `phone_html` bucket.
+    pub async fn from_config(config: &SnapshotConfig, database: &Database) -> Result<Option<Self>, Box<dyn Error>> {
+        match config.storage.as_deref().map(str::trim) {
+            Some(spec) if !spec.is_empty() => Ok(Some(Self::new(ImageStorage::parse_with_bucket(spec, database, "phone_html").await?))),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn storage(&self) -> &ImageStorage {
+        &self.storage
+    }
+
+    /// Compress and store a page; identical pages are stored once
+    pub async fn save(&self, source_url: &str, html: &str) -> Result<HtmlSnapshot, Box<dyn Error>> {
+        let content_hash = format!("{:x}", Sha256::digest(html.as_bytes()));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(html.as_bytes())?;
+        let compressed = encoder.finish()?;
+
+        let storage_ref = self
+            .storage
+            .store(&format!("{}.html.gz", content_hash), &compressed, source_url, Some("text/html"))
+            .await?;
+
+        Ok(HtmlSnapshot {
+            source_url: source_url.to_string(),
+            content_hash,
+            storage_ref,
+            size_bytes: compressed.len() as u64,
+            captured_at: chrono::Utc::now(),
+        })
+    }
+
+    /// Read a snapshot back as HTML
+    pub async fn load(&self, snapshot: &HtmlSnapshot) -> Result<String, Box<dyn Error>> {
+        let compressed = self.storage.load(&snapshot.storage_ref).await?;
+        let mut html = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut html)?;
+        Ok(html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("gsmarena-snapshots-{}", std::process::id()));
+        let store = SnapshotStore::new(ImageStorage::Local(dir.clone()));
+        let html = "<html><h1>Apple iPhone 15</h1></html>".repeat(50);
+
+        let snapshot = store.save("https://www.gsmarena.com/apple_iphone_15-12559.php", &html).await.unwrap();
+        assert!(snapshot.storage_ref.starts_with("file://"));
+        assert!(snapshot.storage_ref.ends_with(".html.gz"));
+        assert!(snapshot.size_bytes < html.len() as u64);
+        assert_eq!(store.load(&snapshot).await.unwrap(), html);
+
+        // Identical pages map to the same object
+        let again = store.save("https://www.gsmarena.com/apple_iphone_15-12559.php", &html).await.unwrap();
+        assert_eq!(again.storage_ref, snapshot.storage_ref);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
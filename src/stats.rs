@@ -0,0 +1,225 @@
+//! Why phones were not saved: failures and skips by category
+//!
+//! "Failed: 217" says nothing about what to fix. `scrape`, `retry-failed`, `specs` and
+//! `ScrapeRunner` count every failed phone under network (no response, timeouts, error
+//! statuses), blocked (429/403, pages without specifications), parse or storage
+//! (MongoDB, files), and every skipped phone under why it was skipped. The run report
+//! keeps the breakdown in `counts.failures`. `ErrorPolicy` decides how many failures a
+//! run puts up with before it stops.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// What kind of error kept a phone from being saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    Network,
+    Blocked,
+    Parse,
+    Storage,
+}
+
+impl FailureCategory {
+    /// Best guess from an error message, for errors that arrive as plain strings
+    /// (fetch errors, and whatever the last strategy of a retry returned)
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let any = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+
+        if any(&["status: 403", "status: 429", "403 forbidden", "429 too many", "blocked", "captcha", "no specifications", "expected content"]) {
+            Self::Blocked
+        } else if any(&["mongodb", "labels: {", "e11000", "transaction", "permission denied", "no space left"]) {
+            Self::Storage
+        } else if any(&["parse", "invalid", "expected", "missing field", "unknown variant", "merge"]) {
+            Self::Parse
+        } else {
+            Self::Network
+        }
+    }
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Network => "network",
+            Self::Blocked => "blocked",
+            Self::Parse => "parse",
+            Self::Storage => "storage",
+        })
+    }
+}
+
+/// Why a phone was passed over without being fetched or saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    Complete, // Stored with complete specifications (`skip_existing`)
+    Existing, // Stored or in the ledger, and left alone (`skip_if_exists`, ledger)
+    Filtered, // Not the requested device type or network technology
+}
+
+/// Failed and skipped phones per category
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailureStats {
+    pub network: u64,
+    pub blocked: u64,
+    pub parse: u64,
+    pub storage: u64,
+    pub skipped_complete: u64,
+    pub skipped_existing: u64,
+    pub skipped_filtered: u64,
+}
+
+impl FailureStats {
+    pub fn record_failure(&mut self, category: FailureCategory) {
+        *match category {
+            FailureCategory::Network => &mut self.network,
+            FailureCategory::Blocked => &mut self.blocked,
+            FailureCategory::Parse => &mut self.parse,
+            FailureCategory::Storage => &mut self.storage,
+        } += 1;
+    }
+
+    pub fn record_skip(&mut self, reason: SkipReason) {
+        *match reason {
+            SkipReason::Complete => &mut self.skipped_complete,
+            SkipReason::Existing => &mut self.skipped_existing,
+            SkipReason::Filtered => &mut self.skipped_filtered,
+        } += 1;
+    }
+
+    pub fn failed(&self) -> u64 {
+        self.network + self.blocked + self.parse + self.storage
+    }
+
+    pub fn skipped(&self) -> u64 {
+        self.skipped_complete + self.skipped_existing + self.skipped_filtered
+    }
+}
+
+/// Non-zero failure categories, e.g. "network=3 blocked=12"; "none" without failures
+impl fmt::Display for FailureStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let categories = [("network", self.network), ("blocked", self.blocked), ("parse", self.parse), ("storage", self.storage)];
+        let parts: Vec<String> = categories.iter().filter(|(_, count)| *count > 0).map(|(name, count)| format!("{}={}", name, count)).collect();
+        match parts.is_empty() {
+            true => f.write_str("none"),
+            false => f.write_str(&parts.join(" ")),
+        }
+    }
+}
+
+/// What a run does when phones or brand listings fail (`scraping.error_policy`, `--error-policy`)
+///
+/// A systemic error such as a MongoDB authentication failure fails every phone the
+/// same way; `fail-fast` stops at the first one instead of logging thousands, and the
+/// run writes its report and checkpoint as for any early stop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ErrorPolicy {
+    /// Log each failure and move on
+    #[default]
+    Continue,
+    /// Stop at the first failure
+    FailFast,
+    /// Stop once this many phones or listings failed
+    FailAfter(u64),
+}
+
+impl ErrorPolicy {
+    /// Why the run should stop after `failed` failures; None to carry on
+    pub fn stop_reason(&self, failed: u64) -> Option<String> {
+        match *self {
+            Self::Continue => None,
+            Self::FailFast => (failed > 0).then(|| "first failure with error_policy fail-fast".to_string()),
+            Self::FailAfter(limit) => (failed >= limit).then(|| format!("{} failures with error_policy {}", failed, self)),
+        }
+    }
+}
+
+impl FromStr for ErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let policy = s.trim().to_lowercase().replace('_', "-");
+        match policy.as_str() {
+            "continue" => Ok(Self::Continue),
+            "fail-fast" => Ok(Self::FailFast),
+            _ => match policy.strip_prefix("fail-after-").and_then(|limit| limit.parse().ok()) {
+                Some(limit) if limit > 0 => Ok(Self::FailAfter(limit)),
+                _ => Err(format!("invalid error policy '{}', expected continue, fail-fast or fail-after-N (N >= 1)", s)),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for ErrorPolicy {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ErrorPolicy> for String {
+    fn from(policy: ErrorPolicy) -> Self {
+        policy.to_string()
+    }
+}
+
+impl fmt::Display for ErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Continue => f.write_str("continue"),
+            Self::FailFast => f.write_str("fail-fast"),
+            Self::FailAfter(limit) => write!(f, "fail-after-{}", limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_stats() {
+        assert_eq!(FailureCategory::classify("GSMArena returned status: 429 Too Many Requests"), FailureCategory::Blocked);
+        assert_eq!(FailureCategory::classify("no specifications on page (blocked or empty)"), FailureCategory::Blocked);
+        assert_eq!(FailureCategory::classify("MongoDB: Kind: Command failed: E11000 duplicate key"), FailureCategory::Storage);
+        assert_eq!(FailureCategory::classify("merge: invalid type: string"), FailureCategory::Parse);
+        assert_eq!(FailureCategory::classify("error sending request for url: operation timed out"), FailureCategory::Network);
+        assert_eq!(FailureCategory::classify("Failed to fetch https://x after trying 3 proxies"), FailureCategory::Network);
+
+        let mut stats = FailureStats::default();
+        assert_eq!(stats.to_string(), "none");
+        stats.record_failure(FailureCategory::Blocked);
+        stats.record_failure(FailureCategory::Blocked);
+        stats.record_failure(FailureCategory::Network);
+        stats.record_skip(SkipReason::Complete);
+        stats.record_skip(SkipReason::Filtered);
+        assert_eq!((stats.failed(), stats.skipped()), (3, 2));
+        assert_eq!(stats.to_string(), "network=1 blocked=2");
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!((json["blocked"].as_u64(), json["skipped_complete"].as_u64()), (Some(2), Some(1)));
+        assert_eq!(serde_json::from_str::<FailureStats>("{}").unwrap(), FailureStats::default());
+    }
+
+    #[test]
+    fn test_error_policy() {
+        assert_eq!("continue".parse::<ErrorPolicy>().unwrap(), ErrorPolicy::Continue);
+        assert_eq!("fail_fast".parse::<ErrorPolicy>().unwrap(), ErrorPolicy::FailFast);
+        let policy: ErrorPolicy = "fail-after-25".parse().unwrap();
+        assert_eq!((policy, policy.to_string()), (ErrorPolicy::FailAfter(25), "fail-after-25".to_string()));
+        assert!("fail-after-0".parse::<ErrorPolicy>().is_err());
+        assert!("fail-after".parse::<ErrorPolicy>().is_err());
+
+        assert_eq!(ErrorPolicy::Continue.stop_reason(10_000), None);
+        assert_eq!(ErrorPolicy::FailFast.stop_reason(0), None);
+        assert!(ErrorPolicy::FailFast.stop_reason(1).is_some());
+        assert_eq!(policy.stop_reason(24), None);
+        assert_eq!(policy.stop_reason(25).as_deref(), Some("25 failures with error_policy fail-after-25"));
+    }
+}
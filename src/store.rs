@@ -0,0 +1,227 @@
+use crate::brand_scraper::{Brand, PhoneListItem};
+use crate::config::CollectionsConfig;
+use crate::mongodb::{BrandDocument, MongoDBClient, PhoneDocument};
+use crate::pricing::MarketPrice;
+use crate::query::PhoneQuery;
+use crate::upsert::UpsertPolicy;
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use std::error::Error;
+
+/// Filters for listing phones; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct PhoneFilter {
+    pub brand: Option<String>,         // Exact brand name as stored
+    pub name: Option<String>,          // Case-insensitive substring
+    pub chipset: Option<String>,       // Case-insensitive substring of platform.chipset
+    pub announced_year: Option<i32>,
+    pub min_battery_mah: Option<u32>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl PhoneFilter {
+    /// The part of the filter that can be expressed as a MongoDB query
+    pub fn to_document(&self) -> Document {
+        let mut filter = doc! {};
+        if let Some(ref brand) = self.brand {
+            filter.insert("brand", brand);
+        }
+        if let Some(ref name) = self.name {
+            filter.insert("name", doc! { "$regex": regex::escape(name), "$options": "i" });
+        }
+        if let Some(ref chipset) = self.chipset {
+            filter.insert("platform.chipset", doc! { "$regex": regex::escape(chipset), "$options": "i" });
+        }
+        if let Some(year) = self.announced_year {
+            filter.insert("launch.announced", doc! { "$regex": format!("^{}", year) });
+        }
+        filter
+    }
+
+    /// The whole filter evaluated in memory, for stores without a query language
+    pub fn matches(&self, phone: &PhoneDocument) -> bool {
+        let contains = |text: Option<&str>, needle: &str| text.is_some_and(|t| t.to_lowercase().contains(&needle.to_lowercase()));
+        self.brand.as_ref().is_none_or(|brand| phone.brand == *brand)
+            && self.name.as_ref().is_none_or(|name| contains(Some(&phone.name), name))
+            && self.chipset.as_ref().is_none_or(|chipset| contains(phone.platform.as_ref().and_then(|p| p.chipset.as_deref()), chipset))
+            && self.announced_year.is_none_or(|year| {
+                phone.launch.as_ref().and_then(|l| l.announced.as_deref()).is_some_and(|announced| announced.starts_with(&year.to_string()))
+            })
+            && self.matches_loaded(phone)
+    }
+
+    /// Whether a phone passes the filters that are checked after loading
+    pub fn matches_loaded(&self, phone: &PhoneDocument) -> bool {
+        self.min_battery_mah
+            .is_none_or(|min| phone.battery_capacity_mah().is_some_and(|mah| mah >= min))
+    }
+}
+
+/// Where scraped phones and brands are kept
+///
+/// Servers (GraphQL, gRPC) and services are written against this trait rather than MongoDB.
+#[async_trait]
+pub trait PhoneStore: Send + Sync {
+    async fn get_phone(&self, phone_id: &str) -> Result<Option<PhoneDocument>, Box<dyn Error>>;
+
+    /// Phones matching the filter, ordered by brand and name
+    async fn find_phones(&self, filter: &PhoneFilter) -> Result<Vec<PhoneDocument>, Box<dyn Error>>;
+
+    /// Phones matching a spec query over the normalized fields, ordered by brand and name
+    async fn query_phones(&self, query: &PhoneQuery) -> Result<Vec<PhoneDocument>, Box<dyn Error>>;
+
+    async fn get_brand(&self, name: &str) -> Result<Option<BrandDocument>, Box<dyn Error>>;
+
+    async fn list_brands(&self) -> Result<Vec<BrandDocument>, Box<dyn Error>>;
+
+    async fn upsert_phone(&self, phone: PhoneDocument) -> Result<(), Box<dyn Error>>;
+
+    /// Replace a stored phone's market prices; store prices move while the specifications
+    /// (and so the rest of the document) stay the same
+    async fn update_market_prices(&self, phone_id: &str, prices: &[MarketPrice]) -> Result<(), Box<dyn Error>> {
+        let Some(mut phone) = self.get_phone(phone_id).await? else {
+            return Ok(());
+        };
+        phone.market_prices = prices.to_vec();
+        self.upsert_phone(phone).await
+    }
+
+    /// A brand's listing, before its phones are fetched (for stores that keep listings)
+    async fn save_brand_listing(&self, _brand: &Brand, _phones: &[PhoneListItem]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Write out anything buffered; called at the end of a run
+    async fn flush(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Phones whose Misc > Models lists this number (regional and carrier variants)
+    async fn find_by_model_number(&self, model: &str) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        self.query_phones(&PhoneQuery::new().model_number(model)).await
+    }
+}
+
+/// `PhoneStore` over the configured MongoDB collections
+pub struct MongoPhoneStore {
+    client: MongoDBClient,
+    collections: CollectionsConfig,
+    upsert_policy: UpsertPolicy,
+}
+
+impl MongoPhoneStore {
+    pub fn new(client: MongoDBClient, collections: CollectionsConfig) -> Self {
+        Self { client, collections, upsert_policy: UpsertPolicy::default() }
+    }
+
+    /// What `upsert_phone` does to an already stored phone (default: overwrite it)
+    pub fn with_upsert_policy(mut self, policy: UpsertPolicy) -> Self {
+        self.upsert_policy = policy;
+        self
+    }
+}
+
+#[async_trait]
+impl PhoneStore for MongoPhoneStore {
+    async fn get_phone(&self, phone_id: &str) -> Result<Option<PhoneDocument>, Box<dyn Error>> {
+        self.client.get_phone(&self.collections.phones, phone_id).await
+    }
+
+    async fn find_phones(&self, filter: &PhoneFilter) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        let collection = self.client.get_collection(&self.collections.phones);
+
+        // Battery capacity is parsed from text, so paging happens after that filter
+        let paged_in_query = filter.min_battery_mah.is_none();
+        let options = FindOptions::builder()
+            .sort(doc! { "brand": 1, "name": 1 })
+            .skip(paged_in_query.then_some(filter.offset as u64))
+            .limit(filter.limit.filter(|_| paged_in_query).map(|limit| limit as i64))
+            .build();
+
+        let mut cursor = collection.find(filter.to_document(), options).await?;
+        let mut phones = Vec::new();
+        while let Some(phone) = cursor.next().await {
+            let phone = phone?;
+            if filter.matches_loaded(&phone) {
+                phones.push(phone);
+            }
+        }
+
+        if !paged_in_query {
+            phones = phones.into_iter().skip(filter.offset).take(filter.limit.unwrap_or(usize::MAX)).collect();
+        }
+        Ok(phones)
+    }
+
+    async fn query_phones(&self, query: &PhoneQuery) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        let collection = self.client.get_collection(&self.collections.phones);
+        let options = FindOptions::builder()
+            .sort(doc! { "brand": 1, "name": 1 })
+            .skip(query.offset as u64)
+            .limit(query.limit.map(|limit| limit as i64))
+            .build();
+
+        let mut cursor = collection.find(query.to_document(), options).await?;
+        let mut phones = Vec::new();
+        while let Some(phone) = cursor.next().await {
+            phones.push(phone?);
+        }
+        Ok(phones)
+    }
+
+    async fn get_brand(&self, name: &str) -> Result<Option<BrandDocument>, Box<dyn Error>> {
+        let collection = self.client.get_brand_collection(&self.collections.brands);
+        let pattern = format!("^{}$", regex::escape(name));
+        Ok(collection.find_one(doc! { "name": { "$regex": pattern, "$options": "i" } }, None).await?)
+    }
+
+    async fn list_brands(&self) -> Result<Vec<BrandDocument>, Box<dyn Error>> {
+        let collection = self.client.get_brand_collection(&self.collections.brands);
+        let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+
+        let mut cursor = collection.find(doc! {}, options).await?;
+        let mut brands = Vec::new();
+        while let Some(brand) = cursor.next().await {
+            brands.push(brand?);
+        }
+        Ok(brands)
+    }
+
+    async fn upsert_phone(&self, phone: PhoneDocument) -> Result<(), Box<dyn Error>> {
+        let phone = match self.upsert_policy {
+            UpsertPolicy::Overwrite => Some(phone),
+            policy => policy.resolve(self.get_phone(&phone.phone_id).await?.as_ref(), phone)?,
+        };
+        match phone {
+            Some(phone) => self.client.upsert_phone(&self.collections.phones, phone).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn update_market_prices(&self, phone_id: &str, prices: &[MarketPrice]) -> Result<(), Box<dyn Error>> {
+        self.client.update_market_prices(&self.collections.phones, phone_id, prices).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phone_filter_query() {
+        let filter = PhoneFilter {
+            brand: Some("Samsung".to_string()),
+            chipset: Some("Snapdragon 8+".to_string()),
+            announced_year: Some(2023),
+            ..Default::default()
+        };
+
+        let query = filter.to_document();
+        assert_eq!(query.get_str("brand").unwrap(), "Samsung");
+        assert_eq!(query.get_document("platform.chipset").unwrap().get_str("$regex").unwrap(), r"Snapdragon 8\+");
+        assert_eq!(query.get_document("launch.announced").unwrap().get_str("$regex").unwrap(), "^2023");
+    }
+}
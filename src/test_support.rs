@@ -0,0 +1,124 @@
+//! Phone documents, an in-memory store and a fixture page source for unit tests
+
+use crate::brand_scraper::PhoneListItem;
+use crate::mongodb::{BrandDocument, PhoneDocument};
+use crate::query::PhoneQuery;
+use crate::service::PageSource;
+use crate::store::{PhoneFilter, PhoneStore};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// A phone as `PhoneDocument::new` would build it from a scraped page
+/// `PhoneDocument::fixture("a-1").brand("Samsung").category("Battery", &[("Type", "Li-Ion 5000 mAh")]).build()`
+pub(crate) struct PhoneFixture {
+    item: PhoneListItem,
+    brand: String,
+    categories: Vec<serde_json::Value>,
+}
+
+impl PhoneDocument {
+    /// Named after the phone ID, brand "Test", no specifications until categories are added
+    pub(crate) fn fixture(phone_id: &str) -> PhoneFixture {
+        PhoneFixture {
+            item: PhoneListItem {
+                phone_id: phone_id.to_string(),
+                name: phone_id.to_string(),
+                url: format!("https://www.gsmarena.com/{}.php", phone_id),
+                image_url: None,
+                device_type: None,
+            },
+            brand: "Test".to_string(),
+            categories: Vec::new(),
+        }
+    }
+}
+
+impl PhoneFixture {
+    pub(crate) fn name(mut self, name: &str) -> Self {
+        self.item.name = name.to_string();
+        self
+    }
+
+    pub(crate) fn brand(mut self, brand: &str) -> Self {
+        self.brand = brand.to_string();
+        self
+    }
+
+    pub(crate) fn image_url(mut self, image_url: &str) -> Self {
+        self.item.image_url = Some(image_url.to_string());
+        self
+    }
+
+    /// A specification table, e.g. `("Battery", &[("Type", "Li-Ion 5000 mAh")])`; rows may be empty
+    pub(crate) fn category(mut self, title: &str, rows: &[(&str, &str)]) -> Self {
+        let rows: Vec<[&str; 2]> = rows.iter().map(|&(key, value)| [key, value]).collect();
+        self.categories.push(serde_json::json!({ "category_title": title, "category_spec": rows }));
+        self
+    }
+
+    pub(crate) fn build(self) -> PhoneDocument {
+        PhoneDocument::new(&self.item, &self.brand, serde_json::json!({ "specification": self.categories }))
+    }
+}
+
+/// `PhoneStore` kept in memory, for services and runners under test
+#[derive(Default)]
+pub(crate) struct MemoryStore(Mutex<HashMap<String, PhoneDocument>>);
+
+impl MemoryStore {
+    pub(crate) fn phone_ids(&self) -> Vec<String> {
+        let mut phone_ids: Vec<String> = self.0.lock().unwrap().keys().cloned().collect();
+        phone_ids.sort();
+        phone_ids
+    }
+}
+
+#[async_trait]
+impl PhoneStore for MemoryStore {
+    async fn get_phone(&self, phone_id: &str) -> Result<Option<PhoneDocument>, Box<dyn Error>> {
+        Ok(self.0.lock().unwrap().get(phone_id).cloned())
+    }
+
+    async fn find_phones(&self, filter: &PhoneFilter) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        Ok(self.0.lock().unwrap().values().filter(|phone| filter.matches(phone)).cloned().collect())
+    }
+
+    async fn query_phones(&self, _query: &PhoneQuery) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        Ok(self.0.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn get_brand(&self, _name: &str) -> Result<Option<BrandDocument>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    async fn list_brands(&self) -> Result<Vec<BrandDocument>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    async fn upsert_phone(&self, phone: PhoneDocument) -> Result<(), Box<dyn Error>> {
+        self.0.lock().unwrap().insert(phone.phone_id.clone(), phone);
+        Ok(())
+    }
+}
+
+/// Base URL served by `FixtureSource`
+pub(crate) const FIXTURE_BASE_URL: &str = "http://mirror.local";
+
+/// `PageSource` serving `tests/fixtures/<path>.html` for URLs under `FIXTURE_BASE_URL`
+/// (query strings ignored), recording every requested URL; unknown pages fail like a 404
+#[derive(Default)]
+pub(crate) struct FixtureSource {
+    pub(crate) requested: Mutex<Vec<String>>,
+}
+
+impl PageSource for FixtureSource {
+    fn fetch_page(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        self.requested.lock().unwrap().push(url.to_string());
+        let path = url.trim_start_matches(FIXTURE_BASE_URL).trim_start_matches('/');
+        let page = path.split('?').next().unwrap_or(path);
+        std::fs::read_to_string(format!("{}/tests/fixtures/{}.html", env!("CARGO_MANIFEST_DIR"), page))
+            .map_err(|_| format!("{} returned status: 404 Not Found", url).into())
+    }
+}
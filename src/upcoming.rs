@@ -0,0 +1,274 @@
+use crate::brand_scraper::PhoneListItem;
+use crate::mongodb::{parse_specifications, MongoDBClient};
+use crate::phone_finder::{search_phone_finder_with, Availability, PhoneFinderQuery};
+use crate::scraper::{parse_specification_html, phone_page_url_from};
+use crate::service::{DirectSource, PageSource};
+use crate::site::DEFAULT_BASE_URL;
+use chrono::{DateTime, Utc};
+use futures::stream::StreamExt;
+use mongodb::bson::doc;
+use mongodb::options::{IndexOptions, UpdateOptions};
+use mongodb::{Collection, IndexModel};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::Arc;
+use tracing::warn;
+
+/// A rumored or announced-but-unreleased device being tracked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingPhone {
+    pub phone_id: String,
+    pub name: String,
+    pub url: String,
+    pub image_url: Option<String>,
+    pub status: Availability,
+    pub status_text: Option<String>, // Raw Launch.status from the last check
+    pub first_seen: DateTime<Utc>,
+    pub last_checked: Option<DateTime<Utc>>,
+    pub available_since: Option<DateTime<Utc>>,
+}
+
+/// Emitted when a tracked device changes availability (e.g. Rumored -> Available)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChangeEvent {
+    pub phone_id: String,
+    pub name: String,
+    pub previous_status: Availability,
+    pub new_status: Availability,
+    pub status_text: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Tracks upcoming devices in their own collection
+pub struct UpcomingTracker {
+    collection: Collection<UpcomingPhone>,
+    base_url: String,
+    source: Arc<dyn PageSource>, // Phone Finder and phone pages; plain GETs unless the CLI plugs in its fetcher
+}
+
+impl UpcomingTracker {
+    pub fn new(mongo_client: &MongoDBClient, collection_name: &str) -> Self {
+        Self {
+            collection: mongo_client.database().collection::<UpcomingPhone>(collection_name),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            source: Arc::new(DirectSource),
+        }
+    }
+
+    /// Fetch Phone Finder results and phone pages from a mirror or test server
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Fetch pages through the run's provider, cache and rate limits
+    pub fn with_source(mut self, source: Arc<dyn PageSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Create the unique phone_id index
+    pub async fn create_indexes(&self) -> Result<(), Box<dyn Error>> {
+        let index = IndexModel::builder()
+            .keys(doc! { "phone_id": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+        self.collection.create_index(index, None).await?;
+        Ok(())
+    }
+
+    /// Discover rumored and coming-soon devices through Phone Finder
+    /// Returns the number of devices that were not tracked before
+    pub async fn discover(&self) -> Result<usize, Box<dyn Error>> {
+        let mut new_devices = 0;
+
+        for availability in [Availability::Rumored, Availability::ComingSoon] {
+            let (source, base_url) = (self.source.clone(), self.base_url.clone());
+            let phones = tokio::task::spawn_blocking(move || {
+                search_upcoming(source.as_ref(), &base_url, availability).map_err(|e| e.to_string())
+            })
+            .await??;
+
+            for phone in phones {
+                if self.track(&phone, availability).await? {
+                    new_devices += 1;
+                }
+            }
+        }
+
+        Ok(new_devices)
+    }
+
+    /// Start tracking a device if it isn't tracked yet; returns true if it was new
+    async fn track(&self, phone: &PhoneListItem, availability: Availability) -> Result<bool, Box<dyn Error>> {
+        let upcoming = UpcomingPhone {
+            phone_id: phone.phone_id.clone(),
+            name: phone.name.clone(),
+            url: phone.url.clone(),
+            image_url: phone.image_url.clone(),
+            status: availability,
+            status_text: None,
+            first_seen: Utc::now(),
+            last_checked: None,
+            available_since: None,
+        };
+
+        let result = self
+            .collection
+            .update_one(
+                doc! { "phone_id": &phone.phone_id },
+                doc! { "$setOnInsert": mongodb::bson::to_bson(&upcoming)? },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+
+        Ok(result.upserted_id.is_some())
+    }
+
+    /// Re-check every tracked device that isn't available yet
+    /// Returns one event per device whose availability changed
+    pub async fn recheck(&self, delay_ms: u64) -> Result<Vec<StatusChangeEvent>, Box<dyn Error>> {
+        let filter = doc! {
+            "status": { "$nin": [
+                mongodb::bson::to_bson(&Availability::Available)?,
+                mongodb::bson::to_bson(&Availability::Cancelled)?,
+            ] }
+        };
+
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut pending = Vec::new();
+        while let Some(result) = cursor.next().await {
+            pending.push(result?);
+        }
+
+        let mut events = Vec::new();
+
+        for phone in pending {
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+
+            let (source, base_url, phone_id) = (self.source.clone(), self.base_url.clone(), phone.phone_id.clone());
+            let status_text = match tokio::task::spawn_blocking(move || fetch_launch_status_with(source.as_ref(), &base_url, &phone_id)).await? {
+                Ok(status) => status,
+                Err(e) => {
+                    warn!(phone = %phone.phone_id, error = %e, "Failed to re-check");
+                    continue;
+                }
+            };
+
+            let now = Utc::now();
+            let new_status = status_text
+                .as_deref()
+                .and_then(Availability::from_status)
+                .unwrap_or(phone.status);
+
+            let mut update = doc! {
+                "status": mongodb::bson::to_bson(&new_status)?,
+                "status_text": status_text.as_deref(),
+                "last_checked": mongodb::bson::to_bson(&now)?,
+            };
+
+            if new_status != phone.status {
+                if new_status == Availability::Available {
+                    update.insert("available_since", mongodb::bson::to_bson(&now)?);
+                }
+
+                events.push(StatusChangeEvent {
+                    phone_id: phone.phone_id.clone(),
+                    name: phone.name.clone(),
+                    previous_status: phone.status,
+                    new_status,
+                    status_text: status_text.clone(),
+                    changed_at: now,
+                });
+            }
+
+            self.collection
+                .update_one(doc! { "phone_id": &phone.phone_id }, doc! { "$set": update }, None)
+                .await?;
+        }
+
+        Ok(events)
+    }
+}
+
+/// Phone Finder listing of devices with the given availability
+pub fn search_upcoming(source: &dyn PageSource, base_url: &str, availability: Availability) -> Result<Vec<PhoneListItem>, Box<dyn Error>> {
+    let query = PhoneFinderQuery::new().availability(availability);
+    search_phone_finder_with(base_url, &query, |url| source.fetch_page(url))
+}
+
+/// Fetch a phone page and return its Launch.status value
+pub fn fetch_launch_status(phone_id: &str) -> Result<Option<String>, String> {
+    fetch_launch_status_from(DEFAULT_BASE_URL, phone_id)
+}
+
+/// Launch.status of a phone page on a mirror or test server
+pub fn fetch_launch_status_from(base_url: &str, phone_id: &str) -> Result<Option<String>, String> {
+    fetch_launch_status_with(&DirectSource, base_url, phone_id)
+}
+
+/// Launch.status of a phone page fetched through any page source
+pub fn fetch_launch_status_with(source: &dyn PageSource, base_url: &str, phone_id: &str) -> Result<Option<String>, String> {
+    let html = source
+        .fetch_page(&phone_page_url_from(base_url, phone_id))
+        .map_err(|e| e.to_string())?;
+    parse_launch_status(phone_id, &html)
+}
+
+/// Launch.status of an already fetched phone page
+pub fn parse_launch_status(phone_id: &str, html: &str) -> Result<Option<String>, String> {
+    let spec = parse_specification_html(phone_id, html).map_err(|e| e.to_string())?;
+    let spec_json = serde_json::to_value(&spec).map_err(|e| e.to_string())?;
+
+    let (_, launch, ..) = parse_specifications(&spec_json);
+    Ok(launch.and_then(|l| l.status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FixtureSource, FIXTURE_BASE_URL};
+
+    #[test]
+    fn test_search_upcoming() {
+        let source = FixtureSource::default();
+
+        let rumored = search_upcoming(&source, FIXTURE_BASE_URL, Availability::Rumored).unwrap();
+        let coming_soon = search_upcoming(&source, FIXTURE_BASE_URL, Availability::ComingSoon).unwrap();
+
+        assert_eq!(
+            *source.requested.lock().unwrap(),
+            vec![
+                "http://mirror.local/results.php3?sAvailabilities=4",
+                "http://mirror.local/results.php3?sAvailabilities=2",
+            ]
+        );
+        assert_eq!(rumored.len(), 2);
+        assert_eq!(rumored[0].phone_id, "xiaomi_16_pro-14120");
+        assert_eq!(rumored[0].name, "Xiaomi 16 Pro");
+        assert_eq!(rumored[1].phone_id, "samsung_galaxy_z_flip8-14188");
+        assert_eq!(rumored[1].image_url.as_deref(), Some("https://fdn2.gsmarena.com/vv/bigpic/samsung-galaxy-z-flip8.jpg"));
+        assert_eq!(coming_soon.len(), 2);
+    }
+
+    #[test]
+    fn test_fetch_launch_status_with() {
+        let source = FixtureSource::default();
+
+        let status = fetch_launch_status_with(&source, FIXTURE_BASE_URL, "xiaomi_16_pro-14120").unwrap();
+        assert_eq!(status.as_deref(), Some("Rumored"));
+        assert_eq!(status.as_deref().and_then(Availability::from_status), Some(Availability::Rumored));
+
+        let status = fetch_launch_status_with(&source, FIXTURE_BASE_URL, "apple_iphone_15-12559").unwrap();
+        assert_eq!(status.as_deref().and_then(Availability::from_status), Some(Availability::Available));
+        assert_eq!(source.requested.lock().unwrap()[1], "http://mirror.local/apple_iphone_15-12559.php");
+
+        assert!(fetch_launch_status_with(&source, FIXTURE_BASE_URL, "removed_phone-1").is_err());
+    }
+
+    #[test]
+    fn test_parse_launch_status_without_specs() {
+        let html = std::fs::read_to_string(format!("{}/tests/fixtures/results.php3.html", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        assert!(parse_launch_status("xiaomi_16_pro-14120", &html).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! What a write does to a phone document that is already stored
+//!
+//! A partially blocked page can parse into a document with whole categories missing.
+//! Overwriting would wipe what an earlier scrape found; the merge policies keep it.
+
+use crate::mongodb::{specs_hash, PhoneDocument};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// How `upsert_phone` treats a stored document with the same phone_id (`mongodb.upsert_policy`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpsertPolicy {
+    /// The new document replaces the stored one
+    #[default]
+    Overwrite,
+    /// Fields of the new document win; stored fields it lacks are kept
+    MergePreferNew,
+    /// Like `MergePreferNew`, but null or empty new values never replace stored ones
+    MergePreferNonNull,
+    /// Stored documents are left untouched
+    SkipIfExists,
+}
+
+impl UpsertPolicy {
+    /// The document to write given what is stored; None when nothing should be written
+    pub fn resolve(&self, stored: Option<&PhoneDocument>, incoming: PhoneDocument) -> Result<Option<PhoneDocument>, Box<dyn Error>> {
+        let Some(stored) = stored else {
+            return Ok(Some(incoming));
+        };
+        let prefer_non_null = match self {
+            UpsertPolicy::Overwrite => return Ok(Some(incoming)),
+            UpsertPolicy::SkipIfExists => return Ok(None),
+            UpsertPolicy::MergePreferNew => false,
+            UpsertPolicy::MergePreferNonNull => true,
+        };
+
+        let mut merged = serde_json::to_value(stored)?;
+        merge(&mut merged, serde_json::to_value(&incoming)?, prefer_non_null);
+        let mut merged: PhoneDocument = serde_json::from_value(merged)?;
+        if prefer_non_null {
+            // Keep the raw rows behind the kept fields, so `reprocess` re-derives them too
+            merged.specifications_raw = merge_raw_categories(&stored.specifications_raw, &incoming.specifications_raw);
+            merged.specs_hash = Some(specs_hash(&merged.specifications_raw));
+        }
+        Ok(Some(merged))
+    }
+}
+
+/// The incoming raw specifications plus the stored categories they lack
+fn merge_raw_categories(stored: &Value, incoming: &Value) -> Value {
+    let title = |category: &Value| category.get("category_title").and_then(Value::as_str).map(str::to_lowercase);
+    let mut merged = incoming.clone();
+    let (Some(stored_categories), Some(categories)) = (
+        stored.get("specification").and_then(Value::as_array),
+        merged.get_mut("specification").and_then(Value::as_array_mut),
+    ) else {
+        return merged;
+    };
+    for category in stored_categories {
+        if !categories.iter().any(|existing| title(existing) == title(category)) {
+            categories.push(category.clone());
+        }
+    }
+    merged
+}
+
+/// Fold `incoming` into `stored`, recursing into objects; arrays are replaced whole
+fn merge(stored: &mut Value, incoming: Value, prefer_non_null: bool) {
+    match (stored, incoming) {
+        (Value::Object(stored), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                match stored.get_mut(&key) {
+                    Some(existing) => merge(existing, value, prefer_non_null),
+                    None => {
+                        stored.insert(key, value);
+                    }
+                }
+            }
+        }
+        (stored, incoming) => {
+            if !(prefer_non_null && is_missing(&incoming)) {
+                *stored = incoming;
+            }
+        }
+    }
+}
+
+fn is_missing(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+impl FromStr for UpsertPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "overwrite" => Ok(UpsertPolicy::Overwrite),
+            "merge_prefer_new" => Ok(UpsertPolicy::MergePreferNew),
+            "merge_prefer_non_null" => Ok(UpsertPolicy::MergePreferNonNull),
+            "skip_if_exists" => Ok(UpsertPolicy::SkipIfExists),
+            other => Err(format!(
+                "unknown upsert policy '{}' (overwrite, merge_prefer_new, merge_prefer_non_null, skip_if_exists)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for UpsertPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UpsertPolicy::Overwrite => "overwrite",
+            UpsertPolicy::MergePreferNew => "merge_prefer_new",
+            UpsertPolicy::MergePreferNonNull => "merge_prefer_non_null",
+            UpsertPolicy::SkipIfExists => "skip_if_exists",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_policies() {
+        let iphone = || PhoneDocument::fixture("apple_iphone_15-12559").name("iPhone 15").brand("Apple");
+        let stored = iphone()
+            .category("Platform", &[("Chipset", "Apple A16 Bionic (4 nm)")])
+            .category("Battery", &[("Type", "Li-Ion 3349 mAh")])
+            .build();
+        // A partially blocked page: the battery category is missing, the chipset reads differently
+        let partial = iphone().category("Platform", &[("Chipset", "Apple A16")]).build();
+
+        let overwritten = UpsertPolicy::Overwrite.resolve(Some(&stored), partial.clone()).unwrap().unwrap();
+        assert!(overwritten.battery.is_none());
+
+        let merged = UpsertPolicy::MergePreferNonNull.resolve(Some(&stored), partial.clone()).unwrap().unwrap();
+        assert_eq!(merged.battery_capacity_mah(), Some(3349));
+        assert_eq!(merged.platform.as_ref().unwrap().chipset.as_deref(), Some("Apple A16"));
+        assert_eq!(merged.updated_at, partial.updated_at);
+        let mut reparsed = merged.clone();
+        reparsed.reparse();
+        assert_eq!(reparsed.battery_capacity_mah(), Some(3349)); // The battery rows were kept as well
+        assert_ne!(merged.specs_hash, partial.specs_hash);
+
+        // Null is a value for MergePreferNew, so the missing battery wins too
+        let merged = UpsertPolicy::MergePreferNew.resolve(Some(&stored), partial.clone()).unwrap().unwrap();
+        assert!(merged.battery.is_none());
+
+        assert!(UpsertPolicy::SkipIfExists.resolve(Some(&stored), partial.clone()).unwrap().is_none());
+        assert!(UpsertPolicy::SkipIfExists.resolve(None, partial).unwrap().is_some());
+
+        assert_eq!("merge-prefer-non-null".parse::<UpsertPolicy>().unwrap(), UpsertPolicy::MergePreferNonNull);
+        assert_eq!(UpsertPolicy::SkipIfExists.to_string().parse::<UpsertPolicy>().unwrap(), UpsertPolicy::SkipIfExists);
+        assert!("replace".parse::<UpsertPolicy>().is_err());
+    }
+}
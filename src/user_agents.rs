@@ -0,0 +1,145 @@
+//! User-Agent headers for direct and proxy requests
+//!
+//! A single hardcoded User-Agent makes a scraper trivial to block, so requests draw
+//! from a pool of current desktop and mobile browsers (or a list from
+//! `user_agents.file`) and switch according to `user_agents.rotation`.
+
+use crate::config::{UserAgentConfig, UserAgentRotation};
+use rand::Rng;
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Built-in pool: recent Chrome, Firefox, Safari and Edge on desktop and mobile
+pub const BUILT_IN: &[&str] = &[
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/128.0.0.0 Safari/537.36 Edg/128.0.0.0",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:131.0) Gecko/20100101 Firefox/131.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.6 Safari/605.1.15",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 14.6; rv:130.0) Gecko/20100101 Firefox/130.0",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0",
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 17_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.6 Mobile/15E148 Safari/604.1",
+    "Mozilla/5.0 (iPad; CPU OS 17_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.6 Mobile/15E148 Safari/604.1",
+    "Mozilla/5.0 (Linux; Android 14; SM-S921B) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Mobile Safari/537.36",
+    "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Mobile Safari/537.36",
+];
+
+/// User-Agents to send, and which one is current; clones share the current choice
+#[derive(Debug, Clone)]
+pub struct UserAgentPool {
+    agents: Arc<Vec<String>>,
+    rotation: UserAgentRotation,
+    current: Arc<AtomicUsize>,
+}
+
+impl Default for UserAgentPool {
+    fn default() -> Self {
+        Self::built_in(UserAgentRotation::default())
+    }
+}
+
+impl UserAgentPool {
+    pub fn new(agents: Vec<String>, rotation: UserAgentRotation) -> Result<Self, Box<dyn Error>> {
+        if agents.is_empty() {
+            return Err("User-Agent pool is empty".into());
+        }
+        let current = rand::thread_rng().gen_range(0..agents.len());
+        Ok(Self {
+            agents: Arc::new(agents),
+            rotation,
+            current: Arc::new(AtomicUsize::new(current)),
+        })
+    }
+
+    pub fn built_in(rotation: UserAgentRotation) -> Self {
+        Self::new(BUILT_IN.iter().map(|agent| agent.to_string()).collect(), rotation).expect("built-in pool is not empty")
+    }
+
+    /// The file's list when `user_agents.file` is set, the built-in one otherwise
+    pub fn from_config(config: &UserAgentConfig) -> Result<Self, Box<dyn Error>> {
+        let Some(ref path) = config.file else {
+            return Ok(Self::built_in(config.rotation));
+        };
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Cannot read user_agents.file {}: {}", path, e))?;
+        Self::new(parse_list(&contents), config.rotation).map_err(|e| format!("{}: {}", path, e).into())
+    }
+
+    pub fn len(&self) -> usize {
+        self.agents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.agents.is_empty()
+    }
+
+    pub fn rotation(&self) -> UserAgentRotation {
+        self.rotation
+    }
+
+    /// User-Agent for the next request; a fresh pick per request with `per_request`
+    pub fn user_agent(&self) -> String {
+        if self.rotation == UserAgentRotation::PerRequest {
+            self.rotate();
+        }
+        self.agents[self.current.load(Ordering::Relaxed)].clone()
+    }
+
+    /// A new brand starts; switches the User-Agent with `per_brand`
+    pub fn start_brand(&self) {
+        if self.rotation == UserAgentRotation::PerBrand {
+            self.rotate();
+        }
+    }
+
+    /// Pick another User-Agent at random (never the current one, unless it is the only one)
+    fn rotate(&self) {
+        if self.agents.len() < 2 {
+            return;
+        }
+        let current = self.current.load(Ordering::Relaxed);
+        let offset = rand::thread_rng().gen_range(1..self.agents.len());
+        self.current.store((current + offset) % self.agents.len(), Ordering::Relaxed);
+    }
+}
+
+/// One User-Agent per line; blank lines and `#` comments are skipped
+pub fn parse_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_agent_rotation() {
+        let agents = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+
+        let session = UserAgentPool::new(agents.clone(), UserAgentRotation::PerSession).unwrap();
+        let first = session.user_agent();
+        session.start_brand();
+        assert!((0..20).all(|_| session.user_agent() == first));
+
+        let per_brand = UserAgentPool::new(agents.clone(), UserAgentRotation::PerBrand).unwrap();
+        let shared = per_brand.clone();
+        let before = per_brand.user_agent();
+        assert_eq!(per_brand.user_agent(), before);
+        shared.start_brand();
+        assert_ne!(per_brand.user_agent(), before); // Clones share the current choice
+
+        let per_request = UserAgentPool::new(agents, UserAgentRotation::PerRequest).unwrap();
+        let picks: Vec<String> = (0..20).map(|_| per_request.user_agent()).collect();
+        assert!(picks.windows(2).all(|pair| pair[0] != pair[1]));
+
+        assert!(UserAgentPool::new(Vec::new(), UserAgentRotation::PerRequest).is_err());
+        assert_eq!(UserAgentPool::default().len(), BUILT_IN.len());
+        assert_eq!(parse_list("# Desktop\nMozilla/5.0 A\n\n  Mozilla/5.0 B  \n"), vec!["Mozilla/5.0 A", "Mozilla/5.0 B"]);
+    }
+}
@@ -62,6 +62,7 @@ pub fn extract_spec(phone: &DeviceSpecification, spec_name: &str) -> Option<Stri
 }
 
 /// Compare two phones by extracting key specifications
+/// See `comparison::compare` for a scored comparison of any number of phones
 pub fn compare_phones(phone1: &DeviceSpecification, phone2: &DeviceSpecification) -> String {
     let mut output = String::new();
     
@@ -0,0 +1,144 @@
+use crate::mongodb::PhoneDocument;
+use serde::{Deserialize, Serialize};
+
+/// Documents scoring below this are flagged for re-scrape unless configured otherwise
+pub const DEFAULT_MIN_COMPLETENESS: f64 = 0.5;
+
+/// Values GSMArena (or a broken parse) uses when it has no data
+const PLACEHOLDERS: [&str; 6] = ["", "-", "n/a", "na", "tbd", "unknown"];
+
+type FieldGetter = fn(&PhoneDocument) -> Option<&String>;
+
+/// Fields every real phone page has; the completeness score is the share present
+const KEY_FIELDS: [(&str, FieldGetter); 14] = [
+    ("network.technology", |d| d.network.as_ref()?.technology.as_ref()),
+    ("launch.announced", |d| d.launch.as_ref()?.announced.as_ref()),
+    ("launch.status", |d| d.launch.as_ref()?.status.as_ref()),
+    ("body.dimensions", |d| d.body.as_ref()?.dimensions.as_ref()),
+    ("body.weight", |d| d.body.as_ref()?.weight.as_ref()),
+    ("display.size", |d| d.display.as_ref()?.size.as_ref()),
+    ("display.resolution", |d| d.display.as_ref()?.resolution.as_ref()),
+    ("platform.os", |d| d.platform.as_ref()?.os.as_ref()),
+    ("platform.chipset", |d| d.platform.as_ref()?.chipset.as_ref()),
+    ("memory.internal", |d| d.memory.as_ref()?.internal.as_ref()),
+    ("main_camera.modules", |d| d.main_camera.as_ref()?.modules.as_ref()),
+    ("comms.wlan", |d| d.comms.as_ref()?.wlan.as_ref()),
+    ("battery.battery_type", |d| d.battery.as_ref()?.battery_type.as_ref()),
+    ("misc.colors", |d| d.misc.as_ref()?.colors.as_ref()),
+];
+
+/// Outcome of checking one document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Validation {
+    pub completeness_score: f64, // Share of key fields with real values, 0.0 - 1.0
+    pub issues: Vec<String>,
+    pub has_specifications: bool, // False for block pages and failed parses
+    pub needs_rescrape: bool,
+}
+
+fn is_placeholder(value: &str) -> bool {
+    PLACEHOLDERS.contains(&value.trim().to_lowercase().as_str())
+}
+
+/// Check a document for missing or obviously wrong data and score its completeness
+pub fn validate(doc: &PhoneDocument, min_completeness: f64) -> Validation {
+    let mut issues = Vec::new();
+
+    for (field, value) in [("phone_id", &doc.phone_id), ("name", &doc.name), ("brand", &doc.brand)] {
+        if value.trim().is_empty() {
+            issues.push(format!("{} is empty", field));
+        }
+    }
+    if !doc.url.starts_with("http") {
+        issues.push(format!("url is not a URL: '{}'", doc.url));
+    }
+
+    // Look at the raw categories: empty ones and all-placeholder pages point at a bad fetch
+    let categories = doc
+        .specifications_raw
+        .get("specification")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut values = 0;
+    let mut placeholders = 0;
+
+    for category in categories {
+        let title = category.get("category_title").and_then(|v| v.as_str()).unwrap_or("");
+        let specs = category.get("category_spec").and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or_default();
+
+        if specs.is_empty() {
+            issues.push(format!("category '{}' is empty", title));
+        }
+        for value in specs.iter().filter_map(|pair| pair.get(1)?.as_str()) {
+            values += 1;
+            if is_placeholder(value) {
+                placeholders += 1;
+            }
+        }
+    }
+
+    let has_specifications = values > placeholders;
+    if categories.is_empty() {
+        issues.push("no specification categories (blocked or empty page?)".to_string());
+    } else if !has_specifications {
+        issues.push("every specification value is a placeholder".to_string());
+    }
+
+    let mut present = 0;
+    for (field, getter) in KEY_FIELDS {
+        match getter(doc) {
+            Some(value) if !is_placeholder(value) => present += 1,
+            Some(value) => issues.push(format!("{} is '{}'", field, value)),
+            None => issues.push(format!("{} missing", field)),
+        }
+    }
+
+    let completeness_score = present as f64 / KEY_FIELDS.len() as f64;
+    let needs_rescrape = !has_specifications || doc.name.trim().is_empty() || completeness_score < min_completeness;
+
+    Validation {
+        completeness_score,
+        issues,
+        has_specifications,
+        needs_rescrape,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::PhoneFixture;
+
+    fn galaxy() -> PhoneFixture {
+        PhoneDocument::fixture("samsung_galaxy_s24-12773").name("Galaxy S24").brand("Samsung")
+    }
+
+    #[test]
+    fn test_validate_scores_and_flags() {
+        let blocked = validate(&galaxy().build(), DEFAULT_MIN_COMPLETENESS);
+        assert!(!blocked.has_specifications);
+        assert!(blocked.needs_rescrape);
+        assert_eq!(blocked.completeness_score, 0.0);
+
+        let placeholders = validate(
+            &galaxy().category("Launch", &[("Announced", "N/A"), ("Status", "-")]).category("Body", &[]).build(),
+            DEFAULT_MIN_COMPLETENESS,
+        );
+        assert!(!placeholders.has_specifications);
+        assert!(placeholders.issues.contains(&"category 'Body' is empty".to_string()));
+        assert!(placeholders.issues.contains(&"launch.announced is 'N/A'".to_string()));
+
+        let partial = validate(
+            &galaxy()
+                .category("Launch", &[("Announced", "2024, January 17"), ("Status", "Available")])
+                .category("Platform", &[("OS", "Android 14"), ("Chipset", "Exynos 2400")])
+                .build(),
+            DEFAULT_MIN_COMPLETENESS,
+        );
+        assert!(partial.has_specifications);
+        assert_eq!(partial.completeness_score, 4.0 / 14.0);
+        assert!(partial.needs_rescrape);
+        assert!(!validate(&galaxy().category("Launch", &[("Announced", "2024")]).build(), 0.0).needs_rescrape);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+/// The fields variant detection looks at
+#[derive(Debug, Clone)]
+pub struct VariantCandidate {
+    pub phone_id: String,
+    pub brand: String,
+    pub name: String,
+    pub models: Option<String>, // Misc > Models, e.g. "SM-S921B, SM-S921B/DS, SM-S921U"
+}
+
+/// Phones that are the same device under different phone_ids
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantGroup {
+    pub variant_group_id: String, // Lowest phone_id of the group
+    pub phone_ids: Vec<String>,
+}
+
+/// Model numbers from a Misc > Models value, without "/DS"-style suffixes
+pub fn model_numbers(models: &str) -> Vec<String> {
+    let mut numbers: Vec<String> = models
+        .split([',', ';'])
+        .filter_map(|model| model.split('/').next())
+        .map(|model| model.trim().trim_end_matches('.').to_uppercase())
+        .filter(|model| model.len() >= 4 && model.chars().any(|c| c.is_ascii_digit()))
+        .collect();
+    numbers.sort();
+    numbers.dedup();
+    numbers
+}
+
+/// Name without parenthesised region or carrier qualifiers ("Galaxy S24 (USA)" -> "galaxy s24")
+pub fn normalized_name(name: &str) -> String {
+    let mut result = String::new();
+    let mut depth = 0usize;
+
+    for c in name.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => result.push(c.to_ascii_lowercase()),
+            _ => {}
+        }
+    }
+
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Link phones sharing a model number, or a normalized name within the same brand
+/// Only groups with two or more phones are returned, largest first
+pub fn group_variants(phones: &[VariantCandidate]) -> Vec<VariantGroup> {
+    let mut parent: Vec<usize> = (0..phones.len()).collect();
+
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut node = i;
+        while parent[node] != root {
+            let next = parent[node];
+            parent[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    let mut first_with_key: HashMap<String, usize> = HashMap::new();
+    for (index, phone) in phones.iter().enumerate() {
+        let name_key = format!("name:{}|{}", phone.brand.to_lowercase(), normalized_name(&phone.name));
+        let model_keys = phone.models.as_deref().map(model_numbers).unwrap_or_default().into_iter().map(|m| format!("model:{}", m));
+
+        for key in std::iter::once(name_key).chain(model_keys) {
+            match first_with_key.get(&key) {
+                Some(&other) => {
+                    let (a, b) = (find(&mut parent, index), find(&mut parent, other));
+                    parent[a] = b;
+                }
+                None => {
+                    first_with_key.insert(key, index);
+                }
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<String>> = HashMap::new();
+    for (index, phone) in phones.iter().enumerate() {
+        let root = find(&mut parent, index);
+        members.entry(root).or_default().push(phone.phone_id.clone());
+    }
+
+    let mut groups: Vec<VariantGroup> = members
+        .into_values()
+        .map(|mut phone_ids| {
+            phone_ids.sort();
+            phone_ids.dedup();
+            VariantGroup {
+                variant_group_id: phone_ids[0].clone(),
+                phone_ids,
+            }
+        })
+        .filter(|group| group.phone_ids.len() > 1)
+        .collect();
+
+    groups.sort_by(|a, b| b.phone_ids.len().cmp(&a.phone_ids.len()).then_with(|| a.variant_group_id.cmp(&b.variant_group_id)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(phone_id: &str, brand: &str, name: &str, models: Option<&str>) -> VariantCandidate {
+        VariantCandidate {
+            phone_id: phone_id.to_string(),
+            brand: brand.to_string(),
+            name: name.to_string(),
+            models: models.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_group_variants() {
+        assert_eq!(model_numbers("SM-S921B, SM-S921B/DS, sm-s921u; SM-S9210."), vec!["SM-S9210", "SM-S921B", "SM-S921U"]);
+        assert_eq!(normalized_name("Galaxy S24  (USA) [Verizon]"), "galaxy s24");
+
+        let phones = vec![
+            candidate("samsung_galaxy_s24-12773", "Samsung", "Galaxy S24", Some("SM-S921B, SM-S921B/DS")),
+            candidate("samsung_galaxy_s24_(usa)-12999", "Samsung", "Galaxy S24 (USA)", Some("SM-S921U")),
+            candidate("samsung_galaxy_s24_exynos-13000", "Samsung", "Galaxy S24 Exynos", Some("SM-S921B/DS")),
+            candidate("samsung_galaxy_s24_5g-13001", "Samsung", "Galaxy S24 5G", Some("SM-S926B")),
+            candidate("xiaomi_galaxy_s24-1", "Xiaomi", "Galaxy S24", None), // Same name, other brand
+        ];
+
+        let groups = group_variants(&phones);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].variant_group_id, "samsung_galaxy_s24-12773");
+        assert_eq!(groups[0].phone_ids.len(), 3);
+        assert!(!groups[0].phone_ids.contains(&"samsung_galaxy_s24_5g-13001".to_string()));
+    }
+}
@@ -0,0 +1,121 @@
+//! Golden files: saved phone pages must parse into the `PhoneDocument` JSON next to them
+//!
+//! Each page in `tests/fixtures` listed below has its expected document in
+//! `tests/fixtures/golden/<phone_id>.json`. After an intended parser change, rewrite
+//! them with `UPDATE_GOLDEN=1 cargo test --test golden` and review the diff.
+
+use chrono::{TimeZone, Utc};
+use gsmarena_scraper::scraper::parse_specification_html;
+use gsmarena_scraper::{PhoneDocument, PhoneListItem};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Phone ID, brand and listing name of every golden page
+const PAGES: [(&str, &str, &str); 7] = [
+    ("samsung_galaxy_s24-12773", "Samsung", "Galaxy S24"), // Flagship
+    ("apple_iphone_15-12559", "Apple", "iPhone 15"), // Flagship
+    ("nokia_105_(2023)-12406", "Nokia", "105 (2023)"), // Feature phone
+    ("samsung_galaxy_z_fold6-13147", "Samsung", "Galaxy Z Fold6"), // Foldable with a cover display
+    ("apple_ipad_air_11_(2024)-12986", "Apple", "iPad Air 11 (2024)"), // Tablet
+    ("samsung_galaxy_watch7-13225", "Samsung", "Galaxy Watch7"), // Watch
+    ("xiaomi_16_pro-14120", "Xiaomi", "16 Pro"), // Rumored, most rows missing
+];
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Parse a saved page the way a scrape does, with fixed timestamps
+fn parse(phone_id: &str, brand: &str, name: &str) -> Value {
+    let html = std::fs::read_to_string(fixtures().join(format!("{}.php.html", phone_id))).unwrap();
+    let spec = parse_specification_html(phone_id, &html).unwrap();
+    let item = PhoneListItem {
+        phone_id: phone_id.to_string(),
+        name: name.to_string(),
+        url: format!("https://www.gsmarena.com/{}.php", phone_id),
+        image_url: None,
+        device_type: None,
+    };
+    let mut document = PhoneDocument::new(&item, brand, serde_json::to_value(&spec).unwrap());
+    document.scraped_at = Utc.timestamp_opt(0, 0).unwrap();
+    document.updated_at = document.scraped_at;
+    serde_json::to_value(&document).unwrap()
+}
+
+/// JSON pointers where the two documents differ
+fn differences(path: &str, expected: &Value, actual: &Value, out: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            let mut keys: Vec<&String> = e.keys().chain(a.keys().filter(|k| !e.contains_key(*k))).collect();
+            keys.sort();
+            for key in keys {
+                let (e, a) = (e.get(key).unwrap_or(&Value::Null), a.get(key).unwrap_or(&Value::Null));
+                differences(&format!("{}/{}", path, key), e, a, out);
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (i, (e, a)) in e.iter().zip(a).enumerate() {
+                differences(&format!("{}/{}", path, i), e, a, out);
+            }
+        }
+        _ if expected != actual => out.push(format!("{}: expected {}, got {}", path, expected, actual)),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_golden_documents() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1");
+    let mut failures = Vec::new();
+
+    for (phone_id, brand, name) in PAGES {
+        let actual = parse(phone_id, brand, name);
+        let path = fixtures().join("golden").join(format!("{}.json", phone_id));
+        if update {
+            std::fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+            continue;
+        }
+
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            failures.push(format!("{}: no golden file (run with UPDATE_GOLDEN=1)", phone_id));
+            continue;
+        };
+        let expected: Value = serde_json::from_str(&text).unwrap();
+        let mut diffs = Vec::new();
+        differences("", &expected, &actual, &mut diffs);
+        if !diffs.is_empty() {
+            failures.push(format!("{}:\n  {}", phone_id, diffs.join("\n  ")));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "parser output drifted from the golden files (UPDATE_GOLDEN=1 rewrites them if intended):\n{}",
+        failures.join("\n")
+    );
+}
+
+/// The cases the golden pages were picked for
+#[test]
+fn test_golden_pages_cover_each_kind() {
+    let kinds: Vec<(String, String)> = PAGES
+        .iter()
+        .map(|(phone_id, brand, name)| {
+            let document = parse(phone_id, brand, name);
+            (document["device_type"].as_str().unwrap().to_string(), document["form_factor"].as_str().unwrap().to_string())
+        })
+        .collect();
+    let kind = |device_type: &str, form_factor: &str| kinds.contains(&(device_type.to_string(), form_factor.to_string()));
+    assert!(kind("phone", "bar"));
+    assert!(kind("phone", "foldable_book"));
+    assert!(kind("tablet", "tablet"));
+    assert!(kind("watch", "watch"));
+
+    let rumored = parse("xiaomi_16_pro-14120", "Xiaomi", "16 Pro");
+    assert_eq!(rumored["launch"]["status"], "Rumored");
+    assert_eq!(rumored["normalized"]["announced_year"], Value::Null);
+    let feature_phone = parse("nokia_105_(2023)-12406", "Nokia", "105 (2023)");
+    assert_eq!(feature_phone["device_type"], "phone"); // 1.8" screen, but it takes a SIM card
+    assert_eq!(feature_phone["normalized"]["has_5g"], false);
+    assert_eq!(feature_phone["normalized"]["battery_removable"], true);
+}
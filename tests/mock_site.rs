@@ -0,0 +1,439 @@
+//! The fetch layer against a local mock of GSMArena serving `tests/fixtures`
+//!
+//! Fixture files are named after the page they stand in for (`makers.php3.html`),
+//! so the same directory also works with `--from-dir`.
+
+use gsmarena_scraper::brand_scraper::{
+    fetch_all_brands_from, fetch_brand_details_from, fetch_brand_listings_concurrently, fetch_phones_by_brand_from,
+    phone_pages_from,
+};
+use gsmarena_scraper::phone_finder::search_phone_finder_from;
+use gsmarena_scraper::scraper::fetch_specification_from;
+use gsmarena_scraper::config::BrandFilter;
+use gsmarena_scraper::site::SiteConfig;
+use gsmarena_scraper::sitemap::discover_phones_from_sitemap;
+use gsmarena_scraper::store::PhoneFilter;
+use gsmarena_scraper::{
+    Brand, BrandDocument, DirectSource, ErrorPolicy, PhoneDocument, PhoneFinderQuery, PhoneListItem, PhoneQuery, PhoneService, PhoneSource, PhoneStore, RateLimiter,
+    ScrapeRunner,
+};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+fn fixture(page: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}.html", env!("CARGO_MANIFEST_DIR"), page);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+/// Serve a fixture at `/<page>`
+fn serve(server: &mut ServerGuard, page: &str) -> Mock {
+    server
+        .mock("GET", format!("/{}", page).as_str())
+        .with_header("content-type", "text/html; charset=utf-8")
+        .with_body(fixture(page))
+        .create()
+}
+
+#[test]
+fn test_fetch_all_brands() {
+    let mut server = Server::new();
+    let makers = serve(&mut server, "makers.php3");
+
+    let brands = fetch_all_brands_from(&server.url()).unwrap();
+    makers.assert();
+
+    let summary: Vec<(&str, &str, u32)> = brands.iter().map(|b| (b.name.as_str(), b.slug.as_str(), b.device_count)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Apple", "apple-phones-48", 3),
+            ("Samsung", "samsung-phones-9", 1),
+            ("Sony Ericsson", "sony_ericsson-phones-19", 0),
+            ("10.or", "10_or-phones-111", 12),
+            ("Nokia", "nokia-phones-1", 1489),
+        ]
+    );
+}
+
+#[test]
+fn test_fetch_phones_by_brand_follows_pages() {
+    let mut server = Server::new();
+    let first = serve(&mut server, "apple-phones-48.php");
+    let second = serve(&mut server, "apple-phones-48-p2.php");
+    let third = server.mock("GET", "/apple-phones-48-p3.php").expect(0).create();
+
+    let phones = fetch_phones_by_brand_from(&server.url(), "apple-phones-48", usize::MAX).unwrap();
+    first.assert();
+    second.assert();
+    third.assert(); // Page 1 says there are 2 pages
+
+    let ids: Vec<&str> = phones.iter().map(|p| p.phone_id.as_str()).collect();
+    assert_eq!(ids, vec!["apple_iphone_15_pro_max-12548", "apple_iphone_15-12559", "apple_iphone_se_(2022)-11410"]);
+    assert_eq!(phones[1].name, "iPhone 15");
+    assert_eq!(phones[1].image_url.as_deref(), Some("https://fdn2.gsmarena.com/vv/bigpic/apple-iphone-15.jpg"));
+
+    let limited = fetch_phones_by_brand_from(&server.url(), "apple-phones-48", 2).unwrap();
+    assert_eq!(limited.len(), 2);
+}
+
+#[test]
+fn test_block_page_mid_listing_is_an_error() {
+    let mut server = Server::new();
+    serve(&mut server, "apple-phones-48.php");
+    server.mock("GET", "/apple-phones-48-p2.php").with_body("<html><body>Too many requests</body></html>").create();
+
+    let error = fetch_phones_by_brand_from(&server.url(), "apple-phones-48", usize::MAX).unwrap_err();
+    assert!(error.to_string().contains("apple-phones-48 page 2 of 2"), "{}", error);
+}
+
+#[test]
+fn test_resume_listing_at_failed_page() {
+    let mut server = Server::new();
+    serve(&mut server, "apple-phones-48.php");
+    server.mock("GET", "/apple-phones-48-p2.php").with_body("<html><body>Too many requests</body></html>").create();
+
+    let mut pages = phone_pages_from(&server.url(), "apple-phones-48", usize::MAX);
+    assert!(pages.next().unwrap().is_ok());
+    assert!(pages.next().unwrap().is_err());
+    assert_eq!(pages.next_page(), 2);
+
+    // Resuming fetches only the failed page onwards, taking the page count from it
+    let mut server = Server::new();
+    let first = server.mock("GET", "/apple-phones-48.php").expect(0).create();
+    serve(&mut server, "apple-phones-48-p2.php");
+    let mut resumed = phone_pages_from(&server.url(), "apple-phones-48", usize::MAX).starting_at(2);
+    let ids: Vec<String> = resumed.next().unwrap().unwrap().into_iter().map(|p| p.phone_id).collect();
+    assert_eq!(ids, vec!["apple_iphone_se_(2022)-11410"]);
+    assert!(resumed.next().is_none());
+    assert_eq!(resumed.total_pages(), Some(2));
+    first.assert();
+}
+
+#[test]
+fn test_phone_pages_fetch_lazily() {
+    let mut server = Server::new();
+    let first = serve(&mut server, "apple-phones-48.php");
+    let second = server.mock("GET", "/apple-phones-48-p2.php").expect(0).create();
+
+    let mut pages = phone_pages_from(&server.url(), "apple-phones-48", usize::MAX);
+    let page = pages.next().unwrap().unwrap();
+    assert_eq!(page.len(), 2);
+    assert_eq!(pages.next_page(), 2);
+    drop(pages); // Stopping here never requests page 2
+
+    first.assert();
+    second.assert();
+
+    // A cap reached on page 1 ends the listing without another request
+    let capped: Vec<_> = phone_pages_from(&server.url(), "apple-phones-48", 1).collect();
+    assert_eq!(capped.len(), 1);
+    assert_eq!(capped[0].as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn test_fetch_brand_listings_concurrently() {
+    let mut server = Server::new();
+    serve(&mut server, "apple-phones-48.php");
+    serve(&mut server, "apple-phones-48-p2.php");
+    serve(&mut server, "samsung-phones-9.php");
+
+    let slugs = ["samsung-phones-9", "apple-phones-48", "nokia-phones-1"].map(String::from);
+    let limiter = RateLimiter::new(std::time::Duration::from_millis(5));
+    let results = fetch_brand_listings_concurrently(&server.url(), &slugs, usize::MAX, 4, &limiter, |url| {
+        let response = reqwest::blocking::get(url)?;
+        if !response.status().is_success() {
+            return Err(format!("status {}", response.status()).into());
+        }
+        Ok(response.text()?)
+    });
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap()[0].phone_id, "samsung_galaxy_s24-12773");
+    assert_eq!(results[1].as_ref().unwrap().len(), 3);
+    assert!(results[2].is_err()); // Not mocked: the first page fails
+}
+
+#[test]
+fn test_fetch_brand_details() {
+    let mut server = Server::new();
+    serve(&mut server, "apple-phones-48.php");
+
+    let details = fetch_brand_details_from(&server.url(), "apple-phones-48").unwrap();
+    assert_eq!(details.slug, "apple-phones-48");
+    assert_eq!(details.name.as_deref(), Some("Apple"));
+    assert_eq!(details.website.as_deref(), Some("https://www.apple.com/"));
+}
+
+#[test]
+fn test_phone_finder_by_year() {
+    let mut server = Server::new();
+    let query = Matcher::AllOf(vec![
+        Matcher::UrlEncoded("sMakers".into(), "48".into()),
+        Matcher::UrlEncoded("nYearMin".into(), "2023".into()),
+        Matcher::UrlEncoded("nYearMax".into(), "2023".into()),
+    ]);
+    let results = server.mock("GET", "/results.php3").match_query(query).with_body(fixture("apple-phones-48.php")).create();
+
+    let apple = Brand { name: "Apple".to_string(), slug: "apple-phones-48".to_string(), device_count: 120 };
+    let finder = PhoneFinderQuery::new().years(2023, 2023).maker(apple.maker_id().unwrap());
+    let phones = search_phone_finder_from(&server.url(), &finder).unwrap();
+    results.assert();
+    assert_eq!(phones[0].phone_id, "apple_iphone_15_pro_max-12548");
+}
+
+#[test]
+fn test_sitemap_discovery_against_base_url() {
+    let mut server = Server::new();
+    let site = SiteConfig::new(server.url());
+    let index = server
+        .mock("GET", "/sitemap.xml")
+        .with_body(format!(
+            "<sitemapindex><sitemap><loc>{0}/sitemap-phones.xml</loc></sitemap><sitemap><loc>{0}/sitemap-news.xml</loc></sitemap></sitemapindex>",
+            server.url()
+        ))
+        .create();
+    let phones = server
+        .mock("GET", "/sitemap-phones.xml")
+        .with_body(format!(
+            "<urlset><url><loc>{0}/apple_iphone_15-12559.php</loc></url><url><loc>{0}/apple_iphone_15-pictures-12559.php</loc></url>\
+             <url><loc>https://www.gsmarena.com/samsung_galaxy_s24-12773.php</loc></url></urlset>",
+            server.url()
+        ))
+        .create();
+
+    let found = discover_phones_from_sitemap(&site, &site.sitemap_url()).unwrap();
+    index.assert();
+    phones.assert();
+
+    // Pictures pages and pages on other hosts are not phones of this site
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].phone_id, "apple_iphone_15-12559");
+    assert_eq!(found[0].url, site.phone_page_url("apple_iphone_15-12559"));
+}
+
+#[test]
+fn test_fetch_and_parse_phone_pages() {
+    let mut server = Server::new();
+    serve(&mut server, "apple_iphone_15-12559.php");
+    serve(&mut server, "samsung_galaxy_s24-12773.php");
+
+    let document = |phone_id: &str, name: &str, brand: &str| {
+        let spec = fetch_specification_from(&server.url(), phone_id).unwrap();
+        let item = PhoneListItem {
+            phone_id: phone_id.to_string(),
+            name: name.to_string(),
+            url: format!("{}/{}.php", server.url(), phone_id),
+            image_url: None,
+            device_type: None,
+        };
+        PhoneDocument::new(&item, brand, serde_json::to_value(spec).unwrap())
+    };
+
+    let iphone = document("apple_iphone_15-12559", "iPhone 15", "Apple");
+    assert_eq!(iphone.platform.as_ref().and_then(|p| p.chipset.as_deref()), Some("Apple A16 Bionic (4 nm)"));
+    assert_eq!(iphone.normalized.announced_year, Some(2023));
+    assert_eq!(iphone.normalized.battery_mah, Some(3349));
+    assert_eq!(iphone.normalized.display_size_in, Some(6.1));
+    assert_eq!(iphone.normalized.storage_gb, Some(512.0));
+    assert_eq!(iphone.normalized.has_5g, Some(true));
+    assert_eq!((iphone.normalized.price_usd, iphone.normalized.price_eur), (Some(729.0), Some(779.0)));
+
+    let galaxy = document("samsung_galaxy_s24-12773", "Galaxy S24", "Samsung");
+    assert_eq!(galaxy.normalized.refresh_rate_hz, Some(120.0));
+    assert_eq!(galaxy.normalized.charging_w, Some(25.0));
+    assert_eq!(galaxy.normalized.wireless_watts, Some(15.0));
+    assert_eq!(galaxy.normalized.reverse_wireless_watts, Some(4.5));
+    assert_eq!(galaxy.normalized.weight_g, Some(167.0));
+    assert_eq!(galaxy.normalized.has_nfc, Some(true));
+}
+
+#[test]
+fn test_fetch_specification_errors() {
+    let mut server = Server::new();
+    server.mock("GET", "/removed_phone-1.php").with_status(404).create();
+    server.mock("GET", "/blocked_phone-2.php").with_body("<html><body>Too many requests</body></html>").create();
+
+    let missing = fetch_specification_from(&server.url(), "removed_phone-1").unwrap_err();
+    assert!(missing.to_string().contains("404"), "{}", missing);
+    assert!(fetch_specification_from(&server.url(), "blocked_phone-2").is_err());
+}
+
+/// Phones kept in memory, for `PhoneService`
+#[derive(Default)]
+struct MemoryStore(Mutex<HashMap<String, PhoneDocument>>);
+
+#[async_trait::async_trait]
+impl PhoneStore for MemoryStore {
+    async fn get_phone(&self, phone_id: &str) -> Result<Option<PhoneDocument>, Box<dyn Error>> {
+        Ok(self.0.lock().unwrap().get(phone_id).cloned())
+    }
+
+    async fn find_phones(&self, _filter: &PhoneFilter) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        Ok(self.0.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn query_phones(&self, _query: &PhoneQuery) -> Result<Vec<PhoneDocument>, Box<dyn Error>> {
+        Ok(self.0.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn get_brand(&self, _name: &str) -> Result<Option<BrandDocument>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    async fn list_brands(&self) -> Result<Vec<BrandDocument>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    async fn upsert_phone(&self, phone: PhoneDocument) -> Result<(), Box<dyn Error>> {
+        self.0.lock().unwrap().insert(phone.phone_id.clone(), phone);
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_phone_service_read_through() {
+    let mut server = Server::new_async().await;
+    let page = server
+        .mock("GET", "/apple_iphone_15-12559.php")
+        .with_body(fixture("apple_iphone_15-12559.php"))
+        .expect(2)
+        .create_async()
+        .await;
+    server.mock("GET", "/removed_phone-1.php").with_status(404).create_async().await;
+
+    let store = Arc::new(MemoryStore::default());
+    let service = PhoneService::new(store.clone(), Arc::new(DirectSource), SiteConfig::new(server.url()));
+
+    let (phone, source) = service.get_with_source("apple_iphone_15-12559").await.unwrap();
+    assert_eq!(source, PhoneSource::Live);
+    assert_eq!((phone.name.as_str(), phone.brand.as_str()), ("Apple Iphone 15", "Apple"));
+    assert_eq!(phone.normalized.battery_mah, Some(3349));
+    assert!(store.get_phone("apple_iphone_15-12559").await.unwrap().is_some());
+
+    // Fresh copies come from the store; flagged ones are fetched again under their stored name
+    assert_eq!(service.get_with_source("apple_iphone_15-12559").await.unwrap().1, PhoneSource::Store);
+    let mut flagged = phone.clone();
+    flagged.name = "iPhone 15".to_string();
+    flagged.needs_rescrape = true;
+    store.upsert_phone(flagged).await.unwrap();
+    let (refreshed, source) = service.get_with_source("apple_iphone_15-12559").await.unwrap();
+    assert_eq!((source, refreshed.name.as_str()), (PhoneSource::Live, "iPhone 15"));
+    page.assert_async().await;
+
+    assert!(service.get("removed_phone-1").await.is_err());
+    let mut stale = phone;
+    stale.phone_id = "removed_phone-1".to_string();
+    stale.needs_rescrape = true;
+    store.upsert_phone(stale).await.unwrap();
+    assert_eq!(service.get_with_source("removed_phone-1").await.unwrap().1, PhoneSource::StaleStore);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scrape_runner_hooks() {
+    let mut server = Server::new_async().await;
+    for page in ["makers.php3", "apple-phones-48.php", "apple_iphone_15-12559.php"] {
+        server.mock("GET", format!("/{}", page).as_str()).with_body(fixture(page)).create_async().await;
+    }
+    server.mock("GET", "/apple_iphone_15_pro_max-12548.php").with_status(404).create_async().await;
+
+    let store = Arc::new(MemoryStore::default());
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (on_brand, on_phone, on_error) = (events.clone(), events.clone(), events.clone());
+    let runner = ScrapeRunner::builder()
+        .site(SiteConfig::new(server.url()))
+        .store(store.clone())
+        .brands(BrandFilter { include: vec!["apple".to_string()], ..Default::default() })
+        .phones_per_brand(2)
+        .delay_between_phones(std::time::Duration::ZERO)
+        .self_check(true) // The canary pages are the Apple listing and the iPhone 15 served above
+        .on_brand_start(move |brand, phones| on_brand.lock().unwrap().push(format!("brand {} {}", brand.name, phones)))
+        .on_phone_scraped(move |phone| on_phone.lock().unwrap().push(format!("phone {}", phone.phone_id)))
+        .on_error(move |error| on_error.lock().unwrap().push(format!("error {}", error.phone_id.as_deref().unwrap_or("-"))))
+        .build();
+
+    let summary = runner.run().await.unwrap();
+    assert_eq!((summary.brands, summary.phones_scraped, summary.errors), (1, 1, 1));
+    assert_eq!((summary.failures.network, summary.failures.failed()), (1, 1)); // The 404
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["brand Apple 2", "error apple_iphone_15_pro_max-12548", "phone apple_iphone_15-12559"]
+    );
+    let iphone = store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap();
+    assert_eq!(iphone.name, "iPhone 15");
+    // Store prices from the pricing widget, a row per store and variant that is sold
+    assert_eq!(iphone.market_prices.len(), 5);
+    let uk = iphone.market_prices.iter().find(|price| price.region.as_deref() == Some("United Kingdom")).unwrap();
+    assert_eq!((uk.store.as_str(), uk.variant.as_deref(), uk.amount, uk.currency.as_deref()), ("Amazon UK", Some("128GB 6GB RAM"), Some(599.0), Some("GBP")));
+
+    // Stored phones are skipped on the next run
+    let summary = runner.run().await.unwrap();
+    assert_eq!((summary.phones_scraped, summary.phones_skipped), (0, 1));
+    assert_eq!(summary.failures.skipped_complete, 1);
+
+    // Scraped again, an identical page leaves the stored document alone
+    let stored = store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap();
+    let runner = ScrapeRunner::builder()
+        .site(SiteConfig::new(server.url()))
+        .store(store.clone())
+        .brands(BrandFilter { include: vec!["apple".to_string()], ..Default::default() })
+        .phones_per_brand(2)
+        .skip_existing(false)
+        .delay_between_phones(std::time::Duration::ZERO)
+        .build();
+    let summary = runner.run().await.unwrap();
+    assert_eq!((summary.phones_scraped, summary.phones_unchanged), (0, 1));
+    assert_eq!(store.get_phone("apple_iphone_15-12559").await.unwrap().unwrap().updated_at, stored.updated_at);
+
+    // With fail-fast the 404 ends the run before the second phone
+    let store = Arc::new(MemoryStore::default());
+    let runner = ScrapeRunner::builder()
+        .site(SiteConfig::new(server.url()))
+        .store(store.clone())
+        .brands(BrandFilter { include: vec!["apple".to_string()], ..Default::default() })
+        .phones_per_brand(2)
+        .delay_between_phones(std::time::Duration::ZERO)
+        .error_policy(ErrorPolicy::FailFast)
+        .build();
+    let error = runner.run().await.unwrap_err();
+    assert!(error.to_string().contains("fail-fast"), "{}", error);
+    assert!(store.get_phone("apple_iphone_15-12559").await.unwrap().is_none());
+}
+
+#[cfg(feature = "ledger")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scrape_runner_ledger() {
+    let mut server = Server::new_async().await;
+    for page in ["makers.php3", "apple-phones-48.php", "apple_iphone_15-12559.php"] {
+        server.mock("GET", format!("/{}", page).as_str()).with_body(fixture(page)).create_async().await;
+    }
+    server.mock("GET", "/apple_iphone_15_pro_max-12548.php").with_status(404).create_async().await;
+
+    // No store: the hook is the only output, as for a JSONL exporter
+    let ledger = gsmarena_scraper::Ledger::in_memory().unwrap();
+    let scraped = Arc::new(Mutex::new(Vec::new()));
+    let runner = |skip_existing: bool| {
+        let scraped = scraped.clone();
+        ScrapeRunner::builder()
+            .site(SiteConfig::new(server.url()))
+            .brands(BrandFilter { include: vec!["apple".to_string()], ..Default::default() })
+            .phones_per_brand(2)
+            .skip_existing(skip_existing)
+            .delay_between_phones(std::time::Duration::ZERO)
+            .ledger(ledger.clone())
+            .on_phone_scraped(move |phone| scraped.lock().unwrap().push(phone.phone_id.clone()))
+            .build()
+    };
+
+    let summary = runner(true).run().await.unwrap();
+    assert_eq!((summary.phones_scraped, summary.errors), (1, 1));
+    assert!(ledger.contains("apple_iphone_15-12559").unwrap());
+
+    let summary = runner(true).run().await.unwrap();
+    assert_eq!((summary.phones_scraped, summary.phones_skipped, summary.failures.skipped_existing), (0, 1, 1));
+
+    let summary = runner(false).run().await.unwrap();
+    assert_eq!((summary.phones_scraped, summary.phones_unchanged), (0, 1));
+    assert_eq!(*scraped.lock().unwrap(), vec!["apple_iphone_15-12559"]);
+}
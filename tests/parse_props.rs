@@ -0,0 +1,157 @@
+//! Property tests: malformed, truncated or unexpected spec input never panics
+//!
+//! `parse_specifications` and the normalizers run on whatever a page fetch produced,
+//! so every input has to come out as a document, with missing fields at worst.
+
+use gsmarena_scraper::mongodb::parse_specifications;
+use gsmarena_scraper::normalize::{max_storage_gb, parse_price};
+use gsmarena_scraper::scraper::parse_specification_html;
+use gsmarena_scraper::{NormalizedSpecs, PhoneDocument, PhoneListItem};
+use proptest::prelude::*;
+use serde_json::{json, Value};
+
+const CATEGORIES: [&str; 13] = [
+    "Network", "Launch", "Body", "Display", "Platform", "Memory", "Main Camera", "Selfie Camera", "Sound", "Comms",
+    "Features", "Battery", "Misc",
+];
+
+const KEYS: [&str; 24] = [
+    "Technology", "Announced", "Status", "Dimensions", "Weight", "SIM", "Type", "Size", "Chipset", "OS", "Internal",
+    "Single", "Triple", "Video", "NFC", "WLAN", "USB", "Charging", "Price", "Models", "SAR", "Colors", "Sensors", "Speed",
+];
+
+fn item() -> PhoneListItem {
+    PhoneListItem {
+        phone_id: "test_phone-1".to_string(),
+        name: "Test Phone".to_string(),
+        url: "https://www.gsmarena.com/test_phone-1.php".to_string(),
+        image_url: None,
+        device_type: None,
+    }
+}
+
+/// Spec strings: free text, or text shaped like GSMArena values with odd numbers in them
+fn spec_value() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<String>(),
+        "[0-9]{0,30}(\\.[0-9]{0,5})? ?(mAh|W|g|GB|TB|MP|Hz|nits|inches|GB RAM)",
+        "(\\$|€|About) ?[0-9,.]{0,20} ?(USD|EUR)?",
+        "(Yes|No|yes|N/A)[ a-z()/]{0,20}",
+        "[0-9]{0,6}, [A-Za-z]{0,10} ?[0-9]{0,3}",
+    ]
+}
+
+fn spec_pair() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        8 => (prop_oneof![prop::sample::select(KEYS.to_vec()).prop_map(String::from), any::<String>()], spec_value())
+            .prop_map(|(key, value)| json!([key, value])),
+        1 => any::<String>().prop_map(|s| json!([s])),
+        1 => any::<i64>().prop_map(|n| json!([n, n])),
+        1 => Just(Value::Null),
+    ]
+}
+
+fn specification() -> impl Strategy<Value = Value> {
+    let category = (
+        prop_oneof![prop::sample::select(CATEGORIES.to_vec()).prop_map(String::from), any::<String>()],
+        prop::collection::vec(spec_pair(), 0..8),
+    )
+        .prop_map(|(title, specs)| json!({ "category_title": title, "category_spec": specs }));
+    prop::collection::vec(category, 0..14).prop_map(|categories| json!({ "name": "Test Phone", "specification": categories }))
+}
+
+/// Any JSON at all, including shapes nothing on the site produces
+fn arbitrary_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<f64>().prop_map(Value::from),
+        any::<String>().prop_map(Value::from),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+            prop::collection::btree_map(
+                prop_oneof![Just("specification".to_string()), Just("category_spec".to_string()), any::<String>()],
+                inner,
+                0..6
+            )
+            .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+fn check_normalized(normalized: &NormalizedSpecs) {
+    for value in [
+        normalized.display_size_in,
+        normalized.brightness_nits,
+        normalized.refresh_rate_hz,
+        normalized.ram_gb,
+        normalized.storage_gb,
+        normalized.main_camera_mp,
+        normalized.charging_w,
+        normalized.wired_watts,
+        normalized.wireless_watts,
+        normalized.reverse_wireless_watts,
+        normalized.sar_us_head,
+        normalized.height_mm,
+        normalized.thickness_mm,
+        normalized.volume_cm3,
+        normalized.sar_eu_head,
+        normalized.weight_g,
+        normalized.price_usd,
+        normalized.price_eur,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        assert!(value >= 0.0, "negative or NaN normalized value: {}", value);
+    }
+}
+
+/// The document must build, normalize and convert to BSON for storage
+fn check_document(spec: Value) {
+    let document = PhoneDocument::new(&item(), "Test", spec);
+    assert_eq!(document.phone_id, "test_phone-1");
+    check_normalized(&document.normalized);
+    mongodb::bson::to_document(&document).expect("document converts to BSON");
+}
+
+fn phone_page_fixture() -> String {
+    let path = format!("{}/tests/fixtures/samsung_galaxy_s24-12773.php.html", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(path).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn spec_shaped_json_always_produces_a_document(spec in specification()) {
+        check_document(spec);
+    }
+
+    #[test]
+    fn arbitrary_json_never_panics(value in arbitrary_json()) {
+        let _ = parse_specifications(&value);
+        check_document(value);
+    }
+
+    #[test]
+    fn truncated_phone_page_parses_or_fails_cleanly(fraction in 0.0f64..=1.0) {
+        let html = phone_page_fixture();
+        let mut end = (html.len() as f64 * fraction) as usize;
+        while !html.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        if let Ok(spec) = parse_specification_html("samsung_galaxy_s24-12773", &html[..end]) {
+            check_document(serde_json::to_value(spec).unwrap());
+        }
+    }
+
+    #[test]
+    fn normalizers_never_panic(text in any::<String>()) {
+        let (usd, eur) = parse_price(&text);
+        for value in [usd, eur, max_storage_gb(&text)].into_iter().flatten() {
+            prop_assert!(value >= 0.0);
+        }
+    }
+}